@@ -0,0 +1,365 @@
+//! This module contains the [`AngleUnit`] used by the trigonometric functions, the conversion of angles between
+//! units and the trigonometric functions themselves. Angles that are exact multiples of 30 or 45 degrees give exact
+//! results, so `sin(30)` is exactly `0.5` and `tan(45)` is exactly `1` in degrees and gradians.
+
+use std::f64::consts::PI;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use super::{Error, Number};
+
+/// The unit of the angles passed to and returned by the trigonometric functions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AngleUnit {
+    /// 360 degrees in a full turn.
+    #[default]
+    Degrees,
+    /// 2π radians in a full turn.
+    Radians,
+    /// 400 gradians in a full turn.
+    Gradians,
+}
+
+impl AngleUnit {
+    /// All angle units, in the order they are cycled through by the `DRG` key.
+    pub const ALL: [AngleUnit; 3] = [AngleUnit::Degrees, AngleUnit::Radians, AngleUnit::Gradians];
+
+    /// Returns the name of the angle unit as stored in the settings file and used by the `angle-unit` action.
+    pub fn name(self) -> &'static str {
+        match self {
+            AngleUnit::Degrees => "degrees",
+            AngleUnit::Radians => "radians",
+            AngleUnit::Gradians => "gradians",
+        }
+    }
+
+    /// Returns the angle unit with the given name, or `None` if there is no such unit.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the angle unit as stored in the settings file.
+    pub fn from_name(name: &str) -> Option<Self> {
+        AngleUnit::ALL.into_iter().find(|unit| unit.name() == name)
+    }
+
+    /// Returns the abbreviation of the angle unit shown by the mode indicator (e.g. `DEG`).
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            AngleUnit::Degrees => "DEG",
+            AngleUnit::Radians => "RAD",
+            AngleUnit::Gradians => "GRAD",
+        }
+    }
+
+    /// Returns the suffix written after an angle in this unit in expressions (e.g. `°`).
+    pub fn suffix(self) -> &'static str {
+        match self {
+            AngleUnit::Degrees => "°",
+            AngleUnit::Radians => " rad",
+            AngleUnit::Gradians => " grad",
+        }
+    }
+
+    /// Returns the angle unit following this one in the `DRG` cycle: degrees, radians, gradians and back to degrees.
+    pub fn next(self) -> Self {
+        match self {
+            AngleUnit::Degrees => AngleUnit::Radians,
+            AngleUnit::Radians => AngleUnit::Gradians,
+            AngleUnit::Gradians => AngleUnit::Degrees,
+        }
+    }
+
+    /// Converts an angle in this unit to another unit. Conversions between degrees and gradians are exact.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The angle in this unit.
+    /// * `unit` - The unit to convert the angle to.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn convert(self, value: &Number, unit: AngleUnit) -> Result<Number, Error> {
+        match (self.turn(), unit.turn()) {
+            (Some(from), Some(to)) => value.mul(&Number::Rational(to / from)),
+            _ => unit.convert_radians(self.to_radians(value)),
+        }
+    }
+
+    /// The number of units in a full turn, or `None` for radians.
+    fn turn(self) -> Option<BigRational> {
+        match self {
+            AngleUnit::Degrees => Some(BigRational::from_integer(BigInt::from(360))),
+            AngleUnit::Radians => None,
+            AngleUnit::Gradians => Some(BigRational::from_integer(BigInt::from(400))),
+        }
+    }
+
+    /// Converts an angle in this unit to radians.
+    fn to_radians(self, value: &Number) -> f64 {
+        match self {
+            AngleUnit::Degrees => value.to_f64().to_radians(),
+            AngleUnit::Radians => value.to_f64(),
+            AngleUnit::Gradians => value.to_f64() * PI / 200.0,
+        }
+    }
+
+    /// Converts an angle in radians to this unit.
+    fn convert_radians(self, radians: f64) -> Result<Number, Error> {
+        Number::from_real(match self {
+            AngleUnit::Degrees => radians.to_degrees(),
+            AngleUnit::Radians => radians,
+            AngleUnit::Gradians => radians * 200.0 / PI,
+        })
+    }
+
+    /// Returns the angle as a fraction of a full turn in `[0, 1)`, or `None` if it cannot be computed exactly.
+    fn exact_turns(self, value: &Number) -> Option<BigRational> {
+        let turns = match (value, self.turn()) {
+            (Number::Rational(value), Some(turn)) => value / turn,
+            _ if value.is_zero() => BigRational::zero(),
+            _ => return None,
+        };
+        Some(&turns - turns.floor())
+    }
+
+    /// Returns the angle of the given fraction of a full turn in this unit. Exact for degrees and gradians.
+    fn convert_turns(self, turns: BigRational) -> Result<Number, Error> {
+        match self.turn() {
+            Some(turn) => Ok(Number::Rational(turns * turn)),
+            None => Number::from_real(turns.to_f64().unwrap_or(f64::NAN) * 2.0 * PI),
+        }
+    }
+}
+
+/// Returns the sine of an angle.
+///
+/// # Arguments
+///
+/// * `value` - The angle.
+/// * `unit` - The unit of the angle.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if the angle is not finite.
+pub fn sin(value: &Number, unit: AngleUnit) -> Result<Number, Error> {
+    if let Some(twelfths) = unit.exact_turns(value).and_then(|turns| multiple_of(&turns, 12)) {
+        if let Some(result) = exact_sine(twelfths) {
+            return Ok(result);
+        }
+    }
+    Number::from_real(unit.to_radians(value).sin())
+}
+
+/// Returns the cosine of an angle.
+///
+/// # Arguments
+///
+/// * `value` - The angle.
+/// * `unit` - The unit of the angle.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if the angle is not finite.
+pub fn cos(value: &Number, unit: AngleUnit) -> Result<Number, Error> {
+    if let Some(twelfths) = unit.exact_turns(value).and_then(|turns| multiple_of(&turns, 12)) {
+        if let Some(result) = exact_sine(twelfths + 3) {
+            return Ok(result);
+        }
+    }
+    Number::from_real(unit.to_radians(value).cos())
+}
+
+/// Returns the tangent of an angle.
+///
+/// # Arguments
+///
+/// * `value` - The angle.
+/// * `unit` - The unit of the angle.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if the tangent is undefined (e.g. `tan(90)` in degrees) or the angle is not finite.
+pub fn tan(value: &Number, unit: AngleUnit) -> Result<Number, Error> {
+    if let Some(eighths) = unit.exact_turns(value).and_then(|turns| multiple_of(&turns, 8)) {
+        return match eighths % 4 {
+            0 => Ok(Number::zero()),
+            1 => Ok(Number::from_integer(1)),
+            2 => Err(Error::InvalidInput),
+            _ => Ok(Number::from_integer(-1)),
+        };
+    }
+    Number::from_real(unit.to_radians(value).tan())
+}
+
+/// Returns the inverse sine of a value as an angle in `[-90°, 90°]`.
+///
+/// # Arguments
+///
+/// * `value` - The sine of the angle.
+/// * `unit` - The unit of the returned angle.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if the value is outside of `[-1, 1]`.
+pub fn asin(value: &Number, unit: AngleUnit) -> Result<Number, Error> {
+    check_unit_interval(value)?;
+    match exact_asin_turns(value) {
+        Some(turns) => unit.convert_turns(turns),
+        None => unit.convert_radians(value.to_f64().asin()),
+    }
+}
+
+/// Returns the inverse cosine of a value as an angle in `[0°, 180°]`.
+///
+/// # Arguments
+///
+/// * `value` - The cosine of the angle.
+/// * `unit` - The unit of the returned angle.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if the value is outside of `[-1, 1]`.
+pub fn acos(value: &Number, unit: AngleUnit) -> Result<Number, Error> {
+    check_unit_interval(value)?;
+    match exact_asin_turns(value) {
+        Some(turns) => unit.convert_turns(BigRational::new(BigInt::one(), BigInt::from(4)) - turns),
+        None => unit.convert_radians(value.to_f64().acos()),
+    }
+}
+
+/// Returns the inverse tangent of a value as an angle in `(-90°, 90°)`.
+///
+/// # Arguments
+///
+/// * `value` - The tangent of the angle.
+/// * `unit` - The unit of the returned angle.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if the value is not finite.
+pub fn atan(value: &Number, unit: AngleUnit) -> Result<Number, Error> {
+    match value {
+        Number::Rational(ratio) if ratio.is_zero() => Ok(Number::zero()),
+        Number::Rational(ratio) if ratio.abs().is_one() => {
+            unit.convert_turns(BigRational::new(ratio.numer().signum(), BigInt::from(8)))
+        }
+        _ => unit.convert_radians(value.to_f64().atan()),
+    }
+}
+
+/// Returns `turns × parts` if it is an integer, as a value in `0..parts`.
+fn multiple_of(turns: &BigRational, parts: i64) -> Option<i64> {
+    let scaled = turns * BigRational::from_integer(BigInt::from(parts));
+    scaled
+        .is_integer()
+        .then(|| scaled.to_integer().to_i64())
+        .flatten()
+}
+
+/// Returns the sine of `twelfths` twelfths of a full turn (multiples of 30°) if it is rational.
+fn exact_sine(twelfths: i64) -> Option<Number> {
+    let half = || Number::Rational(BigRational::new(BigInt::one(), BigInt::from(2)));
+    match twelfths.rem_euclid(12) {
+        0 | 6 => Some(Number::zero()),
+        1 | 5 => Some(half()),
+        3 => Some(Number::from_integer(1)),
+        7 | 11 => Some(half().neg()),
+        9 => Some(Number::from_integer(-1)),
+        _ => None,
+    }
+}
+
+/// Returns the inverse sine of `0`, `±1/2` or `±1` as a fraction of a full turn, or `None` for other values.
+fn exact_asin_turns(value: &Number) -> Option<BigRational> {
+    let Number::Rational(value) = value
+    else {
+        return None;
+    };
+    let sign = value.numer().signum();
+    let twelfths = if value.is_zero() {
+        0
+    }
+    else if value.abs().is_one() {
+        3
+    }
+    else if value.abs() == BigRational::new(BigInt::one(), BigInt::from(2)) {
+        1
+    }
+    else {
+        return None;
+    };
+    Some(BigRational::new(sign * BigInt::from(twelfths), BigInt::from(12)))
+}
+
+/// Checks that a value is in the domain `[-1, 1]` of the inverse sine and cosine.
+fn check_unit_interval(value: &Number) -> Result<(), Error> {
+    let in_domain = match value {
+        Number::Rational(value) => value.abs() <= BigRational::one(),
+        Number::Real(value) => value.abs() <= 1.0,
+    };
+    if in_domain {
+        Ok(())
+    }
+    else {
+        Err(Error::InvalidInput)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses an exact number.
+    fn number(text: &str) -> Number {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn exact_values() {
+        assert_eq!(sin(&number("30"), AngleUnit::Degrees), Ok(number("0.5")));
+        assert_eq!(sin(&number("-390"), AngleUnit::Degrees), Ok(number("-0.5")));
+        assert_eq!(cos(&number("180"), AngleUnit::Degrees), Ok(number("-1")));
+        assert_eq!(cos(&number("100"), AngleUnit::Gradians), Ok(number("0")));
+        assert_eq!(tan(&number("45"), AngleUnit::Degrees), Ok(number("1")));
+        assert_eq!(tan(&number("150"), AngleUnit::Gradians), Ok(number("-1")));
+        assert_eq!(tan(&number("90"), AngleUnit::Degrees), Err(Error::InvalidInput));
+        assert_eq!(asin(&number("0.5"), AngleUnit::Degrees), Ok(number("30")));
+        assert_eq!(acos(&number("-1"), AngleUnit::Gradians), Ok(number("200")));
+        assert_eq!(atan(&number("-1"), AngleUnit::Degrees), Ok(number("-45")));
+        assert_eq!(asin(&number("1.5"), AngleUnit::Degrees), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn radians() {
+        let close = |value: Result<Number, Error>, expected: f64| {
+            assert!((value.unwrap().to_f64() - expected).abs() < 1e-12);
+        };
+        close(sin(&Number::Real(PI / 6.0), AngleUnit::Radians), 0.5);
+        close(cos(&number("1"), AngleUnit::Radians), 1.0_f64.cos());
+        close(asin(&number("0.5"), AngleUnit::Radians), PI / 6.0);
+        close(atan(&number("1"), AngleUnit::Radians), PI / 4.0);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(AngleUnit::Degrees.convert(&number("90"), AngleUnit::Gradians), Ok(number("100")));
+        assert_eq!(AngleUnit::Gradians.convert(&number("50"), AngleUnit::Degrees), Ok(number("45")));
+        let radians = AngleUnit::Degrees.convert(&number("180"), AngleUnit::Radians).unwrap();
+        assert!((radians.to_f64() - PI).abs() < 1e-12);
+        let degrees = AngleUnit::Radians.convert(&Number::Real(PI / 2.0), AngleUnit::Degrees).unwrap();
+        assert!((degrees.to_f64() - 90.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn drg_cycle() {
+        let mut unit = AngleUnit::Degrees;
+        for expected in [AngleUnit::Radians, AngleUnit::Gradians, AngleUnit::Degrees] {
+            unit = unit.next();
+            assert_eq!(unit, expected);
+            assert_eq!(AngleUnit::from_name(unit.name()), Some(unit));
+        }
+    }
+}
@@ -0,0 +1,1104 @@
+//! This module contains the [`Calculator`] state machine. It implements the behaviour of the keypad: entering numbers,
+//! applying operators and functions, and evaluating the pending calculation. The operands it applies and the
+//! calculations it completes are reported as [`Event`]s, from which the calculation tape is derived.
+
+use super::number_theory;
+use super::percent::{percent, percent_operand};
+use super::{angle, AngleUnit, Complex, Error, Factorization, Interval, Number, Quantity, Uncertain, Units, Value};
+
+/// A binary operator of the [`Calculator`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operator {
+    /// Returns the operator with the given action name (e.g. `add`), or `None` if there is no such operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the operator as used by the `op-insert` action.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "add" => Some(Operator::Add),
+            "subtract" => Some(Operator::Subtract),
+            "multiply" => Some(Operator::Multiply),
+            "divide" => Some(Operator::Divide),
+            _ => None,
+        }
+    }
+
+    /// Returns the symbol of the operator as shown in expressions.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Operator::Add => "+",
+            Operator::Subtract => "−",
+            Operator::Multiply => "×",
+            Operator::Divide => "÷",
+        }
+    }
+
+    /// Applies the operator to the given operands.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the underlying [`Number`] operation.
+    pub fn apply(self, lhs: &Number, rhs: &Number) -> Result<Number, Error> {
+        match self {
+            Operator::Add => lhs.add(rhs),
+            Operator::Subtract => lhs.sub(rhs),
+            Operator::Multiply => lhs.mul(rhs),
+            Operator::Divide => lhs.div(rhs),
+        }
+    }
+
+    /// Applies the operator to the given complex operands.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the underlying [`Complex`] operation.
+    pub fn apply_complex(self, lhs: &Complex, rhs: &Complex) -> Result<Complex, Error> {
+        match self {
+            Operator::Add => lhs.add(rhs),
+            Operator::Subtract => lhs.sub(rhs),
+            Operator::Multiply => lhs.mul(rhs),
+            Operator::Divide => lhs.div(rhs),
+        }
+    }
+}
+
+/// A unary function of the [`Calculator`], applied to the displayed value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Function {
+    Square,
+    SquareRoot,
+    Reciprocal,
+    Sine,
+    Cosine,
+    Tangent,
+    ArcSine,
+    ArcCosine,
+    ArcTangent,
+    Exponential,
+    NaturalLogarithm,
+    Logarithm,
+    Factorial,
+    Totient,
+    IsPrime,
+}
+
+impl Function {
+    /// Returns the function with the given action name (e.g. `square`), or `None` if there is no such function.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the function as used by the `op-insert` action.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "square" => Some(Function::Square),
+            "square-root" => Some(Function::SquareRoot),
+            "one-over" => Some(Function::Reciprocal),
+            "sin" => Some(Function::Sine),
+            "cos" => Some(Function::Cosine),
+            "tan" => Some(Function::Tangent),
+            "asin" => Some(Function::ArcSine),
+            "acos" => Some(Function::ArcCosine),
+            "atan" => Some(Function::ArcTangent),
+            "exp" => Some(Function::Exponential),
+            "ln" => Some(Function::NaturalLogarithm),
+            "log" => Some(Function::Logarithm),
+            "factorial" => Some(Function::Factorial),
+            "totient" => Some(Function::Totient),
+            "is-prime" => Some(Function::IsPrime),
+            _ => None,
+        }
+    }
+
+    /// Applies the function to the given value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to apply the function to.
+    /// * `angle_unit` - The unit of the angles taken and returned by the trigonometric functions.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the underlying [`Number`] operation.
+    pub fn apply(self, value: &Number, angle_unit: AngleUnit) -> Result<Number, Error> {
+        match self {
+            Function::Square => value.square(),
+            Function::SquareRoot => value.sqrt(),
+            Function::Reciprocal => value.recip(),
+            Function::Sine => angle::sin(value, angle_unit),
+            Function::Cosine => angle::cos(value, angle_unit),
+            Function::Tangent => angle::tan(value, angle_unit),
+            Function::ArcSine => angle::asin(value, angle_unit),
+            Function::ArcCosine => angle::acos(value, angle_unit),
+            Function::ArcTangent => angle::atan(value, angle_unit),
+            Function::Exponential => value.exp(),
+            Function::NaturalLogarithm => value.ln(),
+            Function::Logarithm => value.log10(),
+            Function::Factorial => number_theory::factorial(value),
+            Function::Totient => number_theory::totient(value),
+            Function::IsPrime => number_theory::is_prime(value),
+        }
+    }
+
+    /// Returns the expression of the function applied to the given operand (e.g. `sqr(3)`). The operands of the
+    /// trigonometric functions are marked with their angle unit (e.g. `sin(30°)`), unless they are written in degrees,
+    /// minutes and seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `operand` - The expression of the operand.
+    /// * `angle_unit` - The unit of the angles taken by the trigonometric functions.
+    pub fn describe(self, operand: &str, angle_unit: AngleUnit) -> String {
+        let suffix = if operand.ends_with(['°', '\'', '"']) {
+            ""
+        }
+        else {
+            angle_unit.suffix()
+        };
+        match self {
+            Function::Square => format!("sqr({})", operand),
+            Function::SquareRoot => format!("√({})", operand),
+            Function::Reciprocal => format!("1/({})", operand),
+            Function::Sine => format!("sin({}{})", operand, suffix),
+            Function::Cosine => format!("cos({}{})", operand, suffix),
+            Function::Tangent => format!("tan({}{})", operand, suffix),
+            Function::ArcSine => format!("asin({})", operand),
+            Function::ArcCosine => format!("acos({})", operand),
+            Function::ArcTangent => format!("atan({})", operand),
+            Function::Exponential => format!("exp({})", operand),
+            Function::NaturalLogarithm => format!("ln({})", operand),
+            Function::Logarithm => format!("log({})", operand),
+            Function::Factorial if operand.chars().all(|c| c.is_ascii_digit()) => format!("{}!", operand),
+            Function::Factorial => format!("({})!", operand),
+            Function::Totient => format!("φ({})", operand),
+            Function::IsPrime => format!("isprime({})", operand),
+        }
+    }
+}
+
+/// The content of the calculator display.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Display {
+    /// A number being entered, without thousands separators (e.g. `-12.5` or `2 3/8`).
+    Entry(String),
+    /// The result of a calculation.
+    Result(Number),
+    /// The result of a calculation that is a complex number, not a real one.
+    Complex(Complex),
+    /// The prime factorization of an integer (e.g. `2^3 × 3 × 5`).
+    Factorization(Factorization),
+    /// The result of a typed expression that is a number with an uncertainty (e.g. `12.3 ± 0.2`).
+    Uncertain(Uncertain),
+    /// The result of a typed expression in interval mode that is an interval (e.g. `[1.2, 1.5]`).
+    Interval(Interval),
+    /// The error of a failed calculation.
+    Error(Error),
+}
+
+/// A completed calculation, as recorded in the history.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Calculation {
+    /// The evaluated expression (e.g. `12 + 30 =`).
+    pub expression:    String,
+    /// The result of the expression, which may be complex.
+    pub result:        Complex,
+    /// The units of the result, empty for plain numbers.
+    pub units:         Units,
+    /// The prime factorization of the result, if the calculation was a factorization.
+    pub factorization: Option<Factorization>,
+    /// The standard uncertainty of the result, if it has one.
+    pub uncertainty:   Option<Number>,
+    /// The interval of the result, if it is an interval. The result is then its midpoint.
+    pub interval:      Option<Interval>,
+}
+
+/// An event of the [`Calculator`], reported for the calculation tape.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// An operand was applied by an operator or by equals.
+    Operand {
+        /// The operator applied to the operand, or `None` for the first operand of a calculation.
+        operator: Option<Operator>,
+        /// The expression of the operand (e.g. `√(16)` or `price * (1 + rate)`).
+        operand:  String,
+        /// The running result of the calculation after the operand.
+        subtotal: Complex,
+    },
+    /// A calculation was completed, by equals or as a typed expression.
+    Total(Box<Calculation>),
+    /// A pending calculation was cleared before it was completed.
+    Cleared,
+    /// A calculation failed.
+    Failed(Error),
+}
+
+/// The calculator state machine driven by the keypad. It behaves like a standard desktop calculator: operators are
+/// evaluated immediately from left to right, and pressing equals again repeats the last operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Calculator {
+    display:          Display,
+    units:            Units,
+    accumulator:      Option<Complex>,
+    pending:          Option<Operator>,
+    operand:          Option<String>,
+    repeat:           Option<(Operator, Complex)>,
+    expression:       String,
+    new_entry:        bool,
+    awaiting_operand: bool,
+    angle_unit:       AngleUnit,
+    complex_mode:     bool,
+    interval_mode:    bool,
+    memory:           Option<Number>,
+    events:           Vec<Event>,
+}
+
+impl Calculator {
+    /// Creates a new [`Calculator`] displaying `0`.
+    pub fn new() -> Self {
+        Self {
+            display:          Display::Entry("0".to_string()),
+            units:            Units::default(),
+            accumulator:      None,
+            pending:          None,
+            operand:          None,
+            repeat:           None,
+            expression:       String::new(),
+            new_entry:        false,
+            awaiting_operand: false,
+            angle_unit:       AngleUnit::default(),
+            complex_mode:     false,
+            interval_mode:    false,
+            memory:           None,
+            events:           Vec::new(),
+        }
+    }
+
+    /// The content of the display.
+    pub fn display(&self) -> &Display {
+        &self.display
+    }
+
+    /// The units of the displayed result. Only results of typed expressions have units; they are dropped as soon as
+    /// the display changes.
+    pub fn units(&self) -> &Units {
+        &self.units
+    }
+
+    /// The value stored in memory, or `None` if the memory is empty.
+    pub fn memory(&self) -> Option<&Number> {
+        self.memory.as_ref()
+    }
+
+    /// The unit of the angles taken and returned by the trigonometric functions.
+    pub fn angle_unit(&self) -> AngleUnit {
+        self.angle_unit
+    }
+
+    /// Sets the unit of the angles taken and returned by the trigonometric functions. The displayed value is not
+    /// converted.
+    ///
+    /// # Arguments
+    ///
+    /// * `angle_unit` - The new angle unit.
+    pub fn set_angle_unit(&mut self, angle_unit: AngleUnit) {
+        self.angle_unit = angle_unit;
+    }
+
+    /// Whether functions outside of their real domain return complex results (e.g. `√-4` is `2i`) rather than an
+    /// error.
+    pub fn complex_mode(&self) -> bool {
+        self.complex_mode
+    }
+
+    /// Sets whether functions outside of their real domain return complex results. The displayed value is not
+    /// changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `complex_mode` - Whether complex results are returned.
+    pub fn set_complex_mode(&mut self, complex_mode: bool) {
+        self.complex_mode = complex_mode;
+    }
+
+    /// Whether typed expressions read a list of two numbers in square brackets as an interval (e.g. `[1.2, 1.5]`)
+    /// rather than a vector. The keypad is not affected.
+    pub fn interval_mode(&self) -> bool {
+        self.interval_mode
+    }
+
+    /// Sets whether typed expressions read a list of two numbers in square brackets as an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval_mode` - Whether such lists are intervals.
+    pub fn set_interval_mode(&mut self, interval_mode: bool) {
+        self.interval_mode = interval_mode;
+    }
+
+    /// Returns the events that occurred since the last call, oldest first, and forgets them.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// The expression of the pending calculation (e.g. `12 +`), or of the last evaluated calculation.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// The value of the display. An error is treated as zero, a complex result as its real part, a factorization as
+    /// the integer factored, a number with an uncertainty as its value, an interval as its midpoint, and an incomplete
+    /// fraction being entered as its whole part (e.g. `2 3/` as `2`).
+    pub fn value(&self) -> Number {
+        match &self.display {
+            Display::Entry(entry) => {
+                complete_entry(entry)
+                    .parse()
+                    .unwrap_or_else(|_| Number::zero())
+            }
+            Display::Result(number) => number.clone(),
+            Display::Complex(complex) => complex.re.clone(),
+            Display::Factorization(factorization) => factorization.value(),
+            Display::Uncertain(uncertain) => uncertain.value.clone(),
+            Display::Interval(interval) => interval.midpoint().unwrap_or_else(|_| interval.lower.clone()),
+            Display::Error(_) => Number::zero(),
+        }
+    }
+
+    /// Appends a digit to the number being entered, starting a new number if necessary. A denominator cannot start
+    /// with zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `digit` - The digit to append.
+    pub fn input_digit(&mut self, digit: char) {
+        self.begin_entry();
+        if let Display::Entry(entry) = &mut self.display {
+            if (entry.ends_with('/') && digit == '0') || entry.ends_with('"') {
+                return;
+            }
+            if entry == "0" {
+                entry.clear();
+            }
+            else if entry == "-0" {
+                entry.truncate(1);
+            }
+            entry.push(digit);
+        }
+    }
+
+    /// Appends a decimal point to the number being entered, unless the part being entered already contains one or
+    /// the number is a fraction.
+    pub fn input_decimal(&mut self) {
+        self.begin_entry();
+        if let Display::Entry(entry) = &mut self.display {
+            let part = entry.rsplit(['°', '\'']).next().unwrap_or_default();
+            if !part.contains('.') && !entry.contains(['/', '"']) {
+                if part.is_empty() {
+                    entry.push('0');
+                }
+                entry.push('.');
+            }
+        }
+    }
+
+    /// Enters the next part of a fraction, like the `a b/c` key of a scientific calculator. The first press starts the
+    /// denominator (`3` becomes `3/`). A second press turns the entered fraction into the whole part and numerator of
+    /// a mixed number (`2/3` becomes `2 3/`). Decimal numbers cannot be entered as fractions.
+    pub fn input_fraction(&mut self) {
+        self.begin_entry();
+        if let Display::Entry(entry) = &mut self.display {
+            if entry.contains(['.', '°']) || entry.ends_with('/') {
+                return;
+            }
+            match (entry.contains(' '), entry.find('/')) {
+                (false, Some(index)) => {
+                    entry.replace_range(index..=index, " ");
+                    entry.push('/');
+                }
+                (_, None) => entry.push('/'),
+                (true, Some(_)) => {}
+            }
+        }
+    }
+
+    /// Enters the next part of an angle in degrees, minutes and seconds: the degree sign, then the minute sign and
+    /// then the second sign (e.g. `12°30'15"`). Fractions cannot be entered as angles.
+    pub fn input_dms(&mut self) {
+        self.begin_entry();
+        if let Display::Entry(entry) = &mut self.display {
+            if entry.contains('/') || entry.ends_with(['°', '\'', '"']) {
+                return;
+            }
+            if !entry.contains('°') {
+                entry.push('°');
+            }
+            else if !entry.contains('\'') {
+                entry.push('\'');
+            }
+            else if !entry.contains('"') {
+                entry.push('"');
+            }
+        }
+    }
+
+    /// Replaces the number being entered (e.g. with a number pasted from the clipboard).
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The number without thousands separators.
+    pub fn set_entry(&mut self, number: &str) {
+        self.begin_entry();
+        self.set_display(Display::Entry(number.to_string()));
+    }
+
+    /// Removes the last character of the number being entered. Results are not affected.
+    pub fn backspace(&mut self) {
+        if self.new_entry {
+            return;
+        }
+        if let Display::Entry(entry) = &mut self.display {
+            entry.pop();
+            let trimmed_len = entry.trim_end().len();
+            entry.truncate(trimmed_len);
+            if entry.is_empty() || entry == "-" {
+                *entry = "0".to_string();
+            }
+        }
+    }
+
+    /// Negates the displayed value.
+    pub fn negate(&mut self) {
+        if matches!(self.display, Display::Error(_)) {
+            return;
+        }
+        if !self.new_entry {
+            if let Display::Entry(entry) = &mut self.display {
+                if entry.starts_with('-') {
+                    entry.remove(0);
+                }
+                else if entry != "0" {
+                    entry.insert(0, '-');
+                }
+                return;
+            }
+        }
+        self.operand = Some(format!("negate({})", self.operand_text()));
+        self.show(self.complex_value().neg());
+        self.awaiting_operand = false;
+        self.update_expression();
+    }
+
+    /// Clears the displayed value, keeping the pending calculation.
+    pub fn clear_entry(&mut self) {
+        if matches!(self.display, Display::Error(_)) {
+            self.clear();
+            return;
+        }
+        self.set_display(Display::Entry("0".to_string()));
+        self.operand = None;
+        self.new_entry = false;
+        self.awaiting_operand = false;
+        self.update_expression();
+    }
+
+    /// Clears the displayed value and the pending calculation. The angle unit, the complex and interval modes, the
+    /// memory and the events not taken yet are kept.
+    pub fn clear(&mut self) {
+        if self.pending.is_some() {
+            self.events.push(Event::Cleared);
+        }
+        *self = Self {
+            angle_unit: self.angle_unit,
+            complex_mode: self.complex_mode,
+            interval_mode: self.interval_mode,
+            memory: self.memory.take(),
+            events: std::mem::take(&mut self.events),
+            ..Self::new()
+        };
+    }
+
+    /// Stores the displayed value in memory. Complex values cannot be stored.
+    pub fn memory_store(&mut self) {
+        if matches!(self.display, Display::Error(_)) {
+            return;
+        }
+        match self.complex_value().to_real() {
+            Ok(value) => {
+                self.memory = Some(value);
+                self.new_entry = true;
+            }
+            Err(error) => self.fail(error),
+        }
+    }
+
+    /// Shows the value stored in memory. Does nothing if the memory is empty.
+    pub fn memory_recall(&mut self) {
+        let Some(memory) = self.memory.clone()
+        else {
+            return;
+        };
+        if matches!(self.display, Display::Error(_)) {
+            self.clear();
+        }
+        self.set_display(Display::Result(memory));
+        self.operand = None;
+        self.new_entry = true;
+        self.awaiting_operand = false;
+        self.update_expression();
+    }
+
+    /// Adds the displayed value to the value stored in memory. An empty memory is treated as zero.
+    pub fn memory_add(&mut self) {
+        self.update_memory(Operator::Add);
+    }
+
+    /// Subtracts the displayed value from the value stored in memory. An empty memory is treated as zero.
+    pub fn memory_subtract(&mut self) {
+        self.update_memory(Operator::Subtract);
+    }
+
+    /// Clears the memory.
+    pub fn memory_clear(&mut self) {
+        self.memory = None;
+    }
+
+    /// Applies a binary operator. A pending calculation is evaluated first, so operators are evaluated from left to
+    /// right. Pressing another operator before entering the next operand replaces the pending operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator` - The operator to apply.
+    pub fn apply_operator(&mut self, operator: Operator) {
+        if matches!(self.display, Display::Error(_)) {
+            return;
+        }
+        if self.awaiting_operand && self.pending.is_some() {
+            self.pending = Some(operator);
+            self.update_expression();
+            return;
+        }
+        let value = self.complex_value();
+        let result = match (&self.accumulator, self.pending) {
+            (Some(accumulator), Some(pending)) => pending.apply_complex(accumulator, &value),
+            _ => Ok(value),
+        };
+        match result {
+            Ok(result) => {
+                self.events.push(Event::Operand {
+                    operator: self.pending,
+                    operand:  self.operand_text(),
+                    subtotal: result.clone(),
+                });
+                self.show(result.clone());
+                self.accumulator = Some(result);
+                self.pending = Some(operator);
+                self.operand = None;
+                self.new_entry = true;
+                self.awaiting_operand = true;
+                self.update_expression();
+            }
+            Err(error) => self.fail(error),
+        }
+    }
+
+    /// Applies a unary function to the displayed value. Complex values give complex results, and so do real values
+    /// outside of the real domain of the function in complex mode (e.g. `√-4` is `2i`).
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function to apply.
+    pub fn apply_function(&mut self, function: Function) {
+        if matches!(self.display, Display::Error(_)) {
+            return;
+        }
+        let value = self.complex_value();
+        let result = if value.is_real() && !self.complex_mode {
+            function.apply(&value.re, self.angle_unit).map(Complex::from)
+        }
+        else {
+            value.apply_function(function, self.angle_unit)
+        };
+        match result {
+            Ok(result) => {
+                self.operand = Some(function.describe(&self.operand_text(), self.angle_unit));
+                self.show(result);
+                self.new_entry = true;
+                self.awaiting_operand = false;
+                self.update_expression();
+            }
+            Err(error) => self.fail(error),
+        }
+    }
+
+    /// Applies a function that is not built into the keypad (e.g. a user-defined function) to the displayed value,
+    /// which must be real.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the function, shown in the expression (e.g. `f(3)`).
+    /// * `function` - The function to apply.
+    pub fn apply_named(&mut self, name: &str, function: impl FnOnce(&Number) -> Result<Number, Error>) {
+        if matches!(self.display, Display::Error(_)) {
+            return;
+        }
+        match self.complex_value().to_real().and_then(|value| function(&value)) {
+            Ok(result) => {
+                self.operand = Some(format!("{}({})", name, self.operand_text()));
+                self.set_display(Display::Result(result));
+                self.new_entry = true;
+                self.awaiting_operand = false;
+                self.update_expression();
+            }
+            Err(error) => self.fail(error),
+        }
+    }
+
+    /// Factors the displayed value into primes and shows its factorization (e.g. `2^3 × 3 × 5` for `120`), discarding
+    /// the pending calculation.
+    ///
+    /// # Returns
+    ///
+    /// The completed calculation, or `None` if the value is not a nonzero integer or cannot be factored.
+    pub fn factor(&mut self) -> Option<Calculation> {
+        if matches!(self.display, Display::Error(_)) {
+            return None;
+        }
+        let expression = format!("factor({}) =", self.operand_text());
+        let result = self
+            .complex_value()
+            .to_real()
+            .and_then(|value| Factorization::new(&value))
+            .map(Value::Factorization);
+        self.set_result(expression, result)
+    }
+
+    /// Replaces the displayed value by a percentage: a fraction of the value before the pending operator for additions
+    /// and subtractions (so `200 + 10 % =` is `220`), or the value as a fraction otherwise (so `200 × 10 % =` is `20`
+    /// and `10 %` alone is `0.1`). The exact rules are listed in the `percent` module.
+    pub fn percent(&mut self) {
+        if matches!(self.display, Display::Error(_)) {
+            return;
+        }
+        let result = self.complex_value().to_real().and_then(|value| {
+            match (&self.accumulator, self.pending) {
+                (Some(accumulator), Some(pending)) => percent_operand(pending, &accumulator.to_real()?, &value),
+                _ => percent(&value),
+            }
+        });
+        match result {
+            Ok(result) => {
+                self.operand = Some(format!("{}%", self.operand_text()));
+                self.set_display(Display::Result(result));
+                self.new_entry = true;
+                self.awaiting_operand = false;
+                self.update_expression();
+            }
+            Err(error) => self.fail(error),
+        }
+    }
+
+    /// Converts the displayed angle from the current angle unit to the next unit of the `DRG` cycle, and switches to
+    /// that unit (e.g. `180` in degrees becomes `π` in radians).
+    pub fn convert_angle(&mut self) {
+        if matches!(self.display, Display::Error(_)) {
+            return;
+        }
+        let unit = self.angle_unit.next();
+        let result = self
+            .complex_value()
+            .to_real()
+            .and_then(|value| self.angle_unit.convert(&value, unit));
+        match result {
+            Ok(result) => {
+                self.operand = Some(format!("{}({})", unit.name(), self.operand_text()));
+                self.set_display(Display::Result(result));
+                self.angle_unit = unit;
+                self.new_entry = true;
+                self.awaiting_operand = false;
+                self.update_expression();
+            }
+            Err(error) => self.fail(error),
+        }
+    }
+
+    /// Evaluates the pending calculation. If there is no pending calculation, the last operation is repeated with the
+    /// displayed value.
+    ///
+    /// # Returns
+    ///
+    /// The completed calculation, or `None` if nothing was evaluated or the evaluation failed.
+    pub fn equals(&mut self) -> Option<Calculation> {
+        if matches!(self.display, Display::Error(_)) {
+            self.clear();
+            return None;
+        }
+        let (operator, lhs, rhs, lhs_text, rhs_text, repeated) = if let Some(operator) = self.pending {
+            let lhs = self
+                .accumulator
+                .clone()
+                .unwrap_or_else(|| Complex::from(Number::zero()));
+            let (rhs, rhs_text) = if self.awaiting_operand {
+                (lhs.clone(), complex_text(&lhs))
+            }
+            else {
+                (self.complex_value(), self.operand_text())
+            };
+            let lhs_text = complex_text(&lhs);
+            (operator, lhs, rhs, lhs_text, rhs_text, false)
+        }
+        else if let Some((operator, rhs)) = self.repeat.clone() {
+            let rhs_text = complex_text(&rhs);
+            (operator, self.complex_value(), rhs, self.operand_text(), rhs_text, true)
+        }
+        else {
+            return None;
+        };
+
+        let expression = format!("{} {} {} =", lhs_text, operator.symbol(), rhs_text);
+        match operator.apply_complex(&lhs, &rhs) {
+            Ok(result) => {
+                // A repeated operation starts a new calculation from the displayed value.
+                if repeated {
+                    self.events.push(Event::Operand {
+                        operator: None,
+                        operand:  lhs_text,
+                        subtotal: lhs,
+                    });
+                }
+                self.events.push(Event::Operand {
+                    operator: Some(operator),
+                    operand:  rhs_text,
+                    subtotal: result.clone(),
+                });
+                self.show(result.clone());
+                self.accumulator = None;
+                self.pending = None;
+                self.operand = None;
+                self.repeat = Some((operator, rhs));
+                self.expression = expression.clone();
+                self.new_entry = true;
+                self.awaiting_operand = false;
+                let calculation = Calculation {
+                    expression,
+                    result,
+                    units: Units::default(),
+                    factorization: None,
+                    uncertainty: None,
+                    interval: None,
+                };
+                self.events.push(Event::Total(Box::new(calculation.clone())));
+                Some(calculation)
+            }
+            Err(error) => {
+                self.fail(error);
+                None
+            }
+        }
+    }
+
+    /// Shows the result of an expression evaluated outside of the keypad (e.g. typed as text), discarding the pending
+    /// calculation.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - The evaluated expression, as shown above the result (e.g. `price * (1 + rate) =`).
+    /// * `result` - The value of the expression, or the error of its evaluation. Matrices cannot be shown.
+    ///
+    /// # Returns
+    ///
+    /// The completed calculation, or `None` if the evaluation failed.
+    pub fn set_result(&mut self, expression: String, result: Result<Value, Error>) -> Option<Calculation> {
+        let result = result.and_then(|value| {
+            let mut calculation = Calculation {
+                expression:    expression.clone(),
+                result:        Complex::from(Number::zero()),
+                units:         Units::default(),
+                factorization: None,
+                uncertainty:   None,
+                interval:      None,
+            };
+            match value {
+                Value::Quantity(Quantity { value, units }) => {
+                    calculation.result = Complex::from(value);
+                    calculation.units = units;
+                }
+                Value::Complex(complex) => calculation.result = complex,
+                Value::Matrix(_) => return Err(Error::UnexpectedMatrix),
+                Value::Factorization(factorization) => {
+                    calculation.result = Complex::from(factorization.value());
+                    calculation.factorization = Some(factorization);
+                }
+                Value::Uncertain(Uncertain { value, uncertainty }) => {
+                    calculation.result = Complex::from(value);
+                    calculation.uncertainty = Some(uncertainty);
+                }
+                Value::Interval(interval) => {
+                    calculation.result = Complex::from(interval.midpoint()?);
+                    calculation.interval = Some(interval);
+                }
+            }
+            Ok(calculation)
+        });
+        match result {
+            Ok(calculation) => {
+                if let Some(factorization) = &calculation.factorization {
+                    self.set_display(Display::Factorization(factorization.clone()));
+                }
+                else if let Some(uncertainty) = &calculation.uncertainty {
+                    self.set_display(Display::Uncertain(Uncertain {
+                        value:       calculation.result.re.clone(),
+                        uncertainty: uncertainty.clone(),
+                    }));
+                }
+                else if let Some(interval) = &calculation.interval {
+                    self.set_display(Display::Interval(interval.clone()));
+                }
+                else {
+                    self.show(calculation.result.clone());
+                }
+                self.units = calculation.units.clone();
+                self.accumulator = None;
+                self.pending = None;
+                self.operand = None;
+                self.repeat = None;
+                self.events.push(Event::Operand {
+                    operator: None,
+                    operand:  expression.trim_end_matches('=').trim_end().to_string(),
+                    subtotal: calculation.result.clone(),
+                });
+                self.events.push(Event::Total(Box::new(calculation.clone())));
+                self.expression = expression;
+                self.new_entry = true;
+                self.awaiting_operand = false;
+                Some(calculation)
+            }
+            Err(error) => {
+                self.fail(error);
+                self.expression = expression;
+                None
+            }
+        }
+    }
+
+    /// Changes the content of the display, dropping the units of the previous result.
+    fn set_display(&mut self, display: Display) {
+        self.display = display;
+        self.units = Units::default();
+    }
+
+    /// Shows a result, as a real number if its imaginary part is zero.
+    fn show(&mut self, result: Complex) {
+        if result.is_real() {
+            self.set_display(Display::Result(result.re));
+        }
+        else {
+            self.set_display(Display::Complex(result));
+        }
+    }
+
+    /// The value of the display, which may be complex. An error is treated as zero.
+    fn complex_value(&self) -> Complex {
+        match &self.display {
+            Display::Complex(complex) => complex.clone(),
+            _ => Complex::from(self.value()),
+        }
+    }
+
+    /// Starts entering a new number if the display does not contain a number being entered.
+    fn begin_entry(&mut self) {
+        if matches!(self.display, Display::Error(_)) {
+            self.clear();
+        }
+        if self.new_entry || !matches!(self.display, Display::Entry(_)) {
+            self.set_display(Display::Entry("0".to_string()));
+            self.operand = None;
+            self.new_entry = false;
+        }
+        self.awaiting_operand = false;
+        if self.pending.is_none() {
+            self.expression.clear();
+        }
+    }
+
+    /// Applies an operator to the value stored in memory and the displayed value, and stores the result in memory.
+    fn update_memory(&mut self, operator: Operator) {
+        if matches!(self.display, Display::Error(_)) {
+            return;
+        }
+        let memory = self.memory.clone().unwrap_or_else(Number::zero);
+        match self
+            .complex_value()
+            .to_real()
+            .and_then(|value| operator.apply(&memory, &value))
+        {
+            Ok(memory) => {
+                self.memory = Some(memory);
+                self.new_entry = true;
+            }
+            Err(error) => self.fail(error),
+        }
+    }
+
+    /// Puts the calculator into the error state, discarding the pending calculation.
+    fn fail(&mut self, error: Error) {
+        self.events.push(Event::Failed(error.clone()));
+        self.set_display(Display::Error(error));
+        self.accumulator = None;
+        self.pending = None;
+        self.operand = None;
+        self.repeat = None;
+        self.new_entry = true;
+        self.awaiting_operand = false;
+    }
+
+    /// The expression of the current operand: the function applied to it if any, otherwise its value. Fractions and
+    /// angles being entered are kept as entered.
+    fn operand_text(&self) -> String {
+        match (&self.operand, &self.display) {
+            (Some(operand), _) => operand.clone(),
+            (None, Display::Entry(entry)) if entry.contains(['/', '°']) => complete_entry(entry).to_string(),
+            _ => complex_text(&self.complex_value()),
+        }
+    }
+
+    /// Updates the expression of the pending calculation.
+    fn update_expression(&mut self) {
+        let operand = self.operand.clone().unwrap_or_default();
+        self.expression = match (&self.accumulator, self.pending) {
+            (Some(accumulator), Some(pending)) => {
+                format!("{} {} {}", complex_text(accumulator), pending.symbol(), operand)
+                    .trim_end()
+                    .to_string()
+            }
+            _ => operand,
+        };
+    }
+}
+
+/// Returns the complete part of a number being entered, dropping the numerator of a fraction whose denominator has
+/// not been entered yet (e.g. `2 3/` becomes `2`, and `3/` becomes `3`).
+fn complete_entry(entry: &str) -> &str {
+    match entry.strip_suffix('/') {
+        Some(whole) => {
+            whole
+                .rsplit_once(' ')
+                .map_or(whole, |(whole, _numerator)| whole)
+        }
+        None => entry,
+    }
+}
+
+/// Returns a value as written in the expression of a calculation, in parentheses if it is complex (e.g. `(3 + 4i)`).
+fn complex_text(value: &Complex) -> String {
+    if value.is_real() {
+        value.re.to_string()
+    }
+    else {
+        format!("({})", value)
+    }
+}
+
+impl Default for Calculator {
+    /// The default implementation of the [`Calculator`]. It creates a new [`Calculator`] displaying `0`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::UndoStack;
+
+    /// Enters a number on the keypad, digit by digit.
+    fn enter(calculator: &mut Calculator, number: &str) {
+        for digit in number.chars() {
+            calculator.input_digit(digit);
+        }
+    }
+
+    /// Returns the displayed value after `lhs operator rhs % =` is keyed in.
+    fn keyed_percent(lhs: &str, operator: Operator, rhs: &str) -> Number {
+        let mut calculator = Calculator::new();
+        enter(&mut calculator, lhs);
+        calculator.apply_operator(operator);
+        enter(&mut calculator, rhs);
+        calculator.percent();
+        calculator.equals();
+        calculator.value()
+    }
+
+    #[test]
+    fn percent_with_operators() {
+        assert_eq!(keyed_percent("200", Operator::Add, "10"), Number::from_integer(220));
+        assert_eq!(keyed_percent("200", Operator::Subtract, "10"), Number::from_integer(180));
+        assert_eq!(keyed_percent("200", Operator::Multiply, "10"), Number::from_integer(20));
+        assert_eq!(keyed_percent("20", Operator::Divide, "10"), Number::from_integer(200));
+    }
+
+    #[test]
+    fn percent_shows_operand_before_equals() {
+        let mut calculator = Calculator::new();
+        enter(&mut calculator, "200");
+        calculator.apply_operator(Operator::Add);
+        enter(&mut calculator, "10");
+        calculator.percent();
+        assert_eq!(calculator.value(), Number::from_integer(20));
+    }
+
+    #[test]
+    fn percent_alone() {
+        let mut calculator = Calculator::new();
+        enter(&mut calculator, "10");
+        calculator.percent();
+        assert_eq!(calculator.value(), "0.1".parse().unwrap());
+    }
+
+    #[test]
+    fn equals_repeats_percent_operand() {
+        let mut calculator = Calculator::new();
+        enter(&mut calculator, "200");
+        calculator.apply_operator(Operator::Add);
+        enter(&mut calculator, "10");
+        calculator.percent();
+        calculator.equals();
+        calculator.equals();
+        assert_eq!(calculator.value(), Number::from_integer(240));
+        calculator.equals();
+        assert_eq!(calculator.value(), Number::from_integer(260));
+    }
+
+    #[test]
+    fn undo_restores_the_calculation() {
+        let mut stack = UndoStack::default();
+        let mut calculator = Calculator::new();
+        for step in ["12", "+", "3", "="] {
+            let before = calculator.clone();
+            match step {
+                "+" => calculator.apply_operator(Operator::Add),
+                "=" => {
+                    calculator.equals();
+                }
+                digits => enter(&mut calculator, digits),
+            }
+            stack.record(before, &calculator);
+        }
+        assert_eq!(calculator.value(), Number::from_integer(15));
+        calculator = stack.undo(calculator).unwrap();
+        assert_eq!(calculator.display(), &Display::Entry("3".to_string()));
+        calculator = stack.undo(calculator).unwrap();
+        calculator = stack.undo(calculator).unwrap();
+        assert_eq!(calculator.display(), &Display::Entry("12".to_string()));
+        calculator = stack.redo(calculator).unwrap();
+        calculator = stack.redo(calculator).unwrap();
+        calculator = stack.redo(calculator).unwrap();
+        assert_eq!(calculator.value(), Number::from_integer(15));
+    }
+}
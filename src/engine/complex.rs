@@ -0,0 +1,415 @@
+//! This module contains the [`Complex`] numbers of the complex mode. Their parts are [`Number`]s, so sums, products,
+//! quotients and integer powers of exact numbers stay exact (e.g. `(1 + 2i)^2` is exactly `-3 + 4i`), as do the
+//! square roots of negative numbers (e.g. `√-4` is `2i`). The other functions are computed with floating point
+//! values, and their results very close to the real or the imaginary axis are put on it (e.g. `e^(iπ)` is `-1`).
+//!
+//! The trigonometric functions take and return angles in the current angle unit, and their complex extensions are
+//! the usual ones: `sin(a + bi) = sin a cosh b + i cos a sinh b`, `asin z = −i ln(iz + √(1 − z²))` and so on. Real
+//! arguments in the domain of the real functions give the same results as the real functions. `exp`, `ln` and `log`
+//! are extended the same way, with the principal logarithm, whose imaginary part is in `(−π, π]` (e.g. `ln(−1)` is
+//! `iπ`).
+
+use std::f64::consts::{FRAC_PI_2, LOG10_E};
+use std::fmt;
+
+use super::{angle, AngleUnit, Error, Function, Number};
+
+/// The size of a part computed with floating point values, relative to the other part, below which it is zero.
+const NEGLIGIBLE: f64 = 1e-14;
+
+/// The largest absolute integer power computed by repeated multiplication. Larger powers are computed approximately.
+const MAX_EXACT_POWER: i64 = 64;
+
+/// A complex number `re + im·i`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Complex {
+    /// The real part.
+    pub re: Number,
+    /// The imaginary part.
+    pub im: Number,
+}
+
+impl Complex {
+    /// Creates a new complex number from its parts.
+    ///
+    /// # Arguments
+    ///
+    /// * `re` - The real part.
+    /// * `im` - The imaginary part.
+    pub fn new(re: Number, im: Number) -> Self {
+        Self { re, im }
+    }
+
+    /// Returns the imaginary unit `i`.
+    pub fn i() -> Self {
+        Self::new(Number::zero(), Number::from_integer(1))
+    }
+
+    /// Creates a new complex number from its modulus and its argument (e.g. `5∠53.13°`). Arguments that are
+    /// multiples of 30 or 45 degrees give exact parts.
+    ///
+    /// # Arguments
+    ///
+    /// * `modulus` - The absolute value of the number.
+    /// * `argument` - The angle of the number from the positive real axis.
+    /// * `unit` - The unit of the angle.
+    ///
+    /// # Errors
+    ///
+    /// The error of the underlying [`Number`] operation.
+    pub fn from_polar(modulus: &Number, argument: &Number, unit: AngleUnit) -> Result<Self, Error> {
+        Ok(Self::new(
+            modulus.mul(&angle::cos(argument, unit)?)?,
+            modulus.mul(&angle::sin(argument, unit)?)?,
+        ))
+    }
+
+    /// Creates a new complex number from floating point parts. A part negligible next to the other is zero.
+    fn from_f64(re: f64, im: f64) -> Result<Self, Error> {
+        let scale = re.abs().max(im.abs());
+        let part = |value: f64| Number::from_real(if value.abs() <= scale * NEGLIGIBLE { 0.0 } else { value });
+        Ok(Self::new(part(re)?, part(im)?))
+    }
+
+    /// Returns `true` if the imaginary part is zero.
+    pub fn is_real(&self) -> bool {
+        self.im.is_zero()
+    }
+
+    /// Returns `true` if the number is zero.
+    pub fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+
+    /// Returns the number as a real number.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedComplex`] if the imaginary part is not zero.
+    pub fn to_real(&self) -> Result<Number, Error> {
+        if self.is_real() {
+            Ok(self.re.clone())
+        }
+        else {
+            Err(Error::UnexpectedComplex)
+        }
+    }
+
+    /// Returns the sum of this number and another one.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn add(&self, other: &Complex) -> Result<Complex, Error> {
+        Ok(Self::new(self.re.add(&other.re)?, self.im.add(&other.im)?))
+    }
+
+    /// Returns the difference of this number and another one.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn sub(&self, other: &Complex) -> Result<Complex, Error> {
+        Ok(Self::new(self.re.sub(&other.re)?, self.im.sub(&other.im)?))
+    }
+
+    /// Returns the product of this number and another one.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn mul(&self, other: &Complex) -> Result<Complex, Error> {
+        let re = self.re.mul(&other.re)?.sub(&self.im.mul(&other.im)?)?;
+        let im = self.re.mul(&other.im)?.add(&self.im.mul(&other.re)?)?;
+        Ok(Self::new(re, im))
+    }
+
+    /// Returns the quotient of this number and another one.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::DivisionByZero`] if the other number is zero.
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn div(&self, other: &Complex) -> Result<Complex, Error> {
+        if other.is_real() {
+            return Ok(Self::new(self.re.div(&other.re)?, self.im.div(&other.re)?));
+        }
+        let denominator = other.re.square()?.add(&other.im.square()?)?;
+        let re = self.re.mul(&other.re)?.add(&self.im.mul(&other.im)?)?;
+        let im = self.im.mul(&other.re)?.sub(&self.re.mul(&other.im)?)?;
+        Ok(Self::new(re.div(&denominator)?, im.div(&denominator)?))
+    }
+
+    /// Returns the negation of the number.
+    pub fn neg(&self) -> Complex {
+        Self::new(self.re.neg(), self.im.neg())
+    }
+
+    /// Returns the complex conjugate `re − im·i` of the number.
+    pub fn conj(&self) -> Complex {
+        Self::new(self.re.clone(), self.im.neg())
+    }
+
+    /// Returns the reciprocal `1/z` of the number.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::DivisionByZero`] if the number is zero.
+    pub fn recip(&self) -> Result<Complex, Error> {
+        Complex::from(Number::from_integer(1)).div(self)
+    }
+
+    /// Returns the absolute value, or modulus, of the number. It is exact for exact parts whose sum of squares is a
+    /// perfect square (e.g. `|3 + 4i|` is `5`).
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn abs(&self) -> Result<Number, Error> {
+        match (self.re.is_zero(), self.im.is_zero()) {
+            (_, true) if self.re.is_negative() => Ok(self.re.neg()),
+            (_, true) => Ok(self.re.clone()),
+            (true, _) if self.im.is_negative() => Ok(self.im.neg()),
+            (true, _) => Ok(self.im.clone()),
+            _ => self.re.square()?.add(&self.im.square()?)?.sqrt(),
+        }
+    }
+
+    /// Returns the argument of the number: its angle from the positive real axis, in `(-180°, 180°]`. The argument
+    /// of zero is zero. Numbers on the axes and on the diagonals have exact arguments in degrees and gradians.
+    ///
+    /// # Arguments
+    ///
+    /// * `unit` - The unit of the returned angle.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if a part is not finite.
+    pub fn arg(&self, unit: AngleUnit) -> Result<Number, Error> {
+        let degrees = if self.is_real() {
+            Some(if self.re.is_negative() { 180 } else { 0 })
+        }
+        else if self.re.is_zero() {
+            Some(if self.im.is_negative() { -90 } else { 90 })
+        }
+        else if self.re == self.im {
+            Some(if self.re.is_negative() { -135 } else { 45 })
+        }
+        else if self.re == self.im.neg() {
+            Some(if self.re.is_negative() { 135 } else { -45 })
+        }
+        else {
+            None
+        };
+        match degrees {
+            Some(degrees) => AngleUnit::Degrees.convert(&Number::from_integer(degrees), unit),
+            None => {
+                let (re, im) = self.to_f64();
+                AngleUnit::Radians.convert(&Number::from_real(im.atan2(re))?, unit)
+            }
+        }
+    }
+
+    /// Returns the principal square root of the number, whose real part is not negative. The square roots of
+    /// negative numbers are exact if the square roots of their absolute values are.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn sqrt(&self) -> Result<Complex, Error> {
+        if self.is_real() {
+            return if self.re.is_negative() {
+                Ok(Self::new(Number::zero(), self.re.neg().sqrt()?))
+            }
+            else {
+                Ok(Self::from(self.re.sqrt()?))
+            };
+        }
+        let modulus = self.abs()?;
+        let two = Number::from_integer(2);
+        let half_root = |value: Number| {
+            let value = value.div(&two)?;
+            if value.is_negative() { Ok(Number::zero()) } else { value.sqrt() }
+        };
+        let re = half_root(modulus.add(&self.re)?)?;
+        let im = half_root(modulus.sub(&self.re)?)?;
+        Ok(Self::new(re, if self.im.is_negative() { im.neg() } else { im }))
+    }
+
+    /// Raises this number to a power. Integer powers are computed by repeated multiplication, so they are exact for
+    /// exact numbers, and other powers are the principal values `e^(w ln z)`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::DivisionByZero`] if zero is raised to a negative power.
+    /// * [`Error::InvalidInput`] if zero is raised to a power whose real part is not positive.
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn pow(&self, exponent: &Complex) -> Result<Complex, Error> {
+        if exponent.is_real() && exponent.re.is_integer() && exponent.re.to_f64().abs() <= MAX_EXACT_POWER as f64 {
+            let power = exponent.re.to_f64() as i64;
+            let mut result = Complex::from(Number::from_integer(1));
+            let mut factor = self.clone();
+            let mut remaining = power.unsigned_abs();
+            while remaining > 0 {
+                if remaining % 2 == 1 {
+                    result = result.mul(&factor)?;
+                }
+                remaining /= 2;
+                if remaining > 0 {
+                    factor = factor.mul(&factor)?;
+                }
+            }
+            return if power < 0 { result.recip() } else { Ok(result) };
+        }
+        if self.is_real() && exponent.is_real() && !self.re.is_negative() {
+            return self.re.pow(&exponent.re).map(Complex::from);
+        }
+        if self.is_zero() {
+            return match (exponent.re.is_negative(), exponent.re.is_zero()) {
+                (true, _) if exponent.is_real() => Err(Error::DivisionByZero),
+                (false, false) => Ok(Complex::from(Number::zero())),
+                _ => Err(Error::InvalidInput),
+            };
+        }
+        self.ln()?.mul(exponent)?.exp()
+    }
+
+    /// Applies a function of the keypad to the number. Real numbers in the domain of the real function give the
+    /// result of the real function, so exact values are kept (e.g. `sin(30)` in degrees is exactly `0.5`).
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function to apply.
+    /// * `angle_unit` - The unit of the angles taken and returned by the trigonometric functions.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::DivisionByZero`] if the reciprocal of zero is taken.
+    /// * [`Error::InvalidInput`] if the function is undefined at the number (e.g. `atan(i)` or `tan(90)` in degrees).
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    /// * [`Error::UnexpectedComplex`] if a number theory function is applied to a number that is not real.
+    pub fn apply_function(&self, function: Function, angle_unit: AngleUnit) -> Result<Complex, Error> {
+        if self.is_real() {
+            match function.apply(&self.re, angle_unit) {
+                Err(Error::InvalidInput)
+                    if matches!(
+                        function,
+                        Function::SquareRoot
+                            | Function::ArcSine
+                            | Function::ArcCosine
+                            | Function::NaturalLogarithm
+                            | Function::Logarithm
+                    ) => {}
+                result => return result.map(Complex::from),
+            }
+        }
+        let radians = angle_unit.convert(&Number::from_integer(1), AngleUnit::Radians)?;
+        let to_unit = || AngleUnit::Radians.convert(&Number::from_integer(1), angle_unit);
+        match function {
+            Function::Square => self.mul(self),
+            Function::SquareRoot => self.sqrt(),
+            Function::Reciprocal => self.recip(),
+            Function::Sine => self.scale(&radians)?.sin(),
+            Function::Cosine => self.scale(&radians)?.cos(),
+            Function::Tangent => {
+                let z = self.scale(&radians)?;
+                z.sin()?.div(&z.cos()?).map_err(|_| Error::InvalidInput)
+            }
+            Function::ArcSine => self.asin()?.scale(&to_unit()?),
+            Function::ArcCosine => {
+                Complex::from(Number::from_real(FRAC_PI_2)?)
+                    .sub(&self.asin()?)?
+                    .scale(&to_unit()?)
+            }
+            Function::ArcTangent => self.atan()?.scale(&to_unit()?),
+            Function::Exponential => self.exp(),
+            Function::NaturalLogarithm => self.ln(),
+            Function::Logarithm => self.ln()?.scale(&Number::from_real(LOG10_E)?),
+            Function::Factorial | Function::Totient | Function::IsPrime => Err(Error::UnexpectedComplex),
+        }
+    }
+
+    /// Returns the number multiplied by a real factor.
+    fn scale(&self, factor: &Number) -> Result<Complex, Error> {
+        Ok(Self::new(self.re.mul(factor)?, self.im.mul(factor)?))
+    }
+
+    /// Returns the parts of the number as floating point values.
+    fn to_f64(&self) -> (f64, f64) {
+        (self.re.to_f64(), self.im.to_f64())
+    }
+
+    /// Returns `e` raised to the number.
+    fn exp(&self) -> Result<Complex, Error> {
+        let (re, im) = self.to_f64();
+        let modulus = re.exp();
+        Self::from_f64(modulus * im.cos(), modulus * im.sin())
+    }
+
+    /// Returns the principal natural logarithm of the number, whose imaginary part is in `(-π, π]`.
+    fn ln(&self) -> Result<Complex, Error> {
+        if self.is_zero() {
+            return Err(Error::InvalidInput);
+        }
+        let (re, im) = self.to_f64();
+        Self::from_f64(re.hypot(im).ln(), im.atan2(re))
+    }
+
+    /// Returns the sine of the number in radians.
+    fn sin(&self) -> Result<Complex, Error> {
+        let (re, im) = self.to_f64();
+        Self::from_f64(re.sin() * im.cosh(), re.cos() * im.sinh())
+    }
+
+    /// Returns the cosine of the number in radians.
+    fn cos(&self) -> Result<Complex, Error> {
+        let (re, im) = self.to_f64();
+        Self::from_f64(re.cos() * im.cosh(), -re.sin() * im.sinh())
+    }
+
+    /// Returns the principal inverse sine `−i ln(iz + √(1 − z²))` of the number, in radians.
+    fn asin(&self) -> Result<Complex, Error> {
+        let one = Complex::from(Number::from_integer(1));
+        let root = one.sub(&self.mul(self)?)?.sqrt()?;
+        let logarithm = Complex::i().mul(self)?.add(&root)?.ln()?;
+        Complex::i().neg().mul(&logarithm)
+    }
+
+    /// Returns the principal inverse tangent `(i/2) (ln(1 − iz) − ln(1 + iz))` of the number, in radians.
+    fn atan(&self) -> Result<Complex, Error> {
+        let one = Complex::from(Number::from_integer(1));
+        let iz = Complex::i().mul(self)?;
+        let difference = one.sub(&iz)?.ln()?.sub(&one.add(&iz)?.ln()?)?;
+        Complex::new(Number::zero(), Number::from_real(0.5)?).mul(&difference)
+    }
+}
+
+impl From<Number> for Complex {
+    /// Creates a real complex number.
+    fn from(re: Number) -> Self {
+        Self::new(re, Number::zero())
+    }
+}
+
+impl fmt::Display for Complex {
+    /// Formats the number as `a + bi` (e.g. `3 - 4i`), leaving out a zero real part and a coefficient of one (e.g.
+    /// `i`). Real numbers are formatted as a [`Number`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_real() {
+            return write!(f, "{}", self.re);
+        }
+        let magnitude = if self.im.is_negative() { self.im.neg() } else { self.im.clone() };
+        let coefficient = if magnitude == Number::from_integer(1) {
+            String::new()
+        }
+        else {
+            magnitude.to_string()
+        };
+        match (self.re.is_zero(), self.im.is_negative()) {
+            (true, false) => write!(f, "{}i", coefficient),
+            (true, true) => write!(f, "-{}i", coefficient),
+            (false, false) => write!(f, "{} + {}i", self.re, coefficient),
+            (false, true) => write!(f, "{} - {}i", self.re, coefficient),
+        }
+    }
+}
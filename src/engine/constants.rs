@@ -0,0 +1,342 @@
+//! This module contains the library of mathematical and physical [`Constant`]s that can be used by name in typed
+//! expressions (e.g. `c`, `h`, `N_A` or `k_B`). The physical constants are the CODATA 2018 recommended values.
+//! Constants that are exact by the definition of the SI units are exact numbers; the others are approximate. Physical
+//! constants carry their units, so `h * c` is in `J·m`.
+
+use super::{Number, Quantity, Units};
+
+/// A named mathematical or physical constant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Constant {
+    /// The names of the constant in expressions. The first one is the main name.
+    pub names:       &'static [&'static str],
+    /// The symbol of the constant as usually written (e.g. `N_A` is written `Nₐ`).
+    pub symbol:      &'static str,
+    /// The description of the constant.
+    pub description: &'static str,
+    /// The value of the constant in decimal notation, in its unit.
+    pub value:       &'static str,
+    /// The standard uncertainty of the value, or `None` if the value is exact or a mathematical constant.
+    pub uncertainty: Option<&'static str>,
+    /// The units of the value as written in expressions (e.g. `m/s`), or an empty string if the constant is
+    /// dimensionless.
+    pub unit:        &'static str,
+    /// Whether the value is exact. The values of irrational constants are rounded and thus not exact.
+    pub exact:       bool,
+}
+
+impl Constant {
+    /// Returns the main name of the constant in expressions.
+    pub fn name(&self) -> &'static str {
+        self.names[0]
+    }
+
+    /// Returns the units of the constant.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if the units of the constant are not valid units.
+    pub fn units(&self) -> Units {
+        self.unit
+            .parse()
+            .expect("The unit of a constant needs to be valid units")
+    }
+
+    /// Returns the value of the constant with its units. Exact constants are exact numbers; others are approximate.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if the value of the constant is not a valid number.
+    pub fn quantity(&self) -> Quantity {
+        Quantity::new(self.number(), self.units())
+    }
+
+    /// Returns the value of the constant without its units.
+    fn number(&self) -> Number {
+        if self.exact {
+            self.value
+                .parse()
+                .expect("The value of a constant needs to be a valid number")
+        }
+        else {
+            Number::from_real(
+                self.value
+                    .parse()
+                    .expect("The value of a constant needs to be a valid number"),
+            )
+            .expect("The value of a constant needs to be finite")
+        }
+    }
+
+    /// Returns `true` if the constant matches a search query: if one of its names, its symbol or its description
+    /// contains the query, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The search query.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        self.names
+            .iter()
+            .chain([&self.symbol, &self.description])
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+}
+
+/// All constants, mathematical constants first.
+pub const CONSTANTS: [Constant; 22] = [
+    Constant {
+        names:       &["pi", "π"],
+        symbol:      "π",
+        description: "Pi, the ratio of the circumference of a circle to its diameter",
+        value:       "3.14159265358979323846",
+        uncertainty: None,
+        unit:        "",
+        exact:       false,
+    },
+    Constant {
+        names:       &["e"],
+        symbol:      "e",
+        description: "Euler's number, the base of the natural logarithm",
+        value:       "2.71828182845904523536",
+        uncertainty: None,
+        unit:        "",
+        exact:       false,
+    },
+    Constant {
+        names:       &["phi", "φ"],
+        symbol:      "φ",
+        description: "Golden ratio",
+        value:       "1.61803398874989484820",
+        uncertainty: None,
+        unit:        "",
+        exact:       false,
+    },
+    Constant {
+        names:       &["gamma", "γ"],
+        symbol:      "γ",
+        description: "Euler–Mascheroni constant",
+        value:       "0.57721566490153286061",
+        uncertainty: None,
+        unit:        "",
+        exact:       false,
+    },
+    Constant {
+        names:       &["c"],
+        symbol:      "c",
+        description: "Speed of light in vacuum",
+        value:       "299792458",
+        uncertainty: None,
+        unit:        "m/s",
+        exact:       true,
+    },
+    Constant {
+        names:       &["h"],
+        symbol:      "h",
+        description: "Planck constant",
+        value:       "6.62607015e-34",
+        uncertainty: None,
+        unit:        "J·s",
+        exact:       true,
+    },
+    Constant {
+        names:       &["hbar", "ħ"],
+        symbol:      "ħ",
+        description: "Reduced Planck constant",
+        value:       "1.054571817646156e-34",
+        uncertainty: None,
+        unit:        "J·s",
+        exact:       false,
+    },
+    Constant {
+        names:       &["q_e"],
+        symbol:      "e",
+        description: "Elementary charge",
+        value:       "1.602176634e-19",
+        uncertainty: None,
+        unit:        "C",
+        exact:       true,
+    },
+    Constant {
+        names:       &["k_B"],
+        symbol:      "k",
+        description: "Boltzmann constant",
+        value:       "1.380649e-23",
+        uncertainty: None,
+        unit:        "J/K",
+        exact:       true,
+    },
+    Constant {
+        names:       &["N_A"],
+        symbol:      "Nₐ",
+        description: "Avogadro constant",
+        value:       "6.02214076e23",
+        uncertainty: None,
+        unit:        "mol^-1",
+        exact:       true,
+    },
+    Constant {
+        names:       &["R"],
+        symbol:      "R",
+        description: "Molar gas constant",
+        value:       "8.31446261815324",
+        uncertainty: None,
+        unit:        "J/(mol·K)",
+        exact:       false,
+    },
+    Constant {
+        names:       &["F"],
+        symbol:      "F",
+        description: "Faraday constant",
+        value:       "96485.33212331001",
+        uncertainty: None,
+        unit:        "C/mol",
+        exact:       false,
+    },
+    Constant {
+        names:       &["sigma", "σ"],
+        symbol:      "σ",
+        description: "Stefan–Boltzmann constant",
+        value:       "5.670374419e-8",
+        uncertainty: None,
+        unit:        "W/(m^2·K^4)",
+        exact:       false,
+    },
+    Constant {
+        names:       &["g_n"],
+        symbol:      "gₙ",
+        description: "Standard acceleration of gravity",
+        value:       "9.80665",
+        uncertainty: None,
+        unit:        "m/s^2",
+        exact:       true,
+    },
+    Constant {
+        names:       &["G"],
+        symbol:      "G",
+        description: "Newtonian constant of gravitation",
+        value:       "6.67430e-11",
+        uncertainty: Some("0.00015e-11"),
+        unit:        "m^3/(kg·s^2)",
+        exact:       false,
+    },
+    Constant {
+        names:       &["m_e"],
+        symbol:      "mₑ",
+        description: "Electron mass",
+        value:       "9.1093837015e-31",
+        uncertainty: Some("0.0000000028e-31"),
+        unit:        "kg",
+        exact:       false,
+    },
+    Constant {
+        names:       &["m_p"],
+        symbol:      "mₚ",
+        description: "Proton mass",
+        value:       "1.67262192369e-27",
+        uncertainty: Some("0.00000000051e-27"),
+        unit:        "kg",
+        exact:       false,
+    },
+    Constant {
+        names:       &["m_n"],
+        symbol:      "mₙ",
+        description: "Neutron mass",
+        value:       "1.67492749804e-27",
+        uncertainty: Some("0.00000000095e-27"),
+        unit:        "kg",
+        exact:       false,
+    },
+    Constant {
+        names:       &["u"],
+        symbol:      "u",
+        description: "Atomic mass constant",
+        value:       "1.66053906660e-27",
+        uncertainty: Some("0.00000000050e-27"),
+        unit:        "kg",
+        exact:       false,
+    },
+    Constant {
+        names:       &["alpha", "α"],
+        symbol:      "α",
+        description: "Fine-structure constant",
+        value:       "7.2973525693e-3",
+        uncertainty: Some("0.0000000011e-3"),
+        unit:        "",
+        exact:       false,
+    },
+    Constant {
+        names:       &["epsilon_0", "ε_0"],
+        symbol:      "ε₀",
+        description: "Vacuum electric permittivity",
+        value:       "8.8541878128e-12",
+        uncertainty: Some("0.0000000013e-12"),
+        unit:        "F/m",
+        exact:       false,
+    },
+    Constant {
+        names:       &["mu_0", "μ_0"],
+        symbol:      "μ₀",
+        description: "Vacuum magnetic permeability",
+        value:       "1.25663706212e-6",
+        uncertainty: Some("0.00000000019e-6"),
+        unit:        "N/A^2",
+        exact:       false,
+    },
+];
+
+/// Returns the constant with the given name in expressions, or `None` if there is no such constant.
+///
+/// # Arguments
+///
+/// * `name` - The name of the constant (e.g. `N_A`).
+pub fn find(name: &str) -> Option<&'static Constant> {
+    CONSTANTS.iter().find(|constant| constant.names.contains(&name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn values_and_units() {
+        for constant in &CONSTANTS {
+            let quantity = constant.quantity();
+            assert_eq!(quantity.value.is_exact(), constant.exact, "{}", constant.name());
+            assert_eq!(constant.units().to_string().is_empty(), constant.unit.is_empty(), "{}", constant.name());
+        }
+    }
+
+    #[test]
+    fn names_are_unique() {
+        let mut names = BTreeSet::new();
+        for name in CONSTANTS.iter().flat_map(|constant| constant.names) {
+            assert!(names.insert(name), "{} is used twice", name);
+        }
+    }
+
+    #[test]
+    fn find_by_name() {
+        assert_eq!(find("π").map(Constant::name), Some("pi"));
+        assert_eq!(find("N_A").map(|constant| constant.symbol), Some("Nₐ"));
+        assert_eq!(find("c").map(|constant| constant.quantity().value), Some("299792458".parse().unwrap()));
+        assert_eq!(find("speed"), None);
+    }
+
+    #[test]
+    fn search() {
+        let names = |query: &str| {
+            CONSTANTS
+                .iter()
+                .filter(|constant| constant.matches(query))
+                .map(Constant::name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("Speed of LIGHT"), ["c"]);
+        assert_eq!(names(" φ "), ["phi"]);
+        assert!(names("boltzmann").contains(&"k_B"));
+        assert!(names("xyz").is_empty());
+    }
+}
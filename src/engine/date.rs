@@ -0,0 +1,356 @@
+//! This module contains the calendar arithmetic of the date page: the [`Date`] type of the proleptic Gregorian
+//! calendar, the [`Period`] between two dates in years, months and days, and the counting of business days (Monday to
+//! Friday, without public holidays). Times of day and time zones are handled by the page itself.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::Error;
+
+/// The earliest year of a [`Date`].
+const MIN_YEAR: i32 = 1;
+
+/// The latest year of a [`Date`].
+const MAX_YEAR: i32 = 9999;
+
+/// A day of the proleptic Gregorian calendar, between the years 1 and 9999.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    year:  i32,
+    month: u32,
+    day:   u32,
+}
+
+/// The time between two dates in whole years, months and days (e.g. 1 year, 2 months and 3 days).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Period {
+    /// Whether the second date is before the first one.
+    pub negative: bool,
+    /// The number of whole years.
+    pub years:    i32,
+    /// The number of whole months after the years, from 0 to 11.
+    pub months:   i32,
+    /// The number of days after the months.
+    pub days:     i64,
+}
+
+impl Date {
+    /// Creates a new date.
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - The year, from 1 to 9999.
+    /// * `month` - The month, from 1 to 12.
+    /// * `day` - The day of the month, from 1 to the number of days in the month.
+    ///
+    /// # Returns
+    ///
+    /// The date, or `None` if it does not exist.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        let exists = (MIN_YEAR..=MAX_YEAR).contains(&year)
+            && (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day);
+        exists.then_some(Self { year, month, day })
+    }
+
+    /// Returns the year of the date.
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month of the date, from 1 to 12.
+    pub fn month(self) -> u32 {
+        self.month
+    }
+
+    /// Returns the day of the month of the date, from 1.
+    pub fn day(self) -> u32 {
+        self.day
+    }
+
+    /// Returns the day of the week of the date, from 1 for Monday to 7 for Sunday.
+    pub fn weekday(self) -> u32 {
+        // 1970-01-01 is a Thursday.
+        (self.to_days() + 3).rem_euclid(7) as u32 + 1
+    }
+
+    /// Returns `true` if the date is a business day, from Monday to Friday.
+    pub fn is_business_day(self) -> bool {
+        self.weekday() <= 5
+    }
+
+    /// Returns the date a number of days later, or earlier for negative numbers.
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - The number of days to add.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the date would be outside of the years 1 to 9999.
+    pub fn add_days(self, days: i64) -> Result<Date, Error> {
+        self.to_days()
+            .checked_add(days)
+            .and_then(Date::from_days)
+            .ok_or(Error::Overflow)
+    }
+
+    /// Returns the date a number of months later, or earlier for negative numbers. The day is clamped to the last day
+    /// of the month, so one month after January 31 is the last day of February.
+    ///
+    /// # Arguments
+    ///
+    /// * `months` - The number of months to add.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the date would be outside of the years 1 to 9999.
+    pub fn add_months(self, months: i64) -> Result<Date, Error> {
+        let index = i64::from(self.year) * 12 + i64::from(self.month) - 1 + months;
+        let year = i32::try_from(index.div_euclid(12)).map_err(|_| Error::Overflow)?;
+        let month = index.rem_euclid(12) as u32 + 1;
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return Err(Error::Overflow);
+        }
+        Ok(Date {
+            year,
+            month,
+            day: self.day.min(days_in_month(year, month)),
+        })
+    }
+
+    /// Returns the date a number of business days later, or earlier for negative numbers. Adding zero business days
+    /// to a weekend day gives the same day.
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - The number of business days to add.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the date would be outside of the years 1 to 9999.
+    pub fn add_business_days(self, days: i64) -> Result<Date, Error> {
+        let step = days.signum();
+        let weeks = (days.abs() - 1).max(0) / 5;
+        let mut date = self.add_days(step * weeks * 7)?;
+        let mut remaining = days.abs() - weeks * 5;
+        while remaining > 0 {
+            date = date.add_days(step)?;
+            if date.is_business_day() {
+                remaining -= 1;
+            }
+        }
+        Ok(date)
+    }
+
+    /// Returns the number of days from this date to another, negative if the other date is earlier.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The date to count to.
+    pub fn days_until(self, other: Date) -> i64 {
+        other.to_days() - self.to_days()
+    }
+
+    /// Returns the number of business days from this date to another: the business days after this date up to and
+    /// including the other date, negative if the other date is earlier. So from a Friday to the next Monday is one
+    /// business day.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The date to count to.
+    pub fn business_days_until(self, other: Date) -> i64 {
+        if other < self {
+            return -other.business_days_until(self);
+        }
+        // Business days in the days `1..=days` after a Monday-based weekday `start` (0 for Monday).
+        let count = |start: i64, days: i64| {
+            let (weeks, rest) = (days / 7, days % 7);
+            weeks * 5 + (1..=rest).filter(|offset| (start + offset) % 7 < 5).count() as i64
+        };
+        count(i64::from(self.weekday()) - 1, self.days_until(other))
+    }
+
+    /// Returns the time from this date to another in years, months and days. The years and months are counted first,
+    /// so from January 31 to March 1 is 1 month and 1 day, counting from the last day of February.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The date to count to.
+    pub fn period_until(self, other: Date) -> Period {
+        if other < self {
+            return Period {
+                negative: true,
+                ..other.period_until(self)
+            };
+        }
+        let mut months = i64::from(other.year - self.year) * 12 + i64::from(other.month) - i64::from(self.month);
+        if other.day < self.day {
+            months -= 1;
+        }
+        let start = self
+            .add_months(months)
+            .expect("A date between two dates needs to be valid");
+        Period {
+            negative: false,
+            years:    (months / 12) as i32,
+            months:   (months % 12) as i32,
+            days:     start.days_until(other),
+        }
+    }
+
+    /// Returns the number of days since 1970-01-01.
+    fn to_days(self) -> i64 {
+        // The algorithm of Howard Hinnant's `days_from_civil`, with years starting in March.
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = i64::from((self.month + 9) % 12);
+        let day_of_year = (153 * month + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// Returns the date a number of days after 1970-01-01, or `None` if it is outside of the years 1 to 9999.
+    fn from_days(days: i64) -> Option<Date> {
+        // The algorithm of Howard Hinnant's `civil_from_days`.
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+        let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Date::new(i32::try_from(year).ok()?, month, day)
+    }
+}
+
+impl fmt::Display for Date {
+    /// Formats the date in ISO 8601 notation (e.g. `2024-03-10`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = Error;
+
+    /// Parses a date in ISO 8601 notation (e.g. `2024-03-10`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, '-').map(|part| {
+            if !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) {
+                part.parse::<u32>().map_err(|_| Error::InvalidInput)
+            }
+            else {
+                Err(Error::InvalidInput)
+            }
+        });
+        let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(Error::InvalidInput);
+        };
+        let year = i32::try_from(year?).map_err(|_| Error::InvalidInput)?;
+        Date::new(year, month?, day?).ok_or(Error::InvalidInput)
+    }
+}
+
+/// Returns `true` if the year is a leap year.
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns the number of days in a month of a year.
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses a date in ISO 8601 notation.
+    fn date(text: &str) -> Date {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_format() {
+        assert_eq!(date("2024-03-10").to_string(), "2024-03-10");
+        assert_eq!(date("1-1-1").to_string(), "0001-01-01");
+        assert_eq!("2023-02-29".parse::<Date>(), Err(Error::InvalidInput));
+        assert_eq!("2024-3".parse::<Date>(), Err(Error::InvalidInput));
+        assert_eq!("2024-+3-10".parse::<Date>(), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(date("1970-01-01").weekday(), 4);
+        assert_eq!(date("2024-03-08").weekday(), 5);
+        assert_eq!(date("2024-03-10").weekday(), 7);
+        assert!(!date("2024-03-09").is_business_day());
+        assert_eq!(date("2000-02-28").add_days(1), Ok(date("2000-02-29")));
+        assert_eq!(date("1900-02-28").add_days(1), Ok(date("1900-03-01")));
+        assert_eq!(date("9999-12-31").add_days(1), Err(Error::Overflow));
+    }
+
+    #[test]
+    fn add_business_days_across_weekends() {
+        assert_eq!(date("2024-03-08").add_business_days(1), Ok(date("2024-03-11")));
+        assert_eq!(date("2024-03-08").add_business_days(5), Ok(date("2024-03-15")));
+        assert_eq!(date("2024-03-08").add_business_days(6), Ok(date("2024-03-18")));
+        assert_eq!(date("2024-03-09").add_business_days(0), Ok(date("2024-03-09")));
+        assert_eq!(date("2024-03-09").add_business_days(1), Ok(date("2024-03-11")));
+        assert_eq!(date("2024-03-11").add_business_days(-1), Ok(date("2024-03-08")));
+        assert_eq!(date("2024-03-10").add_business_days(-6), Ok(date("2024-03-01")));
+    }
+
+    #[test]
+    fn business_days_until_across_weekends() {
+        assert_eq!(date("2024-03-08").business_days_until(date("2024-03-11")), 1);
+        assert_eq!(date("2024-03-11").business_days_until(date("2024-03-08")), -1);
+        assert_eq!(date("2024-03-09").business_days_until(date("2024-03-10")), 0);
+        assert_eq!(date("2024-03-04").business_days_until(date("2024-03-18")), 10);
+        let start = date("2024-03-06");
+        for days in -30..=30 {
+            let end = start.add_business_days(days).unwrap();
+            assert_eq!(start.business_days_until(end), days);
+        }
+    }
+
+    #[test]
+    fn add_months_clamps_the_day() {
+        assert_eq!(date("2024-01-31").add_months(1), Ok(date("2024-02-29")));
+        assert_eq!(date("2023-01-31").add_months(1), Ok(date("2023-02-28")));
+        assert_eq!(date("2024-03-31").add_months(-1), Ok(date("2024-02-29")));
+        assert_eq!(date("2024-05-15").add_months(-17), Ok(date("2022-12-15")));
+        assert_eq!(date("9999-12-01").add_months(1), Err(Error::Overflow));
+    }
+
+    #[test]
+    fn period_until_clamps_the_month() {
+        let period = |years, months, days| Period {
+            negative: false,
+            years,
+            months,
+            days,
+        };
+        assert_eq!(date("2024-01-31").period_until(date("2024-03-01")), period(0, 1, 1));
+        assert_eq!(date("2023-01-31").period_until(date("2023-03-01")), period(0, 1, 1));
+        assert_eq!(date("2020-02-29").period_until(date("2021-03-01")), period(1, 0, 1));
+        assert_eq!(date("2022-06-15").period_until(date("2024-06-15")), period(2, 0, 0));
+        assert_eq!(
+            date("2024-03-01").period_until(date("2024-01-31")),
+            Period {
+                negative: true,
+                ..period(0, 1, 1)
+            }
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! This module contains the [`Error`] type returned by the calculation engine.
+
+use std::fmt;
+
+/// An error that occurred while performing a calculation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A division by zero was attempted.
+    DivisionByZero,
+    /// The input of an operation is outside of its domain (e.g. the square root of a negative number).
+    InvalidInput,
+    /// The result of an operation is too large to be represented.
+    Overflow,
+    /// A typed expression is malformed. Holds a description of the problem.
+    Syntax(String),
+    /// A typed expression refers to a variable or function that does not exist.
+    UnknownName(String),
+    /// A typed expression refers to a previous result (e.g. `ans3`) that is not in the history.
+    MissingResult(String),
+    /// A typed expression assigns to or defines a name that is reserved (e.g. `sin` or `ans`).
+    ReservedName(String),
+    /// A user-defined function called itself too many times (e.g. `f(x) = f(x) + 1`).
+    RecursionLimit(String),
+    /// Quantities of different dimensions were added, subtracted or converted (e.g. `kg + m`). Holds both units, with
+    /// `1` for plain numbers.
+    IncompatibleUnits(String, String),
+    /// A quantity with units was used where a plain number is expected (e.g. `sin(3 m)`). Holds the units.
+    UnexpectedUnits(String),
+    /// An equation solved in an expression has no real root (e.g. `solve(x^2 + 1 = 0, x)`), or a matrix has complex
+    /// eigenvalues.
+    NoSolution,
+    /// Matrices of incompatible sizes were combined, or a matrix was malformed. Holds a description of the problem.
+    MatrixSize(String),
+    /// A matrix was used where a number is expected (e.g. `sin([1, 2])` or `x = [1, 2]`).
+    UnexpectedMatrix,
+    /// A matrix without an inverse was inverted, or a linear system without a unique solution was solved.
+    SingularMatrix,
+    /// A complex number was used where a real number is expected (e.g. `x = 2i` or `3i m`).
+    UnexpectedComplex,
+    /// A number with an uncertainty was used where an exact number is expected (e.g. `x = 12.3 ± 0.2` or
+    /// `fact(5 ± 1)`).
+    UnexpectedUncertainty,
+    /// An interval was used where a number is expected (e.g. `x = [1, 2]` in interval mode or `fact([4, 5])`).
+    UnexpectedInterval,
+    /// The result of an interval operation is unbounded (e.g. `1 / [−1, 2]` or `tan([80, 100])` in degrees).
+    UnboundedInterval,
+    /// A number could not be factored into primes because it has several large prime factors.
+    FactorizationLimit,
+    /// A modular inverse was requested for a number that is not coprime with the modulus (e.g. `modinv(4, 6)`).
+    NoInverse,
+    /// A function was called with the wrong number of arguments.
+    ArgumentCount {
+        /// The name of the function.
+        name:     String,
+        /// The number of arguments the function takes.
+        expected: usize,
+        /// The number of arguments the function was called with.
+        found:    usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DivisionByZero => write!(f, "Cannot divide by zero"),
+            Error::InvalidInput => write!(f, "Invalid input"),
+            Error::Overflow => write!(f, "Overflow"),
+            Error::Syntax(message) => write!(f, "{}", message),
+            Error::UnknownName(name) => write!(f, "Unknown name ‘{}’", name),
+            Error::MissingResult(name) => write!(f, "No result for ‘{}’ in the history", name),
+            Error::ReservedName(name) => write!(f, "‘{}’ is a reserved name", name),
+            Error::RecursionLimit(name) => write!(f, "Too many nested calls of ‘{}’", name),
+            Error::IncompatibleUnits(lhs, rhs) => write!(f, "Incompatible units ‘{}’ and ‘{}’", lhs, rhs),
+            Error::UnexpectedUnits(units) => write!(f, "Expected a number without units, not ‘{}’", units),
+            Error::NoSolution => write!(f, "No real solution"),
+            Error::MatrixSize(message) => write!(f, "{}", message),
+            Error::UnexpectedMatrix => write!(f, "Expected a number, not a matrix"),
+            Error::SingularMatrix => write!(f, "The matrix is singular"),
+            Error::UnexpectedComplex => write!(f, "Expected a real number, not a complex one"),
+            Error::UnexpectedUncertainty => write!(f, "Expected a number without uncertainty"),
+            Error::UnexpectedInterval => write!(f, "Expected a number, not an interval"),
+            Error::UnboundedInterval => write!(f, "The interval result is unbounded"),
+            Error::FactorizationLimit => write!(f, "The number has a prime factor too large to find"),
+            Error::NoInverse => write!(f, "The number has no inverse for this modulus"),
+            Error::ArgumentCount {
+                name,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "{} takes {} argument{}, not {}",
+                    name,
+                    expected,
+                    if *expected == 1 { "" } else { "s" },
+                    found
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
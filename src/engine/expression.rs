@@ -0,0 +1,2077 @@
+//! This module contains the parser and evaluator of typed expressions (e.g. `price * (1 + rate)`) and of variable
+//! assignments (e.g. `rate = 0.0725`). Expressions use `.` as decimal point, `+ - * /` or `+ − × ÷` as operators, `^`
+//! or `**` for powers, and the functions of the keypad by their short names (e.g. `sqrt(2)` or `sin(30)`). `exp` is
+//! the exponential, `ln` the natural logarithm and `log` the decimal logarithm.
+//!
+//! `ans` refers to the previous result, and `ans2`, `ans3` and so on to the results before it. The [`CONSTANTS`]
+//! (e.g. `pi`, `c` or `N_A`) can be used by name, unless a variable with the same name shadows them.
+//!
+//! A number or a parenthesized expression may be followed by units (e.g. `3 ft`, `9.81 m/s^2` or `(1 + 2) kg`), and
+//! `to` converts the value of an expression to other units (e.g. `3 ft + 20 cm to m`). After a quantity, `*` and `/`
+//! continue its units only when a unit name follows them, so `2 m * x` multiplies by the variable `x` while `2 m / s`
+//! is a speed. Names that are not variables or constants refer to one of their unit (e.g. `kg + m` is an error).
+//!
+//! Durations and times of day are written `h:mm` or `h:mm:ss` (e.g. `1:45:30 + 2:20` is `4:05:30`). They are
+//! quantities in the `hms` unit, so they can be converted to other units of time (e.g. `2:20 to min`) and back
+//! (e.g. `150 min to hms`). A time of day is the duration since midnight.
+//!
+//! Numbers are angles in the current angle unit. An angle in degrees is written with `°` after a number, with
+//! optional minutes and seconds as on the keypad (e.g. `30°`, `12°30'15"` or `12° 30′`), and is converted to the
+//! current angle unit (e.g. `sin(30°)` is `0.5` in any angle unit, and `12°30'` is `12.5` in degrees). `to deg`,
+//! `to rad` and `to grad` convert an angle in the current angle unit to another unit (e.g. `30° to rad` is
+//! `0.523599…`).
+//!
+//! A number followed by `%` is a percentage (e.g. `10 %` is `0.1`), except directly on the right of `+` or `−`, where
+//! it is a percentage of the left operand (e.g. `200 + 10 %` is `220`). `pctchange(old, new)`, `markup(cost, price)`,
+//! `margin(cost, price)` and `pctof(x, y)` return percentages; their exact rules are listed in the `percent` module.
+//!
+//! `solve(lhs = rhs, x)` is the smallest real root of an equation in `x` (e.g. `solve(x^2 - 2 = 0, x)` is
+//! `−1.41421…`), where `= rhs` may be left out for `= 0`. Polynomials are solved exactly; the real roots of other
+//! equations are searched between −100 and 100, or between the two numbers given after the variable (e.g.
+//! `solve(cos(x) = x, x, 0, 1)`). The rules are detailed in the `solver` module, and the solver page lists all roots.
+//!
+//! Matrices are written row by row in square brackets (e.g. `[[1, 2], [3, 4]]`), and vectors as a single list, which
+//! is a column (e.g. `[1, 2, 3]`). Matrices of the same size are added and subtracted, matrices are multiplied with
+//! `*` and by numbers, and square matrices are raised to integer powers (e.g. `[[1, 2], [3, 4]]^-1`). The matrix
+//! functions are `det`, `inv`, `transpose`, `rank`, `eig` (the real eigenvalues, as a column), `linsolve(A, b)` (the
+//! solution of `A x = b`), `dot` and `cross`. Variables, previous results and the arguments of user-defined
+//! functions are numbers, so only a whole expression can be a matrix.
+//!
+//! Complex numbers are written with `i` directly after a number or on its own (e.g. `3 + 4i` or `2 * i`), unless a
+//! variable named `i` shadows it, or in polar form (e.g. `5∠53.13°`), where the angle is in the current angle unit
+//! unless it is followed by `°`. The functions and powers take complex numbers, and `re`, `im`, `conj`, `arg` and `abs`
+//! return their parts, conjugate, argument and modulus. In complex mode, functions outside of their real domain return
+//! complex results rather than an error (e.g. `sqrt(-4)` is `2i`, `ln(-1)` is `πi` and `(-8)^(1/3)` is
+//! `1 + 1.73205…i`). Previous results may be complex, but variables, the arguments of user-defined functions and the
+//! entries of matrices are real numbers.
+//!
+//! The number theory functions take integers and return exact integers: `fact` (also written `n!`), `nCr(n, r)`,
+//! `nPr(n, r)`, `gcd`, `lcm`, `modpow(base, exponent, modulus)`, `modinv(x, modulus)`, `totient` and `isprime`,
+//! which returns `1` or `0`. `factor(n)` is the prime factorization of an integer (e.g. `factor(120)` is
+//! `2^3 × 3 × 5`), which is shown as such and is an ordinary number when it is used in a larger expression.
+//!
+//! A number followed by `±` or `+/-` and its standard uncertainty is a measurement (e.g. `12.3 ± 0.2`). Uncertainties
+//! are propagated to first order through arithmetic, powers and the functions of the keypad, as detailed in the
+//! `uncertain` module (e.g. `(12.3 ± 0.2) * 2` is `24.6 ± 0.4`). Such numbers have no units, and variables and the
+//! arguments of other functions are exact numbers.
+//!
+//! In interval mode, a list of two numbers in square brackets is the interval between them rather than a vector
+//! (e.g. `[1.2, 1.5]`). Intervals are combined with arithmetic, powers and the functions of the keypad so that the
+//! result contains every possible value, with approximate bounds rounded outward, as detailed in the `interval`
+//! module (e.g. `[1.2, 1.5] * 2 - [0.1, 0.2]` is `[2.2, 2.9]`). Like numbers with uncertainties, intervals have no
+//! units, and variables and the arguments of other functions are numbers.
+//!
+//! Functions can be defined by the user (e.g. `f(x, y) = sqrt(x^2 + y^2)`) and called like the built-in ones. A
+//! definition is checked before it is stored: its parameters must be distinct, every name in its body must refer to a
+//! parameter, a variable, a constant or a function, and every function must be called with the right number of
+//! arguments. Since expressions have no conditionals, a function calling itself never terminates; such calls are
+//! stopped after [`MAX_CALL_DEPTH`] nested calls.
+//!
+//! [`CONSTANTS`]: super::CONSTANTS
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::complex::Complex;
+use super::constants;
+use super::interval::Interval;
+use super::matrix::Matrix;
+use super::number_theory::{self, Factorization};
+use super::percent;
+use super::solver::{Polynomial, Solution, MAX_DEGREE};
+use super::uncertain::Uncertain;
+use super::units::{self, Quantity, Units};
+use super::{AngleUnit, Error, Function, Number, Operator};
+
+/// The functions that can be called in expressions, by name.
+const FUNCTIONS: [(&str, Function); 15] = [
+    ("sqr", Function::Square),
+    ("sqrt", Function::SquareRoot),
+    ("recip", Function::Reciprocal),
+    ("sin", Function::Sine),
+    ("cos", Function::Cosine),
+    ("tan", Function::Tangent),
+    ("asin", Function::ArcSine),
+    ("acos", Function::ArcCosine),
+    ("atan", Function::ArcTangent),
+    ("exp", Function::Exponential),
+    ("ln", Function::NaturalLogarithm),
+    ("log", Function::Logarithm),
+    ("fact", Function::Factorial),
+    ("totient", Function::Totient),
+    ("isprime", Function::IsPrime),
+];
+
+/// A function of two numbers returning a percentage.
+type PercentageFunction = fn(&Number, &Number) -> Result<Number, Error>;
+
+/// The percentage functions that can be called in expressions, by name. They take two plain numbers.
+const PERCENTAGE_FUNCTIONS: [(&str, PercentageFunction); 4] = [
+    ("pctchange", percent::percent_change),
+    ("markup", percent::markup),
+    ("margin", percent::margin),
+    ("pctof", percent::percent_of),
+];
+
+/// A function of integers returning an integer.
+type IntegerFunction = fn(&[Number]) -> Result<Number, Error>;
+
+/// The number theory functions of several integers that can be called in expressions, by name, with the number of
+/// arguments they take.
+const INTEGER_FUNCTIONS: [(&str, usize, IntegerFunction); 6] = [
+    ("gcd", 2, |arguments| number_theory::gcd(&arguments[0], &arguments[1])),
+    ("lcm", 2, |arguments| number_theory::lcm(&arguments[0], &arguments[1])),
+    ("modpow", 3, |arguments| {
+        number_theory::mod_pow(&arguments[0], &arguments[1], &arguments[2])
+    }),
+    ("modinv", 2, |arguments| number_theory::mod_inverse(&arguments[0], &arguments[1])),
+    ("nCr", 2, |arguments| number_theory::combinations(&arguments[0], &arguments[1])),
+    ("nPr", 2, |arguments| number_theory::permutations(&arguments[0], &arguments[1])),
+];
+
+/// A function of a complex number, in the given angle unit.
+type ComplexFunction = fn(&Complex, AngleUnit) -> Result<Value, Error>;
+
+/// The functions of complex numbers that can be called in expressions, by name. They take one plain number.
+const COMPLEX_FUNCTIONS: [(&str, ComplexFunction); 5] = [
+    ("re", |z, _| Ok(Value::from(z.re.clone()))),
+    ("im", |z, _| Ok(Value::from(z.im.clone()))),
+    ("conj", |z, _| Ok(Value::from(z.conj()))),
+    ("arg", |z, angle_unit| z.arg(angle_unit).map(Value::from)),
+    ("abs", |z, _| z.abs().map(Value::from)),
+];
+
+/// A function of matrices, with plain numbers taken as matrices of a single entry.
+type MatrixFunction = fn(&[Matrix]) -> Result<Value, Error>;
+
+/// The matrix functions that can be called in expressions, by name, with the number of arguments they take.
+const MATRIX_FUNCTIONS: [(&str, usize, MatrixFunction); 8] = [
+    ("det", 1, |arguments| arguments[0].determinant().map(Value::from)),
+    ("inv", 1, |arguments| arguments[0].inverse().map(Value::Matrix)),
+    ("transpose", 1, |arguments| Ok(Value::Matrix(arguments[0].transpose()))),
+    ("rank", 1, |arguments| {
+        Ok(Value::from(Number::from_integer(arguments[0].rank()? as i64)))
+    }),
+    ("eig", 1, eigenvalues),
+    ("linsolve", 2, |arguments| arguments[0].solve(&arguments[1]).map(Value::Matrix)),
+    ("dot", 2, |arguments| arguments[0].dot(&arguments[1]).map(Value::from)),
+    ("cross", 2, |arguments| arguments[0].cross(&arguments[1]).map(Value::Matrix)),
+];
+
+/// The name referring to the previous result. Earlier results are referred to with a suffix (e.g. `ans2`).
+const ANSWER: &str = "ans";
+
+/// The name of the imaginary unit, unless a variable with the same name shadows it.
+const IMAGINARY_UNIT: &str = "i";
+
+/// The keyword converting the value of an expression to other units (e.g. `3 ft to m`).
+const CONVERSION: &str = "to";
+
+/// The name of the function returning the prime factorization of an integer (e.g. `factor(120)`).
+const FACTOR: &str = "factor";
+
+/// The name of the function solving an equation (e.g. `solve(x^2 - 2 = 0, x)`).
+const SOLVE: &str = "solve";
+
+/// The largest number of nested calls of user-defined functions.
+const MAX_CALL_DEPTH: usize = 64;
+
+/// A token of an expression.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(Number),
+    Imaginary(Number),
+    Clock(Number),
+    Identifier(String),
+    Operator(Operator),
+    Power,
+    Percent,
+    Factorial,
+    LeftParen,
+    RightParen,
+    Comma,
+    Equals,
+    LeftBracket,
+    RightBracket,
+    Angle,
+    Degree,
+    PlusMinus,
+}
+
+impl fmt::Display for Token {
+    /// Formats the token as written in expressions, for error messages.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "{}", number),
+            Token::Imaginary(number) => write!(f, "{}i", number),
+            Token::Clock(seconds) => write!(f, "{} hms", seconds),
+            Token::Identifier(name) => write!(f, "{}", name),
+            Token::Operator(operator) => write!(f, "{}", operator.symbol()),
+            Token::Power => write!(f, "^"),
+            Token::Percent => write!(f, "%"),
+            Token::Factorial => write!(f, "!"),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+            Token::Equals => write!(f, "="),
+            Token::LeftBracket => write!(f, "["),
+            Token::RightBracket => write!(f, "]"),
+            Token::Angle => write!(f, "∠"),
+            Token::Degree => write!(f, "°"),
+            Token::PlusMinus => write!(f, "±"),
+        }
+    }
+}
+
+/// A parsed expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    /// A number literal.
+    Number(Number),
+    /// An imaginary number literal (e.g. `4i`): its imaginary part.
+    Imaginary(Number),
+    /// A variable, or a previous result (e.g. `ans2`).
+    Variable(String),
+    /// The negation of an expression.
+    Negate(Box<Expression>),
+    /// A percentage (e.g. `10 %`).
+    Percent(Box<Expression>),
+    /// A binary operation.
+    Binary(Operator, Box<Expression>, Box<Expression>),
+    /// A power: the base and the exponent.
+    Power(Box<Expression>, Box<Expression>),
+    /// A function call: the name of the function and its arguments.
+    Call(String, Vec<Expression>),
+    /// An expression followed by units (e.g. `3 ft`).
+    WithUnits(Box<Expression>, Units),
+    /// The conversion of an expression to other units (e.g. `3 ft to m`).
+    Convert(Box<Expression>, Units),
+    /// An angle in another unit than the current angle unit (e.g. `30°`): its value and its unit.
+    Angle(Box<Expression>, AngleUnit),
+    /// The conversion of an angle in the current angle unit to another angle unit (e.g. `30° to rad`).
+    ConvertAngle(Box<Expression>, AngleUnit),
+    /// The smallest real root of an equation (e.g. `solve(x^2 - 2 = 0, x)`): the difference of its two sides, the
+    /// variable solved for, and the bracket searched if the equation is not a polynomial.
+    Solve(Box<Expression>, String, Option<(Box<Expression>, Box<Expression>)>),
+    /// A matrix literal: its rows of entries.
+    Matrix(Vec<Vec<Expression>>),
+    /// A complex number in polar form (e.g. `5∠53.13°`): its modulus, its argument, and the unit of the argument if
+    /// it is not the current angle unit.
+    Polar(Box<Expression>, Box<Expression>, Option<AngleUnit>),
+    /// A number with an uncertainty (e.g. `12.3 ± 0.2`): its value and its standard uncertainty.
+    Uncertain(Box<Expression>, Box<Expression>),
+}
+
+/// The value of an expression: a quantity, a complex number, a matrix, a prime factorization, a number with an
+/// uncertainty or an interval.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// A real number, with units or not.
+    Quantity(Quantity),
+    /// A complex number that is not real.
+    Complex(Complex),
+    /// A matrix of plain numbers.
+    Matrix(Matrix),
+    /// The prime factorization of an integer, returned by `factor`. It is the integer itself when it is used in a
+    /// larger expression.
+    Factorization(Factorization),
+    /// A plain number with a nonzero uncertainty.
+    Uncertain(Uncertain),
+    /// An interval of plain numbers holding more than one number.
+    Interval(Interval),
+}
+
+impl Value {
+    /// Returns the quantity of the value.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedComplex`] if the value is a complex number.
+    /// * [`Error::UnexpectedMatrix`] if the value is a matrix.
+    /// * [`Error::UnexpectedUncertainty`] if the value has an uncertainty.
+    /// * [`Error::UnexpectedInterval`] if the value is an interval.
+    pub fn into_quantity(self) -> Result<Quantity, Error> {
+        match self {
+            Value::Quantity(quantity) => Ok(quantity),
+            Value::Complex(_) => Err(Error::UnexpectedComplex),
+            Value::Matrix(_) => Err(Error::UnexpectedMatrix),
+            Value::Factorization(factorization) => Ok(Quantity::from(factorization.value())),
+            Value::Uncertain(_) => Err(Error::UnexpectedUncertainty),
+            Value::Interval(_) => Err(Error::UnexpectedInterval),
+        }
+    }
+
+    /// Returns the value as a complex number.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedUnits`] if the value is a quantity with units.
+    /// * [`Error::UnexpectedMatrix`] if the value is a matrix.
+    /// * [`Error::UnexpectedUncertainty`] if the value has an uncertainty.
+    /// * [`Error::UnexpectedInterval`] if the value is an interval.
+    pub fn into_complex(self) -> Result<Complex, Error> {
+        match self {
+            Value::Quantity(quantity) => quantity.into_number().map(Complex::from),
+            Value::Complex(complex) => Ok(complex),
+            Value::Matrix(_) => Err(Error::UnexpectedMatrix),
+            Value::Factorization(factorization) => Ok(Complex::from(factorization.value())),
+            Value::Uncertain(_) => Err(Error::UnexpectedUncertainty),
+            Value::Interval(_) => Err(Error::UnexpectedInterval),
+        }
+    }
+
+    /// Returns the value as a matrix. A plain number is a matrix of a single entry.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedUnits`] if the value is a quantity with units.
+    /// * [`Error::UnexpectedComplex`] if the value is a complex number.
+    /// * [`Error::UnexpectedUncertainty`] if the value has an uncertainty.
+    /// * [`Error::UnexpectedInterval`] if the value is an interval.
+    fn into_matrix(self) -> Result<Matrix, Error> {
+        match self {
+            Value::Quantity(quantity) => Matrix::new(vec![vec![quantity.into_number()?]]),
+            Value::Complex(_) => Err(Error::UnexpectedComplex),
+            Value::Matrix(matrix) => Ok(matrix),
+            Value::Factorization(factorization) => Matrix::new(vec![vec![factorization.value()]]),
+            Value::Uncertain(_) => Err(Error::UnexpectedUncertainty),
+            Value::Interval(_) => Err(Error::UnexpectedInterval),
+        }
+    }
+
+    /// Returns the value as a number with an uncertainty. A plain number has no uncertainty.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedUnits`] if the value is a quantity with units.
+    /// * [`Error::UnexpectedComplex`] if the value is a complex number.
+    /// * [`Error::UnexpectedMatrix`] if the value is a matrix.
+    /// * [`Error::UnexpectedInterval`] if the value is an interval.
+    fn into_uncertain(self) -> Result<Uncertain, Error> {
+        match self {
+            Value::Quantity(quantity) => quantity.into_number().map(Uncertain::from),
+            Value::Complex(_) => Err(Error::UnexpectedComplex),
+            Value::Matrix(_) => Err(Error::UnexpectedMatrix),
+            Value::Factorization(factorization) => Ok(Uncertain::from(factorization.value())),
+            Value::Uncertain(uncertain) => Ok(uncertain),
+            Value::Interval(_) => Err(Error::UnexpectedInterval),
+        }
+    }
+
+    /// Returns the value as an interval. A plain number is an interval holding only that number.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedUnits`] if the value is a quantity with units.
+    /// * [`Error::UnexpectedComplex`] if the value is a complex number.
+    /// * [`Error::UnexpectedMatrix`] if the value is a matrix.
+    /// * [`Error::UnexpectedUncertainty`] if the value has an uncertainty.
+    fn into_interval(self) -> Result<Interval, Error> {
+        match self {
+            Value::Quantity(quantity) => quantity.into_number().map(Interval::from),
+            Value::Complex(_) => Err(Error::UnexpectedComplex),
+            Value::Matrix(_) => Err(Error::UnexpectedMatrix),
+            Value::Factorization(factorization) => Ok(Interval::from(factorization.value())),
+            Value::Uncertain(_) => Err(Error::UnexpectedUncertainty),
+            Value::Interval(interval) => Ok(interval),
+        }
+    }
+
+    /// Returns the integer of a prime factorization as a plain number, and any other value unchanged.
+    fn reduce(self) -> Value {
+        match self {
+            Value::Factorization(factorization) => Value::from(factorization.value()),
+            value => value,
+        }
+    }
+
+    /// Applies a binary operator. Complex numbers are combined with plain numbers and complex numbers, and so are
+    /// numbers with uncertainties and intervals. Matrices are added to and subtracted from matrices of the same size,
+    /// and multiplied by matrices and plain numbers.
+    fn apply(self, operator: Operator, other: Value) -> Result<Value, Error> {
+        match (self.reduce(), other.reduce()) {
+            (Value::Quantity(lhs), Value::Quantity(rhs)) => lhs.apply(operator, &rhs).map(Value::Quantity),
+            (Value::Interval(_), Value::Complex(_) | Value::Matrix(_) | Value::Uncertain(_))
+            | (Value::Complex(_) | Value::Matrix(_) | Value::Uncertain(_), Value::Interval(_)) => {
+                Err(Error::UnexpectedInterval)
+            }
+            (lhs @ Value::Interval(_), rhs) | (lhs, rhs @ Value::Interval(_)) => {
+                lhs.into_interval()?
+                    .apply(operator, &rhs.into_interval()?)
+                    .map(Value::from)
+            }
+            (Value::Uncertain(_), Value::Complex(_) | Value::Matrix(_))
+            | (Value::Complex(_) | Value::Matrix(_), Value::Uncertain(_)) => Err(Error::UnexpectedUncertainty),
+            (lhs @ Value::Uncertain(_), rhs) | (lhs, rhs @ Value::Uncertain(_)) => {
+                lhs.into_uncertain()?
+                    .apply(operator, &rhs.into_uncertain()?)
+                    .map(Value::from)
+            }
+            (Value::Complex(_), Value::Matrix(_)) | (Value::Matrix(_), Value::Complex(_)) => {
+                Err(Error::UnexpectedComplex)
+            }
+            (lhs @ Value::Complex(_), rhs) | (lhs, rhs @ Value::Complex(_)) => {
+                operator
+                    .apply_complex(&lhs.into_complex()?, &rhs.into_complex()?)
+                    .map(Value::from)
+            }
+            (Value::Matrix(lhs), Value::Matrix(rhs)) => {
+                match operator {
+                    Operator::Add => lhs.add(&rhs).map(Value::Matrix),
+                    Operator::Subtract => lhs.sub(&rhs).map(Value::Matrix),
+                    Operator::Multiply => lhs.mul(&rhs).map(Value::Matrix),
+                    Operator::Divide => {
+                        Err(Error::MatrixSize(
+                            "Cannot divide by a matrix, multiply by its inverse instead".to_string(),
+                        ))
+                    }
+                }
+            }
+            (Value::Quantity(factor), Value::Matrix(matrix)) if operator == Operator::Multiply => {
+                matrix.scale(&factor.into_number()?).map(Value::Matrix)
+            }
+            (Value::Matrix(matrix), Value::Quantity(factor)) if operator == Operator::Multiply => {
+                matrix.scale(&factor.into_number()?).map(Value::Matrix)
+            }
+            (Value::Matrix(matrix), Value::Quantity(divisor)) if operator == Operator::Divide => {
+                matrix.scale(&divisor.into_number()?.recip()?).map(Value::Matrix)
+            }
+            (Value::Quantity(_), Value::Matrix(_)) if operator == Operator::Divide => {
+                Err(Error::MatrixSize("Cannot divide by a matrix".to_string()))
+            }
+            _ => Err(Error::MatrixSize("Cannot add a number to a matrix or subtract one".to_string())),
+        }
+    }
+
+    /// Raises the value to a power. Complex numbers are raised to complex powers, numbers with uncertainties to
+    /// powers with uncertainties, intervals to interval powers, and square matrices to integer powers. In complex
+    /// mode, negative numbers raised to fractional powers are complex.
+    fn pow(self, exponent: Value, complex: bool) -> Result<Value, Error> {
+        match (self.reduce(), exponent.reduce()) {
+            (_, Value::Matrix(_)) => Err(Error::UnexpectedMatrix),
+            (base @ Value::Interval(_), exponent) | (base, exponent @ Value::Interval(_)) => {
+                if matches!(base, Value::Complex(_) | Value::Matrix(_) | Value::Uncertain(_))
+                    || matches!(exponent, Value::Complex(_) | Value::Uncertain(_))
+                {
+                    return Err(Error::UnexpectedInterval);
+                }
+                base.into_interval()?.pow(&exponent.into_interval()?).map(Value::from)
+            }
+            (base @ Value::Uncertain(_), exponent) | (base, exponent @ Value::Uncertain(_)) => {
+                if matches!(base, Value::Complex(_) | Value::Matrix(_)) || matches!(exponent, Value::Complex(_)) {
+                    return Err(Error::UnexpectedUncertainty);
+                }
+                base.into_uncertain()?.pow(&exponent.into_uncertain()?).map(Value::from)
+            }
+            (Value::Matrix(base), exponent) => {
+                let exponent = exponent.into_quantity()?.into_number()?;
+                if !exponent.is_integer() || exponent.to_f64().abs() > f64::from(i32::MAX) {
+                    return Err(Error::InvalidInput);
+                }
+                base.pow(exponent.to_f64() as i32).map(Value::Matrix)
+            }
+            (Value::Quantity(base), Value::Quantity(exponent)) => {
+                match base.pow(&exponent) {
+                    Err(Error::InvalidInput) if complex && base.units.is_empty() && exponent.units.is_empty() => {
+                        Complex::from(base.value)
+                            .pow(&Complex::from(exponent.value))
+                            .map(Value::from)
+                    }
+                    result => result.map(Value::Quantity),
+                }
+            }
+            (base, exponent) => base.into_complex()?.pow(&exponent.into_complex()?).map(Value::from),
+        }
+    }
+}
+
+impl From<Quantity> for Value {
+    fn from(quantity: Quantity) -> Self {
+        Value::Quantity(quantity)
+    }
+}
+
+impl From<Number> for Value {
+    fn from(number: Number) -> Self {
+        Value::Quantity(Quantity::from(number))
+    }
+}
+
+impl From<Complex> for Value {
+    /// Creates a plain number from a real complex number, and a complex value otherwise.
+    fn from(complex: Complex) -> Self {
+        if complex.is_real() {
+            Value::from(complex.re)
+        }
+        else {
+            Value::Complex(complex)
+        }
+    }
+}
+
+impl From<Uncertain> for Value {
+    /// Creates a plain number from a number without uncertainty, and a number with an uncertainty otherwise.
+    fn from(uncertain: Uncertain) -> Self {
+        if uncertain.is_exact() {
+            Value::from(uncertain.value)
+        }
+        else {
+            Value::Uncertain(uncertain)
+        }
+    }
+}
+
+impl From<Interval> for Value {
+    /// Creates a plain number from an interval holding a single number, and an interval otherwise.
+    fn from(interval: Interval) -> Self {
+        if interval.is_point() {
+            Value::from(interval.lower)
+        }
+        else {
+            Value::Interval(interval)
+        }
+    }
+}
+
+impl Expression {
+    /// Evaluates the expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The variables, functions and previous results the expression can refer to.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnknownName`] if the expression refers to a variable or function that does not exist.
+    /// * [`Error::MissingResult`] if the expression refers to a previous result that is not in the history.
+    /// * [`Error::ArgumentCount`] if a function is called with the wrong number of arguments.
+    /// * [`Error::RecursionLimit`] if a user-defined function calls itself more than [`MAX_CALL_DEPTH`] times.
+    /// * [`Error::IncompatibleUnits`] if quantities of different dimensions are added or converted.
+    /// * [`Error::UnexpectedUnits`] if a quantity with units is used where a plain number is expected.
+    /// * [`Error::NoSolution`] if an equation solved has no real root.
+    /// * [`Error::UnexpectedComplex`] if the expression is complex, or a complex number is used where a real number
+    ///   is expected.
+    /// * [`Error::UnexpectedMatrix`] if the expression is a matrix, or a matrix is used where a number is expected.
+    /// * [`Error::MatrixSize`] if matrices of incompatible sizes are combined.
+    /// * [`Error::SingularMatrix`] if a matrix without an inverse is inverted.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn evaluate(&self, context: &Context) -> Result<Quantity, Error> {
+        self.evaluate_in(context, &Scope::default())
+    }
+
+    /// Evaluates the expression, which may be complex or a matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The variables, functions and previous results the expression can refer to.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`Expression::evaluate`], except that the expression itself may be complex or a matrix.
+    pub fn value(&self, context: &Context) -> Result<Value, Error> {
+        self.value_in(context, &Scope::default())
+    }
+
+    /// Evaluates the expression with a value for one of its names, which shadows any variable with the same name
+    /// (e.g. `x` when plotting `sin(x)`).
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The variables, functions and previous results the expression can refer to.
+    /// * `name` - The name given a value.
+    /// * `value` - The value of the name.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`Expression::evaluate`].
+    pub fn evaluate_with(&self, context: &Context, name: &str, value: &Quantity) -> Result<Quantity, Error> {
+        let parameters = [name.to_string()];
+        let scope = Scope {
+            parameters: &parameters,
+            arguments:  std::slice::from_ref(value),
+            depth:      0,
+        };
+        self.evaluate_in(context, &scope)
+    }
+
+    /// Evaluates the expression in the body of a user-defined function, or at the top level for an empty scope.
+    fn evaluate_in(&self, context: &Context, scope: &Scope) -> Result<Quantity, Error> {
+        self.value_in(context, scope)?.into_quantity()
+    }
+
+    /// Evaluates the expression, which may be complex or a matrix, in the body of a user-defined function or at the
+    /// top level for an empty scope.
+    fn value_in(&self, context: &Context, scope: &Scope) -> Result<Value, Error> {
+        let quantity = match self {
+            Expression::Number(number) => Quantity::from(number.clone()),
+            Expression::Imaginary(number) => return Ok(Value::from(Complex::new(Number::zero(), number.clone()))),
+            Expression::Variable(name) => {
+                return match scope.argument(name) {
+                    Some(value) => Ok(Value::Quantity(value.clone())),
+                    None => context.variable(name),
+                };
+            }
+            Expression::Negate(operand) => {
+                return match operand.value_in(context, scope)? {
+                    Value::Quantity(quantity) => Ok(Value::Quantity(quantity.neg())),
+                    Value::Complex(complex) => Ok(Value::Complex(complex.neg())),
+                    Value::Matrix(matrix) => Ok(Value::Matrix(matrix.neg())),
+                    Value::Factorization(factorization) => {
+                        Ok(Value::Factorization(Factorization {
+                            negative: !factorization.negative,
+                            ..factorization
+                        }))
+                    }
+                    Value::Uncertain(uncertain) => Ok(Value::Uncertain(uncertain.neg())),
+                    Value::Interval(interval) => Ok(Value::Interval(interval.neg())),
+                };
+            }
+            Expression::Percent(percentage) => {
+                percentage
+                    .evaluate_in(context, scope)?
+                    .apply(Operator::Divide, &Quantity::from(Number::from_integer(100)))?
+            }
+            Expression::Binary(operator @ (Operator::Add | Operator::Subtract), lhs, rhs)
+                if matches!(**rhs, Expression::Percent(_)) =>
+            {
+                // A percentage of the left operand (e.g. `200 + 10 %` adds `20`).
+                let lhs = lhs.evaluate_in(context, scope)?;
+                let rhs = lhs.apply(Operator::Multiply, &rhs.evaluate_in(context, scope)?)?;
+                lhs.apply(*operator, &rhs)?
+            }
+            Expression::Binary(operator, lhs, rhs) => {
+                return lhs
+                    .value_in(context, scope)?
+                    .apply(*operator, rhs.value_in(context, scope)?);
+            }
+            Expression::Power(base, exponent) => {
+                return base
+                    .value_in(context, scope)?
+                    .pow(exponent.value_in(context, scope)?, context.complex);
+            }
+            Expression::Call(name, arguments) if function(name).is_some() || complex_function(name).is_some() => {
+                check_argument_count(name, 1, arguments.len())?;
+                return context.call_scientific(name, arguments[0].value_in(context, scope)?);
+            }
+            Expression::Call(name, arguments) if name == FACTOR => {
+                check_argument_count(name, 1, arguments.len())?;
+                let value = arguments[0].evaluate_in(context, scope)?.into_number()?;
+                return Factorization::new(&value).map(Value::Factorization);
+            }
+            Expression::Call(name, arguments) if matrix_function(name).is_some() => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| argument.value_in(context, scope))
+                    .collect::<Result<Vec<Value>, Error>>()?;
+                return call_matrix_function(name, arguments);
+            }
+            Expression::Call(name, arguments) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| argument.evaluate_in(context, scope))
+                    .collect::<Result<Vec<Quantity>, Error>>()?;
+                context.call(name, &arguments, scope.depth)?
+            }
+            Expression::WithUnits(expression, units) => {
+                let units = Quantity::new(Number::from_integer(1), units.clone());
+                expression
+                    .evaluate_in(context, scope)?
+                    .apply(Operator::Multiply, &units)?
+            }
+            Expression::Convert(expression, units) => expression.evaluate_in(context, scope)?.convert(units)?,
+            Expression::Angle(angle, angle_unit) => {
+                let angle = angle.evaluate_in(context, scope)?.into_number()?;
+                Quantity::from(angle_unit.convert(&angle, context.angle_unit)?)
+            }
+            Expression::ConvertAngle(angle, angle_unit) => {
+                let angle = angle.evaluate_in(context, scope)?.into_number()?;
+                Quantity::from(context.angle_unit.convert(&angle, *angle_unit)?)
+            }
+            Expression::Solve(equation, variable, bracket) => {
+                let bracket = match bracket {
+                    Some((from, to)) => {
+                        Some((
+                            from.evaluate_in(context, scope)?.into_number()?.to_f64(),
+                            to.evaluate_in(context, scope)?.into_number()?.to_f64(),
+                        ))
+                    }
+                    None => None,
+                };
+                let root = equation
+                    .solve_in(context, scope, variable, bracket)?
+                    .first_real_root()
+                    .ok_or(Error::NoSolution)?;
+                Quantity::from(Number::from_real(root)?)
+            }
+            Expression::Matrix(rows)
+                if context.interval && rows.len() == 2 && rows.iter().all(|row| row.len() == 1) =>
+            {
+                let lower = rows[0][0].evaluate_in(context, scope)?.into_number()?;
+                let upper = rows[1][0].evaluate_in(context, scope)?.into_number()?;
+                return Interval::new(lower, upper).map(Value::from);
+            }
+            Expression::Matrix(rows) => {
+                let rows = rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|entry| entry.evaluate_in(context, scope)?.into_number())
+                            .collect::<Result<Vec<Number>, Error>>()
+                    })
+                    .collect::<Result<Vec<Vec<Number>>, Error>>()?;
+                return Matrix::new(rows).map(Value::Matrix);
+            }
+            Expression::Polar(modulus, argument, angle_unit) => {
+                let modulus = modulus.evaluate_in(context, scope)?.into_number()?;
+                let argument = argument.evaluate_in(context, scope)?.into_number()?;
+                let complex = Complex::from_polar(&modulus, &argument, angle_unit.unwrap_or(context.angle_unit))?;
+                return Ok(Value::from(complex));
+            }
+            Expression::Uncertain(value, uncertainty) => {
+                let value = value.evaluate_in(context, scope)?.into_number()?;
+                let uncertainty = uncertainty.evaluate_in(context, scope)?.into_number()?;
+                return Uncertain::new(value, uncertainty).map(Value::from);
+            }
+        };
+        Ok(Value::Quantity(quantity))
+    }
+
+    /// Solves the equation `self = 0` for a variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The variables, functions and previous results the equation can refer to.
+    /// * `variable` - The name of the variable solved for.
+    /// * `bracket` - The interval searched if the equation is not a polynomial, or `None` for the default one. The
+    ///   roots of a polynomial outside of a given bracket are left out.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if every value of the variable is a root (e.g. `x - x`).
+    /// * The errors of [`Expression::evaluate`] for the names the equation refers to.
+    pub(super) fn solve(
+        &self,
+        context: &Context,
+        variable: &str,
+        bracket: Option<(f64, f64)>,
+    ) -> Result<Solution, Error> {
+        self.solve_in(context, &Scope::default(), variable, bracket)
+    }
+
+    /// Solves the equation `self = 0` for a variable, in the body of a user-defined function or at the top level for
+    /// an empty scope. The variable shadows the parameters of the function.
+    fn solve_in(
+        &self,
+        context: &Context,
+        scope: &Scope,
+        variable: &str,
+        bracket: Option<(f64, f64)>,
+    ) -> Result<Solution, Error> {
+        if let Some(polynomial) = self.polynomial_in(context, scope, variable)? {
+            let mut roots = polynomial.roots();
+            if let Some((from, to)) = bracket {
+                roots.retain(|root| root.is_real() && from.min(to) <= root.re && root.re <= from.max(to));
+            }
+            return match polynomial.degree() {
+                None => Err(Error::InvalidInput),
+                degree => Ok(Solution { roots, degree }),
+            };
+        }
+        let parameters = std::iter::once(variable.to_string())
+            .chain(scope.parameters.iter().cloned())
+            .collect::<Vec<String>>();
+        let evaluate = |x: f64| {
+            let arguments = std::iter::once(Quantity::from(Number::from_real(x)?))
+                .chain(scope.arguments.iter().cloned())
+                .collect::<Vec<Quantity>>();
+            let scope = Scope {
+                parameters: &parameters,
+                arguments:  &arguments,
+                depth:      scope.depth,
+            };
+            self.evaluate_in(context, &scope)?.into_number()
+        };
+        let (from, to) = bracket.unwrap_or(super::solver::DEFAULT_BRACKET);
+        // Errors outside of the domain of the equation only leave out points, but the others hold everywhere.
+        match evaluate((from + to) / 2.0) {
+            Err(Error::DivisionByZero | Error::InvalidInput | Error::Overflow | Error::UnexpectedComplex) | Ok(_) => {}
+            Err(error) => return Err(error),
+        }
+        let f = |x: f64| evaluate(x).ok().map(|y| y.to_f64()).filter(|y| y.is_finite());
+        Ok(Solution {
+            roots:  super::plot::roots(f, from, to).into_iter().map(super::solver::Root::real).collect(),
+            degree: None,
+        })
+    }
+
+    /// Returns the expression as a polynomial in a variable, replacing the other names by their values, or `None`
+    /// if it is not a polynomial of at most [`MAX_DEGREE`] (e.g. `sin(x)`, `1 / x` or `x^0.5`).
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`Expression::evaluate`] for the parts of the expression that do not depend on the variable.
+    fn polynomial_in(&self, context: &Context, scope: &Scope, variable: &str) -> Result<Option<Polynomial>, Error> {
+        if !self.depends_on(variable) {
+            let value = self.evaluate_in(context, scope)?.into_number()?.to_f64();
+            return Ok(Some(Polynomial::constant(value)));
+        }
+        let polynomial = |expression: &Expression| expression.polynomial_in(context, scope, variable);
+        let result = match self {
+            Expression::Variable(_) => Some(Polynomial::variable()),
+            Expression::Negate(operand) => polynomial(operand)?.map(|operand| operand.scale(-1.0)),
+            Expression::Percent(operand) => polynomial(operand)?.map(|operand| operand.scale(0.01)),
+            Expression::Binary(operator, lhs, rhs) => {
+                // A percentage on the right of `+` or `−` is one of the left operand (e.g. `x + 10 %`).
+                let percentage = matches!(**rhs, Expression::Percent(_));
+                let (Some(lhs), Some(rhs)) = (polynomial(lhs)?, polynomial(rhs)?)
+                else {
+                    return Ok(None);
+                };
+                match operator {
+                    Operator::Add if percentage => Some(lhs.add(&lhs.mul(&rhs))),
+                    Operator::Subtract if percentage => Some(lhs.add(&lhs.mul(&rhs).scale(-1.0))),
+                    Operator::Add => Some(lhs.add(&rhs)),
+                    Operator::Subtract => Some(lhs.add(&rhs.scale(-1.0))),
+                    Operator::Multiply => Some(lhs.mul(&rhs)),
+                    Operator::Divide => {
+                        rhs.as_constant()
+                            .filter(|divisor| *divisor != 0.0)
+                            .map(|divisor| lhs.scale(1.0 / divisor))
+                    }
+                }
+            }
+            Expression::Power(base, exponent) if !exponent.depends_on(variable) => {
+                let exponent = exponent.evaluate_in(context, scope)?.into_number()?;
+                if !exponent.is_integer() || exponent.is_negative() || exponent.to_f64() > MAX_DEGREE as f64 {
+                    return Ok(None);
+                }
+                polynomial(base)?.map(|base| base.pow(exponent.to_f64() as usize))
+            }
+            _ => None,
+        };
+        Ok(result.filter(|polynomial| polynomial.degree().is_none_or(|degree| degree <= MAX_DEGREE)))
+    }
+
+    /// Returns `true` if the expression refers to a variable, other than through the body of a function.
+    fn depends_on(&self, variable: &str) -> bool {
+        match self {
+            Expression::Number(_) | Expression::Imaginary(_) => false,
+            Expression::Variable(name) => name == variable,
+            Expression::Negate(operand)
+            | Expression::Percent(operand)
+            | Expression::WithUnits(operand, _)
+            | Expression::Convert(operand, _)
+            | Expression::Angle(operand, _)
+            | Expression::ConvertAngle(operand, _) => operand.depends_on(variable),
+            Expression::Binary(_, lhs, rhs)
+            | Expression::Power(lhs, rhs)
+            | Expression::Polar(lhs, rhs, _)
+            | Expression::Uncertain(lhs, rhs) => lhs.depends_on(variable) || rhs.depends_on(variable),
+            Expression::Call(_, arguments) => arguments.iter().any(|argument| argument.depends_on(variable)),
+            Expression::Matrix(rows) => rows.iter().flatten().any(|entry| entry.depends_on(variable)),
+            Expression::Solve(equation, bound, bracket) => {
+                (bound != variable && equation.depends_on(variable))
+                    || bracket
+                        .as_ref()
+                        .is_some_and(|(from, to)| from.depends_on(variable) || to.depends_on(variable))
+            }
+        }
+    }
+
+    /// Checks the names used by the expression in the body of a function definition, where the variables of the
+    /// equations solved around the expression are bound too.
+    fn check(&self, context: &Context, definition: &UserFunction, bound: &[&str]) -> Result<(), Error> {
+        match self {
+            Expression::Number(_) | Expression::Imaginary(_) => Ok(()),
+            Expression::Variable(name) => {
+                if definition.parameters.contains(name)
+                    || bound.contains(&name.as_str())
+                    || answer_index(name).is_some()
+                    || context.variables.contains_key(name)
+                    || name == IMAGINARY_UNIT
+                    || constants::find(name).is_some()
+                    || units::find(name).is_some()
+                {
+                    Ok(())
+                }
+                else {
+                    Err(Error::UnknownName(name.clone()))
+                }
+            }
+            Expression::Negate(operand)
+            | Expression::Percent(operand)
+            | Expression::WithUnits(operand, _)
+            | Expression::Convert(operand, _)
+            | Expression::Angle(operand, _)
+            | Expression::ConvertAngle(operand, _) => operand.check(context, definition, bound),
+            Expression::Binary(_, lhs, rhs)
+            | Expression::Power(lhs, rhs)
+            | Expression::Polar(lhs, rhs, _)
+            | Expression::Uncertain(lhs, rhs) => {
+                lhs.check(context, definition, bound)?;
+                rhs.check(context, definition, bound)
+            }
+            Expression::Call(name, arguments) => {
+                let expected = if *name == definition.name {
+                    definition.parameters.len()
+                }
+                else {
+                    context.arity(name)?
+                };
+                check_argument_count(name, expected, arguments.len())?;
+                arguments
+                    .iter()
+                    .try_for_each(|argument| argument.check(context, definition, bound))
+            }
+            Expression::Solve(equation, variable, bracket) => {
+                if let Some((from, to)) = bracket {
+                    from.check(context, definition, bound)?;
+                    to.check(context, definition, bound)?;
+                }
+                let bound = bound
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once(variable.as_str()))
+                    .collect::<Vec<&str>>();
+                equation.check(context, definition, &bound)
+            }
+            Expression::Matrix(rows) => {
+                rows.iter()
+                    .flatten()
+                    .try_for_each(|entry| entry.check(context, definition, bound))
+            }
+        }
+    }
+}
+
+/// A function defined by the user (e.g. `f(x, y) = sqrt(x^2 + y^2)`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserFunction {
+    /// The name of the function.
+    pub name:       String,
+    /// The names of the parameters, in order.
+    pub parameters: Vec<String>,
+    /// The body of the function.
+    pub body:       Expression,
+    /// The text of the body as typed (e.g. `sqrt(x^2 + y^2)`).
+    pub source:     String,
+}
+
+impl UserFunction {
+    /// Returns the signature of the function (e.g. `f(x, y)`).
+    pub fn signature(&self) -> String {
+        format!("{}({})", self.name, self.parameters.join(", "))
+    }
+
+    /// Returns the definition of the function as it can be typed again (e.g. `f(x, y) = sqrt(x^2 + y^2)`).
+    pub fn definition(&self) -> String {
+        format!("{} = {}", self.signature(), self.source)
+    }
+}
+
+/// The arguments of the user-defined function being evaluated, and the number of nested calls.
+#[derive(Default)]
+struct Scope<'a> {
+    parameters: &'a [String],
+    arguments:  &'a [Quantity],
+    depth:      usize,
+}
+
+impl Scope<'_> {
+    /// Returns the argument passed for the parameter with the given name.
+    fn argument(&self, name: &str) -> Option<&Quantity> {
+        self.parameters
+            .iter()
+            .position(|parameter| parameter == name)
+            .and_then(|index| self.arguments.get(index))
+    }
+}
+
+/// A parsed line of input: an assignment of a variable or a plain expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Statement {
+    /// An assignment of the value of an expression to a variable (e.g. `rate = 0.0725`).
+    Assignment(String, Expression),
+    /// An expression to evaluate (e.g. `price * (1 + rate)`).
+    Expression(Expression),
+    /// A definition of a function (e.g. `f(x, y) = sqrt(x^2 + y^2)`). It is checked with [`Context::check`] before
+    /// it is stored.
+    Definition(UserFunction),
+}
+
+impl Statement {
+    /// Parses a line of input. A trailing `=` is ignored, so `2 + 3 =` is read as `2 + 3`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to parse.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Syntax`] if the text is not a valid expression, assignment or definition, or a definition has
+    ///   duplicate parameters.
+    /// * [`Error::ReservedName`] if the text assigns to or defines the name of a built-in function or of a previous
+    ///   result, or uses such a name as a parameter.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut tokens = tokenize(text)?;
+        if tokens.last() == Some(&Token::Equals) {
+            tokens.pop();
+        }
+        let mut parser = Parser { tokens, position: 0 };
+        if let Some((name, parameters)) = parser.signature()? {
+            let source = text
+                .split_once('=')
+                .map(|(_, body)| body.trim().trim_end_matches('=').trim_end())
+                .unwrap_or_default()
+                .to_string();
+            let body = parser.conversion()?;
+            if let Some(token) = parser.peek() {
+                return Err(Error::Syntax(format!("Unexpected ‘{}’", token)));
+            }
+            return Ok(Statement::Definition(UserFunction {
+                name,
+                parameters,
+                body,
+                source,
+            }));
+        }
+        let statement = match parser.tokens.as_slice() {
+            [Token::Identifier(name), Token::Equals, ..] => {
+                let name = name.clone();
+                if is_reserved(&name) {
+                    return Err(Error::ReservedName(name));
+                }
+                parser.position = 2;
+                Statement::Assignment(name, parser.conversion()?)
+            }
+            _ => Statement::Expression(parser.conversion()?),
+        };
+        match parser.peek() {
+            Some(token) => Err(Error::Syntax(format!("Unexpected ‘{}’", token))),
+            None => Ok(statement),
+        }
+    }
+
+}
+
+/// The names and settings an expression is evaluated with.
+#[derive(Clone, Copy, Debug)]
+pub struct Context<'a> {
+    /// The variables, by name.
+    pub variables:  &'a BTreeMap<String, Quantity>,
+    /// The user-defined functions, by name.
+    pub functions:  &'a BTreeMap<String, UserFunction>,
+    /// The previous results, most recent first.
+    pub answers:    &'a [Value],
+    /// The unit of the angles taken and returned by the trigonometric functions.
+    pub angle_unit: AngleUnit,
+    /// Whether functions outside of their real domain return complex results (e.g. `sqrt(-4)` is `2i`) rather than
+    /// an error.
+    pub complex:    bool,
+    /// Whether a list of two numbers in square brackets is an interval (e.g. `[1.2, 1.5]`) rather than a vector.
+    pub interval:   bool,
+}
+
+impl Context<'_> {
+    /// Checks a function definition before it is stored. The function may call itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `definition` - The function to check.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnknownName`] if the body refers to a name that is not a parameter, a variable, a constant or a
+    ///   function.
+    /// * [`Error::ArgumentCount`] if the body calls a function with the wrong number of arguments.
+    pub fn check(&self, definition: &UserFunction) -> Result<(), Error> {
+        definition.body.check(self, definition, &[])
+    }
+
+    /// Calls a built-in or user-defined function.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the function.
+    /// * `arguments` - The values of the arguments.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnknownName`] if there is no function with the given name.
+    /// * [`Error::ArgumentCount`] if the number of arguments does not match the function.
+    /// * [`Error::RecursionLimit`] if the function calls itself more than [`MAX_CALL_DEPTH`] times.
+    /// * The error of the evaluation of the function.
+    pub fn call_function(&self, name: &str, arguments: &[Quantity]) -> Result<Quantity, Error> {
+        self.call(name, arguments, 0)
+    }
+
+    /// Returns the number of arguments taken by a built-in or user-defined function.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the function.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnknownName`] if there is no function with the given name.
+    pub fn arity(&self, name: &str) -> Result<usize, Error> {
+        if function(name).is_some() || complex_function(name).is_some() || name == FACTOR {
+            return Ok(1);
+        }
+        if percentage_function(name).is_some() {
+            return Ok(2);
+        }
+        if let Some((_, arity)) = matrix_function(name) {
+            return Ok(arity);
+        }
+        if let Some((_, arity)) = integer_function(name) {
+            return Ok(arity);
+        }
+        self.functions
+            .get(name)
+            .map(|function| function.parameters.len())
+            .ok_or_else(|| Error::UnknownName(name.to_string()))
+    }
+
+    /// Calls a function from `depth` nested calls of user-defined functions.
+    fn call(&self, name: &str, arguments: &[Quantity], depth: usize) -> Result<Quantity, Error> {
+        check_argument_count(name, self.arity(name)?, arguments.len())?;
+        if function(name).is_some() || complex_function(name).is_some() {
+            return self
+                .call_scientific(name, Value::Quantity(arguments[0].clone()))?
+                .into_quantity();
+        }
+        if let Some(function) = percentage_function(name) {
+            let (x, y) = (arguments[0].clone().into_number()?, arguments[1].clone().into_number()?);
+            return function(&x, &y).map(Quantity::from);
+        }
+        if let Some((function, _)) = integer_function(name) {
+            let arguments = arguments
+                .iter()
+                .map(|argument| argument.clone().into_number())
+                .collect::<Result<Vec<Number>, Error>>()?;
+            return function(&arguments).map(Quantity::from);
+        }
+        if name == FACTOR {
+            // A factorization used in a larger expression is the integer itself.
+            let value = arguments[0].clone().into_number()?;
+            return Factorization::new(&value).map(|factorization| Quantity::from(factorization.value()));
+        }
+        if matrix_function(name).is_some() {
+            let arguments = arguments.iter().cloned().map(Value::Quantity).collect();
+            return call_matrix_function(name, arguments)?.into_quantity();
+        }
+        let function = &self.functions[name];
+        if depth >= MAX_CALL_DEPTH {
+            return Err(Error::RecursionLimit(name.to_string()));
+        }
+        let scope = Scope {
+            parameters: &function.parameters,
+            arguments,
+            depth: depth + 1,
+        };
+        function.body.evaluate_in(self, &scope)
+    }
+
+    /// Calls a built-in function of one number, or a function of complex numbers. Complex arguments give complex
+    /// results, and so do real arguments outside of the real domain of the function in complex mode.
+    fn call_scientific(&self, name: &str, argument: Value) -> Result<Value, Error> {
+        if let Some(function) = complex_function(name) {
+            return function(&argument.into_complex()?, self.angle_unit);
+        }
+        let function = function(name).ok_or_else(|| Error::UnknownName(name.to_string()))?;
+        match argument.reduce() {
+            Value::Quantity(quantity) => {
+                match quantity.apply_function(function, self.angle_unit) {
+                    Err(Error::InvalidInput) if self.complex && quantity.units.is_empty() => {
+                        Complex::from(quantity.value)
+                            .apply_function(function, self.angle_unit)
+                            .map(Value::from)
+                    }
+                    result => result.map(Value::Quantity),
+                }
+            }
+            Value::Complex(complex) => complex.apply_function(function, self.angle_unit).map(Value::from),
+            Value::Matrix(_) => Err(Error::UnexpectedMatrix),
+            Value::Factorization(_) => unreachable!("Reduced above"),
+            Value::Uncertain(uncertain) => uncertain.apply_function(function, self.angle_unit).map(Value::from),
+            Value::Interval(interval) => interval.apply_function(function, self.angle_unit).map(Value::from),
+        }
+    }
+
+    /// Returns the value of a variable, a previous result, the imaginary unit, a constant or one of a unit. Variables
+    /// shadow the imaginary unit and constants, and constants shadow units (e.g. `h` is the Planck constant, but
+    /// `2 h` is two hours).
+    fn variable(&self, name: &str) -> Result<Value, Error> {
+        if let Some(index) = answer_index(name) {
+            return self
+                .answers
+                .get(index)
+                .cloned()
+                .ok_or_else(|| Error::MissingResult(name.to_string()));
+        }
+        self.variables
+            .get(name)
+            .cloned()
+            .map(Value::Quantity)
+            .or_else(|| (name == IMAGINARY_UNIT).then(|| Value::Complex(Complex::i())))
+            .or_else(|| constants::find(name).map(|constant| Value::Quantity(constant.quantity())))
+            .or_else(|| {
+                units::find(name).map(|unit| Value::Quantity(Quantity::new(Number::from_integer(1), Units::unit(unit))))
+            })
+            .ok_or_else(|| Error::UnknownName(name.to_string()))
+    }
+}
+
+/// Returns `true` if the name cannot be assigned to: the name of a built-in function, of a function of complex
+/// numbers, of a number theory function, of a matrix function, of a previous result, `solve` or the `to` keyword.
+///
+/// # Arguments
+///
+/// * `name` - The name to check.
+pub fn is_reserved(name: &str) -> bool {
+    function(name).is_some()
+        || percentage_function(name).is_some()
+        || complex_function(name).is_some()
+        || integer_function(name).is_some()
+        || matrix_function(name).is_some()
+        || answer_index(name).is_some()
+        || name == CONVERSION
+        || name == SOLVE
+        || name == FACTOR
+}
+
+/// Parses units as written in expressions (e.g. `km/h` or `m^3/(kg·s^2)`).
+///
+/// # Errors
+///
+/// * [`Error::Syntax`] if the text is not a valid product of units.
+pub(super) fn parse_units(text: &str) -> Result<Units, Error> {
+    let mut parser = Parser {
+        tokens:   tokenize(text)?,
+        position: 0,
+    };
+    let units = parser.units(false)?;
+    match parser.peek() {
+        Some(token) => Err(Error::Syntax(format!("Unexpected ‘{}’", token))),
+        None => Ok(units),
+    }
+}
+
+/// Checks that a function is called with the number of arguments it takes.
+fn check_argument_count(name: &str, expected: usize, found: usize) -> Result<(), Error> {
+    if expected == found {
+        Ok(())
+    }
+    else {
+        Err(Error::ArgumentCount {
+            name: name.to_string(),
+            expected,
+            found,
+        })
+    }
+}
+
+/// Returns the built-in function that can be called in expressions with the given name.
+fn function(name: &str) -> Option<Function> {
+    FUNCTIONS
+        .iter()
+        .find(|(function_name, _)| *function_name == name)
+        .map(|(_, function)| *function)
+}
+
+/// Returns the percentage function that can be called in expressions with the given name.
+fn percentage_function(name: &str) -> Option<PercentageFunction> {
+    PERCENTAGE_FUNCTIONS
+        .iter()
+        .find(|(function_name, _)| *function_name == name)
+        .map(|(_, function)| *function)
+}
+
+/// Returns the function of complex numbers that can be called in expressions with the given name.
+fn complex_function(name: &str) -> Option<ComplexFunction> {
+    COMPLEX_FUNCTIONS
+        .iter()
+        .find(|(function_name, _)| *function_name == name)
+        .map(|(_, function)| *function)
+}
+
+/// Returns the number theory function of several integers that can be called in expressions with the given name,
+/// and the number of arguments it takes.
+fn integer_function(name: &str) -> Option<(IntegerFunction, usize)> {
+    INTEGER_FUNCTIONS
+        .iter()
+        .find(|(function_name, ..)| *function_name == name)
+        .map(|(_, arity, function)| (*function, *arity))
+}
+
+/// Calls a matrix function. Plain numbers are taken as matrices of a single entry.
+fn call_matrix_function(name: &str, arguments: Vec<Value>) -> Result<Value, Error> {
+    let Some((function, arity)) = matrix_function(name)
+    else {
+        return Err(Error::UnknownName(name.to_string()));
+    };
+    check_argument_count(name, arity, arguments.len())?;
+    let arguments = arguments
+        .into_iter()
+        .map(Value::into_matrix)
+        .collect::<Result<Vec<Matrix>, Error>>()?;
+    function(&arguments)
+}
+
+/// Returns the matrix function that can be called in expressions with the given name, and the number of arguments
+/// it takes.
+fn matrix_function(name: &str) -> Option<(MatrixFunction, usize)> {
+    MATRIX_FUNCTIONS
+        .iter()
+        .find(|(function_name, ..)| *function_name == name)
+        .map(|(_, arity, function)| (*function, *arity))
+}
+
+/// Returns the real eigenvalues of a square matrix as a column, in increasing order.
+///
+/// # Errors
+///
+/// * [`Error::NoSolution`] if an eigenvalue is complex.
+/// * The errors of [`Matrix::eigenvalues`].
+fn eigenvalues(arguments: &[Matrix]) -> Result<Value, Error> {
+    let eigenvalues = arguments[0]
+        .eigenvalues()?
+        .into_iter()
+        .map(|root| if root.is_real() { Number::from_real(root.re) } else { Err(Error::NoSolution) })
+        .collect::<Result<Vec<Number>, Error>>()?;
+    Matrix::column(eigenvalues).map(Value::Matrix)
+}
+
+/// Returns the index of the previous result with the given name, most recent first: `0` for `ans` and `ans1`, `1`
+/// for `ans2` and so on.
+fn answer_index(name: &str) -> Option<usize> {
+    let suffix = name.strip_prefix(ANSWER)?;
+    if suffix.is_empty() {
+        return Some(0);
+    }
+    if suffix.starts_with('0') || !suffix.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    suffix.parse::<usize>().ok().map(|n| n - 1)
+}
+
+/// Parses a duration or a time of day written `h:mm` or `h:mm:ss` (e.g. `1:45:30`). The minutes and the seconds
+/// need to be below 60, and only the last part may have a fractional part.
+///
+/// # Returns
+///
+/// The number of seconds.
+///
+/// # Errors
+///
+/// * [`Error::Syntax`] if the text is not a valid duration.
+fn parse_clock(text: &str) -> Result<Number, Error> {
+    let invalid = || Error::Syntax(format!("Invalid time ‘{}’", text));
+    let parts = text.split(':').collect::<Vec<&str>>();
+    if parts.len() > 3 {
+        return Err(invalid());
+    }
+    let sixty = Number::from_integer(60);
+    let mut seconds = Number::zero();
+    for (i, part) in parts.iter().enumerate() {
+        let whole = part.split('.').next().unwrap_or_default();
+        let last = i + 1 == parts.len();
+        if whole.is_empty() || (!last && whole.len() < part.len()) || (i > 0 && (whole.len() != 2 || whole >= "60")) {
+            return Err(invalid());
+        }
+        let value = part.parse::<Number>().map_err(|_| invalid())?;
+        seconds = seconds.mul(&sixty)?.add(&value)?;
+    }
+    if parts.len() == 2 {
+        seconds = seconds.mul(&sixty)?;
+    }
+    Ok(seconds)
+}
+
+/// Splits an expression into tokens.
+fn tokenize(text: &str) -> Result<Vec<Token>, Error> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                let digits = if matches!(chars.get(i + 1), Some('+' | '-')) { i + 2 } else { i + 1 };
+                if chars.get(digits).is_some_and(char::is_ascii_digit) {
+                    i = digits;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            if chars.get(i) == Some(&':') && chars.get(i + 1).is_some_and(char::is_ascii_digit) {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.' || chars[i] == ':') {
+                    i += 1;
+                }
+                let clock = chars[start..i].iter().collect::<String>();
+                tokens.push(Token::Clock(parse_clock(&clock)?));
+                continue;
+            }
+            if chars.get(i) == Some(&'°') {
+                if let Some(end) = dms_end(&chars, i + 1) {
+                    let angle = chars[start..end].iter().collect::<String>();
+                    let value = angle
+                        .parse::<Number>()
+                        .map_err(|_| Error::Syntax(format!("Invalid angle ‘{}’", angle)))?;
+                    tokens.push(Token::Number(value));
+                    tokens.push(Token::Degree);
+                    i = end;
+                    continue;
+                }
+            }
+            let number = chars[start..i].iter().collect::<String>();
+            let value = number
+                .parse::<Number>()
+                .map_err(|_| Error::Syntax(format!("Invalid number ‘{}’", number)))?;
+            // An `i` directly after a number makes it imaginary (e.g. `4i`), unless it starts a name (e.g. `2 in`).
+            if chars.get(i) == Some(&'i') && !chars.get(i + 1).is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+                i += 1;
+                tokens.push(Token::Imaginary(value));
+            }
+            else {
+                tokens.push(Token::Number(value));
+            }
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' || (c == '°' && chars.get(i + 1).is_some_and(|c| c.is_alphabetic())) {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Identifier(chars[start..i].iter().collect()));
+            continue;
+        }
+        let token = match c {
+            '+' if chars.get(i + 1) == Some(&'/') && chars.get(i + 2) == Some(&'-') => {
+                i += 2;
+                Token::PlusMinus
+            }
+            '±' => Token::PlusMinus,
+            '+' => Token::Operator(Operator::Add),
+            '-' | '−' => Token::Operator(Operator::Subtract),
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                i += 1;
+                Token::Power
+            }
+            '*' | '×' | '·' => Token::Operator(Operator::Multiply),
+            '/' | '÷' => Token::Operator(Operator::Divide),
+            '^' => Token::Power,
+            '%' => Token::Percent,
+            '!' => Token::Factorial,
+            '√' => Token::Identifier("sqrt".to_string()),
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            ',' => Token::Comma,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            '∠' => Token::Angle,
+            '°' => Token::Degree,
+            '=' => Token::Equals,
+            c => return Err(Error::Syntax(format!("Unexpected character ‘{}’", c))),
+        };
+        tokens.push(token);
+        i += 1;
+    }
+    if tokens.is_empty() {
+        return Err(Error::Syntax("Empty expression".to_string()));
+    }
+    Ok(tokens)
+}
+
+/// Returns the end of the minutes and seconds of an angle in degrees, minutes and seconds (e.g. `30'15"` in
+/// `12°30'15"`), or `None` if the degree sign at `start - 1` is not followed by minutes.
+///
+/// # Arguments
+///
+/// * `chars` - The characters of the expression.
+/// * `start` - The index of the character after the degree sign.
+fn dms_end(chars: &[char], start: usize) -> Option<usize> {
+    let part = |from: usize, marks: &[char]| -> Option<usize> {
+        let mut i = from;
+        while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+            i += 1;
+        }
+        let digits = i;
+        while chars.get(i).is_some_and(|c| c.is_ascii_digit() || *c == '.') {
+            i += 1;
+        }
+        (i > digits && chars.get(i).is_some_and(|c| marks.contains(c))).then_some(i + 1)
+    };
+    let end = part(start, &['\'', '′'])?;
+    Some(part(end, &['"', '″']).unwrap_or(end))
+}
+
+/// A recursive descent parser over the tokens of an expression.
+struct Parser {
+    tokens:   Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    /// Returns the next token without consuming it.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    /// Consumes and returns the next token.
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Parses the signature of a function definition (e.g. `f(x, y) =`) at the start of the tokens, up to and
+    /// including the `=`.
+    ///
+    /// # Returns
+    ///
+    /// The name and the parameters of the function, or `None` if the tokens do not start with a signature. Nothing is
+    /// consumed in that case.
+    fn signature(&mut self) -> Result<Option<(String, Vec<String>)>, Error> {
+        let [Token::Identifier(name), Token::LeftParen, rest @ ..] = self.tokens.as_slice()
+        else {
+            return Ok(None);
+        };
+        let mut parameters = Vec::new();
+        let mut index = 0;
+        if rest.first() != Some(&Token::RightParen) {
+            loop {
+                let Some(Token::Identifier(parameter)) = rest.get(index)
+                else {
+                    return Ok(None);
+                };
+                parameters.push(parameter.clone());
+                match rest.get(index + 1) {
+                    Some(Token::Comma) => index += 2,
+                    Some(Token::RightParen) => break,
+                    _ => return Ok(None),
+                }
+            }
+            index += 1;
+        }
+        if rest.get(index) != Some(&Token::RightParen) || rest.get(index + 1) != Some(&Token::Equals) {
+            return Ok(None);
+        }
+
+        for name in std::iter::once(name).chain(&parameters) {
+            if is_reserved(name) {
+                return Err(Error::ReservedName(name.clone()));
+            }
+        }
+        if let Some(duplicate) = parameters
+            .iter()
+            .enumerate()
+            .find_map(|(i, parameter)| parameters[..i].contains(parameter).then_some(parameter))
+        {
+            return Err(Error::Syntax(format!("The parameter ‘{}’ appears twice", duplicate)));
+        }
+        let name = name.clone();
+        self.position = 2 + index + 2;
+        Ok(Some((name, parameters)))
+    }
+
+    /// Consumes the next token, which must be the given one.
+    fn expect(&mut self, expected: &Token) -> Result<(), Error> {
+        match self.next() {
+            Some(token) if token == *expected => Ok(()),
+            Some(token) => Err(Error::Syntax(format!("Expected ‘{}’ before ‘{}’", expected, token))),
+            None => Err(Error::Syntax(format!("Missing ‘{}’", expected))),
+        }
+    }
+
+    /// Parses an expression, optionally converted to other units or to an angle unit with `to`.
+    fn conversion(&mut self) -> Result<Expression, Error> {
+        let expression = self.expression()?;
+        if matches!(self.peek(), Some(Token::Identifier(name)) if name == CONVERSION) {
+            self.position += 1;
+            if let Some(angle_unit) = self.angle_unit() {
+                return Ok(Expression::ConvertAngle(Box::new(expression), angle_unit));
+            }
+            return Ok(Expression::Convert(Box::new(expression), self.units(false)?));
+        }
+        Ok(expression)
+    }
+
+    /// Consumes the name of an angle unit (`deg` or `°`, `rad` or `grad`) if it is the next token.
+    ///
+    /// # Returns
+    ///
+    /// The angle unit, or `None` if the next token is not an angle unit.
+    fn angle_unit(&mut self) -> Option<AngleUnit> {
+        let angle_unit = match self.peek()? {
+            Token::Degree => AngleUnit::Degrees,
+            Token::Identifier(name) => {
+                match name.as_str() {
+                    "deg" => AngleUnit::Degrees,
+                    "rad" => AngleUnit::Radians,
+                    "grad" => AngleUnit::Gradians,
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        self.position += 1;
+        Some(angle_unit)
+    }
+
+    /// Parses a sum or difference of terms.
+    fn expression(&mut self) -> Result<Expression, Error> {
+        let mut lhs = self.term()?;
+        while let Some(Token::Operator(operator @ (Operator::Add | Operator::Subtract))) = self.peek() {
+            let operator = *operator;
+            self.position += 1;
+            lhs = Expression::Binary(operator, Box::new(lhs), Box::new(self.term()?));
+        }
+        Ok(lhs)
+    }
+
+    /// Parses a product or quotient of factors.
+    fn term(&mut self) -> Result<Expression, Error> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Operator(operator @ (Operator::Multiply | Operator::Divide))) = self.peek() {
+            let operator = *operator;
+            self.position += 1;
+            lhs = Expression::Binary(operator, Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    /// Parses a factor with an optional sign. Powers bind more tightly than signs, so `-2^2` is `-4`.
+    fn unary(&mut self) -> Result<Expression, Error> {
+        match self.peek() {
+            Some(Token::Operator(Operator::Subtract)) => {
+                self.position += 1;
+                Ok(Expression::Negate(Box::new(self.unary()?)))
+            }
+            Some(Token::Operator(Operator::Add)) => {
+                self.position += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    /// Parses a power and the percentages and factorials of its base, a complex number in polar form (e.g.
+    /// `5∠53.13°`) or a number with an uncertainty (e.g. `12.3 ± 0.2`). Powers are right associative, so `2^3^2` is
+    /// `2^9`, and factorials bind more tightly than powers, so `3!^2` is `36`.
+    fn power(&mut self) -> Result<Expression, Error> {
+        let mut base = self.primary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Percent) => base = Expression::Percent(Box::new(base)),
+                Some(Token::Factorial) => base = Expression::Call("fact".to_string(), vec![base]),
+                _ => break,
+            }
+            self.position += 1;
+        }
+        if self.peek() == Some(&Token::Angle) {
+            self.position += 1;
+            let argument = self.unary()?;
+            let angle_unit = (self.peek() == Some(&Token::Degree)).then_some(AngleUnit::Degrees);
+            if angle_unit.is_some() {
+                self.position += 1;
+            }
+            return Ok(Expression::Polar(Box::new(base), Box::new(argument), angle_unit));
+        }
+        if self.peek() == Some(&Token::PlusMinus) {
+            self.position += 1;
+            return Ok(Expression::Uncertain(Box::new(base), Box::new(self.unary()?)));
+        }
+        if self.peek() == Some(&Token::Power) {
+            self.position += 1;
+            return Ok(Expression::Power(Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    /// Parses a number, an imaginary number, a duration, a variable, a function call or a parenthesized expression.
+    /// The argument of a function that takes a single argument may be written without parentheses (e.g. `√2`).
+    /// Numbers and parenthesized expressions may be followed by units.
+    fn primary(&mut self) -> Result<Expression, Error> {
+        match self.next() {
+            Some(Token::Number(number)) if self.peek() == Some(&Token::Degree) => {
+                self.position += 1;
+                Ok(Expression::Angle(Box::new(Expression::Number(number)), AngleUnit::Degrees))
+            }
+            Some(Token::Number(number)) => self.with_units(Expression::Number(number)),
+            Some(Token::Imaginary(number)) => Ok(Expression::Imaginary(number)),
+            Some(Token::Clock(seconds)) => {
+                Ok(Expression::WithUnits(
+                    Box::new(Expression::Number(seconds)),
+                    Units::clock(),
+                ))
+            }
+            Some(Token::Identifier(name)) => {
+                if name == SOLVE && self.peek() == Some(&Token::LeftParen) {
+                    self.position += 1;
+                    self.solve()
+                }
+                else if self.peek() == Some(&Token::LeftParen) {
+                    self.position += 1;
+                    let arguments = self.arguments()?;
+                    Ok(Expression::Call(name, arguments))
+                }
+                else if function(&name).is_some() {
+                    let argument = self.power()?;
+                    Ok(Expression::Call(name, vec![argument]))
+                }
+                else {
+                    Ok(Expression::Variable(name))
+                }
+            }
+            Some(Token::LeftParen) => {
+                let expression = self.conversion()?;
+                self.expect(&Token::RightParen)?;
+                self.with_units(expression)
+            }
+            Some(Token::LeftBracket) => self.matrix(),
+            Some(token) => Err(Error::Syntax(format!("Unexpected ‘{}’", token))),
+            None => Err(Error::Syntax("Unexpected end of expression".to_string())),
+        }
+    }
+
+    /// Parses a matrix after its opening bracket: bracketed rows (e.g. `[[1, 2], [3, 4]]`), or the entries of a
+    /// column vector (e.g. `[1, 2, 3]`).
+    fn matrix(&mut self) -> Result<Expression, Error> {
+        if self.peek() != Some(&Token::LeftBracket) {
+            let entries = self.list(&Token::RightBracket)?;
+            return Ok(Expression::Matrix(entries.into_iter().map(|entry| vec![entry]).collect()));
+        }
+        let mut rows = Vec::new();
+        loop {
+            self.expect(&Token::LeftBracket)?;
+            rows.push(self.list(&Token::RightBracket)?);
+            if self.peek() == Some(&Token::Comma) {
+                self.position += 1;
+            }
+            else {
+                self.expect(&Token::RightBracket)?;
+                return Ok(Expression::Matrix(rows));
+            }
+        }
+    }
+
+    /// Parses the comma separated arguments of a function call, after its opening parenthesis.
+    fn arguments(&mut self) -> Result<Vec<Expression>, Error> {
+        if self.peek() == Some(&Token::RightParen) {
+            self.position += 1;
+            return Ok(Vec::new());
+        }
+        self.list(&Token::RightParen)
+    }
+
+    /// Parses a non-empty list of comma separated expressions, up to and including the closing token.
+    fn list(&mut self, close: &Token) -> Result<Vec<Expression>, Error> {
+        let mut expressions = Vec::new();
+        loop {
+            expressions.push(self.conversion()?);
+            if self.peek() == Some(&Token::Comma) {
+                self.position += 1;
+            }
+            else {
+                self.expect(close)?;
+                return Ok(expressions);
+            }
+        }
+    }
+
+    /// Parses the arguments of `solve`, after its opening parenthesis: an equation or an expression equal to zero,
+    /// the variable solved for, and optionally the two ends of the bracket searched.
+    fn solve(&mut self) -> Result<Expression, Error> {
+        let lhs = self.conversion()?;
+        let equation = if self.peek() == Some(&Token::Equals) {
+            self.position += 1;
+            Expression::Binary(Operator::Subtract, Box::new(lhs), Box::new(self.conversion()?))
+        }
+        else {
+            lhs
+        };
+        self.expect(&Token::Comma)?;
+        let variable = match self.next() {
+            Some(Token::Identifier(name)) if !is_reserved(&name) => name,
+            Some(token) => return Err(Error::Syntax(format!("Expected a variable before ‘{}’", token))),
+            None => return Err(Error::Syntax("Missing variable to solve for".to_string())),
+        };
+        let bracket = if self.peek() == Some(&Token::Comma) {
+            self.position += 1;
+            let from = self.conversion()?;
+            self.expect(&Token::Comma)?;
+            Some((Box::new(from), Box::new(self.conversion()?)))
+        }
+        else {
+            None
+        };
+        self.expect(&Token::RightParen)?;
+        Ok(Expression::Solve(Box::new(equation), variable, bracket))
+    }
+
+    /// Returns `true` if the token at the given offset from the current position is the name of a unit, and not a
+    /// function call.
+    fn is_unit_at(&self, offset: usize) -> bool {
+        matches!(self.tokens.get(self.position + offset), Some(Token::Identifier(name)) if units::find(name).is_some())
+            && self.tokens.get(self.position + offset + 1) != Some(&Token::LeftParen)
+    }
+
+    /// Parses the units following a number or a parenthesized expression, if any.
+    fn with_units(&mut self, expression: Expression) -> Result<Expression, Error> {
+        if self.is_unit_at(0) {
+            return Ok(Expression::WithUnits(Box::new(expression), self.units(true)?));
+        }
+        Ok(expression)
+    }
+
+    /// Parses a product or quotient of units (e.g. `km/h` or `m^3/(kg·s^2)`).
+    ///
+    /// # Arguments
+    ///
+    /// * `postfix` - Whether the units follow a quantity. `*` and `/` then only continue the units when a unit name
+    ///   follows them.
+    fn units(&mut self, postfix: bool) -> Result<Units, Error> {
+        let mut units = self.unit_power()?;
+        while let Some(Token::Operator(operator @ (Operator::Multiply | Operator::Divide))) = self.peek() {
+            let operator = *operator;
+            if postfix && !self.is_unit_at(1) {
+                break;
+            }
+            self.position += 1;
+            let rhs = self.unit_power()?;
+            units = match operator {
+                Operator::Divide => units.mul(&rhs.pow(-1)),
+                _ => units.mul(&rhs),
+            };
+        }
+        Ok(units)
+    }
+
+    /// Parses a unit or parenthesized units, with an optional integer power (e.g. `s^-2`).
+    fn unit_power(&mut self) -> Result<Units, Error> {
+        let units = match self.next() {
+            Some(Token::Identifier(name)) => {
+                let unit = units::find(&name).ok_or_else(|| Error::Syntax(format!("Unknown unit ‘{}’", name)))?;
+                Units::unit(unit)
+            }
+            Some(Token::LeftParen) => {
+                let units = self.units(false)?;
+                self.expect(&Token::RightParen)?;
+                units
+            }
+            Some(token) => return Err(Error::Syntax(format!("Expected a unit before ‘{}’", token))),
+            None => return Err(Error::Syntax("Missing unit".to_string())),
+        };
+        if self.peek() != Some(&Token::Power) {
+            return Ok(units);
+        }
+        self.position += 1;
+        let negative = self.peek() == Some(&Token::Operator(Operator::Subtract));
+        if negative {
+            self.position += 1;
+        }
+        let exponent = match self.next() {
+            Some(Token::Number(number)) if number.is_integer() => {
+                number
+                    .to_string()
+                    .parse::<i32>()
+                    .map_err(|_| Error::Syntax(format!("The power ‘{}’ of a unit is too large", number)))?
+            }
+            Some(token) => return Err(Error::Syntax(format!("Expected an integer power before ‘{}’", token))),
+            None => return Err(Error::Syntax("Missing power of a unit".to_string())),
+        };
+        Ok(units.pow(if negative { -exponent } else { exponent }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Evaluates a typed expression without variables, functions or previous results.
+    fn value(text: &str, angle_unit: AngleUnit, complex: bool) -> Result<Value, Error> {
+        let (variables, functions) = (BTreeMap::new(), BTreeMap::new());
+        let context = Context {
+            variables: &variables,
+            functions: &functions,
+            answers: &[],
+            angle_unit,
+            complex,
+            interval: false,
+        };
+        match Statement::parse(text)? {
+            Statement::Expression(expression) => expression.value(&context),
+            statement => panic!("{:?} is not an expression", statement),
+        }
+    }
+
+    /// Evaluates a typed expression with a real result, in the given angle unit.
+    fn real(text: &str, angle_unit: AngleUnit) -> f64 {
+        value(text, angle_unit, false)
+            .and_then(Value::into_quantity)
+            .and_then(Quantity::into_number)
+            .unwrap_or_else(|error| panic!("{}: {}", text, error))
+            .to_f64()
+    }
+
+    /// Asserts that two numbers are equal up to rounding errors.
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() <= 1e-9 * expected.abs().max(1.0), "{} is not {}", actual, expected);
+    }
+
+    #[test]
+    fn angles_in_degrees() {
+        for angle_unit in AngleUnit::ALL {
+            assert_close(real("sin(30°)", angle_unit), 0.5);
+            assert_close(real("cos(12°30' + 47°30')", angle_unit), 0.5);
+        }
+        assert_close(real("30°", AngleUnit::Degrees), 30.0);
+        assert_close(real("12°30'15\"", AngleUnit::Degrees), 12.504_166_666_666_667);
+        assert_close(real("-45°", AngleUnit::Gradians), -50.0);
+        assert_close(real("2 * 45°", AngleUnit::Radians), std::f64::consts::FRAC_PI_2);
+    }
+
+    #[test]
+    fn angle_conversions() {
+        assert_close(real("30° to rad", AngleUnit::Degrees), std::f64::consts::FRAC_PI_6);
+        assert_close(real("30° to rad", AngleUnit::Gradians), std::f64::consts::FRAC_PI_6);
+        assert_close(real("1 to deg", AngleUnit::Radians), 57.295_779_513_082_32);
+        assert_close(real("90 to grad", AngleUnit::Degrees), 100.0);
+        assert_close(real("100 to °", AngleUnit::Gradians), 90.0);
+    }
+
+    #[test]
+    fn polar_angles_in_degrees() {
+        for angle_unit in AngleUnit::ALL {
+            let Ok(Value::Complex(complex)) = value("2∠90°", angle_unit, false)
+            else {
+                panic!("2∠90° is not complex");
+            };
+            assert_close(complex.re.to_f64(), 0.0);
+            assert_close(complex.im.to_f64(), 2.0);
+        }
+    }
+
+    /// Evaluates a typed expression with a complex result in complex mode, in radians, as its real and imaginary
+    /// parts.
+    fn complex(text: &str) -> (f64, f64) {
+        match value(text, AngleUnit::Radians, true) {
+            Ok(Value::Complex(complex)) => (complex.re.to_f64(), complex.im.to_f64()),
+            Ok(Value::Quantity(quantity)) if quantity.units.is_empty() => (quantity.value.to_f64(), 0.0),
+            result => panic!("{}: {:?}", text, result),
+        }
+    }
+
+    /// Asserts that two complex numbers are equal up to rounding errors.
+    fn assert_complex(actual: (f64, f64), expected: (f64, f64)) {
+        assert_close(actual.0, expected.0);
+        assert_close(actual.1, expected.1);
+    }
+
+    #[test]
+    fn exponential_and_logarithms() {
+        assert_eq!(real("exp(0)", AngleUnit::Degrees), 1.0);
+        assert_close(real("exp(1)", AngleUnit::Degrees), std::f64::consts::E);
+        assert_close(real("ln(e^3)", AngleUnit::Degrees), 3.0);
+        assert_eq!(real("log(1000)", AngleUnit::Degrees), 3.0);
+        assert_eq!(real("log(0.01)", AngleUnit::Degrees), -2.0);
+        assert_close(real("log(2)", AngleUnit::Degrees), std::f64::consts::LOG10_2);
+        assert_eq!(value("ln(0)", AngleUnit::Degrees, false), Err(Error::InvalidInput));
+        assert_eq!(value("log(-1)", AngleUnit::Degrees, false), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn complex_exponential_and_logarithms() {
+        use std::f64::consts::{FRAC_PI_2, LN_2, LOG10_E, PI};
+
+        assert_complex(complex("exp(pi * i)"), (-1.0, 0.0));
+        assert_complex(complex("exp(1 + pi / 2 * i)"), (0.0, std::f64::consts::E));
+        assert_complex(complex("ln(-1)"), (0.0, PI));
+        assert_complex(complex("ln(2i)"), (LN_2, FRAC_PI_2));
+        assert_complex(complex("ln(-i)"), (0.0, -FRAC_PI_2));
+        assert_complex(complex("log(-100)"), (2.0, PI * LOG10_E));
+        assert_complex(complex("exp(ln(3 - 4i))"), (3.0, -4.0));
+    }
+
+    #[test]
+    fn complex_trigonometry() {
+        let (sinh, cosh) = (1.0_f64.sinh(), 1.0_f64.cosh());
+        assert_complex(complex("sin(i)"), (0.0, sinh));
+        assert_complex(complex("cos(i)"), (cosh, 0.0));
+        assert_complex(complex("tan(i)"), (0.0, sinh / cosh));
+        assert_complex(complex("sin(asin(2))"), (2.0, 0.0));
+        assert_complex(complex("cos(acos(3 + i))"), (3.0, 1.0));
+        assert_complex(complex("tan(atan(1 + 2i))"), (1.0, 2.0));
+        assert_complex(complex("asin(2)"), (std::f64::consts::FRAC_PI_2, -(2.0 + 3.0_f64.sqrt()).ln()));
+    }
+
+    #[test]
+    fn bare_degree_sign() {
+        assert!(matches!(value("°", AngleUnit::Degrees, false), Err(Error::Syntax(_))));
+        assert!(matches!(value("sin(°)", AngleUnit::Degrees, false), Err(Error::Syntax(_))));
+    }
+
+    #[test]
+    fn assignments() {
+        match Statement::parse("rate = 0.0725") {
+            Ok(Statement::Assignment(name, _)) => assert_eq!(name, "rate"),
+            statement => panic!("{:?} is not an assignment", statement),
+        }
+        assert!(matches!(Statement::parse("price * (1 + rate) ="), Ok(Statement::Expression(_))));
+        assert_eq!(Statement::parse("sin = 2"), Err(Error::ReservedName("sin".to_string())));
+        assert_eq!(Statement::parse("ans2 = 2"), Err(Error::ReservedName("ans2".to_string())));
+        assert!(matches!(Statement::parse("= 2"), Err(Error::Syntax(_))));
+    }
+
+    #[test]
+    fn variables_and_previous_results() {
+        let number = |text: &str| text.parse::<Number>().unwrap();
+        let variables = BTreeMap::from([
+            ("price".to_string(), Quantity::from(number("200"))),
+            ("rate".to_string(), Quantity::from(number("0.0725"))),
+        ]);
+        let functions = BTreeMap::new();
+        let answers = [Value::from(number("3")), Value::from(number("5"))];
+        let context = Context {
+            variables: &variables,
+            functions: &functions,
+            answers: &answers,
+            angle_unit: AngleUnit::Degrees,
+            complex: false,
+            interval: false,
+        };
+        let evaluate = |text: &str| match Statement::parse(text)? {
+            Statement::Expression(expression) => expression.evaluate(&context).map(|quantity| quantity.value),
+            statement => panic!("{:?} is not an expression", statement),
+        };
+        assert_eq!(evaluate("price * (1 + rate)"), Ok(number("214.5")));
+        assert_eq!(evaluate("ans + ans1"), Ok(number("6")));
+        assert_eq!(evaluate("ans2 - ans"), Ok(number("2")));
+        assert_eq!(evaluate("ans3"), Err(Error::MissingResult("ans3".to_string())));
+        assert_eq!(evaluate("cost"), Err(Error::UnknownName("cost".to_string())));
+    }
+
+    #[test]
+    fn matrix_functions() {
+        let matrix = |text: &str| match value(text, AngleUnit::Degrees, false) {
+            Ok(Value::Matrix(matrix)) => matrix.to_string(),
+            value => panic!("{}: {:?} is not a matrix", text, value),
+        };
+        assert_eq!(matrix("linsolve([[2, 1], [1, 3]], [3, 5])"), "[[0.8], [1.4]]");
+        assert_eq!(matrix("eig([[2, 1], [1, 2]])"), "[[1], [3]]");
+        assert_eq!(value("eig([[0, -1], [1, 0]])", AngleUnit::Degrees, false), Err(Error::NoSolution));
+        assert_eq!(value("linsolve([[1, 2], [2, 4]], [1, 2])", AngleUnit::Degrees, false), Err(Error::SingularMatrix));
+    }
+}
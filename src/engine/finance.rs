@@ -0,0 +1,312 @@
+//! This module contains the time value of money calculations of the financial page: the [`Tvm`] problem relating the
+//! number of periods, the interest rate, the present value, the payment and the future value of a loan or an
+//! investment, and its [`AmortizationRow`]s.
+//!
+//! Cash flows use the usual sign convention of financial calculators: money received is positive and money paid is
+//! negative. A loan of 200000 repaid monthly has a positive present value and a negative payment. The five values
+//! satisfy
+//!
+//! `PV·(1 + i)^N + PMT·(1 + i·k)·((1 + i)^N − 1)/i + FV = 0`
+//!
+//! where `i` is the interest rate per period and `k` is `1` for payments at the beginning of the periods and `0` for
+//! payments at their end. Any of the values can be solved for from the four others. The calculations are exact
+//! whenever the number of periods is an integer; only the interest rate and fractional numbers of periods are
+//! approximate. Amounts of money in the amortization schedule are rounded to cents with banker's rounding.
+
+use super::{Error, Number};
+
+/// The number of decimal places of amounts of money.
+const CENTS: usize = 2;
+
+/// The largest number of periods of an amortization schedule.
+const MAX_SCHEDULE_PERIODS: i64 = 12_000;
+
+/// The largest number of iterations when solving for the interest rate.
+const MAX_RATE_ITERATIONS: usize = 200;
+
+/// One of the five values of a [`Tvm`] problem.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TvmValue {
+    /// The number of periods `N`.
+    Periods,
+    /// The nominal annual interest rate `I/Y` in percent.
+    Rate,
+    /// The present value `PV`.
+    PresentValue,
+    /// The payment per period `PMT`.
+    Payment,
+    /// The future value `FV`.
+    FutureValue,
+}
+
+/// A time value of money problem (e.g. a loan, a mortgage or a savings plan).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tvm {
+    /// The number of periods `N`.
+    pub periods:          Number,
+    /// The nominal annual interest rate `I/Y` in percent.
+    pub rate:             Number,
+    /// The present value `PV`.
+    pub present_value:    Number,
+    /// The payment per period `PMT`.
+    pub payment:          Number,
+    /// The future value `FV`.
+    pub future_value:     Number,
+    /// The number of periods per year `P/Y` (e.g. 12 for monthly payments).
+    pub periods_per_year: u32,
+    /// Whether payments are made at the beginning of the periods (an annuity due) rather than at their end.
+    pub due:              bool,
+}
+
+/// A period of an amortization schedule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AmortizationRow {
+    /// The number of the period, from 1.
+    pub period:    i64,
+    /// The payment made in the period.
+    pub payment:   Number,
+    /// The interest accrued in the period.
+    pub interest:  Number,
+    /// The change of the balance in the period, the sum of the payment and the interest. It is negative when a loan
+    /// is being repaid.
+    pub principal: Number,
+    /// The balance after the period.
+    pub balance:   Number,
+}
+
+impl Tvm {
+    /// Returns the value of the problem solving for one of its values from the four others. The value being solved
+    /// for is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to solve for.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if there is no solution (e.g. a payment that never repays a loan) or the number of
+    ///   periods per year is zero.
+    /// * [`Error::DivisionByZero`] if the problem does not determine the value (e.g. solving for the payment of zero
+    ///   periods).
+    /// * The error of the underlying [`Number`] operation.
+    pub fn solve(&self, value: TvmValue) -> Result<Number, Error> {
+        let one = Number::from_integer(1);
+        let i = self.periodic_rate()?;
+        if value == TvmValue::Rate {
+            return self.solve_rate();
+        }
+        if value == TvmValue::Periods {
+            if i.is_zero() {
+                return self
+                    .present_value
+                    .add(&self.future_value)?
+                    .neg()
+                    .div(&self.payment);
+            }
+            let payment = self.payment.mul(&self.due_factor(&i)?)?;
+            let ratio = payment
+                .sub(&self.future_value.mul(&i)?)?
+                .div(&payment.add(&self.present_value.mul(&i)?)?)?;
+            if ratio.is_negative() || ratio.is_zero() {
+                return Err(Error::InvalidInput);
+            }
+            let periods = ratio.to_f64().ln() / one.add(&i)?.to_f64().ln();
+            if (periods - periods.round()).abs() < 1e-9 {
+                return Ok(Number::from_integer(periods.round() as i64));
+            }
+            return Number::from_real(periods);
+        }
+
+        let growth = one.add(&i)?.pow(&self.periods)?;
+        // The future value of a payment of one per period.
+        let annuity = if i.is_zero() {
+            self.periods.clone()
+        }
+        else {
+            growth
+                .sub(&one)?
+                .div(&i)?
+                .mul(&self.due_factor(&i)?)?
+        };
+        match value {
+            TvmValue::PresentValue => {
+                self.future_value
+                    .add(&self.payment.mul(&annuity)?)?
+                    .neg()
+                    .div(&growth)
+            }
+            TvmValue::Payment => {
+                self.present_value
+                    .mul(&growth)?
+                    .add(&self.future_value)?
+                    .neg()
+                    .div(&annuity)
+            }
+            TvmValue::FutureValue => {
+                Ok(self
+                    .present_value
+                    .mul(&growth)?
+                    .add(&self.payment.mul(&annuity)?)?
+                    .neg())
+            }
+            TvmValue::Periods | TvmValue::Rate => unreachable!("Solved above"),
+        }
+    }
+
+    /// Returns the amortization schedule of the problem: for each period, the payment, the interest and the
+    /// resulting balance, starting from the present value. The payment is rounded to cents, the interest of each
+    /// period is rounded to cents with banker's rounding, and the last payment is adjusted so that the final balance
+    /// is exactly the negated future value.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the number of periods is not a positive integer up to 12000, or the number of
+    ///   periods per year is zero.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn amortization(&self) -> Result<Vec<AmortizationRow>, Error> {
+        let periods = self
+            .periods
+            .is_integer()
+            .then(|| self.periods.to_f64() as i64)
+            .filter(|periods| (1..=MAX_SCHEDULE_PERIODS).contains(periods))
+            .ok_or(Error::InvalidInput)?;
+        let i = self.periodic_rate()?;
+        let payment = self.payment.round_half_even(CENTS)?;
+        let end = self.future_value.neg().round_half_even(CENTS)?;
+        let mut balance = self.present_value.round_half_even(CENTS)?;
+        // The interest of a period, on the balance after the payment for payments at the beginning of the period.
+        let interest_on = |balance: &Number, payment: &Number| -> Result<Number, Error> {
+            let principal = if self.due { balance.add(payment)? } else { balance.clone() };
+            principal.mul(&i)?.round_half_even(CENTS)
+        };
+        let mut rows = Vec::new();
+        for period in 1..=periods {
+            let mut interest = interest_on(&balance, &payment)?;
+            let mut payment = payment.clone();
+            if period == periods {
+                // The last payment clears the remaining balance, up to the future value. Payments at the beginning
+                // of the period are made before the interest accrues, so the interest depends on the payment.
+                if self.due {
+                    payment = end
+                        .div(&Number::from_integer(1).add(&i)?)?
+                        .sub(&balance)?
+                        .round_half_even(CENTS)?;
+                    interest = interest_on(&balance, &payment)?;
+                }
+                payment = end.sub(&balance)?.sub(&interest)?;
+            }
+            let principal = payment.add(&interest)?;
+            balance = balance.add(&principal)?;
+            rows.push(AmortizationRow {
+                period,
+                payment,
+                interest,
+                principal,
+                balance: balance.clone(),
+            });
+        }
+        Ok(rows)
+    }
+
+    /// Returns the interest rate per period as a fraction (e.g. `0.005` for 6 % per year paid monthly).
+    fn periodic_rate(&self) -> Result<Number, Error> {
+        if self.periods_per_year == 0 {
+            return Err(Error::InvalidInput);
+        }
+        self.rate
+            .div(&Number::from_integer(100 * i64::from(self.periods_per_year)))
+    }
+
+    /// Returns the factor of the payments: `1 + i` for payments at the beginning of the periods, `1` otherwise.
+    fn due_factor(&self, i: &Number) -> Result<Number, Error> {
+        if self.due {
+            Number::from_integer(1).add(i)
+        }
+        else {
+            Ok(Number::from_integer(1))
+        }
+    }
+
+    /// Solves for the nominal annual interest rate with Newton's method, starting from 10 % per year. The rate is
+    /// exactly zero when the payments alone account for the present and future values.
+    fn solve_rate(&self) -> Result<Number, Error> {
+        let periods = self.periods.to_f64();
+        let (pv, pmt, fv) = (
+            self.present_value.to_f64(),
+            self.payment.to_f64(),
+            self.future_value.to_f64(),
+        );
+        let k = if self.due { 1.0 } else { 0.0 };
+        // `((1 + i)^N − 1)/i`, written with `exp_m1` and `ln_1p` so that it stays accurate for small rates.
+        let annuity = |i: f64| {
+            if i == 0.0 {
+                periods
+            }
+            else {
+                (periods * i.ln_1p()).exp_m1() / i
+            }
+        };
+        // The value of the equation for a periodic rate, which is zero at the solution.
+        let equation = |i: f64| pv * (periods * i.ln_1p()).exp() + pmt * (1.0 + i * k) * annuity(i) + fv;
+        let scale = pv.abs() + (pmt * periods).abs() + fv.abs();
+        if equation(0.0).abs() <= 1e-12 * scale {
+            return Ok(Number::zero());
+        }
+        let mut i = 0.1 / f64::from(self.periods_per_year.max(1));
+        for _ in 0..MAX_RATE_ITERATIONS {
+            let step = (i.abs() * 1e-6).max(1e-10);
+            let slope = (equation(i + step) - equation(i - step)) / (2.0 * step);
+            if slope == 0.0 || !slope.is_finite() {
+                break;
+            }
+            let next = i - equation(i) / slope;
+            if !next.is_finite() || next <= -1.0 {
+                break;
+            }
+            if (next - i).abs() < 1e-14 || (next - i).abs() <= 1e-12 * next.abs() {
+                return Number::from_real(next * 100.0 * f64::from(self.periods_per_year));
+            }
+            i = next;
+        }
+        Err(Error::InvalidInput)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a problem with payments at the end of the periods.
+    fn tvm(periods: i64, rate: &str, present_value: &str, payment: &str, periods_per_year: u32) -> Tvm {
+        Tvm {
+            periods: Number::from_integer(periods),
+            rate: rate.parse().unwrap(),
+            present_value: present_value.parse().unwrap(),
+            payment: payment.parse().unwrap(),
+            future_value: Number::zero(),
+            periods_per_year,
+            due: false,
+        }
+    }
+
+    #[test]
+    fn rate_is_zero_without_interest() {
+        let tvm = tvm(12, "0", "12000", "-1000", 12);
+        assert_eq!(tvm.solve(TvmValue::Rate), Ok(Number::zero()));
+    }
+
+    #[test]
+    fn rate_of_a_mortgage() {
+        let tvm = tvm(360, "0", "200000", "-1199.101050", 12);
+        let rate = tvm.solve(TvmValue::Rate).unwrap().to_f64();
+        assert!((rate - 6.0).abs() < 1e-6, "{}", rate);
+    }
+
+    #[test]
+    fn small_rate_round_trip() {
+        let mut tvm = tvm(48, "0.01", "10000", "0", 12);
+        tvm.payment = tvm.solve(TvmValue::Payment).unwrap();
+        let rate = tvm.solve(TvmValue::Rate).unwrap().to_f64();
+        assert!((rate - 0.01).abs() < 1e-9, "{}", rate);
+    }
+}
@@ -0,0 +1,377 @@
+//! This module contains the [`Interval`] numbers of typed expressions in interval mode: ranges of values (e.g.
+//! `[1.2, 1.5]`) used to bound worst-case tolerances. The result of an operation on intervals contains the result of
+//! the operation on every combination of values of its operands, so `[1, 2] − [1, 2]` is `[−1, 1]`.
+//!
+//! Exact bounds stay exact. Approximate bounds are rounded outward: the lower bound down and the upper bound up by two
+//! units in the last place, which covers the rounding of the operands and of the operation itself.
+//!
+//! Monotonic functions are applied to both bounds, in reverse order for decreasing ones (e.g. `acos`). The sine and
+//! cosine reach `1` or `−1` when a maximum or a minimum lies in the interval, whatever their values at the bounds.
+//! Results that would be unbounded are an [`Error::UnboundedInterval`]: a division by an interval containing zero
+//! (e.g. `1 / [−1, 2]`), and the tangent of an interval containing one of its poles (e.g. `tan([80, 100])` in
+//! degrees).
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::{AngleUnit, Error, Function, Number, Operator};
+
+/// The number of units in the last place by which approximate bounds are widened.
+const ROUNDING_ULPS: usize = 2;
+
+/// The margin, relative to the angles of an interval, within which a maximum, a minimum or a pole of a trigonometric
+/// function just outside of the interval is treated as inside of it, so that approximate angles are not missed.
+const ANGLE_MARGIN: f64 = 1e-12;
+
+/// A closed interval of real numbers (e.g. `[1.2, 1.5]`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interval {
+    /// The smallest value of the interval.
+    pub lower: Number,
+    /// The largest value of the interval, not less than the lower bound.
+    pub upper: Number,
+}
+
+impl Interval {
+    /// Creates a new interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `lower` - The smallest value of the interval.
+    /// * `upper` - The largest value of the interval.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the lower bound is greater than the upper bound.
+    pub fn new(lower: Number, upper: Number) -> Result<Self, Error> {
+        if compare(&lower, &upper) == Ordering::Greater {
+            return Err(Error::InvalidInput);
+        }
+        Ok(Self { lower, upper })
+    }
+
+    /// Returns `true` if the interval holds a single number.
+    pub fn is_point(&self) -> bool {
+        compare(&self.lower, &self.upper) == Ordering::Equal
+    }
+
+    /// Returns `true` if zero lies in the interval.
+    pub fn contains_zero(&self) -> bool {
+        (self.lower.is_negative() || self.lower.is_zero()) && !self.upper.is_negative()
+    }
+
+    /// Returns the number halfway between the bounds.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn midpoint(&self) -> Result<Number, Error> {
+        self.lower.add(&self.upper)?.div(&Number::from_integer(2))
+    }
+
+    /// Applies a binary operator to two intervals.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnboundedInterval`] if the operator is a division and `other` contains zero.
+    /// * [`Error::Overflow`] if an approximate bound is too large.
+    pub fn apply(&self, operator: Operator, other: &Interval) -> Result<Interval, Error> {
+        match operator {
+            Operator::Add => outward(self.lower.add(&other.lower)?, self.upper.add(&other.upper)?),
+            Operator::Subtract => outward(self.lower.sub(&other.upper)?, self.upper.sub(&other.lower)?),
+            Operator::Multiply => {
+                hull(vec![
+                    self.lower.mul(&other.lower)?,
+                    self.lower.mul(&other.upper)?,
+                    self.upper.mul(&other.lower)?,
+                    self.upper.mul(&other.upper)?,
+                ])
+            }
+            Operator::Divide => {
+                if other.contains_zero() {
+                    return Err(Error::UnboundedInterval);
+                }
+                hull(vec![
+                    self.lower.div(&other.lower)?,
+                    self.lower.div(&other.upper)?,
+                    self.upper.div(&other.lower)?,
+                    self.upper.div(&other.upper)?,
+                ])
+            }
+        }
+    }
+
+    /// Returns the negated interval.
+    pub fn neg(&self) -> Interval {
+        Self {
+            lower: self.upper.neg(),
+            upper: self.lower.neg(),
+        }
+    }
+
+    /// Returns the interval of the reciprocals of the values of the interval.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnboundedInterval`] if the interval contains zero.
+    /// * [`Error::Overflow`] if an approximate bound is too large.
+    pub fn recip(&self) -> Result<Interval, Error> {
+        if self.contains_zero() {
+            return Err(Error::UnboundedInterval);
+        }
+        outward(self.upper.recip()?, self.lower.recip()?)
+    }
+
+    /// Raises the interval to a power, which may be an interval too. Integer powers take any interval; other powers
+    /// take intervals of numbers that are not negative.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the exponent is not an integer and the interval contains negative numbers.
+    /// * [`Error::UnboundedInterval`] if the interval contains zero and the exponent is negative, or may be.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn pow(&self, exponent: &Interval) -> Result<Interval, Error> {
+        if exponent.is_point() && exponent.lower.is_integer() {
+            let exponent = &exponent.lower;
+            if exponent.is_negative() {
+                return self.pow(&Interval::from(exponent.neg()))?.recip();
+            }
+            let mut values = vec![self.lower.pow(exponent)?, self.upper.pow(exponent)?];
+            // Even powers of intervals containing zero reach zero between their bounds.
+            let even = exponent.div(&Number::from_integer(2))?.is_integer();
+            if even && self.contains_zero() {
+                values.push(Number::zero());
+            }
+            return hull(values);
+        }
+        if self.lower.is_negative() {
+            return Err(Error::InvalidInput);
+        }
+        if self.lower.is_zero() && !is_positive(&exponent.lower) {
+            return Err(Error::UnboundedInterval);
+        }
+        // `x^y` is monotonic in `x` and in `y` for positive `x`, so its extremes are at the corners.
+        hull(vec![
+            self.lower.pow(&exponent.lower)?,
+            self.lower.pow(&exponent.upper)?,
+            self.upper.pow(&exponent.lower)?,
+            self.upper.pow(&exponent.upper)?,
+        ])
+    }
+
+    /// Applies a function of the keypad to the interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function to apply.
+    /// * `angle_unit` - The unit of the angles taken and returned by the trigonometric functions.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedInterval`] if the function is a number theory function, which takes exact integers.
+    /// * [`Error::UnboundedInterval`] if the function has a pole in the interval (e.g. `1/x` or `tan`).
+    /// * The error of the function applied to a bound outside of its domain (e.g. `asin([0, 2])`).
+    pub fn apply_function(&self, function: Function, angle_unit: AngleUnit) -> Result<Interval, Error> {
+        let apply = |value: &Number| function.apply(value, angle_unit);
+        match function {
+            Function::Square => self.pow(&Interval::from(Number::from_integer(2))),
+            Function::Reciprocal => self.recip(),
+            Function::SquareRoot
+            | Function::ArcSine
+            | Function::ArcTangent
+            | Function::Exponential
+            | Function::NaturalLogarithm
+            | Function::Logarithm => {
+                outward(apply(&self.lower)?, apply(&self.upper)?)
+            }
+            Function::ArcCosine => outward(apply(&self.upper)?, apply(&self.lower)?),
+            Function::Tangent => {
+                if self.reaches(90.0, 180.0, angle_unit)? {
+                    return Err(Error::UnboundedInterval);
+                }
+                outward(apply(&self.lower)?, apply(&self.upper)?)
+            }
+            Function::Sine => self.periodic(function, 90.0, 270.0, angle_unit),
+            Function::Cosine => self.periodic(function, 0.0, 180.0, angle_unit),
+            Function::Factorial | Function::Totient | Function::IsPrime => Err(Error::UnexpectedInterval),
+        }
+    }
+
+    /// Applies the sine or the cosine to the interval, which is bounded by `[−1, 1]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The sine or the cosine.
+    /// * `maximum` - The angle in degrees of a maximum of the function.
+    /// * `minimum` - The angle in degrees of a minimum of the function.
+    /// * `angle_unit` - The unit of the angles of the interval.
+    fn periodic(
+        &self,
+        function: Function,
+        maximum: f64,
+        minimum: f64,
+        angle_unit: AngleUnit,
+    ) -> Result<Interval, Error> {
+        let one = Number::from_integer(1);
+        let mut interval = hull(vec![
+            function.apply(&self.lower, angle_unit)?,
+            function.apply(&self.upper, angle_unit)?,
+        ])?;
+        interval.upper = if self.reaches(maximum, 360.0, angle_unit)? || compare(&interval.upper, &one).is_gt() {
+            one.clone()
+        }
+        else {
+            interval.upper
+        };
+        interval.lower = if self.reaches(minimum, 360.0, angle_unit)? || compare(&interval.lower, &one.neg()).is_lt() {
+            one.neg()
+        }
+        else {
+            interval.lower
+        };
+        Ok(interval)
+    }
+
+    /// Returns `true` if one of the angles `angle + k × period` lies in the interval, for any integer `k`.
+    ///
+    /// # Arguments
+    ///
+    /// * `angle` - An angle in degrees.
+    /// * `period` - The period of the angles in degrees.
+    /// * `angle_unit` - The unit of the angles of the interval.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the interval is too large to be converted to degrees.
+    fn reaches(&self, angle: f64, period: f64, angle_unit: AngleUnit) -> Result<bool, Error> {
+        let lower = angle_unit.convert(&self.lower, AngleUnit::Degrees)?.to_f64();
+        let upper = angle_unit.convert(&self.upper, AngleUnit::Degrees)?.to_f64();
+        let margin = ANGLE_MARGIN * lower.abs().max(upper.abs()).max(1.0);
+        Ok(((lower - margin - angle) / period).ceil() <= ((upper + margin - angle) / period).floor())
+    }
+}
+
+impl From<Number> for Interval {
+    /// Creates an interval holding a single number.
+    fn from(number: Number) -> Self {
+        Self {
+            lower: number.clone(),
+            upper: number,
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    /// Formats the interval as its bounds in square brackets (e.g. `[1.2, 1.5]`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lower, self.upper)
+    }
+}
+
+/// Compares two numbers exactly.
+fn compare(a: &Number, b: &Number) -> Ordering {
+    match (a.to_rational(), b.to_rational()) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.to_f64().total_cmp(&b.to_f64()),
+    }
+}
+
+/// Returns `true` if the number is greater than zero.
+fn is_positive(number: &Number) -> bool {
+    !number.is_negative() && !number.is_zero()
+}
+
+/// Returns the smallest interval containing the given numbers, rounded outward.
+fn hull(values: Vec<Number>) -> Result<Interval, Error> {
+    let lower = values.iter().min_by(|a, b| compare(a, b)).cloned().unwrap_or_else(Number::zero);
+    let upper = values.into_iter().max_by(compare).unwrap_or_else(Number::zero);
+    outward(lower, upper)
+}
+
+/// Creates an interval from its bounds, widening approximate bounds by [`ROUNDING_ULPS`] units in the last place.
+///
+/// # Errors
+///
+/// * [`Error::Overflow`] if a widened bound is too large.
+fn outward(lower: Number, upper: Number) -> Result<Interval, Error> {
+    Ok(Interval {
+        lower: widen(lower, f64::next_down)?,
+        upper: widen(upper, f64::next_up)?,
+    })
+}
+
+/// Moves an approximate number by [`ROUNDING_ULPS`] steps to the next floating point value, and returns an exact
+/// number unchanged. An approximate zero is kept too, since floating point sums and the functions of the keypad are
+/// only exactly zero when the exact result is (e.g. `atan(0)` in radians).
+fn widen(number: Number, step: fn(f64) -> f64) -> Result<Number, Error> {
+    match number {
+        Number::Real(value) if value != 0.0 => {
+            Number::from_real((0..ROUNDING_ULPS).fold(value, |value, _| step(value)))
+        }
+        number => Ok(number),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an interval from the decimal notations of its bounds.
+    fn interval(lower: &str, upper: &str) -> Interval {
+        Interval::new(lower.parse().unwrap(), upper.parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn exact_bounds() {
+        let apply = |a: Interval, operator, b: Interval| a.apply(operator, &b);
+        assert_eq!(apply(interval("1", "2"), Operator::Subtract, interval("1", "2")), Ok(interval("-1", "1")));
+        assert_eq!(apply(interval("1", "2"), Operator::Multiply, interval("-3", "4")), Ok(interval("-6", "8")));
+        assert_eq!(apply(interval("1", "2"), Operator::Divide, interval("4", "8")), Ok(interval("0.125", "0.5")));
+        assert_eq!(interval("-2", "3").pow(&interval("2", "2")), Ok(interval("0", "9")));
+        assert_eq!(interval("-2", "3").pow(&interval("3", "3")), Ok(interval("-8", "27")));
+        assert_eq!(interval("2", "4").pow(&interval("-1", "-1")), Ok(interval("0.25", "0.5")));
+        assert_eq!(interval("1", "2").neg(), interval("-2", "-1"));
+        assert_eq!(interval("1", "2").midpoint(), Ok("1.5".parse().unwrap()));
+        assert_eq!(Interval::new(Number::from_integer(2), Number::from_integer(1)), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn outward_rounding() {
+        let root = interval("2", "3").apply_function(Function::SquareRoot, AngleUnit::Radians).unwrap();
+        assert_eq!(root.lower.to_f64(), 2f64.sqrt().next_down().next_down());
+        assert_eq!(root.upper.to_f64(), 3f64.sqrt().next_up().next_up());
+        let tenths = Interval::new(Number::Real(0.1), Number::Real(0.2)).unwrap();
+        let sum = tenths.apply(Operator::Add, &tenths).unwrap();
+        assert!(sum.lower.to_f64() < 0.2 && sum.upper.to_f64() > 0.4);
+        let arctangent = interval("0", "1").apply_function(Function::ArcTangent, AngleUnit::Radians).unwrap();
+        assert!(arctangent.lower.is_zero());
+        let arccosine = interval("0", "1").apply_function(Function::ArcCosine, AngleUnit::Radians).unwrap();
+        assert!(arccosine.lower.is_zero() && arccosine.upper.to_f64() > std::f64::consts::FRAC_PI_2);
+    }
+
+    #[test]
+    fn division_by_an_interval_containing_zero() {
+        let one = Interval::from(Number::from_integer(1));
+        assert_eq!(one.apply(Operator::Divide, &interval("-1", "2")), Err(Error::UnboundedInterval));
+        assert_eq!(one.apply(Operator::Divide, &interval("0", "1")), Err(Error::UnboundedInterval));
+        assert_eq!(interval("-1", "0").recip(), Err(Error::UnboundedInterval));
+        assert_eq!(interval("-1", "2").pow(&interval("-2", "-2")), Err(Error::UnboundedInterval));
+        assert_eq!(interval("0", "4").pow(&interval("-0.5", "0.5")), Err(Error::UnboundedInterval));
+        assert_eq!(interval("-1", "4").pow(&interval("0.5", "0.5")), Err(Error::InvalidInput));
+        assert_eq!(one.apply(Operator::Divide, &interval("-2", "-1")), Ok(interval("-1", "-0.5")));
+    }
+
+    #[test]
+    fn trigonometric_functions() {
+        let apply = |function, lower: &str, upper: &str| {
+            interval(lower, upper).apply_function(function, AngleUnit::Degrees)
+        };
+        assert_eq!(apply(Function::Sine, "0", "30"), Ok(interval("0", "0.5")));
+        assert_eq!(apply(Function::Sine, "80", "100").map(|sine| sine.upper), Ok(Number::from_integer(1)));
+        assert_eq!(apply(Function::Cosine, "90", "270").map(|cosine| cosine.lower), Ok(Number::from_integer(-1)));
+        assert_eq!(apply(Function::Sine, "-720", "720"), Ok(interval("-1", "1")));
+        assert_eq!(apply(Function::Tangent, "80", "100"), Err(Error::UnboundedInterval));
+        assert_eq!(apply(Function::Tangent, "-45", "45"), Ok(interval("-1", "1")));
+        assert_eq!(apply(Function::Factorial, "1", "2"), Err(Error::UnexpectedInterval));
+    }
+}
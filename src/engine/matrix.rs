@@ -0,0 +1,564 @@
+//! This module contains the [`Matrix`] type of typed expressions and of the matrix page. Matrices are written row by
+//! row (e.g. `[[1, 2], [3, 4]]`), and vectors as a single list of entries (e.g. `[1, 2, 3]`), which is a column.
+//!
+//! Entries are [`Number`]s, so the determinant, the inverse and the solution of a linear system of a matrix of exact
+//! entries are exact. They are computed by Gauss–Jordan elimination, choosing the entry of largest magnitude as
+//! pivot. With approximate entries, entries smaller than the largest one by a factor of [`NEGLIGIBLE`] are taken as
+//! zero, so nearly singular matrices are singular. Eigenvalues are the roots of the characteristic polynomial,
+//! computed exactly with the Faddeev–LeVerrier algorithm and then solved like an equation, so they may be complex.
+
+use std::fmt;
+
+use super::solver::{Polynomial, Root};
+use super::{Error, Number};
+
+/// The largest number of rows of the matrices whose eigenvalues are computed.
+pub const MAX_EIGENVALUE_SIZE: usize = 10;
+
+/// The ratio to the largest entry under which approximate entries are taken as zero during elimination.
+const NEGLIGIBLE: f64 = 1e-12;
+
+/// A matrix of numbers, with at least one row and one column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix {
+    rows:    usize,
+    columns: usize,
+    /// The entries, row by row.
+    entries: Vec<Number>,
+}
+
+/// The result of a Gauss–Jordan elimination.
+struct Elimination {
+    /// The matrix in reduced row echelon form.
+    reduced:     Matrix,
+    /// The number of pivots, in the columns eliminated.
+    rank:        usize,
+    /// The product of the pivots, negated for each swap of rows: the determinant of a square matrix.
+    determinant: Number,
+}
+
+impl Matrix {
+    /// Creates a matrix from its rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The rows of the matrix, which must all have the same number of entries.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if there are no entries or the rows have different lengths.
+    pub fn new(rows: Vec<Vec<Number>>) -> Result<Self, Error> {
+        let columns = rows.first().map_or(0, Vec::len);
+        if columns == 0 {
+            return Err(Error::MatrixSize("A matrix needs at least one entry".to_string()));
+        }
+        if rows.iter().any(|row| row.len() != columns) {
+            return Err(Error::MatrixSize("The rows of a matrix need the same number of entries".to_string()));
+        }
+        Ok(Self {
+            rows: rows.len(),
+            columns,
+            entries: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Creates a column vector.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if there are no entries.
+    pub fn column(entries: Vec<Number>) -> Result<Self, Error> {
+        Self::new(entries.into_iter().map(|entry| vec![entry]).collect())
+    }
+
+    /// Creates the identity matrix with `size` rows and columns.
+    pub fn identity(size: usize) -> Self {
+        Self::from_fn(size, size, |row, column| Number::from_integer(i64::from(row == column)))
+    }
+
+    /// Creates a matrix from a function of the row and the column of each entry.
+    fn from_fn(rows: usize, columns: usize, f: impl Fn(usize, usize) -> Number) -> Self {
+        Self {
+            rows,
+            columns,
+            entries: (0..rows * columns)
+                .map(|index| f(index / columns, index % columns))
+                .collect(),
+        }
+    }
+
+    /// Creates a matrix from a fallible function of the row and the column of each entry.
+    fn try_from_fn(
+        rows: usize,
+        columns: usize,
+        f: impl Fn(usize, usize) -> Result<Number, Error>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            rows,
+            columns,
+            entries: (0..rows * columns)
+                .map(|index| f(index / columns, index % columns))
+                .collect::<Result<Vec<Number>, Error>>()?,
+        })
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the entry at a row and a column, counted from zero.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if the row or the column is out of range.
+    pub fn get(&self, row: usize, column: usize) -> &Number {
+        assert!(row < self.rows && column < self.columns, "Matrix index out of range");
+        &self.entries[row * self.columns + column]
+    }
+
+    /// Returns the size of the matrix as shown in error messages (e.g. `2×3`).
+    pub fn size(&self) -> String {
+        format!("{}×{}", self.rows, self.columns)
+    }
+
+    /// Returns the entries of a vector: a matrix with a single row or a single column.
+    fn vector(&self) -> Option<&[Number]> {
+        (self.rows == 1 || self.columns == 1).then_some(self.entries.as_slice())
+    }
+
+    /// Checks that the matrix is square.
+    fn check_square(&self) -> Result<(), Error> {
+        if self.rows == self.columns {
+            Ok(())
+        }
+        else {
+            Err(Error::MatrixSize(format!("Expected a square matrix, not a {} one", self.size())))
+        }
+    }
+
+    /// Adds two matrices of the same size.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if the matrices have different sizes.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn add(&self, other: &Matrix) -> Result<Matrix, Error> {
+        self.check_same_size(other)?;
+        Self::try_from_fn(self.rows, self.columns, |row, column| {
+            self.get(row, column).add(other.get(row, column))
+        })
+    }
+
+    /// Subtracts a matrix of the same size from this one.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if the matrices have different sizes.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn sub(&self, other: &Matrix) -> Result<Matrix, Error> {
+        self.check_same_size(other)?;
+        Self::try_from_fn(self.rows, self.columns, |row, column| {
+            self.get(row, column).sub(other.get(row, column))
+        })
+    }
+
+    /// Checks that two matrices have the same size.
+    fn check_same_size(&self, other: &Matrix) -> Result<(), Error> {
+        if self.rows == other.rows && self.columns == other.columns {
+            Ok(())
+        }
+        else {
+            Err(Error::MatrixSize(format!(
+                "Cannot combine a {} matrix with a {} one",
+                self.size(),
+                other.size()
+            )))
+        }
+    }
+
+    /// Multiplies this matrix by another one on the right.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if the number of columns of this matrix is not the number of rows of the other one.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn mul(&self, other: &Matrix) -> Result<Matrix, Error> {
+        if self.columns != other.rows {
+            return Err(Error::MatrixSize(format!(
+                "Cannot multiply a {} matrix by a {} one",
+                self.size(),
+                other.size()
+            )));
+        }
+        Self::try_from_fn(self.rows, other.columns, |row, column| {
+            (0..self.columns).try_fold(Number::zero(), |sum, k| {
+                sum.add(&self.get(row, k).mul(other.get(k, column))?)
+            })
+        })
+    }
+
+    /// Multiplies every entry by a number.
+    ///
+    /// # Errors
+    ///
+    /// The error of the underlying [`Number`] operation.
+    pub fn scale(&self, factor: &Number) -> Result<Matrix, Error> {
+        Self::try_from_fn(self.rows, self.columns, |row, column| self.get(row, column).mul(factor))
+    }
+
+    /// Returns the matrix with every entry negated.
+    pub fn neg(&self) -> Matrix {
+        Self::from_fn(self.rows, self.columns, |row, column| self.get(row, column).neg())
+    }
+
+    /// Returns the transpose of the matrix.
+    pub fn transpose(&self) -> Matrix {
+        Self::from_fn(self.columns, self.rows, |row, column| self.get(column, row).clone())
+    }
+
+    /// Raises a square matrix to an integer power. Negative powers are powers of the inverse.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if the matrix is not square.
+    /// * [`Error::SingularMatrix`] if the power is negative and the matrix has no inverse.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn pow(&self, exponent: i32) -> Result<Matrix, Error> {
+        self.check_square()?;
+        let mut base = if exponent < 0 { self.inverse()? } else { self.clone() };
+        let mut exponent = exponent.unsigned_abs();
+        let mut power = Self::identity(self.rows);
+        // Exponentiation by squaring.
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                power = power.mul(&base)?;
+            }
+            exponent /= 2;
+            if exponent > 0 {
+                base = base.mul(&base)?;
+            }
+        }
+        Ok(power)
+    }
+
+    /// Returns the determinant of a square matrix.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if the matrix is not square.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn determinant(&self) -> Result<Number, Error> {
+        self.check_square()?;
+        let elimination = self.eliminate(self.columns)?;
+        if elimination.rank < self.rows {
+            return Ok(Number::zero());
+        }
+        Ok(elimination.determinant)
+    }
+
+    /// Returns the inverse of a square matrix.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if the matrix is not square.
+    /// * [`Error::SingularMatrix`] if the matrix has no inverse.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn inverse(&self) -> Result<Matrix, Error> {
+        self.solve(&Self::identity(self.rows))
+    }
+
+    /// Returns the rank of the matrix: the number of its linearly independent rows.
+    ///
+    /// # Errors
+    ///
+    /// The error of the underlying [`Number`] operation.
+    pub fn rank(&self) -> Result<usize, Error> {
+        Ok(self.eliminate(self.columns)?.rank)
+    }
+
+    /// Solves the linear system `self × x = b` for a square matrix with an inverse.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The right-hand side, with as many rows as the matrix. Each of its columns is solved for.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if the matrix is not square or `b` has another number of rows.
+    /// * [`Error::SingularMatrix`] if the matrix has no inverse.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn solve(&self, b: &Matrix) -> Result<Matrix, Error> {
+        self.check_square()?;
+        if b.rows != self.rows {
+            return Err(Error::MatrixSize(format!(
+                "Cannot solve a {} system for a {} right-hand side",
+                self.size(),
+                b.size()
+            )));
+        }
+        let augmented = Self::from_fn(self.rows, self.columns + b.columns, |row, column| {
+            if column < self.columns {
+                self.get(row, column).clone()
+            }
+            else {
+                b.get(row, column - self.columns).clone()
+            }
+        });
+        let elimination = augmented.eliminate(self.columns)?;
+        if elimination.rank < self.rows {
+            return Err(Error::SingularMatrix);
+        }
+        let reduced = elimination.reduced;
+        Ok(Self::from_fn(b.rows, b.columns, |row, column| {
+            reduced.get(row, self.columns + column).clone()
+        }))
+    }
+
+    /// Returns the dot product of two vectors of the same length.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if a matrix is not a vector, or the vectors have different lengths.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn dot(&self, other: &Matrix) -> Result<Number, Error> {
+        match (self.vector(), other.vector()) {
+            (Some(u), Some(v)) if u.len() == v.len() => {
+                u.iter()
+                    .zip(v)
+                    .try_fold(Number::zero(), |sum, (a, b)| sum.add(&a.mul(b)?))
+            }
+            _ => {
+                Err(Error::MatrixSize(format!(
+                    "Cannot take the dot product of a {} matrix and a {} one",
+                    self.size(),
+                    other.size()
+                )))
+            }
+        }
+    }
+
+    /// Returns the cross product of two vectors of length 3, as a column.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if a matrix is not a vector of length 3.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn cross(&self, other: &Matrix) -> Result<Matrix, Error> {
+        let (Some([a1, a2, a3]), Some([b1, b2, b3])) = (self.vector(), other.vector())
+        else {
+            return Err(Error::MatrixSize(format!(
+                "Cannot take the cross product of a {} matrix and a {} one",
+                self.size(),
+                other.size()
+            )));
+        };
+        Self::column(vec![
+            a2.mul(b3)?.sub(&a3.mul(b2)?)?,
+            a3.mul(b1)?.sub(&a1.mul(b3)?)?,
+            a1.mul(b2)?.sub(&a2.mul(b1)?)?,
+        ])
+    }
+
+    /// Returns the eigenvalues of a square matrix of at most [`MAX_EIGENVALUE_SIZE`] rows, each repeated as many
+    /// times as its algebraic multiplicity: the real ones in increasing order, then the complex ones.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MatrixSize`] if the matrix is not square or is too large.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn eigenvalues(&self) -> Result<Vec<Root>, Error> {
+        self.check_square()?;
+        if self.rows > MAX_EIGENVALUE_SIZE {
+            return Err(Error::MatrixSize(format!(
+                "Eigenvalues are only computed for matrices of up to {} rows",
+                MAX_EIGENVALUE_SIZE
+            )));
+        }
+        // Faddeev–LeVerrier: M₁ = I, cₙ₋ₖ = −tr(A Mₖ) / k and Mₖ₊₁ = A Mₖ + cₙ₋ₖ I, from the leading coefficient down.
+        let n = self.rows;
+        let mut coefficients = vec![Number::zero(); n + 1];
+        coefficients[n] = Number::from_integer(1);
+        let mut m = Self::identity(n);
+        for k in 1..=n {
+            let am = self.mul(&m)?;
+            let trace = (0..n).try_fold(Number::zero(), |sum, i| sum.add(am.get(i, i)))?;
+            let coefficient = trace.neg().div(&Number::from_integer(k as i64))?;
+            m = am.add(&Self::identity(n).scale(&coefficient)?)?;
+            coefficients[n - k] = coefficient;
+        }
+        let polynomial = Polynomial::from_coefficients(coefficients.iter().map(Number::to_f64).collect());
+        Ok(polynomial.roots())
+    }
+
+    /// Reduces the first columns of the matrix to reduced row echelon form by Gauss–Jordan elimination.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - The number of columns to eliminate, from the left. The other columns are transformed along.
+    fn eliminate(&self, columns: usize) -> Result<Elimination, Error> {
+        let scale = self
+            .entries
+            .iter()
+            .fold(0.0_f64, |scale, entry| scale.max(entry.to_f64().abs()));
+        let negligible =
+            |entry: &Number| entry.is_zero() || (!entry.is_exact() && entry.to_f64().abs() <= NEGLIGIBLE * scale);
+        let mut reduced = self.clone();
+        let mut rank = 0;
+        let mut determinant = Number::from_integer(1);
+        for column in 0..columns {
+            if rank == reduced.rows {
+                break;
+            }
+            let Some(pivot_row) = (rank..reduced.rows)
+                .filter(|row| !negligible(reduced.get(*row, column)))
+                .max_by(|a, b| {
+                    let a = reduced.get(*a, column).to_f64().abs();
+                    let b = reduced.get(*b, column).to_f64().abs();
+                    a.total_cmp(&b)
+                })
+            else {
+                continue;
+            };
+            if pivot_row != rank {
+                reduced.swap_rows(pivot_row, rank);
+                determinant = determinant.neg();
+            }
+            let pivot = reduced.get(rank, column).clone();
+            determinant = determinant.mul(&pivot)?;
+            for k in 0..reduced.columns {
+                let index = rank * reduced.columns + k;
+                reduced.entries[index] = reduced.entries[index].div(&pivot)?;
+            }
+            for row in (0..reduced.rows).filter(|row| *row != rank) {
+                let factor = reduced.get(row, column).clone();
+                if factor.is_zero() {
+                    continue;
+                }
+                for k in 0..reduced.columns {
+                    let value = reduced.get(row, k).sub(&factor.mul(reduced.get(rank, k))?)?;
+                    reduced.entries[row * reduced.columns + k] = value;
+                }
+            }
+            rank += 1;
+        }
+        Ok(Elimination {
+            reduced,
+            rank,
+            determinant,
+        })
+    }
+
+    /// Swaps two rows of the matrix.
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for k in 0..self.columns {
+            self.entries.swap(a * self.columns + k, b * self.columns + k);
+        }
+    }
+}
+
+impl fmt::Display for Matrix {
+    /// Formats the matrix as it is typed (e.g. `[[1, 2], [3, 4]]`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = (0..self.rows)
+            .map(|row| {
+                let entries = (0..self.columns)
+                    .map(|column| self.get(row, column).to_string())
+                    .collect::<Vec<String>>();
+                format!("[{}]", entries.join(", "))
+            })
+            .collect::<Vec<String>>();
+        write!(f, "[{}]", rows.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a matrix of integers from its rows.
+    fn matrix(rows: &[&[i64]]) -> Matrix {
+        Matrix::new(
+            rows.iter()
+                .map(|row| row.iter().map(|entry| Number::from_integer(*entry)).collect())
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    /// Parses an exact number.
+    fn number(text: &str) -> Number {
+        text.parse().unwrap()
+    }
+
+    /// Asserts that the eigenvalues are the expected ones, given as `(re, im)`, up to rounding errors.
+    fn assert_eigenvalues(matrix: &Matrix, expected: &[(f64, f64)]) {
+        let actual = matrix.eigenvalues().unwrap();
+        assert_eq!(actual.len(), expected.len(), "{:?} is not {:?}", actual, expected);
+        for (root, (re, im)) in actual.iter().zip(expected) {
+            assert!((root.re - re).abs() < 1e-9 && (root.im - im).abs() < 1e-9, "{:?} is not {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        assert!(matches!(Matrix::new(vec![]), Err(Error::MatrixSize(_))));
+        assert!(matches!(Matrix::new(vec![vec![Number::zero()], vec![]]), Err(Error::MatrixSize(_))));
+        assert_eq!(matrix(&[&[1, 2], &[3, 4]]).to_string(), "[[1, 2], [3, 4]]");
+        assert_eq!(matrix(&[&[1, 2, 3]]).transpose(), matrix(&[&[1], &[2], &[3]]));
+        assert!(matches!(matrix(&[&[1, 2]]).mul(&matrix(&[&[1, 2]])), Err(Error::MatrixSize(_))));
+    }
+
+    #[test]
+    fn determinant_and_inverse() {
+        let a = matrix(&[&[1, 2], &[3, 4]]);
+        assert_eq!(a.determinant(), Ok(number("-2")));
+        let inverse = a.inverse().unwrap();
+        assert_eq!(inverse.get(1, 0), &number("1.5"));
+        assert_eq!(a.mul(&inverse), Ok(Matrix::identity(2)));
+        assert_eq!(a.pow(-1), Ok(inverse));
+        assert_eq!(matrix(&[&[1, 1], &[1, 0]]).pow(10), Ok(matrix(&[&[89, 55], &[55, 34]])));
+        let singular = matrix(&[&[1, 2], &[2, 4]]);
+        assert_eq!(singular.determinant(), Ok(Number::zero()));
+        assert_eq!(singular.rank(), Ok(1));
+        assert_eq!(singular.inverse(), Err(Error::SingularMatrix));
+    }
+
+    #[test]
+    fn linear_systems() {
+        let a = matrix(&[&[2, 1], &[1, 3]]);
+        let b = matrix(&[&[3], &[5]]);
+        let x = Matrix::column(vec![number("0.8"), number("1.4")]).unwrap();
+        assert_eq!(a.solve(&b), Ok(x));
+        let system = matrix(&[&[0, 2, 1], &[1, 0, 0], &[3, 1, 1]]);
+        let solution = system.solve(&matrix(&[&[5, 1], &[1, 0], &[6, 1]])).unwrap();
+        assert_eq!(system.mul(&solution), Ok(matrix(&[&[5, 1], &[1, 0], &[6, 1]])));
+        assert_eq!(matrix(&[&[1, 2], &[2, 4]]).solve(&b), Err(Error::SingularMatrix));
+        assert!(matches!(a.solve(&matrix(&[&[1]])), Err(Error::MatrixSize(_))));
+        assert!(matches!(matrix(&[&[1, 2]]).solve(&matrix(&[&[1]])), Err(Error::MatrixSize(_))));
+    }
+
+    #[test]
+    fn eigenvalues() {
+        assert_eigenvalues(&matrix(&[&[3, 0], &[0, 2]]), &[(2.0, 0.0), (3.0, 0.0)]);
+        assert_eigenvalues(&matrix(&[&[2, 1], &[1, 2]]), &[(1.0, 0.0), (3.0, 0.0)]);
+        assert_eigenvalues(&matrix(&[&[1, 1], &[0, 1]]), &[(1.0, 0.0), (1.0, 0.0)]);
+        assert_eigenvalues(&matrix(&[&[0, -1], &[1, 0]]), &[(0.0, -1.0), (0.0, 1.0)]);
+        assert_eigenvalues(&matrix(&[&[2, 0, 0], &[0, 3, 4], &[0, 4, 9]]), &[(1.0, 0.0), (2.0, 0.0), (11.0, 0.0)]);
+        assert!(matches!(matrix(&[&[1, 2]]).eigenvalues(), Err(Error::MatrixSize(_))));
+        assert!(matches!(Matrix::identity(MAX_EIGENVALUE_SIZE + 1).eigenvalues(), Err(Error::MatrixSize(_))));
+    }
+
+    #[test]
+    fn vectors() {
+        let (u, v) = (matrix(&[&[1, 2, 3]]), matrix(&[&[4], &[5], &[6]]));
+        assert_eq!(u.dot(&v), Ok(number("32")));
+        assert_eq!(u.cross(&v), Ok(matrix(&[&[-3], &[6], &[-3]])));
+        assert!(matches!(u.dot(&matrix(&[&[1, 2]])), Err(Error::MatrixSize(_))));
+    }
+}
@@ -0,0 +1,70 @@
+//! This module contains the calculation engine of the application. It is independent of the user interface and
+//! provides the [`Number`] type used for all calculations, the [`Calculator`] state machine driven by the keypad, the
+//! parser of typed expressions with its library of constants and units, the percentage calculations, the calendar
+//! arithmetic of the date page, the time value of money calculations of the financial page, the descriptive
+//! statistics of the statistics page, the curves of the plot page, the equation solver, the matrices of the matrix
+//! page, the complex numbers of the complex mode, the number theory functions of the scientific keypad, the numbers
+//! with uncertainties and the intervals of typed expressions, the line by line evaluation of the worksheet page, and
+//! the calculation tape.
+
+mod angle;
+pub use self::angle::AngleUnit;
+
+mod calculator;
+pub use self::calculator::{Calculation, Calculator, Display, Event, Function, Operator};
+
+mod complex;
+pub use self::complex::Complex;
+
+mod constants;
+pub use self::constants::{Constant, CONSTANTS};
+
+mod date;
+pub use self::date::{Date, Period};
+
+mod error;
+pub use self::error::Error;
+
+mod expression;
+pub use self::expression::{Context, Statement, UserFunction, Value};
+
+mod finance;
+pub use self::finance::{AmortizationRow, Tvm, TvmValue};
+
+mod interval;
+pub use self::interval::Interval;
+
+mod matrix;
+pub use self::matrix::Matrix;
+
+mod number;
+pub use self::number::{Digits, Number};
+
+mod number_theory;
+pub use self::number_theory::Factorization;
+
+mod percent;
+
+mod plot;
+pub use self::plot::{extrema, intersections, roots, Curve, Feature, FeatureKind, Viewport};
+
+mod solver;
+pub use self::solver::{Equation, Root, Solution, DEFAULT_BRACKET};
+
+mod statistics;
+pub use self::statistics::{DataKind, DataPoint, DataSet, Summary};
+
+mod tape;
+pub use self::tape::{Tape, TapeLine};
+
+mod uncertain;
+pub use self::uncertain::Uncertain;
+
+mod undo;
+pub use self::undo::UndoStack;
+
+mod units;
+pub use self::units::{Category, Quantity, Unit, Units};
+
+mod worksheet;
+pub use self::worksheet::{evaluate_worksheet, LineResult};
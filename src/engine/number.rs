@@ -0,0 +1,787 @@
+//! This module contains the [`Number`] type used by the calculation engine. Numbers are kept as exact rationals for
+//! as long as possible and only fall back to floating point values for results that cannot be represented exactly.
+
+use std::fmt;
+use std::str::FromStr;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Pow, Signed, ToPrimitive, Zero};
+
+use super::Error;
+
+/// The largest number of significant digits shown when a [`Number`] is displayed.
+const DISPLAY_DIGITS: usize = 16;
+
+/// The largest number of digits of an exact integer written in full. Larger integers are written in scientific
+/// notation.
+const MAX_INTEGER_DIGITS: u64 = 10_000;
+
+/// The largest number of significant digits of a [`Number::Real`] that are meaningful.
+const REAL_DIGITS: usize = 15;
+
+/// The largest absolute decimal exponent accepted when parsing a number.
+const MAX_EXPONENT: i64 = 100_000;
+
+/// The largest number of bits of an exact power. Larger powers are computed approximately.
+const MAX_POWER_BITS: u64 = 1_000_000;
+
+/// A number used by the calculation engine.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Number {
+    /// An exact rational number.
+    Rational(BigRational),
+    /// An approximate floating point number, used for results that cannot be represented exactly.
+    Real(f64),
+}
+
+/// The decimal digits of a [`Number`] rounded to a number of significant digits.
+/// The represented value is `d₀.d₁d₂… × 10^exponent`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Digits {
+    /// Whether the number is negative.
+    pub negative: bool,
+    /// The significant digits, each in the range `0..=9`.
+    pub digits:   Vec<u8>,
+    /// The decimal exponent of the first digit.
+    pub exponent: i64,
+}
+
+/// A [`Number`] written as a mixed number `whole numerator/denominator` (e.g. `2 3/8`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fraction {
+    /// Whether the number is negative.
+    pub negative:    bool,
+    /// The integer part of the absolute value.
+    pub whole:       BigInt,
+    /// The numerator of the fractional part, less than the denominator.
+    pub numerator:   BigInt,
+    /// The denominator of the fractional part.
+    pub denominator: BigInt,
+    /// Whether the fraction is the exact value of the number, rather than the nearest fraction with a bounded
+    /// denominator.
+    pub exact:       bool,
+}
+
+impl Number {
+    /// Creates a new [`Number`] with the value zero.
+    pub fn zero() -> Self {
+        Number::Rational(BigRational::zero())
+    }
+
+    /// Creates a new exact [`Number`] from an integer.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The integer value of the number.
+    pub fn from_integer(value: i64) -> Self {
+        Number::Rational(BigRational::from_integer(BigInt::from(value)))
+    }
+
+    /// Creates a new approximate [`Number`] from a floating point value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The floating point value of the number.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the value is infinite.
+    /// * [`Error::InvalidInput`] if the value is not a number.
+    pub fn from_real(value: f64) -> Result<Self, Error> {
+        if value.is_finite() {
+            Ok(Number::Real(value))
+        }
+        else if value.is_nan() {
+            Err(Error::InvalidInput)
+        }
+        else {
+            Err(Error::Overflow)
+        }
+    }
+
+    /// Returns `true` if the number is zero.
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::Rational(value) => value.is_zero(),
+            Number::Real(value) => *value == 0.0,
+        }
+    }
+
+    /// Returns `true` if the number is less than zero.
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Number::Rational(value) => value.is_negative(),
+            Number::Real(value) => *value < 0.0,
+        }
+    }
+
+    /// Returns `true` if the number is represented exactly.
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Number::Rational(_))
+    }
+
+    /// Returns `true` if the number is an integer.
+    pub fn is_integer(&self) -> bool {
+        match self {
+            Number::Rational(value) => value.is_integer(),
+            Number::Real(value) => value.fract() == 0.0,
+        }
+    }
+
+    /// Returns the decimal digits of an exact integer of at most 10000 digits, with a leading minus sign if it is
+    /// negative (e.g. `-1267650600228229401496703205376`).
+    ///
+    /// # Returns
+    ///
+    /// The digits of the integer, or `None` if the number is not an exact integer or has too many digits.
+    pub fn integer_digits(&self) -> Option<String> {
+        match self {
+            // `bits × log10(2)` bounds the number of digits from below, so a few more integers are written in full.
+            Number::Rational(value) if value.is_integer() && value.numer().bits() * 3 / 10 <= MAX_INTEGER_DIGITS => {
+                Some(value.numer().to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the number as a floating point value. Rationals too large for an `f64` are returned as infinity, with
+    /// their sign.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Rational(value) => {
+                value.to_f64().unwrap_or(if value.is_negative() { f64::NEG_INFINITY } else { f64::INFINITY })
+            }
+            Number::Real(value) => *value,
+        }
+    }
+
+    /// Returns the exact rational value of the number, or `None` if the number is not finite.
+    pub fn to_rational(&self) -> Option<BigRational> {
+        match self {
+            Number::Rational(value) => Some(value.clone()),
+            Number::Real(value) => BigRational::from_float(*value),
+        }
+    }
+
+    /// Adds two numbers.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn add(&self, other: &Number) -> Result<Number, Error> {
+        match (self, other) {
+            (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a + b)),
+            _ => Number::from_real(self.to_f64() + other.to_f64()),
+        }
+    }
+
+    /// Subtracts `other` from this number.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn sub(&self, other: &Number) -> Result<Number, Error> {
+        match (self, other) {
+            (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a - b)),
+            _ => Number::from_real(self.to_f64() - other.to_f64()),
+        }
+    }
+
+    /// Multiplies two numbers.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn mul(&self, other: &Number) -> Result<Number, Error> {
+        match (self, other) {
+            (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a * b)),
+            _ => Number::from_real(self.to_f64() * other.to_f64()),
+        }
+    }
+
+    /// Divides this number by `other`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::DivisionByZero`] if `other` is zero.
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn div(&self, other: &Number) -> Result<Number, Error> {
+        if other.is_zero() {
+            return Err(Error::DivisionByZero);
+        }
+        match (self, other) {
+            (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a / b)),
+            _ => Number::from_real(self.to_f64() / other.to_f64()),
+        }
+    }
+
+    /// Returns the negated number.
+    pub fn neg(&self) -> Number {
+        match self {
+            Number::Rational(value) => Number::Rational(-value),
+            Number::Real(value) => Number::Real(-value),
+        }
+    }
+
+    /// Returns the reciprocal `1/x` of the number.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::DivisionByZero`] if the number is zero.
+    pub fn recip(&self) -> Result<Number, Error> {
+        Number::from_integer(1).div(self)
+    }
+
+    /// Returns the square of the number.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn square(&self) -> Result<Number, Error> {
+        self.mul(self)
+    }
+
+    /// Returns the square root of the number. The result is exact if the numerator and the denominator are both
+    /// perfect squares.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the number is negative.
+    pub fn sqrt(&self) -> Result<Number, Error> {
+        if self.is_negative() {
+            return Err(Error::InvalidInput);
+        }
+        if let Number::Rational(value) = self {
+            let numer = value.numer().sqrt();
+            let denom = value.denom().sqrt();
+            if &numer * &numer == *value.numer() && &denom * &denom == *value.denom() {
+                return Ok(Number::Rational(BigRational::new(numer, denom)));
+            }
+        }
+        Number::from_real(self.to_f64().sqrt())
+    }
+
+    /// Raises this number to the power `exponent`. Integer powers of exact numbers are exact.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::DivisionByZero`] if zero is raised to a negative power.
+    /// * [`Error::InvalidInput`] if a negative number is raised to a non-integer power.
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn pow(&self, exponent: &Number) -> Result<Number, Error> {
+        if self.is_zero() && exponent.is_negative() {
+            return Err(Error::DivisionByZero);
+        }
+        if let (Number::Rational(base), Number::Rational(power)) = (self, exponent) {
+            if let Some(power) = power.is_integer().then(|| power.to_integer().to_i32()).flatten() {
+                let bits = base.numer().bits().max(base.denom().bits());
+                if bits.saturating_mul(u64::from(power.unsigned_abs())) <= MAX_POWER_BITS {
+                    return Ok(Number::Rational(Pow::pow(base, power)));
+                }
+            }
+        }
+        Number::from_real(self.to_f64().powf(exponent.to_f64()))
+    }
+
+    /// Returns `e` raised to the number. The result is exact for zero.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn exp(&self) -> Result<Number, Error> {
+        if self.is_zero() {
+            return Ok(Number::from_integer(1));
+        }
+        Number::from_real(self.to_f64().exp())
+    }
+
+    /// Returns the natural logarithm of the number. The result is exact for one.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the number is not positive.
+    pub fn ln(&self) -> Result<Number, Error> {
+        if self.is_negative() || self.is_zero() {
+            return Err(Error::InvalidInput);
+        }
+        if *self == Number::from_integer(1) {
+            return Ok(Number::zero());
+        }
+        Number::from_real(self.to_f64().ln())
+    }
+
+    /// Returns the decimal logarithm of the number. The result is exact for integer powers of ten (e.g. `3` for
+    /// `1000` and `-2` for `0.01`).
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the number is not positive.
+    pub fn log10(&self) -> Result<Number, Error> {
+        if self.is_negative() || self.is_zero() {
+            return Err(Error::InvalidInput);
+        }
+        if let Number::Rational(value) = self {
+            let power_of_ten = |value: &BigInt| {
+                let digits = value.to_string();
+                (digits.starts_with('1') && digits[1..].bytes().all(|digit| digit == b'0'))
+                    .then(|| digits.len() as i64 - 1)
+            };
+            if value.denom().is_one() {
+                if let Some(exponent) = power_of_ten(value.numer()) {
+                    return Ok(Number::from_integer(exponent));
+                }
+            }
+            if value.numer().is_one() {
+                if let Some(exponent) = power_of_ten(value.denom()) {
+                    return Ok(Number::from_integer(-exponent));
+                }
+            }
+        }
+        Number::from_real(self.to_f64().log10())
+    }
+
+    /// Returns the number multiplied by `10^exponent`. The result is exact.
+    ///
+    /// # Arguments
+    ///
+    /// * `exponent` - The decimal exponent to shift the number by.
+    pub fn shifted(&self, exponent: i64) -> Number {
+        match self.to_rational() {
+            Some(value) => Number::Rational(value * pow10(exponent)),
+            None => self.clone(),
+        }
+    }
+
+    /// Returns the decimal digits of the number rounded half away from zero to `significant` significant digits.
+    /// Approximate numbers are rounded to at most 15 significant digits.
+    ///
+    /// # Arguments
+    ///
+    /// * `significant` - The number of significant digits. At least one digit is always returned.
+    ///
+    /// # Returns
+    ///
+    /// The rounded digits, or `None` if the number is not finite.
+    pub fn to_significant_digits(&self, significant: usize) -> Option<Digits> {
+        let significant = if self.is_exact() {
+            significant.max(1)
+        }
+        else {
+            significant.clamp(1, REAL_DIGITS)
+        };
+        let value = self.to_rational()?;
+        if value.is_zero() {
+            return Some(Digits {
+                negative: false,
+                digits:   vec![0; significant],
+                exponent: 0,
+            });
+        }
+
+        let negative = value.is_negative();
+        let value = value.abs();
+        let mut exponent = decimal_exponent(&value);
+        let mut scaled = (value * pow10(significant as i64 - 1 - exponent))
+            .round()
+            .to_integer();
+        if scaled >= *pow10(significant as i64).numer() {
+            scaled /= 10;
+            exponent += 1;
+        }
+        Some(Digits {
+            negative,
+            digits: scaled.to_string().bytes().map(|b| b - b'0').collect(),
+            exponent,
+        })
+    }
+
+    /// Returns the number rounded to `places` decimal places, with halves rounded to the nearest even digit (banker's
+    /// rounding, e.g. `2.345` is `2.34` and `2.355` is `2.36` to two places). The result is exact. Approximate
+    /// numbers are rounded from their exact binary value.
+    ///
+    /// # Arguments
+    ///
+    /// * `places` - The number of decimal places.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the number is not finite.
+    pub fn round_half_even(&self, places: usize) -> Result<Number, Error> {
+        let scale = pow10(places as i64);
+        let scaled = self.to_rational().ok_or(Error::InvalidInput)? * &scale;
+        let floor = scaled.floor();
+        let half = BigRational::new(BigInt::one(), BigInt::from(2));
+        let rounded = match (&scaled - &floor).cmp(&half) {
+            std::cmp::Ordering::Less => floor,
+            std::cmp::Ordering::Greater => floor + BigRational::one(),
+            std::cmp::Ordering::Equal if floor.to_integer() % 2 == BigInt::zero() => floor,
+            std::cmp::Ordering::Equal => floor + BigRational::one(),
+        };
+        Ok(Number::Rational(rounded / scale))
+    }
+
+    /// Returns the number rounded half away from zero to `places` decimal places.
+    ///
+    /// # Arguments
+    ///
+    /// * `places` - The number of decimal places.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of whether the rounded number is negative, its integer digits and its `places` fractional digits, or
+    /// `None` if the number is not finite.
+    pub fn to_fixed_digits(&self, places: usize) -> Option<(bool, String, String)> {
+        let value = self.to_rational()?;
+        let scaled = (value.abs() * pow10(places as i64)).round().to_integer();
+        let negative = value.is_negative() && !scaled.is_zero();
+        let mut digits = scaled.to_string();
+        if digits.len() <= places {
+            digits = format!("{}{}", "0".repeat(places + 1 - digits.len()), digits);
+        }
+        let fraction = digits.split_off(digits.len() - places);
+        Some((negative, digits, fraction))
+    }
+
+    /// Returns the number as a mixed number. Exact numbers whose denominator is at most `max_denominator` are
+    /// returned exactly. Other numbers are rounded half away from zero to the nearest multiple of
+    /// `1/max_denominator` (e.g. the nearest 1/64 for a `max_denominator` of 64), reduced to lowest terms.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_denominator` - The largest denominator of the fraction. Values below one are treated as one.
+    ///
+    /// # Returns
+    ///
+    /// The mixed number, or `None` if the number is not finite.
+    pub fn to_fraction(&self, max_denominator: u64) -> Option<Fraction> {
+        let value = self.to_rational()?;
+        let max_denominator = BigInt::from(max_denominator.max(1));
+        let exact = self.is_exact() && *value.denom() <= max_denominator;
+        let value = if exact {
+            value
+        }
+        else {
+            let scaled = (&value * BigRational::from_integer(max_denominator.clone())).round();
+            BigRational::new(scaled.to_integer(), max_denominator)
+        };
+
+        let negative = value.is_negative();
+        let value = value.abs();
+        let whole = value.trunc().to_integer();
+        let fraction = value.fract();
+        Some(Fraction {
+            negative,
+            whole,
+            numerator: fraction.numer().clone(),
+            denominator: fraction.denom().clone(),
+            exact,
+        })
+    }
+}
+
+impl Digits {
+    /// Removes trailing zeros from the digits, keeping at least one digit.
+    pub fn trim(&mut self) {
+        while self.digits.len() > 1 && self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+    }
+
+    /// Pads the digits with trailing zeros up to `len` digits.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The minimum number of digits.
+    pub fn pad(&mut self, len: usize) {
+        if self.digits.len() < len {
+            self.digits.resize(len, 0);
+        }
+    }
+
+    /// Returns the digits in positional notation (e.g. `-0.00123` or `12300`).
+    pub fn to_positional(&self) -> String {
+        let digits = self.digit_string();
+        let mut result = String::new();
+        if self.negative {
+            result.push('-');
+        }
+        if self.exponent < 0 {
+            result.push_str("0.");
+            result.push_str(&"0".repeat((-self.exponent - 1) as usize));
+            result.push_str(&digits);
+        }
+        else {
+            let int_len = self.exponent as usize + 1;
+            if digits.len() <= int_len {
+                result.push_str(&digits);
+                result.push_str(&"0".repeat(int_len - digits.len()));
+            }
+            else {
+                result.push_str(&digits[..int_len]);
+                result.push('.');
+                result.push_str(&digits[int_len..]);
+            }
+        }
+        result
+    }
+
+    /// Returns the digits in scientific notation (e.g. `-1.23e+5`).
+    pub fn to_scientific(&self) -> String {
+        let digits = self.digit_string();
+        let mut result = String::new();
+        if self.negative {
+            result.push('-');
+        }
+        result.push_str(&digits[..1]);
+        if digits.len() > 1 {
+            result.push('.');
+            result.push_str(&digits[1..]);
+        }
+        result.push_str(&format_exponent(self.exponent));
+        result
+    }
+
+    /// Returns the digits as a string without sign or decimal point.
+    fn digit_string(&self) -> String {
+        self.digits.iter().map(|d| char::from(b'0' + d)).collect()
+    }
+}
+
+impl fmt::Display for Number {
+    /// Formats the number in positional notation with up to 16 significant digits, switching to scientific notation
+    /// for very large and very small numbers. Exact integers are written in full, up to 10000 digits.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(digits) = self.integer_digits() {
+            return write!(f, "{}", digits);
+        }
+        let Some(mut digits) = self.to_significant_digits(DISPLAY_DIGITS)
+        else {
+            return write!(f, "NaN");
+        };
+        digits.trim();
+        if digits.exponent >= DISPLAY_DIGITS as i64 || digits.exponent < -5 {
+            write!(f, "{}", digits.to_scientific())
+        }
+        else {
+            write!(f, "{}", digits.to_positional())
+        }
+    }
+}
+
+impl FromStr for Number {
+    type Err = Error;
+
+    /// Parses a decimal number (e.g. `-12.5` or `1.5e-3`), a fraction (e.g. `3/8`), a mixed number (e.g. `-2 3/8`)
+    /// or an angle in degrees, minutes and seconds (e.g. `12°30'15"`) into an exact [`Number`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((whole, denominator)) = s.rsplit_once('/') {
+            return parse_fraction(whole, denominator);
+        }
+        if let Some((degrees, rest)) = s.split_once('°') {
+            return parse_dms(degrees, rest);
+        }
+        let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => {
+                (
+                    mantissa,
+                    exponent
+                        .strip_prefix('+')
+                        .unwrap_or(exponent)
+                        .parse::<i64>()
+                        .map_err(|_| Error::InvalidInput)?,
+                )
+            }
+            None => (s, 0),
+        };
+        let (negative, mantissa) = match mantissa.strip_prefix('-') {
+            Some(mantissa) => (true, mantissa),
+            None => (false, mantissa),
+        };
+        let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !int_part.chars().all(|c| c.is_ascii_digit())
+            || !frac_part.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(Error::InvalidInput);
+        }
+        if exponent.abs() > MAX_EXPONENT {
+            return Err(Error::Overflow);
+        }
+
+        let digits = format!("0{}{}", int_part, frac_part)
+            .parse::<BigInt>()
+            .map_err(|_| Error::InvalidInput)?;
+        let value = BigRational::from_integer(digits) * pow10(exponent - frac_part.len() as i64);
+        Ok(Number::Rational(if negative { -value } else { value }))
+    }
+}
+
+/// Parses a fraction or a mixed number, split at its fraction bar.
+///
+/// # Arguments
+///
+/// * `whole` - The text before the fraction bar: a numerator, or a whole number and a numerator separated by
+///   whitespace (e.g. `-2 3`).
+/// * `denominator` - The text after the fraction bar.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if a part is not an unsigned integer, or the denominator is zero.
+fn parse_fraction(whole: &str, denominator: &str) -> Result<Number, Error> {
+    let (negative, whole) = match whole.trim().strip_prefix('-') {
+        Some(whole) => (true, whole),
+        None => (false, whole.trim()),
+    };
+    let (whole, numerator) = whole
+        .rsplit_once(char::is_whitespace)
+        .unwrap_or(("0", whole));
+    let parse = |part: &str| {
+        let part = part.trim();
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Error::InvalidInput);
+        }
+        part.parse::<BigInt>().map_err(|_| Error::InvalidInput)
+    };
+    let denominator = parse(denominator)?;
+    if denominator.is_zero() {
+        return Err(Error::InvalidInput);
+    }
+    let value = BigRational::from_integer(parse(whole)?) + BigRational::new(parse(numerator)?, denominator);
+    Ok(Number::Rational(if negative { -value } else { value }))
+}
+
+/// Parses an angle in degrees, minutes and seconds, split at its degree sign. Minutes and seconds may be omitted, and
+/// each part may have a fractional part.
+///
+/// # Arguments
+///
+/// * `degrees` - The text before the degree sign (e.g. `-12`).
+/// * `rest` - The text after the degree sign (e.g. `30'15"`).
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if a part is not an unsigned decimal number.
+fn parse_dms(degrees: &str, rest: &str) -> Result<Number, Error> {
+    let (negative, degrees) = match degrees.trim().strip_prefix('-') {
+        Some(degrees) => (true, degrees),
+        None => (false, degrees),
+    };
+    let (minutes, seconds) = rest.split_once(['\'', '′']).unwrap_or((rest, ""));
+    let seconds = seconds.trim().trim_end_matches(['"', '″']);
+    let parse = |part: &str, per_degree: i64| -> Result<Number, Error> {
+        let part = part.trim();
+        if part.is_empty() {
+            return Ok(Number::zero());
+        }
+        if part.starts_with(['-', '+']) {
+            return Err(Error::InvalidInput);
+        }
+        part.parse::<Number>()?.div(&Number::from_integer(per_degree))
+    };
+    if degrees.trim().is_empty() {
+        return Err(Error::InvalidInput);
+    }
+    let value = parse(degrees, 1)?
+        .add(&parse(minutes, 60)?)?
+        .add(&parse(seconds, 3600)?)?;
+    Ok(if negative { value.neg() } else { value })
+}
+
+/// Returns `10^exponent` as an exact rational.
+fn pow10(exponent: i64) -> BigRational {
+    let power = Pow::pow(BigInt::from(10), exponent.unsigned_abs());
+    if exponent < 0 {
+        BigRational::new(BigInt::one(), power)
+    }
+    else {
+        BigRational::from_integer(power)
+    }
+}
+
+/// Returns the decimal exponent `floor(log10(value))` of a positive rational.
+fn decimal_exponent(value: &BigRational) -> i64 {
+    let mut exponent = value.numer().to_string().len() as i64 - value.denom().to_string().len() as i64;
+    if *value < pow10(exponent) {
+        exponent -= 1;
+    }
+    exponent
+}
+
+/// Formats a decimal exponent with an explicit sign (e.g. `e+12` or `e-3`).
+fn format_exponent(exponent: i64) -> String {
+    format!("e{}{}", if exponent < 0 { '-' } else { '+' }, exponent.abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses an exact number.
+    fn number(text: &str) -> Number {
+        text.parse().unwrap()
+    }
+
+    /// Returns the parts of the mixed number of a number, as the sign, the whole part, the numerator, the
+    /// denominator and whether it is exact.
+    fn fraction(value: &Number, max_denominator: u64) -> (bool, i64, i64, i64, bool) {
+        let fraction = value.to_fraction(max_denominator).unwrap();
+        (
+            fraction.negative,
+            fraction.whole.to_i64().unwrap(),
+            fraction.numerator.to_i64().unwrap(),
+            fraction.denominator.to_i64().unwrap(),
+            fraction.exact,
+        )
+    }
+
+    #[test]
+    fn exact_fractions() {
+        assert_eq!(fraction(&number("0.375"), 64), (false, 0, 3, 8, true));
+        assert_eq!(fraction(&number("-2.375"), 64), (true, 2, 3, 8, true));
+        assert_eq!(fraction(&number("5"), 64), (false, 5, 0, 1, true));
+        assert_eq!(fraction(&number("22/7"), 7), (false, 3, 1, 7, true));
+        assert_eq!(fraction(&number("1/3"), 64), (false, 0, 1, 3, true));
+    }
+
+    #[test]
+    fn rounded_fractions() {
+        assert_eq!(fraction(&number("0.3"), 8), (false, 0, 1, 4, false));
+        assert_eq!(fraction(&number("1/100"), 64), (false, 0, 1, 64, false));
+        assert_eq!(fraction(&number("22/7"), 4), (false, 3, 1, 4, false));
+        assert_eq!(fraction(&number("0.999"), 16), (false, 1, 0, 1, false));
+        assert_eq!(fraction(&Number::Real(0.1), 10), (false, 0, 1, 10, false));
+        assert_eq!(fraction(&number("2.5"), 0), (false, 3, 0, 1, false));
+    }
+
+    #[test]
+    fn parse_fractions() {
+        assert_eq!(number("3/8"), number("0.375"));
+        assert_eq!(number("-2 3/8"), number("-2.375"));
+        assert_eq!(number("2 6/16"), number("2.375"));
+        assert_eq!("1/0".parse::<Number>(), Err(Error::InvalidInput));
+        assert_eq!("1.5/2".parse::<Number>(), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn exact_integers() {
+        let power = number("2").pow(&number("200")).unwrap();
+        assert_eq!(power.to_string(), "1606938044258990275541962092341162602522202993782792835301376");
+        let factorial = (2..=50).fold(number("1"), |product, factor| {
+            product.mul(&Number::from_integer(factor)).unwrap()
+        });
+        assert_eq!(
+            factorial.integer_digits().as_deref(),
+            Some("30414093201713378043612608166064768844377641568960512000000000000")
+        );
+        assert_eq!(number("-3").pow(&number("41")).unwrap().to_string(), "-36472996377170786403");
+        assert_eq!(number("2.5").integer_digits(), None);
+        assert_eq!(Number::Real(1e20).integer_digits(), None);
+        let huge = number("10").pow(&number("10100")).unwrap();
+        assert_eq!(huge.integer_digits(), None);
+        assert!(huge.to_string().len() < 30);
+        assert_eq!(huge.to_f64(), f64::INFINITY);
+        assert_eq!(huge.neg().to_f64(), f64::NEG_INFINITY);
+    }
+}
@@ -0,0 +1,473 @@
+//! This module contains the number theory functions of the scientific keypad and of typed expressions: primality,
+//! prime factorization, greatest common divisors and least common multiples, modular powers and inverses, Euler's
+//! totient, and the exact factorials, combinations and permutations. They take integers, which may be written as
+//! decimals (e.g. `12.0`), and their results are exact integers.
+//!
+//! Primality is decided by the Miller–Rabin test with the first twelve primes as bases, which is exact below
+//! 3.3 × 10²⁴ and leaves a negligible chance of error above. Factors are found by trial division by the numbers up
+//! to [`TRIAL_DIVISION_LIMIT`], then by Pollard's rho method, which finds prime factors of up to about ten digits
+//! quickly. A number with two or more larger prime factors cannot be factored.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use super::{Error, Number};
+
+/// The bases of the Miller–Rabin test, which are also checked as divisors first.
+const SMALL_PRIMES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// The largest divisor tried by trial division before Pollard's rho method is used.
+const TRIAL_DIVISION_LIMIT: u32 = 10_000;
+
+/// The largest number of steps of Pollard's rho method spent on a number before giving up.
+const MAX_RHO_STEPS: u32 = 1 << 18;
+
+/// The number of steps of Pollard's rho method whose differences are multiplied together before their greatest
+/// common divisor with the number is taken.
+const RHO_BATCH: u32 = 64;
+
+/// The largest number whose factorial is computed, and the largest number of factors of a combination or a
+/// permutation. `20000!` has 77338 digits.
+const MAX_FACTORS: u64 = 20_000;
+
+/// The prime factorization of a nonzero integer (e.g. `2^3 × 3 × 5` for `120`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Factorization {
+    /// Whether the integer is negative.
+    pub negative: bool,
+    /// The prime factors in increasing order, with their exponents. Empty for `1` and `-1`.
+    pub factors:  Vec<(BigInt, u32)>,
+}
+
+impl Factorization {
+    /// Factors an integer into primes.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The integer to factor.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the value is zero or not an integer.
+    /// * [`Error::FactorizationLimit`] if the value has prime factors too large to be found.
+    pub fn new(value: &Number) -> Result<Self, Error> {
+        let value = integer(value)?;
+        if value.is_zero() {
+            return Err(Error::InvalidInput);
+        }
+        let mut factors = BTreeMap::new();
+        let mut rest = value.abs();
+        let mut divisor = 2;
+        while divisor <= TRIAL_DIVISION_LIMIT && BigInt::from(divisor * divisor) <= rest {
+            let big_divisor = BigInt::from(divisor);
+            while (&rest % &big_divisor).is_zero() {
+                rest /= &big_divisor;
+                *factors.entry(big_divisor.clone()).or_insert(0) += 1;
+            }
+            divisor += if divisor == 2 { 1 } else { 2 };
+        }
+        let mut composites = Vec::new();
+        if !rest.is_one() {
+            composites.push(rest);
+        }
+        while let Some(number) = composites.pop() {
+            if is_probable_prime(&number) {
+                *factors.entry(number).or_insert(0) += 1;
+            }
+            else {
+                let factor = find_factor(&number).ok_or(Error::FactorizationLimit)?;
+                composites.push(&number / &factor);
+                composites.push(factor);
+            }
+        }
+        Ok(Self {
+            negative: value.is_negative(),
+            factors:  factors.into_iter().collect(),
+        })
+    }
+
+    /// Returns the integer whose factorization this is.
+    pub fn value(&self) -> Number {
+        let product = self.factors.iter().fold(BigInt::one(), |product, (prime, exponent)| {
+            product * num_traits::pow(prime.clone(), *exponent as usize)
+        });
+        integer_number(if self.negative { -product } else { product })
+    }
+}
+
+impl fmt::Display for Factorization {
+    /// Formats the factorization as a product of prime powers in increasing order (e.g. `2^3 × 3 × 5`). The
+    /// factorization of `1` is `1`, and the ones of negative numbers start with a minus sign (e.g. `-2 × 3`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        if self.factors.is_empty() {
+            return write!(f, "1");
+        }
+        let factors = self
+            .factors
+            .iter()
+            .map(|(prime, exponent)| {
+                if *exponent == 1 {
+                    prime.to_string()
+                }
+                else {
+                    format!("{}^{}", prime, exponent)
+                }
+            })
+            .collect::<Vec<String>>();
+        write!(f, "{}", factors.join(" × "))
+    }
+}
+
+/// Returns `1` if the value is a prime number, and `0` otherwise.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if the value is not an integer.
+pub fn is_prime(value: &Number) -> Result<Number, Error> {
+    Ok(Number::from_integer(i64::from(is_probable_prime(&integer(value)?))))
+}
+
+/// Returns the factorial `n!` of a number.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if the value is negative or not an integer.
+/// * [`Error::Overflow`] if the value is larger than [`MAX_FACTORS`].
+pub fn factorial(value: &Number) -> Result<Number, Error> {
+    let n = count(value)?;
+    Ok(integer_number((2..=n).fold(BigInt::one(), |product, factor| product * factor)))
+}
+
+/// Returns the number of combinations `nCr` of `r` items among `n`, which is zero if `r` is larger than `n`.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if a value is negative or not an integer.
+/// * [`Error::Overflow`] if the computation takes more than [`MAX_FACTORS`] factors.
+pub fn combinations(n: &Number, r: &Number) -> Result<Number, Error> {
+    let (n, r) = (natural(n)?, natural(r)?);
+    if r > n {
+        return Ok(Number::zero());
+    }
+    let k = count(&integer_number(r.clone().min(&n - &r)))?;
+    let start = &n - BigInt::from(k);
+    let result = (1..=k).fold(BigInt::one(), |product, i| product * (&start + i) / i);
+    Ok(integer_number(result))
+}
+
+/// Returns the number of permutations `nPr` of `r` items among `n`, which is zero if `r` is larger than `n`.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if a value is negative or not an integer.
+/// * [`Error::Overflow`] if `r` is larger than [`MAX_FACTORS`].
+pub fn permutations(n: &Number, r: &Number) -> Result<Number, Error> {
+    let (n, r) = (natural(n)?, natural(r)?);
+    if r > n {
+        return Ok(Number::zero());
+    }
+    let r = count(&integer_number(r))?;
+    let start = &n - BigInt::from(r);
+    Ok(integer_number((1..=r).fold(BigInt::one(), |product, i| product * (&start + i))))
+}
+
+/// Returns the greatest common divisor of two integers, which is not negative. The greatest common divisor of zero
+/// and zero is zero.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if a value is not an integer.
+pub fn gcd(a: &Number, b: &Number) -> Result<Number, Error> {
+    Ok(integer_number(big_gcd(integer(a)?, integer(b)?)))
+}
+
+/// Returns the least common multiple of two integers, which is not negative. The least common multiple of zero and
+/// any integer is zero.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if a value is not an integer.
+pub fn lcm(a: &Number, b: &Number) -> Result<Number, Error> {
+    let (a, b) = (integer(a)?, integer(b)?);
+    if a.is_zero() || b.is_zero() {
+        return Ok(Number::zero());
+    }
+    let divisor = big_gcd(a.clone(), b.clone());
+    Ok(integer_number((a * b).abs() / divisor))
+}
+
+/// Returns `base^exponent mod modulus`, in `[0, modulus)`. A negative exponent is a power of the modular inverse of
+/// the base.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if a value is not an integer or the modulus is negative.
+/// * [`Error::DivisionByZero`] if the modulus is zero.
+/// * [`Error::NoInverse`] if the exponent is negative and the base has no inverse modulo the modulus.
+pub fn mod_pow(base: &Number, exponent: &Number, modulus: &Number) -> Result<Number, Error> {
+    let modulus = modulus_of(modulus)?;
+    let mut base = integer(base)?;
+    let exponent = integer(exponent)?;
+    if exponent.is_negative() {
+        base = big_mod_inverse(&base, &modulus)?;
+    }
+    Ok(integer_number(modulo(&base, &modulus).modpow(&exponent.abs(), &modulus)))
+}
+
+/// Returns the inverse of an integer modulo another one: the number `x` in `[0, modulus)` such that
+/// `value × x mod modulus` is `1`.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if a value is not an integer or the modulus is negative.
+/// * [`Error::DivisionByZero`] if the modulus is zero.
+/// * [`Error::NoInverse`] if the value and the modulus have a common divisor other than one.
+pub fn mod_inverse(value: &Number, modulus: &Number) -> Result<Number, Error> {
+    big_mod_inverse(&integer(value)?, &modulus_of(modulus)?).map(integer_number)
+}
+
+/// Returns Euler's totient of a positive integer: the number of integers up to it that are coprime with it.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if the value is not a positive integer.
+/// * [`Error::FactorizationLimit`] if the value cannot be factored.
+pub fn totient(value: &Number) -> Result<Number, Error> {
+    let factorization = Factorization::new(value)?;
+    if factorization.negative {
+        return Err(Error::InvalidInput);
+    }
+    let result = factorization
+        .factors
+        .iter()
+        .fold(BigInt::one(), |product, (prime, exponent)| {
+            product * num_traits::pow(prime.clone(), *exponent as usize - 1) * (prime - 1)
+        });
+    Ok(integer_number(result))
+}
+
+/// Returns the value as an integer.
+///
+/// # Errors
+///
+/// * [`Error::InvalidInput`] if the value is not an integer.
+fn integer(value: &Number) -> Result<BigInt, Error> {
+    if !value.is_integer() {
+        return Err(Error::InvalidInput);
+    }
+    value
+        .to_rational()
+        .map(|value| value.to_integer())
+        .ok_or(Error::InvalidInput)
+}
+
+/// Returns the value as an integer that is not negative.
+fn natural(value: &Number) -> Result<BigInt, Error> {
+    let value = integer(value)?;
+    if value.is_negative() {
+        Err(Error::InvalidInput)
+    }
+    else {
+        Ok(value)
+    }
+}
+
+/// Returns the value as a number of factors, at most [`MAX_FACTORS`].
+fn count(value: &Number) -> Result<u64, Error> {
+    natural(value)?
+        .to_u64()
+        .filter(|count| *count <= MAX_FACTORS)
+        .ok_or(Error::Overflow)
+}
+
+/// Returns the value as a modulus, which must be positive.
+fn modulus_of(value: &Number) -> Result<BigInt, Error> {
+    let modulus = natural(value)?;
+    if modulus.is_zero() {
+        Err(Error::DivisionByZero)
+    }
+    else {
+        Ok(modulus)
+    }
+}
+
+/// Returns an exact [`Number`] from an integer.
+fn integer_number(value: BigInt) -> Number {
+    Number::Rational(BigRational::from_integer(value))
+}
+
+/// Returns the remainder of the division of a value by a positive modulus, in `[0, modulus)`.
+fn modulo(value: &BigInt, modulus: &BigInt) -> BigInt {
+    ((value % modulus) + modulus) % modulus
+}
+
+/// Returns the greatest common divisor of two integers, with Euclid's algorithm.
+fn big_gcd(a: BigInt, b: BigInt) -> BigInt {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while !b.is_zero() {
+        let rest = &a % &b;
+        a = b;
+        b = rest;
+    }
+    a
+}
+
+/// Returns the inverse of an integer modulo a positive modulus, with the extended Euclidean algorithm.
+fn big_mod_inverse(value: &BigInt, modulus: &BigInt) -> Result<BigInt, Error> {
+    let (mut r, mut next_r) = (modulus.clone(), modulo(value, modulus));
+    let (mut t, mut next_t) = (BigInt::zero(), BigInt::one());
+    while !next_r.is_zero() {
+        let quotient = &r / &next_r;
+        (t, next_t) = (next_t.clone(), t - &quotient * next_t);
+        (r, next_r) = (next_r.clone(), r - &quotient * next_r);
+    }
+    if !r.is_one() {
+        return Err(Error::NoInverse);
+    }
+    Ok(modulo(&t, modulus))
+}
+
+/// Returns `true` if an integer is prime, with the Miller–Rabin test on the bases [`SMALL_PRIMES`].
+fn is_probable_prime(n: &BigInt) -> bool {
+    if *n < BigInt::from(2) {
+        return false;
+    }
+    for prime in SMALL_PRIMES {
+        let prime = BigInt::from(prime);
+        if *n == prime {
+            return true;
+        }
+        if (n % &prime).is_zero() {
+            return false;
+        }
+    }
+    let n_minus_one = n - BigInt::one();
+    let shift = n_minus_one.trailing_zeros().unwrap_or(0);
+    let odd = &n_minus_one >> shift;
+    'bases: for base in SMALL_PRIMES {
+        let mut x = BigInt::from(base).modpow(&odd, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..shift {
+            x = &x * &x % n;
+            if x == n_minus_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Returns a divisor of a composite number other than one and itself, found with Pollard's rho method, or `None`
+/// if none was found in [`MAX_RHO_STEPS`] steps.
+fn find_factor(n: &BigInt) -> Option<BigInt> {
+    let mut steps = 0;
+    for constant in 1.. {
+        let step = |x: &BigInt| (x * x + constant) % n;
+        let (mut slow, mut fast) = (BigInt::from(2), BigInt::from(2));
+        loop {
+            let mut product = BigInt::one();
+            for _ in 0..RHO_BATCH {
+                slow = step(&slow);
+                fast = step(&step(&fast));
+                product = product * (&slow - &fast).abs() % n;
+            }
+            steps += RHO_BATCH;
+            let divisor = big_gcd(product, n.clone());
+            if divisor == *n {
+                // The cycle was closed, or the factors were found together: try another sequence.
+                break;
+            }
+            if !divisor.is_one() {
+                return Some(divisor);
+            }
+            if steps >= MAX_RHO_STEPS {
+                return None;
+            }
+        }
+        if steps >= MAX_RHO_STEPS {
+            return None;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses an exact number.
+    fn number(text: &str) -> Number {
+        text.parse().unwrap()
+    }
+
+    /// Factors a number and formats its factorization.
+    fn factors(text: &str) -> Result<String, Error> {
+        Factorization::new(&number(text)).map(|factorization| factorization.to_string())
+    }
+
+    #[test]
+    fn factorizations() {
+        assert_eq!(factors("120"), Ok("2^3 × 3 × 5".to_string()));
+        assert_eq!(factors("-6"), Ok("-2 × 3".to_string()));
+        assert_eq!(factors("1"), Ok("1".to_string()));
+        assert_eq!(factors("12.0"), Ok("2^2 × 3".to_string()));
+        assert_eq!(factors("998244359987710471"), Ok("998244353 × 1000000007".to_string()));
+        assert_eq!(factors("2305843009213693951"), Ok("2305843009213693951".to_string()));
+        assert_eq!(factors("0"), Err(Error::InvalidInput));
+        assert_eq!(factors("1.5"), Err(Error::InvalidInput));
+        let value = number("-3628800");
+        assert_eq!(Factorization::new(&value).map(|factorization| factorization.value()), Ok(value));
+    }
+
+    #[test]
+    fn primes() {
+        let prime = |text: &str| is_prime(&number(text)).unwrap() == Number::from_integer(1);
+        assert!(prime("2") && prime("97") && prime("2305843009213693951"));
+        assert!(!prime("0") && !prime("1") && !prime("91") && !prime("561"));
+        assert_eq!(is_prime(&number("7.5")), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn factorials_combinations_and_permutations() {
+        assert_eq!(factorial(&number("0")), Ok(number("1")));
+        assert_eq!(factorial(&number("10")), Ok(number("3628800")));
+        assert_eq!(factorial(&number("-1")), Err(Error::InvalidInput));
+        assert_eq!(factorial(&number("20001")), Err(Error::Overflow));
+        assert_eq!(combinations(&number("52"), &number("5")), Ok(number("2598960")));
+        assert_eq!(combinations(&number("5"), &number("6")), Ok(Number::zero()));
+        assert_eq!(permutations(&number("5"), &number("2")), Ok(number("20")));
+        assert_eq!(permutations(&number("5"), &number("0")), Ok(number("1")));
+    }
+
+    #[test]
+    fn divisors() {
+        assert_eq!(gcd(&number("-12"), &number("18")), Ok(number("6")));
+        assert_eq!(gcd(&number("0"), &number("0")), Ok(Number::zero()));
+        assert_eq!(lcm(&number("-4"), &number("6")), Ok(number("12")));
+        assert_eq!(lcm(&number("0"), &number("5")), Ok(Number::zero()));
+        assert_eq!(totient(&number("36")), Ok(number("12")));
+        assert_eq!(totient(&number("1")), Ok(number("1")));
+        assert_eq!(totient(&number("-5")), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(mod_pow(&number("2"), &number("10"), &number("1000")), Ok(number("24")));
+        assert_eq!(mod_pow(&number("-2"), &number("3"), &number("5")), Ok(number("2")));
+        assert_eq!(mod_pow(&number("3"), &number("-1"), &number("7")), Ok(number("5")));
+        assert_eq!(mod_pow(&number("2"), &number("3"), &number("0")), Err(Error::DivisionByZero));
+        assert_eq!(mod_inverse(&number("3"), &number("7")), Ok(number("5")));
+        assert_eq!(mod_inverse(&number("2"), &number("4")), Err(Error::NoInverse));
+        assert_eq!(mod_inverse(&number("2"), &number("-7")), Err(Error::InvalidInput));
+    }
+}
@@ -0,0 +1,189 @@
+//! This module contains the percentage calculations of the keypad and of typed expressions. Every calculator handles
+//! percentages a little differently, so the exact rules are listed here, `x` being the value before the operator and
+//! `p` the percentage:
+//!
+//! | Input         | Result              | Example            |
+//! |---------------|---------------------|--------------------|
+//! | `p %`         | `p / 100`           | `10 %` = `0.1`     |
+//! | `x + p %`     | `x + x · p / 100`   | `200 + 10 %` = 220 |
+//! | `x − p %`     | `x − x · p / 100`   | `200 − 10 %` = 180 |
+//! | `x × p %`     | `x · p / 100`       | `200 × 10 %` = 20  |
+//! | `x ÷ p %`     | `x / (p / 100)`     | `20 ÷ 10 %` = 200  |
+//!
+//! On the keypad, `%` replaces the displayed operand by its percentage as soon as it is pressed, so `200 + 10 %`
+//! shows `20` before equals is pressed, and equals then shows `220`. Pressing equals again repeats `+ 20`. In typed
+//! expressions, `%` is a postfix operator binding tighter than every other operator, so `2^10 %` is `2^(10 %)`, and
+//! only a percentage directly on the right of `+` or `−` is taken relative to the left operand: `200 + 10 % × 2` is
+//! `200 + 0.2`.
+//!
+//! The helpers below are available as functions of typed expressions:
+//!
+//! | Function            | Result                        | Example                      |
+//! |---------------------|-------------------------------|------------------------------|
+//! | `pctchange(a, b)`   | `(b − a) / a · 100`           | `pctchange(80, 100)` = 25    |
+//! | `markup(c, p)`      | `(p − c) / c · 100`           | `markup(80, 100)` = 25       |
+//! | `margin(c, p)`      | `(p − c) / p · 100`           | `margin(80, 100)` = 20       |
+//! | `pctof(x, y)`       | `x / y · 100`                 | `pctof(20, 80)` = 25         |
+//!
+//! The percentage change and the markup are the same calculation, from the old value or the cost to the new value
+//! or the price. All of them are exact for exact operands.
+
+use super::{Error, Number, Operator};
+
+/// Returns the number one hundred.
+fn hundred() -> Number {
+    Number::from_integer(100)
+}
+
+/// Returns a percentage as a fraction (e.g. `0.1` for `10 %`).
+///
+/// # Arguments
+///
+/// * `percentage` - The percentage.
+///
+/// # Errors
+///
+/// Returns the error of the underlying [`Number`] operation.
+pub fn percent(percentage: &Number) -> Result<Number, Error> {
+    percentage.div(&hundred())
+}
+
+/// Returns the operand that replaces a percentage on the right of an operator: a fraction of the left operand for
+/// additions and subtractions (so `200 + 10 %` adds `20`), or the percentage as a fraction for multiplications and
+/// divisions (so `200 × 10 %` multiplies by `0.1`).
+///
+/// # Arguments
+///
+/// * `operator` - The operator the percentage is applied with.
+/// * `lhs` - The left operand of the operator.
+/// * `percentage` - The percentage.
+///
+/// # Errors
+///
+/// Returns the error of the underlying [`Number`] operation.
+pub fn percent_operand(operator: Operator, lhs: &Number, percentage: &Number) -> Result<Number, Error> {
+    match operator {
+        Operator::Add | Operator::Subtract => lhs.mul(&percent(percentage)?),
+        Operator::Multiply | Operator::Divide => percent(percentage),
+    }
+}
+
+/// Returns the change from one value to another in percent of the first value (e.g. `25` from `80` to `100`).
+///
+/// # Arguments
+///
+/// * `old` - The value before the change.
+/// * `new` - The value after the change.
+///
+/// # Errors
+///
+/// * [`Error::DivisionByZero`] if the value before the change is zero.
+/// * The error of the underlying [`Number`] operation.
+pub fn percent_change(old: &Number, new: &Number) -> Result<Number, Error> {
+    new.sub(old)?.div(old)?.mul(&hundred())
+}
+
+/// Returns the markup of a price over a cost, in percent of the cost (e.g. `25` for a cost of `80` and a price of
+/// `100`).
+///
+/// # Arguments
+///
+/// * `cost` - The cost.
+/// * `price` - The price.
+///
+/// # Errors
+///
+/// * [`Error::DivisionByZero`] if the cost is zero.
+/// * The error of the underlying [`Number`] operation.
+pub fn markup(cost: &Number, price: &Number) -> Result<Number, Error> {
+    percent_change(cost, price)
+}
+
+/// Returns the margin of a price over a cost, in percent of the price (e.g. `20` for a cost of `80` and a price of
+/// `100`).
+///
+/// # Arguments
+///
+/// * `cost` - The cost.
+/// * `price` - The price.
+///
+/// # Errors
+///
+/// * [`Error::DivisionByZero`] if the price is zero.
+/// * The error of the underlying [`Number`] operation.
+pub fn margin(cost: &Number, price: &Number) -> Result<Number, Error> {
+    price.sub(cost)?.div(price)?.mul(&hundred())
+}
+
+/// Returns what percentage a value is of another (e.g. `25` for `20` of `80`).
+///
+/// # Arguments
+///
+/// * `part` - The value.
+/// * `whole` - The value it is a percentage of.
+///
+/// # Errors
+///
+/// * [`Error::DivisionByZero`] if `whole` is zero.
+/// * The error of the underlying [`Number`] operation.
+pub fn percent_of(part: &Number, whole: &Number) -> Result<Number, Error> {
+    part.div(whole)?.mul(&hundred())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::engine::{AngleUnit, Context, Statement};
+
+    /// Parses an exact number.
+    fn number(text: &str) -> Number {
+        text.parse().unwrap()
+    }
+
+    /// Evaluates a typed expression without variables, functions or previous results.
+    fn evaluate(text: &str) -> Result<Number, Error> {
+        let (variables, functions) = (BTreeMap::new(), BTreeMap::new());
+        let context = Context {
+            variables:  &variables,
+            functions:  &functions,
+            answers:    &[],
+            angle_unit: AngleUnit::Degrees,
+            complex:    false,
+            interval:   false,
+        };
+        match Statement::parse(text)? {
+            Statement::Expression(expression) => Ok(expression.evaluate(&context)?.value),
+            statement => panic!("{:?} is not an expression", statement),
+        }
+    }
+
+    #[test]
+    fn expressions() {
+        assert_eq!(evaluate("200 + 10 %"), Ok(number("220")));
+        assert_eq!(evaluate("200 − 10 %"), Ok(number("180")));
+        assert_eq!(evaluate("200 × 10 %"), Ok(number("20")));
+        assert_eq!(evaluate("20 ÷ 10 %"), Ok(number("200")));
+        assert_eq!(evaluate("10 %"), Ok(number("0.1")));
+        assert_eq!(evaluate("200 + 10 % × 2"), Ok(number("200.2")));
+    }
+
+    #[test]
+    fn helpers() {
+        assert_eq!(evaluate("pctchange(80, 100)"), Ok(number("25")));
+        assert_eq!(evaluate("pctchange(100, 80)"), Ok(number("-20")));
+        assert_eq!(evaluate("markup(80, 100)"), Ok(number("25")));
+        assert_eq!(evaluate("margin(80, 100)"), Ok(number("20")));
+        assert_eq!(evaluate("pctof(20, 80)"), Ok(number("25")));
+    }
+
+    #[test]
+    fn helpers_divide_by_zero() {
+        assert_eq!(percent_change(&Number::zero(), &number("5")), Err(Error::DivisionByZero));
+        assert_eq!(markup(&Number::zero(), &number("5")), Err(Error::DivisionByZero));
+        assert_eq!(margin(&number("5"), &Number::zero()), Err(Error::DivisionByZero));
+        assert_eq!(percent_of(&number("5"), &Number::zero()), Err(Error::DivisionByZero));
+        assert_eq!(evaluate("pctof(5, 0)"), Err(Error::DivisionByZero));
+    }
+}
@@ -0,0 +1,477 @@
+//! This module contains the calculations of the plot page: the [`Curve`] of a function of `x`, the [`Viewport`] of the
+//! plane shown by the page, and the search for the [`Feature`]s of curves (their roots, minima and maxima, and the
+//! intersections of two curves) in the visible part of the plane.
+//!
+//! Plots are drawn and searched with floating point numbers. Features are found by sampling the curves at
+//! [`SAMPLES`] points across the viewport and refining every sign change (of the function for roots and
+//! intersections, of its slope for extrema) by bisection or golden section search, so features closer together than
+//! the samples may be missed. A sign change across a discontinuity (e.g. `tan(x)` at 90°) is not a root.
+
+use super::expression::Expression;
+use super::{Context, Error, Number, Quantity, Statement};
+
+/// The number of points curves are sampled at when searching for features.
+pub const SAMPLES: usize = 1000;
+
+/// The number of refinement steps of the search for a feature between two samples.
+const REFINEMENT_STEPS: usize = 100;
+
+/// The name of the variable of curves typed as plain expressions (e.g. `x^2 - 2`).
+const VARIABLE: &str = "x";
+
+/// The kind of a [`Feature`] of curves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeatureKind {
+    /// A point where a curve crosses or touches the `x` axis.
+    Root,
+    /// A local minimum of a curve.
+    Minimum,
+    /// A local maximum of a curve.
+    Maximum,
+    /// A point where two curves cross.
+    Intersection,
+}
+
+impl FeatureKind {
+    /// Returns the name of the kind of feature as shown in the list of features.
+    pub fn name(self) -> &'static str {
+        match self {
+            FeatureKind::Root => "Root",
+            FeatureKind::Minimum => "Minimum",
+            FeatureKind::Maximum => "Maximum",
+            FeatureKind::Intersection => "Intersection",
+        }
+    }
+}
+
+/// A remarkable point of one or two curves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Feature {
+    /// The kind of the feature.
+    pub kind: FeatureKind,
+    /// The `x` coordinate of the point.
+    pub x:    f64,
+    /// The `y` coordinate of the point.
+    pub y:    f64,
+}
+
+/// The curve of a function of one variable, typed as an expression in `x` (e.g. `x^2 - 2`), as `y = …`, or as a
+/// function definition with one parameter (e.g. `f(t) = sin(t)`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Curve {
+    expression: Expression,
+    variable:   String,
+}
+
+/// The rectangle of the plane shown by the plot page.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    /// The smallest visible `x`.
+    pub x_min: f64,
+    /// The largest visible `x`.
+    pub x_max: f64,
+    /// The smallest visible `y`.
+    pub y_min: f64,
+    /// The largest visible `y`.
+    pub y_max: f64,
+}
+
+impl Curve {
+    /// Parses a curve.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The curve as typed.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Syntax`] if the text is not a valid expression, or assigns to another name than `y`.
+    /// * [`Error::ArgumentCount`] if the text defines a function of more than one parameter.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        match Statement::parse(text)? {
+            Statement::Expression(expression) => {
+                Ok(Self {
+                    expression,
+                    variable: VARIABLE.to_string(),
+                })
+            }
+            Statement::Assignment(name, expression) if name == "y" => {
+                Ok(Self {
+                    expression,
+                    variable: VARIABLE.to_string(),
+                })
+            }
+            Statement::Assignment(name, _) => {
+                Err(Error::Syntax(format!("Expected a function of x, not ‘{} =’", name)))
+            }
+            Statement::Definition(function) => {
+                let [variable] = <[String; 1]>::try_from(function.parameters.clone()).map_err(|parameters| {
+                    Error::ArgumentCount {
+                        name:     function.name.clone(),
+                        expected: 1,
+                        found:    parameters.len(),
+                    }
+                })?;
+                Ok(Self {
+                    expression: function.body,
+                    variable,
+                })
+            }
+        }
+    }
+
+    /// Returns the value of the curve at a point, or `None` if it is undefined there (e.g. `sqrt(x)` for a negative
+    /// `x`) or is not a plain number.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The variables, functions and previous results the curve can refer to.
+    /// * `x` - The point.
+    pub fn evaluate(&self, context: &Context, x: f64) -> Option<f64> {
+        let x = Quantity::from(Number::from_real(x).ok()?);
+        let y = self
+            .expression
+            .evaluate_with(context, &self.variable, &x)
+            .and_then(Quantity::into_number)
+            .ok()?
+            .to_f64();
+        y.is_finite().then_some(y)
+    }
+}
+
+impl Default for Viewport {
+    /// The standard viewport, from -10 to 10 on both axes.
+    fn default() -> Self {
+        Self {
+            x_min: -10.0,
+            x_max: 10.0,
+            y_min: -10.0,
+            y_max: 10.0,
+        }
+    }
+}
+
+impl Viewport {
+    /// Returns `true` if the viewport is a non-empty finite rectangle.
+    pub fn is_valid(&self) -> bool {
+        [self.x_min, self.x_max, self.y_min, self.y_max]
+            .iter()
+            .all(|bound| bound.is_finite())
+            && self.x_min < self.x_max
+            && self.y_min < self.y_max
+    }
+
+    /// Returns the viewport moved by a distance in the plane.
+    ///
+    /// # Arguments
+    ///
+    /// * `dx` - The distance along the `x` axis.
+    /// * `dy` - The distance along the `y` axis.
+    pub fn pan(&self, dx: f64, dy: f64) -> Viewport {
+        Viewport {
+            x_min: self.x_min + dx,
+            x_max: self.x_max + dx,
+            y_min: self.y_min + dy,
+            y_max: self.y_max + dy,
+        }
+    }
+
+    /// Returns the viewport scaled around a point, which stays at the same place.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - The factor the sizes of the viewport are multiplied by: less than 1 zooms in.
+    /// * `x` - The `x` coordinate of the fixed point.
+    /// * `y` - The `y` coordinate of the fixed point.
+    pub fn zoom(&self, factor: f64, x: f64, y: f64) -> Viewport {
+        Viewport {
+            x_min: x + (self.x_min - x) * factor,
+            x_max: x + (self.x_max - x) * factor,
+            y_min: y + (self.y_min - y) * factor,
+            y_max: y + (self.y_max - y) * factor,
+        }
+    }
+
+    /// Returns the positions of the grid lines along one axis: multiples of 1, 2 or 5 times a power of ten, about
+    /// `count` of them between `from` and `to`.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The start of the axis.
+    /// * `to` - The end of the axis.
+    /// * `count` - The approximate number of grid lines.
+    pub fn ticks(from: f64, to: f64, count: usize) -> Vec<f64> {
+        let span = to - from;
+        if !(span.is_finite() && span > 0.0) || count == 0 {
+            return Vec::new();
+        }
+        let rough = span / count as f64;
+        let power = 10f64.powf(rough.log10().floor());
+        let step = [1.0, 2.0, 5.0, 10.0]
+            .iter()
+            .map(|multiple| multiple * power)
+            .find(|step| *step >= rough)
+            .unwrap_or(10.0 * power);
+        let first = (from / step).ceil() as i64;
+        let last = (to / step).floor() as i64;
+        // Multiplying the index by the step avoids accumulating rounding errors, and rounding to the number of
+        // decimals of the step avoids values like 0.30000000000000004.
+        let decimals = (-step.log10().floor()).max(0.0) as i32;
+        let scale = 10f64.powi(decimals);
+        (first..=last)
+            .map(|index| (index as f64 * step * scale).round() / scale)
+            .collect()
+    }
+}
+
+/// Returns the roots of a function between two points, in increasing order, including the points where it touches
+/// the `x` axis.
+///
+/// # Arguments
+///
+/// * `f` - The function, returning `None` where it is undefined.
+/// * `from` - The start of the interval searched.
+/// * `to` - The end of the interval searched.
+pub fn roots(f: impl Fn(f64) -> Option<f64>, from: f64, to: f64) -> Vec<f64> {
+    let samples = sample(&f, from, to);
+    let mut roots = Vec::new();
+    for pair in samples.windows(2) {
+        let ((a, fa), (b, fb)) = (pair[0], pair[1]);
+        let (Some(fa), Some(fb)) = (fa, fb)
+        else {
+            continue;
+        };
+        if fa == 0.0 {
+            roots.push(a);
+        }
+        else if fa.signum() != fb.signum() && fb != 0.0 {
+            if let Some(root) = bisect(&f, a, b, fa) {
+                roots.push(root);
+            }
+        }
+    }
+    if let Some((x, Some(y))) = samples.last() {
+        if *y == 0.0 {
+            roots.push(*x);
+        }
+    }
+    // A root where the function touches the axis without crossing it is an extremum at zero.
+    let tolerance = 1e-9 * (to - from);
+    for extremum in extrema(&f, from, to) {
+        if extremum.y.abs() < tolerance && roots.iter().all(|root| (root - extremum.x).abs() > tolerance) {
+            roots.push(extremum.x);
+        }
+    }
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Returns the local minima and maxima of a function between two points, in increasing order of `x`.
+///
+/// # Arguments
+///
+/// * `f` - The function, returning `None` where it is undefined.
+/// * `from` - The start of the interval searched.
+/// * `to` - The end of the interval searched.
+pub fn extrema(f: impl Fn(f64) -> Option<f64>, from: f64, to: f64) -> Vec<Feature> {
+    let samples = sample(&f, from, to);
+    let mut extrema = Vec::new();
+    for triple in samples.windows(3) {
+        let ((a, fa), (_, fm), (b, fb)) = (triple[0], triple[1], triple[2]);
+        let (Some(fa), Some(fm), Some(fb)) = (fa, fm, fb)
+        else {
+            continue;
+        };
+        let kind = if fm < fa && fm <= fb {
+            FeatureKind::Minimum
+        }
+        else if fm > fa && fm >= fb {
+            FeatureKind::Maximum
+        }
+        else {
+            continue;
+        };
+        if let Some((x, y)) = golden_section(&f, a, b, kind == FeatureKind::Maximum) {
+            // Between the samples, a smooth function stays close to the middle sample, unlike one running off to
+            // infinity at a discontinuity (e.g. `tan(x)` at 90°).
+            if (y - fm).abs() <= (fa - fm).abs() + (fb - fm).abs() {
+                extrema.push(Feature { kind, x, y });
+            }
+        }
+    }
+    extrema
+}
+
+/// Returns the intersections of two functions between two points, in increasing order of `x`.
+///
+/// # Arguments
+///
+/// * `f` - The first function, returning `None` where it is undefined.
+/// * `g` - The second function, returning `None` where it is undefined.
+/// * `from` - The start of the interval searched.
+/// * `to` - The end of the interval searched.
+pub fn intersections(
+    f: impl Fn(f64) -> Option<f64>,
+    g: impl Fn(f64) -> Option<f64>,
+    from: f64,
+    to: f64,
+) -> Vec<Feature> {
+    roots(|x| Some(f(x)? - g(x)?), from, to)
+        .into_iter()
+        .filter_map(|x| {
+            Some(Feature {
+                kind: FeatureKind::Intersection,
+                x,
+                y: f(x)?,
+            })
+        })
+        .collect()
+}
+
+/// Samples a function at [`SAMPLES`] evenly spaced points between two points, both included.
+fn sample(f: &impl Fn(f64) -> Option<f64>, from: f64, to: f64) -> Vec<(f64, Option<f64>)> {
+    (0..=SAMPLES)
+        .map(|index| {
+            let x = from + (to - from) * index as f64 / SAMPLES as f64;
+            (x, f(x))
+        })
+        .collect()
+}
+
+/// Finds the root of a function between two points where it has opposite signs by bisection, or returns `None` if
+/// the sign change is a discontinuity.
+fn bisect(f: &impl Fn(f64) -> Option<f64>, mut a: f64, mut b: f64, mut fa: f64) -> Option<f64> {
+    let scale = fa.abs().max(f(b)?.abs()).max(1.0);
+    for _ in 0..REFINEMENT_STEPS {
+        let middle = (a + b) / 2.0;
+        if middle <= a || middle >= b {
+            break;
+        }
+        let fm = f(middle)?;
+        if fm == 0.0 {
+            return Some(middle);
+        }
+        if fm.signum() == fa.signum() {
+            a = middle;
+            fa = fm;
+        }
+        else {
+            b = middle;
+        }
+    }
+    let root = (a + b) / 2.0;
+    (f(root)?.abs() <= 1e-6 * scale).then_some(root)
+}
+
+/// Finds the minimum or the maximum of a function between two points by golden section search.
+fn golden_section(f: &impl Fn(f64) -> Option<f64>, mut a: f64, mut b: f64, maximum: bool) -> Option<(f64, f64)> {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    // Searching for the minimum of the negated function finds the maximum.
+    let value = |x: f64| f(x).map(|y| if maximum { -y } else { y });
+    for _ in 0..REFINEMENT_STEPS {
+        let c = b - ratio * (b - a);
+        let d = a + ratio * (b - a);
+        if c <= a || d >= b {
+            break;
+        }
+        if value(c)? < value(d)? {
+            b = d;
+        }
+        else {
+            a = c;
+        }
+    }
+    let x = (a + b) / 2.0;
+    Some((x, f(x)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::engine::AngleUnit;
+
+    /// Asserts that the `x` coordinates found are the expected ones up to the precision of the search.
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} is not {:?}", actual, expected);
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-6, "{:?} is not {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn curves() {
+        let (variables, functions) = (BTreeMap::new(), BTreeMap::new());
+        let context = Context {
+            variables:  &variables,
+            functions:  &functions,
+            answers:    &[],
+            angle_unit: AngleUnit::Degrees,
+            complex:    false,
+            interval:   false,
+        };
+        let evaluate = |text: &str, x: f64| Curve::parse(text).unwrap().evaluate(&context, x);
+        assert_eq!(evaluate("x^2 - 2", 3.0), Some(7.0));
+        assert_eq!(evaluate("y = 2 * x", 1.5), Some(3.0));
+        assert_eq!(evaluate("f(t) = t^2 - 1", 3.0), Some(8.0));
+        assert_eq!(evaluate("sqrt(x)", -1.0), None);
+        assert_eq!(evaluate("1 / x", 0.0), None);
+        assert!(matches!(Curve::parse("z = x"), Err(Error::Syntax(_))));
+        assert!(matches!(Curve::parse("f(a, b) = a"), Err(Error::ArgumentCount { found: 2, .. })));
+    }
+
+    #[test]
+    fn roots_of_functions() {
+        assert_close(&roots(|x| Some(x * x - 2.0), -3.0, 3.0), &[-2f64.sqrt(), 2f64.sqrt()]);
+        assert_close(&roots(|x| Some((x - 1.0).powi(2)), -2.05, 3.1), &[1.0]);
+        assert_close(&roots(|x| Some(x.sin()), -1.0, 7.0), &[0.0, std::f64::consts::PI, 2.0 * std::f64::consts::PI]);
+        assert_close(&roots(|x| Some(x.tan()), 1.0, 2.0), &[]);
+        assert_close(&roots(|x| (x >= 0.0).then(|| x.sqrt() - 1.0), -4.0, 4.0), &[1.0]);
+    }
+
+    #[test]
+    fn extrema_of_functions() {
+        use std::f64::consts::FRAC_PI_2;
+
+        let features = extrema(|x| Some(x.sin()), 0.0, 7.0);
+        let kinds = features.iter().map(|feature| feature.kind).collect::<Vec<_>>();
+        assert_eq!(kinds, [FeatureKind::Maximum, FeatureKind::Minimum]);
+        let xs = features.iter().map(|feature| feature.x).collect::<Vec<_>>();
+        assert_close(&xs, &[FRAC_PI_2, 3.0 * FRAC_PI_2]);
+        assert!(extrema(|x| Some(x.tan()), 1.0, 2.0).is_empty());
+        assert!(extrema(|x| Some(2.0 * x), -5.0, 5.0).is_empty());
+    }
+
+    #[test]
+    fn intersections_of_functions() {
+        let features = intersections(|x| Some(x * x), |x| Some(x + 2.0), -5.0, 5.0);
+        let xs = features.iter().map(|feature| feature.x).collect::<Vec<_>>();
+        let ys = features.iter().map(|feature| feature.y).collect::<Vec<_>>();
+        assert_close(&xs, &[-1.0, 2.0]);
+        assert_close(&ys, &[1.0, 4.0]);
+        assert!(features
+            .iter()
+            .all(|feature| feature.kind == FeatureKind::Intersection));
+        assert!(intersections(Some, |x| Some(x + 1.0), -5.0, 5.0).is_empty());
+    }
+
+    #[test]
+    fn viewports_and_ticks() {
+        let viewport = Viewport::default().zoom(0.5, 2.0, 0.0).pan(1.0, -1.0);
+        assert_eq!(
+            viewport,
+            Viewport {
+                x_min: -3.0,
+                x_max: 7.0,
+                y_min: -6.0,
+                y_max: 4.0,
+            }
+        );
+        assert!(viewport.is_valid());
+        assert!(!Viewport::default().zoom(0.0, 0.0, 0.0).is_valid());
+        assert_eq!(Viewport::ticks(0.0, 1.0, 5), [0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
+        assert_eq!(Viewport::ticks(-3.0, 7.0, 4), [0.0, 5.0]);
+        assert!(Viewport::ticks(1.0, 1.0, 5).is_empty());
+    }
+}
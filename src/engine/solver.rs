@@ -0,0 +1,491 @@
+//! This module contains the solver of equations in one variable (e.g. `x^2 - 2 = 0` or `cos(x) = x`), used by
+//! `solve(…)` in expressions and by the solver page.
+//!
+//! An equation whose two sides are polynomials in the variable, once their other names are replaced by their values,
+//! is solved exactly for degrees 1 and 2 and with the Weierstrass (Durand–Kerner) iteration up to [`MAX_DEGREE`]. All
+//! its roots are found, real and complex, each repeated as many times as its multiplicity. Any other equation is
+//! solved numerically: its real roots are searched between the two ends of a bracket, [`DEFAULT_BRACKET`] unless
+//! given, like the roots of a curve of the plot page, so roots closer together than the samples may be missed. When
+//! a bracket is given for a polynomial, only its real roots within the bracket are kept.
+//!
+//! Roots are floating point numbers. Complex roots are only reported, since numbers are real.
+
+use std::ops::{Add, Div, Mul, Sub};
+
+use super::expression::Expression;
+use super::{Context, Error, Operator, Statement};
+
+/// The largest degree of polynomials whose roots are all found. Equations of higher degree are solved numerically.
+pub const MAX_DEGREE: usize = 24;
+
+/// The interval searched for the real roots of an equation that is not a polynomial, when no bracket is given.
+pub const DEFAULT_BRACKET: (f64, f64) = (-100.0, 100.0);
+
+/// The largest number of steps of the Weierstrass iteration.
+const MAX_ITERATIONS: usize = 1000;
+
+/// The distance, relative to their size, under which roots are checked for being a single multiple root.
+const CLUSTER_DISTANCE: f64 = 1e-4;
+
+/// A root of an equation, which is a complex number for some polynomials.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Root {
+    /// The real part of the root.
+    pub re: f64,
+    /// The imaginary part of the root, `0` for a real root.
+    pub im: f64,
+}
+
+/// A polynomial in one variable with floating point coefficients.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polynomial {
+    /// The coefficients, from the constant term up. The last one is not zero, and there are none for the zero
+    /// polynomial.
+    coefficients: Vec<f64>,
+}
+
+/// The roots of an equation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+    /// The roots: the real ones in increasing order, then the complex ones.
+    pub roots:  Vec<Root>,
+    /// The degree of the equation if it is a polynomial of at most [`MAX_DEGREE`], or `None` if it was solved
+    /// numerically. Only then can roots be missing, and then all are real.
+    pub degree: Option<usize>,
+}
+
+/// An equation in one variable, typed as `lhs = rhs` or as an expression equal to zero.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Equation {
+    expression: Expression,
+    variable:   String,
+}
+
+impl Root {
+    /// Creates a real root.
+    pub fn real(re: f64) -> Self {
+        Self { re, im: 0.0 }
+    }
+
+    /// Returns `true` if the root is real.
+    pub fn is_real(&self) -> bool {
+        self.im == 0.0
+    }
+
+    /// Returns the absolute value of the root.
+    fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+impl Add for Root {
+    type Output = Root;
+
+    fn add(self, other: Root) -> Root {
+        Root {
+            re: self.re + other.re,
+            im: self.im + other.im,
+        }
+    }
+}
+
+impl Sub for Root {
+    type Output = Root;
+
+    fn sub(self, other: Root) -> Root {
+        Root {
+            re: self.re - other.re,
+            im: self.im - other.im,
+        }
+    }
+}
+
+impl Mul for Root {
+    type Output = Root;
+
+    fn mul(self, other: Root) -> Root {
+        Root {
+            re: self.re * other.re - self.im * other.im,
+            im: self.re * other.im + self.im * other.re,
+        }
+    }
+}
+
+impl Div for Root {
+    type Output = Root;
+
+    fn div(self, other: Root) -> Root {
+        let denominator = other.re * other.re + other.im * other.im;
+        Root {
+            re: (self.re * other.re + self.im * other.im) / denominator,
+            im: (self.im * other.re - self.re * other.im) / denominator,
+        }
+    }
+}
+
+impl Polynomial {
+    /// Creates a constant polynomial.
+    pub fn constant(value: f64) -> Self {
+        Self::from_coefficients(vec![value])
+    }
+
+    /// Creates the polynomial of the variable itself.
+    pub fn variable() -> Self {
+        Self::from_coefficients(vec![0.0, 1.0])
+    }
+
+    /// Creates a polynomial from its coefficients, from the constant term up. Trailing zeros are removed.
+    pub fn from_coefficients(mut coefficients: Vec<f64>) -> Self {
+        while coefficients.last() == Some(&0.0) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    /// Returns the degree of the polynomial, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// Returns the value of the polynomial if it is constant.
+    pub fn as_constant(&self) -> Option<f64> {
+        match self.coefficients.as_slice() {
+            [] => Some(0.0),
+            [value] => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the sum of two polynomials.
+    pub fn add(&self, other: &Polynomial) -> Polynomial {
+        let len = self.coefficients.len().max(other.coefficients.len());
+        Self::from_coefficients(
+            (0..len)
+                .map(|i| self.coefficient(i) + other.coefficient(i))
+                .collect(),
+        )
+    }
+
+    /// Returns the product of the polynomial and a number.
+    pub fn scale(&self, factor: f64) -> Polynomial {
+        Self::from_coefficients(self.coefficients.iter().map(|c| c * factor).collect())
+    }
+
+    /// Returns the product of two polynomials.
+    pub fn mul(&self, other: &Polynomial) -> Polynomial {
+        if self.coefficients.is_empty() || other.coefficients.is_empty() {
+            return Self::from_coefficients(Vec::new());
+        }
+        let mut coefficients = vec![0.0; self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                coefficients[i + j] += a * b;
+            }
+        }
+        Self::from_coefficients(coefficients)
+    }
+
+    /// Returns the polynomial to a power.
+    pub fn pow(&self, exponent: usize) -> Polynomial {
+        (0..exponent).fold(Self::constant(1.0), |power, _| power.mul(self))
+    }
+
+    /// Returns the coefficient of the `i`th power of the variable.
+    fn coefficient(&self, i: usize) -> f64 {
+        self.coefficients.get(i).copied().unwrap_or_default()
+    }
+
+    /// Returns the derivative of the polynomial.
+    fn derivative(&self) -> Polynomial {
+        Self::from_coefficients(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, c)| c * i as f64)
+                .collect(),
+        )
+    }
+
+    /// Returns the value of the polynomial at a complex point.
+    fn evaluate(&self, z: Root) -> Root {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Root::real(0.0), |value, c| value * z + Root::real(*c))
+    }
+
+    /// Returns the sum of the absolute values of the terms of the polynomial at a point, the scale of the rounding
+    /// errors of its value there.
+    fn magnitude(&self, z: Root) -> f64 {
+        let norm = z.norm();
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |value, c| value * norm + c.abs())
+    }
+
+    /// Returns all the roots of the polynomial, each repeated as many times as its multiplicity: the real ones in
+    /// increasing order, then the complex ones by increasing real part. A constant polynomial has no roots.
+    pub fn roots(&self) -> Vec<Root> {
+        // Roots at zero are exact.
+        let zeros = self.coefficients.iter().take_while(|c| **c == 0.0).count();
+        let reduced = Self::from_coefficients(self.coefficients[zeros..].to_vec());
+        let mut roots = vec![Root::real(0.0); zeros];
+        match reduced.coefficients.as_slice() {
+            [] | [_] => {}
+            [b, a] => roots.push(Root::real(-b / a)),
+            [c, b, a] => roots.extend(quadratic_roots(*a, *b, *c)),
+            _ => roots.extend(reduced.weierstrass()),
+        }
+        for root in &mut roots {
+            // Avoids showing `-0`.
+            root.re += 0.0;
+            // The complex roots of a real polynomial come in conjugate pairs, so a tiny imaginary part is an error.
+            if root.im.abs() <= 1e-10 * root.re.abs().max(1.0) {
+                root.im = 0.0;
+            }
+        }
+        roots.sort_by(|a, b| {
+            b.is_real()
+                .cmp(&a.is_real())
+                .then(a.re.total_cmp(&b.re))
+                .then(a.im.total_cmp(&b.im))
+        });
+        roots
+    }
+
+    /// Finds the roots of a polynomial of degree 3 or more with the Weierstrass iteration, then polishes them.
+    fn weierstrass(&self) -> Vec<Root> {
+        let degree = self.coefficients.len() - 1;
+        let leading = self.coefficients[degree];
+        let monic = self.scale(1.0 / leading);
+        // All the roots are within the Cauchy bound, and the starting points must not be symmetric.
+        let radius = 1.0
+            + monic.coefficients[..degree]
+                .iter()
+                .fold(0.0_f64, |bound, c| bound.max(c.abs()));
+        let mut roots = (0..degree)
+            .map(|k| {
+                let angle = std::f64::consts::TAU * k as f64 / degree as f64 + 0.4;
+                Root {
+                    re: radius * angle.cos(),
+                    im: radius * angle.sin(),
+                }
+            })
+            .collect::<Vec<Root>>();
+        for _ in 0..MAX_ITERATIONS {
+            let mut change = 0.0_f64;
+            for k in 0..degree {
+                let z = roots[k];
+                let denominator = roots
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != k)
+                    .fold(Root::real(1.0), |product, (_, other)| product * (z - *other));
+                let step = monic.evaluate(z) / denominator;
+                if step.re.is_finite() && step.im.is_finite() {
+                    roots[k] = z - step;
+                    change = change.max(step.norm() / z.norm().max(1.0));
+                }
+            }
+            if change <= 1e-15 {
+                break;
+            }
+        }
+        // A Newton step removes the last rounding errors of simple roots, if it brings the value closer to zero.
+        let derivative = self.derivative();
+        for root in &mut roots {
+            let polished = *root - self.evaluate(*root) / derivative.evaluate(*root);
+            if self.evaluate(polished).norm() < self.evaluate(*root).norm() {
+                *root = polished;
+            }
+        }
+        self.merge_multiple_roots(&mut roots);
+        roots
+    }
+
+    /// Replaces the approximations of a multiple root, which are spread around it, by the root itself. Roots close
+    /// to each other are only merged if the polynomial is zero at their center up to rounding errors, so distinct
+    /// roots that are merely close are kept apart.
+    fn merge_multiple_roots(&self, roots: &mut [Root]) {
+        let mut merged = vec![false; roots.len()];
+        for i in 0..roots.len() {
+            if merged[i] {
+                continue;
+            }
+            let distance = CLUSTER_DISTANCE * roots[i].norm().max(1.0);
+            let cluster = (i..roots.len())
+                .filter(|j| !merged[*j] && (roots[*j] - roots[i]).norm() < distance)
+                .collect::<Vec<usize>>();
+            if cluster.len() < 2 {
+                continue;
+            }
+            let sum = cluster
+                .iter()
+                .fold(Root::real(0.0), |sum, j| sum + roots[*j]);
+            let mut center = sum / Root::real(cluster.len() as f64);
+            // A root of multiplicity m is a simple root of the (m - 1)th derivative.
+            let derivative = (1..cluster.len()).fold(self.clone(), |derivative, _| derivative.derivative());
+            let slope = derivative.derivative();
+            for _ in 0..3 {
+                let step = derivative.evaluate(center) / slope.evaluate(center);
+                if step.re.is_finite() && step.im.is_finite() {
+                    center = center - step;
+                }
+            }
+            if self.evaluate(center).norm() <= 1e-12 * self.magnitude(center) {
+                for j in cluster {
+                    roots[j] = center;
+                    merged[j] = true;
+                }
+            }
+        }
+    }
+}
+
+impl Solution {
+    /// Returns the smallest real root, or `None` if there is none.
+    pub fn first_real_root(&self) -> Option<f64> {
+        self.roots
+            .iter()
+            .find(|root| root.is_real())
+            .map(|root| root.re)
+    }
+}
+
+impl Equation {
+    /// Parses an equation. Without `=`, the expression is equal to zero (e.g. `x^2 - 2` is `x^2 - 2 = 0`).
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The equation as typed.
+    /// * `variable` - The name of the variable solved for.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Syntax`] if a side is not a valid expression, or there is more than one `=`.
+    pub fn parse(text: &str, variable: &str) -> Result<Self, Error> {
+        let mut sides = text.split('=').map(|side| {
+            match Statement::parse(side)? {
+                Statement::Expression(expression) => Ok(expression),
+                _ => Err(Error::Syntax(format!("Expected an expression, not ‘{}’", side.trim()))),
+            }
+        });
+        let lhs = sides.next().unwrap_or_else(|| Err(Error::Syntax("Empty equation".to_string())))?;
+        let expression = match sides.next() {
+            Some(rhs) => Expression::Binary(Operator::Subtract, Box::new(lhs), Box::new(rhs?)),
+            None => lhs,
+        };
+        if sides.next().is_some() {
+            return Err(Error::Syntax("An equation has a single ‘=’".to_string()));
+        }
+        Ok(Self {
+            expression,
+            variable: variable.to_string(),
+        })
+    }
+
+    /// Solves the equation.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The variables, functions and previous results the equation can refer to.
+    /// * `bracket` - The interval searched if the equation is not a polynomial, or `None` for [`DEFAULT_BRACKET`]. The
+    ///   roots of a polynomial outside of a given bracket are left out.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if every value of the variable is a root (e.g. `x = x`).
+    /// * The errors of [`Expression::evaluate`] for names the equation refers to.
+    pub fn solve(&self, context: &Context, bracket: Option<(f64, f64)>) -> Result<Solution, Error> {
+        self.expression.solve(context, &self.variable, bracket)
+    }
+}
+
+/// Returns the roots of `a x^2 + b x + c`, computed without cancellation.
+fn quadratic_roots(a: f64, b: f64, c: f64) -> [Root; 2] {
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant >= 0.0 {
+        let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+        if q == 0.0 {
+            return [Root::real(0.0), Root::real(0.0)];
+        }
+        [Root::real(q / a), Root::real(c / q)]
+    }
+    else {
+        let re = -b / (2.0 * a);
+        let im = (-discriminant).sqrt() / (2.0 * a).abs();
+        [Root { re, im: -im }, Root { re, im }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::engine::{AngleUnit, Number, Quantity};
+
+    /// Asserts that the roots found are the expected ones, given as `(re, im)`, up to rounding errors.
+    fn assert_roots(actual: &[Root], expected: &[(f64, f64)]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} is not {:?}", actual, expected);
+        for (root, (re, im)) in actual.iter().zip(expected) {
+            assert!((root.re - re).abs() < 1e-9 && (root.im - im).abs() < 1e-9, "{:?} is not {:?}", actual, expected);
+        }
+    }
+
+    /// Solves an equation in `x` with a variable `a = 2`, with angles in radians.
+    fn solve(text: &str, bracket: Option<(f64, f64)>) -> Result<Solution, Error> {
+        let variables = BTreeMap::from([("a".to_string(), Quantity::from(Number::from_integer(2)))]);
+        let functions = BTreeMap::new();
+        let context = Context {
+            variables:  &variables,
+            functions:  &functions,
+            answers:    &[],
+            angle_unit: AngleUnit::Radians,
+            complex:    false,
+            interval:   false,
+        };
+        Equation::parse(text, "x")?.solve(&context, bracket)
+    }
+
+    #[test]
+    fn polynomial_roots() {
+        let roots = |coefficients: &[f64]| Polynomial::from_coefficients(coefficients.to_vec()).roots();
+        assert_roots(&roots(&[2.0, -3.0, 1.0]), &[(1.0, 0.0), (2.0, 0.0)]);
+        assert_roots(&roots(&[1.0, 0.0, 1.0]), &[(0.0, -1.0), (0.0, 1.0)]);
+        assert_roots(&roots(&[-6.0, 11.0, -6.0, 1.0]), &[(1.0, 0.0), (2.0, 0.0), (3.0, 0.0)]);
+        assert_roots(&roots(&[-1.0, 3.0, -3.0, 1.0]), &[(1.0, 0.0), (1.0, 0.0), (1.0, 0.0)]);
+        assert_roots(&roots(&[0.0, 0.0, -4.0, 0.0, 1.0]), &[(-2.0, 0.0), (0.0, 0.0), (0.0, 0.0), (2.0, 0.0)]);
+        assert_roots(&roots(&[5.0]), &[]);
+        assert_eq!(Polynomial::from_coefficients(vec![1.0, 2.0, 0.0, 0.0]).degree(), Some(1));
+        assert_eq!(Polynomial::constant(0.0).degree(), None);
+    }
+
+    #[test]
+    fn polynomial_equations() {
+        let sqrt_2 = 2f64.sqrt();
+        let solution = solve("x^2 = a", None).unwrap();
+        assert_eq!(solution.degree, Some(2));
+        assert_roots(&solution.roots, &[(-sqrt_2, 0.0), (sqrt_2, 0.0)]);
+        assert_eq!(solution.first_real_root().map(f64::abs), Some(sqrt_2));
+        assert_roots(&solve("(x - 1) * (x + a) * x", None).unwrap().roots, &[(-2.0, 0.0), (0.0, 0.0), (1.0, 0.0)]);
+        assert_roots(&solve("x^2 - 4", Some((0.0, 10.0))).unwrap().roots, &[(2.0, 0.0)]);
+        assert_roots(&solve("x^2 + 1", Some((-10.0, 10.0))).unwrap().roots, &[]);
+        assert_roots(&solve("x^2 + 1 = x^2", None).unwrap().roots, &[]);
+        assert_eq!(solve("x = x", None), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn numerical_equations() {
+        let solution = solve("cos(x) = x", None).unwrap();
+        assert_eq!(solution.degree, None);
+        assert_roots(&solution.roots, &[(0.7390851332151607, 0.0)]);
+        let pi = std::f64::consts::PI;
+        assert_roots(&solve("sin(x)", Some((-4.0, 4.0))).unwrap().roots, &[(-pi, 0.0), (0.0, 0.0), (pi, 0.0)]);
+        assert_roots(&solve("sqrt(x) = a", None).unwrap().roots, &[(4.0, 0.0)]);
+        assert_eq!(solve("x + unknown", None), Err(Error::UnknownName("unknown".to_string())));
+        assert!(matches!(solve("x = 1 = 2", None), Err(Error::Syntax(_))));
+    }
+}
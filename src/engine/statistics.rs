@@ -0,0 +1,487 @@
+//! This module contains the calculations of the statistics page: the [`DataSet`] of single values, weighted values or
+//! paired `x, y` values, its descriptive statistics as a [`Summary`], and the least squares [`Regression`] line of
+//! paired values.
+//!
+//! Weights are frequencies: a value with a weight of 3 counts as three equal values, so weights must be non-negative
+//! integers. The median and the quartiles are those of the sorted values, the quartiles being the medians of the lower
+//! and upper halves, both without the median itself when the count is odd. The modes are the values occurring most
+//! often, and there are none when every value occurs equally often. The sample variance divides by `n − 1` and the
+//! population variance by `n`.
+
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use super::{Error, Number};
+
+/// How the two columns of a [`DataSet`] are read.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DataKind {
+    /// Single values. The second column is ignored.
+    #[default]
+    Values,
+    /// Values with their weight (frequency) in the second column.
+    Weighted,
+    /// Paired `x, y` values.
+    Paired,
+}
+
+impl DataKind {
+    /// Returns the kind of the data of a CSV file from its header line (e.g. `x,y` for paired values), or `None` if the
+    /// line is not one of the headers written by [`DataSet::to_csv`].
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The first line of the file.
+    pub fn from_header(header: &str) -> Option<Self> {
+        let header = header.replace(' ', "").to_lowercase();
+        [DataKind::Values, DataKind::Weighted, DataKind::Paired]
+            .into_iter()
+            .find(|kind| kind.header() == header)
+    }
+
+    /// Returns the header of the CSV files of data sets of this kind.
+    fn header(self) -> &'static str {
+        match self {
+            DataKind::Values => "x",
+            DataKind::Weighted => "x,weight",
+            DataKind::Paired => "x,y",
+        }
+    }
+}
+
+/// A data point of a [`DataSet`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataPoint {
+    /// The value, or the `x` value of paired values.
+    pub x: Number,
+    /// The weight of a weighted value, or the `y` value of paired values. It is `1` for single values.
+    pub y: Number,
+}
+
+/// A list of data points read as a [`DataKind`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DataSet {
+    /// How the data points are read.
+    pub kind:   DataKind,
+    /// The data points, in the order they were entered.
+    pub points: Vec<DataPoint>,
+}
+
+/// The descriptive statistics of a list of values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    /// The number of values, counting their weights.
+    pub count:                Number,
+    /// The sum of the values.
+    pub sum:                  Number,
+    /// The arithmetic mean.
+    pub mean:                 Number,
+    /// The median.
+    pub median:               Number,
+    /// The values occurring most often, in increasing order. Empty if every value occurs equally often.
+    pub modes:                Vec<Number>,
+    /// The sample variance, or `None` for a single value.
+    pub sample_variance:      Option<Number>,
+    /// The sample standard deviation, or `None` for a single value.
+    pub sample_deviation:     Option<Number>,
+    /// The population variance.
+    pub population_variance:  Number,
+    /// The population standard deviation.
+    pub population_deviation: Number,
+    /// The smallest value.
+    pub minimum:              Number,
+    /// The largest value.
+    pub maximum:              Number,
+    /// The first and third quartiles, or `None` for a single value.
+    pub quartiles:            Option<(Number, Number)>,
+}
+
+/// The least squares regression line `y = slope · x + intercept` of paired values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+    /// The slope of the line.
+    pub slope:       Number,
+    /// The value of the line at `x = 0`.
+    pub intercept:   Number,
+    /// The correlation coefficient `r`, or `None` if all the `y` values are equal.
+    pub correlation: Option<Number>,
+    /// The coefficient of determination `r²`, or `None` if all the `y` values are equal.
+    pub r_squared:   Option<Number>,
+}
+
+impl DataPoint {
+    /// Parses a data point from one or two numbers separated by a comma, a semicolon, a tab or spaces (e.g. `12.5` or
+    /// `3, 7.2`). A single number has a second column of `1`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Syntax`] if the text is not one or two numbers.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let fields = fields(text);
+        let number = |field: &str| {
+            field
+                .parse::<Number>()
+                .map_err(|_| Error::Syntax(format!("Invalid number ‘{}’", field)))
+        };
+        match fields.as_slice() {
+            [x] => {
+                Ok(Self {
+                    x: number(x)?,
+                    y: Number::from_integer(1),
+                })
+            }
+            [x, y] => {
+                Ok(Self {
+                    x: number(x)?,
+                    y: number(y)?,
+                })
+            }
+            _ => Err(Error::Syntax(format!("Expected one or two numbers, not ‘{}’", text.trim()))),
+        }
+    }
+}
+
+impl DataSet {
+    /// Parses pasted or loaded data points, one per line (e.g. the content of a CSV file). Empty lines are skipped,
+    /// and so is a header on the first line (e.g. `x,y`). For single values, every number of every line is a value, so
+    /// a row of values can be pasted as well as a column.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The data, with `.` as decimal point.
+    /// * `kind` - How the data points are read.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Syntax`] if a line is not a valid data point (e.g. a single number for paired values). The message
+    ///   holds the number of the line.
+    pub fn parse(text: &str, kind: DataKind) -> Result<Self, Error> {
+        let mut points = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line_points = if kind == DataKind::Values {
+                fields(line)
+                    .into_iter()
+                    .map(DataPoint::parse)
+                    .collect::<Result<Vec<_>, Error>>()
+            }
+            else if line.trim().is_empty() {
+                Ok(Vec::new())
+            }
+            else if kind == DataKind::Paired && fields(line).len() == 1 {
+                Err(Error::Syntax(format!("Expected an x and a y value, not ‘{}’", line.trim())))
+            }
+            else {
+                DataPoint::parse(line).map(|point| vec![point])
+            };
+            match line_points {
+                Ok(line_points) => points.extend(line_points),
+                Err(_) if index == 0 && line.chars().any(char::is_alphabetic) => continue,
+                Err(error) => return Err(Error::Syntax(format!("Line {}: {}", index + 1, error))),
+            }
+        }
+        Ok(Self { kind, points })
+    }
+
+    /// Returns the data points as a CSV file with a header line and `.` as decimal point.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{}\n", self.kind.header());
+        for point in &self.points {
+            // Writing to a `String` cannot fail.
+            let _ = match self.kind {
+                DataKind::Values => writeln!(csv, "{}", point.x),
+                DataKind::Weighted | DataKind::Paired => writeln!(csv, "{},{}", point.x, point.y),
+            };
+        }
+        csv
+    }
+
+    /// Returns the statistics of the values, or of the `x` values of paired values.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if there are no values, or a weight is negative or not an integer.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn summary(&self) -> Result<Summary, Error> {
+        let one = Number::from_integer(1);
+        let values = self
+            .points
+            .iter()
+            .map(|point| {
+                match self.kind {
+                    DataKind::Weighted => (point.x.clone(), point.y.clone()),
+                    DataKind::Values | DataKind::Paired => (point.x.clone(), one.clone()),
+                }
+            })
+            .collect::<Vec<_>>();
+        summary(values)
+    }
+
+    /// Returns the statistics of the `y` values of paired values.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the values are not paired or there are none.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn y_summary(&self) -> Result<Summary, Error> {
+        if self.kind != DataKind::Paired {
+            return Err(Error::InvalidInput);
+        }
+        summary(
+            self.points
+                .iter()
+                .map(|point| (point.y.clone(), Number::from_integer(1)))
+                .collect(),
+        )
+    }
+
+    /// Returns the least squares regression line of paired values.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the values are not paired or there are fewer than two of them.
+    /// * [`Error::DivisionByZero`] if all the `x` values are equal.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn regression(&self) -> Result<Regression, Error> {
+        if self.kind != DataKind::Paired || self.points.len() < 2 {
+            return Err(Error::InvalidInput);
+        }
+        let count = Number::from_integer(self.points.len() as i64);
+        let mean = |value: fn(&DataPoint) -> &Number| {
+            self.points
+                .iter()
+                .try_fold(Number::zero(), |sum, point| sum.add(value(point)))?
+                .div(&count)
+        };
+        let (x_mean, y_mean) = (mean(|point| &point.x)?, mean(|point| &point.y)?);
+        let (mut sxx, mut syy, mut sxy) = (Number::zero(), Number::zero(), Number::zero());
+        for point in &self.points {
+            let (dx, dy) = (point.x.sub(&x_mean)?, point.y.sub(&y_mean)?);
+            sxx = sxx.add(&dx.square()?)?;
+            syy = syy.add(&dy.square()?)?;
+            sxy = sxy.add(&dx.mul(&dy)?)?;
+        }
+        let slope = sxy.div(&sxx)?;
+        let intercept = y_mean.sub(&slope.mul(&x_mean)?)?;
+        let (correlation, r_squared) = if syy.is_zero() {
+            (None, None)
+        }
+        else {
+            let r_squared = sxy.square()?.div(&sxx.mul(&syy)?)?;
+            let r = r_squared.sqrt()?;
+            (Some(if sxy.is_negative() { r.neg() } else { r }), Some(r_squared))
+        };
+        Ok(Regression {
+            slope,
+            intercept,
+            correlation,
+            r_squared,
+        })
+    }
+}
+
+/// Splits a line of data into its numbers, separated by commas, semicolons, tabs or spaces.
+fn fields(line: &str) -> Vec<&str> {
+    line.split([',', ';', '\t', ' '])
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .collect()
+}
+
+/// Compares two numbers. Numbers that are not finite are smaller than all the others.
+fn compare(a: &Number, b: &Number) -> Ordering {
+    a.to_rational().cmp(&b.to_rational())
+}
+
+/// Returns the statistics of values with their weights.
+fn summary(mut values: Vec<(Number, Number)>) -> Result<Summary, Error> {
+    if values
+        .iter()
+        .any(|(_, weight)| weight.is_negative() || !weight.is_integer())
+    {
+        return Err(Error::InvalidInput);
+    }
+    values.retain(|(_, weight)| !weight.is_zero());
+    values.sort_by(|(a, _), (b, _)| compare(a, b));
+    let (Some((minimum, _)), Some((maximum, _))) = (values.first(), values.last())
+    else {
+        return Err(Error::InvalidInput);
+    };
+
+    let mut count = Number::zero();
+    let mut sum = Number::zero();
+    for (value, weight) in &values {
+        count = count.add(weight)?;
+        sum = sum.add(&value.mul(weight)?)?;
+    }
+    let mean = sum.div(&count)?;
+    let mut squares = Number::zero();
+    for (value, weight) in &values {
+        squares = squares.add(&value.sub(&mean)?.square()?.mul(weight)?)?;
+    }
+    let population_variance = squares.div(&count)?;
+    let sample_variance = if count.is_integer() && count.to_f64() > 1.0 {
+        Some(squares.div(&count.sub(&Number::from_integer(1))?)?)
+    }
+    else {
+        None
+    };
+
+    // Equal values are merged, adding their weights, to find the modes.
+    let mut groups: Vec<(Number, Number)> = Vec::new();
+    for (value, weight) in &values {
+        match groups.last_mut() {
+            Some((last, total)) if compare(last, value) == Ordering::Equal => *total = total.add(weight)?,
+            _ => groups.push((value.clone(), weight.clone())),
+        }
+    }
+    let largest = groups
+        .iter()
+        .map(|(_, total)| total)
+        .max_by(|a, b| compare(a, b))
+        .cloned()
+        .unwrap_or_else(Number::zero);
+    let modes = if groups
+        .iter()
+        .all(|(_, total)| compare(total, &largest) == Ordering::Equal)
+    {
+        Vec::new()
+    }
+    else {
+        groups
+            .iter()
+            .filter(|(_, total)| compare(total, &largest) == Ordering::Equal)
+            .map(|(value, _)| value.clone())
+            .collect()
+    };
+
+    // The position of a value in the sorted list of values, counting weights.
+    let n = count.to_f64() as u64;
+    let at = |position: u64| {
+        let mut end = 0;
+        for (value, weight) in &values {
+            end += weight.to_f64() as u64;
+            if position < end {
+                return value.clone();
+            }
+        }
+        maximum.clone()
+    };
+    // The median of the values at the positions `start..end`.
+    let median_of = |start: u64, end: u64| -> Result<Number, Error> {
+        let middle = start + (end - start) / 2;
+        if (end - start) % 2 == 1 {
+            Ok(at(middle))
+        }
+        else {
+            at(middle - 1)
+                .add(&at(middle))?
+                .div(&Number::from_integer(2))
+        }
+    };
+    let quartiles = if n > 1 {
+        Some((median_of(0, n / 2)?, median_of(n.div_ceil(2), n)?))
+    }
+    else {
+        None
+    };
+
+    Ok(Summary {
+        count,
+        sum,
+        mean,
+        median: median_of(0, n)?,
+        modes,
+        sample_deviation: sample_variance.as_ref().map(Number::sqrt).transpose()?,
+        sample_variance,
+        population_deviation: population_variance.sqrt()?,
+        population_variance,
+        minimum: minimum.clone(),
+        maximum: maximum.clone(),
+        quartiles,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses an exact number.
+    fn number(text: &str) -> Number {
+        text.parse().unwrap()
+    }
+
+    /// Returns the summary of pasted data.
+    fn summary(text: &str, kind: DataKind) -> Result<Summary, Error> {
+        DataSet::parse(text, kind)?.summary()
+    }
+
+    #[test]
+    fn parse_and_csv() {
+        let data = DataSet::parse("x,y\n1, 2\n\n3;4.5\n", DataKind::Paired).unwrap();
+        assert_eq!(data.points.len(), 2);
+        assert_eq!(data.points[1].y, number("4.5"));
+        assert_eq!(data.to_csv(), "x,y\n1,2\n3,4.5\n");
+        assert_eq!(DataSet::parse(&data.to_csv(), DataKind::Paired), Ok(data));
+        assert_eq!(DataKind::from_header("X, Weight"), Some(DataKind::Weighted));
+        assert_eq!(DataSet::parse("1 2 3\n4", DataKind::Values).unwrap().points.len(), 4);
+        assert!(matches!(DataSet::parse("1,2\n3", DataKind::Paired), Err(Error::Syntax(_))));
+    }
+
+    #[test]
+    fn quartiles() {
+        let odd = summary("1 2 3 4 5 6 7 8 9", DataKind::Values).unwrap();
+        assert_eq!(odd.median, number("5"));
+        assert_eq!(odd.quartiles, Some((number("2.5"), number("7.5"))));
+        let even = summary("8 7 6 5 4 3 2 1", DataKind::Values).unwrap();
+        assert_eq!(even.median, number("4.5"));
+        assert_eq!(even.quartiles, Some((number("2.5"), number("6.5"))));
+        let single = summary("42", DataKind::Values).unwrap();
+        assert_eq!(single.quartiles, None);
+        assert_eq!(single.sample_variance, None);
+    }
+
+    #[test]
+    fn weighted_values() {
+        let weighted = summary("1,2\n2,1\n3,3\n4,0", DataKind::Weighted).unwrap();
+        assert_eq!(weighted.count, number("6"));
+        assert_eq!(weighted.mean, number("13").div(&number("6")).unwrap());
+        assert_eq!(weighted.median, number("2.5"));
+        assert_eq!(weighted.quartiles, Some((number("1"), number("3"))));
+        assert_eq!(weighted.modes, [number("3")]);
+        assert_eq!(weighted.maximum, number("3"));
+        assert_eq!(summary("1,-1", DataKind::Weighted), Err(Error::InvalidInput));
+        assert_eq!(summary("1,0.5", DataKind::Weighted), Err(Error::InvalidInput));
+        assert_eq!(summary("", DataKind::Values), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn variance_and_modes() {
+        let values = summary("2 4 4 4 5 5 7 9", DataKind::Values).unwrap();
+        assert_eq!(values.mean, number("5"));
+        assert_eq!(values.population_variance, number("4"));
+        assert_eq!(values.population_deviation.to_f64(), 2.0);
+        assert_eq!(values.sample_variance, Some(number("32").div(&number("7")).unwrap()));
+        assert_eq!(values.modes, [number("4")]);
+        assert!(summary("1 2 3", DataKind::Values).unwrap().modes.is_empty());
+    }
+
+    #[test]
+    fn regression() {
+        let regression = |text: &str| DataSet::parse(text, DataKind::Paired)?.regression();
+        let increasing = regression("1,3\n2,5\n3,7").unwrap();
+        assert_eq!((increasing.slope, increasing.intercept), (number("2"), number("1")));
+        assert_eq!(increasing.correlation.map(|r| r.to_f64()), Some(1.0));
+        let decreasing = regression("0,4\n1,2\n2,0").unwrap();
+        assert_eq!((decreasing.slope, decreasing.intercept), (number("-2"), number("4")));
+        assert_eq!(decreasing.correlation.map(|r| r.to_f64()), Some(-1.0));
+        let scattered = regression("0,1\n1,3\n2,2\n3,4").unwrap();
+        assert_eq!((scattered.slope, scattered.intercept), (number("0.8"), number("1.3")));
+        assert_eq!(scattered.r_squared, Some(number("0.64")));
+        assert_eq!(scattered.correlation.map(|r| r.to_f64()), Some(0.8));
+        let flat = regression("1,2\n2,2").unwrap();
+        assert_eq!((flat.slope, flat.correlation), (number("0"), None));
+        assert_eq!(regression("1,2\n1,3"), Err(Error::DivisionByZero));
+        assert_eq!(regression("1,2"), Err(Error::InvalidInput));
+        assert_eq!(DataSet::parse("1 2", DataKind::Values).unwrap().regression(), Err(Error::InvalidInput));
+    }
+}
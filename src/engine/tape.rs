@@ -0,0 +1,167 @@
+//! This module contains the [`Tape`] of calculations: an adding machine style list of the operands entered, each with
+//! the operator applied to it and the running subtotal, and of the totals of the completed calculations. It is derived
+//! from the [`Event`]s of the [`Calculator`], and each line can be annotated (e.g. `Office supplies`).
+//!
+//! [`Calculator`]: super::Calculator
+
+use super::Event;
+
+/// A line of a [`Tape`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TapeLine {
+    /// The event of the calculator shown by the line.
+    pub event:      Event,
+    /// The note added to the line, empty if there is none.
+    pub annotation: String,
+}
+
+/// The lines of the calculation tape, oldest first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tape {
+    lines: Vec<TapeLine>,
+}
+
+impl Tape {
+    /// Creates a new empty [`Tape`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The lines of the tape, oldest first.
+    pub fn lines(&self) -> &[TapeLine] {
+        &self.lines
+    }
+
+    /// Returns `true` if the tape has no lines.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Adds the events of the calculator to the end of the tape, without annotations.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The events to add, oldest first.
+    pub fn record(&mut self, events: impl IntoIterator<Item = Event>) {
+        self.lines.extend(events.into_iter().map(|event| {
+            TapeLine {
+                event,
+                annotation: String::new(),
+            }
+        }));
+    }
+
+    /// Sets the note of a line. Does nothing if there is no such line.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the line, from `0` for the oldest.
+    /// * `annotation` - The note, or an empty string to remove it.
+    pub fn annotate(&mut self, index: usize, annotation: &str) {
+        if let Some(line) = self.lines.get_mut(index) {
+            line.annotation = annotation.trim().to_string();
+        }
+    }
+
+    /// Removes all the lines of the tape.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{Calculator, Complex, Number, Operator};
+
+    /// Returns the operators, the operands and the running subtotals of the operand lines of a tape.
+    fn operands(tape: &Tape) -> Vec<(Option<Operator>, String, Complex)> {
+        tape.lines()
+            .iter()
+            .filter_map(|line| {
+                match &line.event {
+                    Event::Operand {
+                        operator,
+                        operand,
+                        subtotal,
+                    } => Some((*operator, operand.clone(), subtotal.clone())),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns a subtotal from its decimal notation.
+    fn subtotal(text: &str) -> Complex {
+        Complex::from(text.parse::<Number>().unwrap())
+    }
+
+    /// Enters a number on the keypad, digit by digit.
+    fn enter(calculator: &mut Calculator, number: &str) {
+        for digit in number.chars() {
+            calculator.input_digit(digit);
+        }
+    }
+
+    #[test]
+    fn running_subtotals() {
+        let mut calculator = Calculator::new();
+        enter(&mut calculator, "12");
+        calculator.apply_operator(Operator::Add);
+        enter(&mut calculator, "30");
+        calculator.apply_operator(Operator::Subtract);
+        enter(&mut calculator, "2");
+        calculator.equals();
+        calculator.equals();
+        let mut tape = Tape::new();
+        tape.record(calculator.take_events());
+        assert_eq!(
+            operands(&tape),
+            [
+                (None, "12".to_string(), subtotal("12")),
+                (Some(Operator::Add), "30".to_string(), subtotal("42")),
+                (Some(Operator::Subtract), "2".to_string(), subtotal("40")),
+                (None, "40".to_string(), subtotal("40")),
+                (Some(Operator::Subtract), "2".to_string(), subtotal("38")),
+            ]
+        );
+        let totals = tape
+            .lines()
+            .iter()
+            .filter_map(|line| {
+                match &line.event {
+                    Event::Total(calculation) => Some(calculation.result.clone()),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(totals, [subtotal("40"), subtotal("38")]);
+    }
+
+    #[test]
+    fn cleared_calculations() {
+        let mut calculator = Calculator::new();
+        enter(&mut calculator, "5");
+        calculator.apply_operator(Operator::Multiply);
+        calculator.clear();
+        let mut tape = Tape::new();
+        tape.record(calculator.take_events());
+        assert_eq!(operands(&tape), [(None, "5".to_string(), subtotal("5"))]);
+        assert_eq!(tape.lines().last().map(|line| &line.event), Some(&Event::Cleared));
+    }
+
+    #[test]
+    fn annotations() {
+        let mut tape = Tape::new();
+        assert!(tape.is_empty());
+        tape.record([Event::Cleared, Event::Cleared]);
+        tape.annotate(1, "  Office supplies ");
+        tape.annotate(5, "Ignored");
+        assert_eq!(tape.lines()[0].annotation, "");
+        assert_eq!(tape.lines()[1].annotation, "Office supplies");
+        tape.annotate(1, "");
+        assert_eq!(tape.lines()[1].annotation, "");
+        tape.clear();
+        assert!(tape.is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+//! This module contains the [`Uncertain`] numbers of typed expressions: measured values with a standard uncertainty
+//! (e.g. `12.3 ± 0.2`). Uncertainties are propagated to first order, assuming that the operands are independent: the
+//! uncertainty of `f(x, y)` is `√((∂f/∂x · σx)² + (∂f/∂y · σy)²)`. A value combined with itself is therefore treated
+//! as two independent measurements (e.g. `x − x` has a nonzero uncertainty).
+//!
+//! Sums, differences, products and quotients of exact values keep exact uncertainties when only one operand is
+//! uncertain (e.g. `(12.3 ± 0.2) × 2` is exactly `24.6 ± 0.4`). The other uncertainties are computed with floating
+//! point values.
+
+use std::f64::consts::LN_10;
+use std::fmt;
+
+use super::{angle, AngleUnit, Error, Function, Number, Operator};
+
+/// A number with a standard uncertainty (e.g. `12.3 ± 0.2`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Uncertain {
+    /// The measured or computed value.
+    pub value:       Number,
+    /// The standard uncertainty of the value, which is not negative.
+    pub uncertainty: Number,
+}
+
+impl Uncertain {
+    /// Creates a new number with an uncertainty.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value of the number.
+    /// * `uncertainty` - The standard uncertainty of the value.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the uncertainty is negative.
+    pub fn new(value: Number, uncertainty: Number) -> Result<Self, Error> {
+        if uncertainty.is_negative() {
+            return Err(Error::InvalidInput);
+        }
+        Ok(Self { value, uncertainty })
+    }
+
+    /// Returns `true` if the uncertainty is zero.
+    pub fn is_exact(&self) -> bool {
+        self.uncertainty.is_zero()
+    }
+
+    /// Applies a binary operator to two numbers with uncertainties.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::DivisionByZero`] if `other` is zero and the operator is a division.
+    /// * [`Error::Overflow`] if the approximate result is too large.
+    pub fn apply(&self, operator: Operator, other: &Uncertain) -> Result<Uncertain, Error> {
+        let value = operator.apply(&self.value, &other.value)?;
+        let uncertainty = match operator {
+            Operator::Add | Operator::Subtract => hypot(&self.uncertainty, &other.uncertainty)?,
+            Operator::Multiply => {
+                hypot(
+                    &other.value.mul(&self.uncertainty)?,
+                    &self.value.mul(&other.uncertainty)?,
+                )?
+            }
+            Operator::Divide => {
+                hypot(&self.uncertainty, &value.mul(&other.uncertainty)?)?.div(&magnitude(&other.value))?
+            }
+        };
+        Ok(Self { value, uncertainty })
+    }
+
+    /// Returns the negated number, with the same uncertainty.
+    pub fn neg(&self) -> Uncertain {
+        Self {
+            value:       self.value.neg(),
+            uncertainty: self.uncertainty.clone(),
+        }
+    }
+
+    /// Raises the number to a power, which may be uncertain too.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidInput`] if the power is undefined, or if the exponent is uncertain and the base is not
+    ///   positive.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn pow(&self, exponent: &Uncertain) -> Result<Uncertain, Error> {
+        let value = self.value.pow(&exponent.value)?;
+        // ∂(x^y)/∂x = y·x^(y−1), computed only for an uncertain base so that `0^2` stays defined.
+        let base_term = if self.is_exact() {
+            Number::zero()
+        }
+        else {
+            let one = Number::from_integer(1);
+            exponent
+                .value
+                .mul(&self.value.pow(&exponent.value.sub(&one)?)?)?
+                .mul(&self.uncertainty)?
+        };
+        // ∂(x^y)/∂y = x^y·ln x.
+        let exponent_term = if exponent.is_exact() {
+            Number::zero()
+        }
+        else {
+            if self.value.is_negative() || self.value.is_zero() {
+                return Err(Error::InvalidInput);
+            }
+            let ln = Number::from_real(self.value.to_f64().ln())?;
+            value.mul(&ln)?.mul(&exponent.uncertainty)?
+        };
+        Ok(Self {
+            value,
+            uncertainty: hypot(&base_term, &exponent_term)?,
+        })
+    }
+
+    /// Applies a function of the keypad to the number, propagating its uncertainty with the derivative of the
+    /// function.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function to apply.
+    /// * `angle_unit` - The unit of the angles taken and returned by the trigonometric functions.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedUncertainty`] if the function is a number theory function, which takes exact integers.
+    /// * The error of the function, or [`Error::DivisionByZero`] if its derivative is infinite at the value (e.g.
+    ///   `√(0 ± 1)`).
+    pub fn apply_function(&self, function: Function, angle_unit: AngleUnit) -> Result<Uncertain, Error> {
+        if matches!(function, Function::Factorial | Function::Totient | Function::IsPrime) {
+            return Err(Error::UnexpectedUncertainty);
+        }
+        let value = function.apply(&self.value, angle_unit)?;
+        if self.is_exact() {
+            return Ok(Self {
+                value,
+                uncertainty: Number::zero(),
+            });
+        }
+        let x = &self.value;
+        let one = Number::from_integer(1);
+        // The number of radians in the angle unit, for the derivatives of the trigonometric functions.
+        let radians = || angle_unit.convert(&one, AngleUnit::Radians);
+        let derivative = match function {
+            Function::Square => x.mul(&Number::from_integer(2))?,
+            Function::SquareRoot => value.mul(&Number::from_integer(2))?.recip()?,
+            Function::Reciprocal => value.square()?,
+            Function::Sine => angle::cos(x, angle_unit)?.mul(&radians()?)?,
+            Function::Cosine => angle::sin(x, angle_unit)?.mul(&radians()?)?,
+            Function::Tangent => one.add(&value.square()?)?.mul(&radians()?)?,
+            Function::ArcSine | Function::ArcCosine => one.sub(&x.square()?)?.sqrt()?.mul(&radians()?)?.recip()?,
+            Function::ArcTangent => one.add(&x.square()?)?.mul(&radians()?)?.recip()?,
+            Function::Exponential => value.clone(),
+            Function::NaturalLogarithm => x.recip()?,
+            Function::Logarithm => x.mul(&Number::from_real(LN_10)?)?.recip()?,
+            Function::Factorial | Function::Totient | Function::IsPrime => unreachable!("Checked above"),
+        };
+        Ok(Self {
+            value,
+            uncertainty: magnitude(&derivative.mul(&self.uncertainty)?),
+        })
+    }
+}
+
+impl From<Number> for Uncertain {
+    /// Creates a number without uncertainty.
+    fn from(value: Number) -> Self {
+        Self {
+            value,
+            uncertainty: Number::zero(),
+        }
+    }
+}
+
+impl fmt::Display for Uncertain {
+    /// Formats the number as its value and its uncertainty (e.g. `12.3 ± 0.2`), without rounding the value to the
+    /// uncertainty.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ± {}", self.value, self.uncertainty)
+    }
+}
+
+/// Returns the absolute value of a number.
+fn magnitude(number: &Number) -> Number {
+    if number.is_negative() {
+        number.neg()
+    }
+    else {
+        number.clone()
+    }
+}
+
+/// Returns `√(a² + b²)`, exactly if either number is zero.
+fn hypot(a: &Number, b: &Number) -> Result<Number, Error> {
+    if a.is_zero() {
+        return Ok(magnitude(b));
+    }
+    if b.is_zero() {
+        return Ok(magnitude(a));
+    }
+    a.square()?.add(&b.square()?)?.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a number with an uncertainty from their decimal notations.
+    fn uncertain(value: &str, uncertainty: &str) -> Uncertain {
+        Uncertain::new(value.parse().unwrap(), uncertainty.parse().unwrap()).unwrap()
+    }
+
+    /// Asserts that a number has the expected value and uncertainty up to rounding errors.
+    fn assert_close(actual: &Uncertain, value: f64, uncertainty: f64) {
+        let close = |a: f64, b: f64| (a - b).abs() <= 1e-12 * b.abs().max(1.0);
+        assert!(
+            close(actual.value.to_f64(), value) && close(actual.uncertainty.to_f64(), uncertainty),
+            "{} is not {} ± {}",
+            actual,
+            value,
+            uncertainty
+        );
+    }
+
+    #[test]
+    fn exact_propagation() {
+        let two = Uncertain::from(Number::from_integer(2));
+        assert_eq!(uncertain("12.3", "0.2").apply(Operator::Multiply, &two), Ok(uncertain("24.6", "0.4")));
+        assert_eq!(uncertain("6", "0.3").apply(Operator::Divide, &two), Ok(uncertain("3", "0.15")));
+        assert_eq!(uncertain("12.3", "0.2").apply(Operator::Add, &two), Ok(uncertain("14.3", "0.2")));
+        assert_eq!(uncertain("1.5", "0.1").neg(), uncertain("-1.5", "0.1"));
+        assert_eq!(uncertain("3", "0.1").pow(&two), Ok(uncertain("9", "0.6")));
+        assert!(two.is_exact());
+        assert_eq!(Uncertain::new(Number::zero(), Number::from_integer(-1)), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn independent_propagation() {
+        let apply = |operator, a: Uncertain, b: Uncertain| a.apply(operator, &b).unwrap();
+        assert_close(&apply(Operator::Add, uncertain("10", "3"), uncertain("20", "4")), 30.0, 5.0);
+        assert_close(&apply(Operator::Subtract, uncertain("10", "3"), uncertain("10", "4")), 0.0, 5.0);
+        assert_close(&apply(Operator::Multiply, uncertain("2", "0.1"), uncertain("3", "0.2")), 6.0, 0.5);
+        assert_close(&apply(Operator::Divide, uncertain("6", "0.3"), uncertain("2", "0.2")), 3.0, 45f64.sqrt() / 20.0);
+        // ∂(x^y)/∂x · σx = 3 · 2² · 0.1 and ∂(x^y)/∂y · σy = 2³ · ln 2 · 0.1.
+        let power = uncertain("2", "0.1").pow(&uncertain("3", "0.1")).unwrap();
+        assert_close(&power, 8.0, 1.2f64.hypot(0.8 * 2f64.ln()));
+        assert_eq!(uncertain("-2", "0").pow(&uncertain("2", "0.1")), Err(Error::InvalidInput));
+    }
+
+    #[test]
+    fn functions() {
+        let apply = |function, x: Uncertain| x.apply_function(function, AngleUnit::Radians);
+        assert_eq!(apply(Function::SquareRoot, uncertain("16", "2")), Ok(uncertain("4", "0.25")));
+        assert_eq!(apply(Function::Square, uncertain("-3", "0.1")), Ok(uncertain("9", "0.6")));
+        assert_close(&apply(Function::NaturalLogarithm, uncertain("10", "1")).unwrap(), 10f64.ln(), 0.1);
+        assert_close(&apply(Function::Logarithm, uncertain("100", "1")).unwrap(), 2.0, 0.01 / 10f64.ln());
+        assert_close(&apply(Function::Exponential, uncertain("0", "0.1")).unwrap(), 1.0, 0.1);
+        assert_close(&apply(Function::Cosine, uncertain("0", "0.1")).unwrap(), 1.0, 0.0);
+        let degrees = uncertain("0", "1").apply_function(Function::Sine, AngleUnit::Degrees).unwrap();
+        assert_close(&degrees, 0.0, std::f64::consts::PI / 180.0);
+        assert_eq!(apply(Function::SquareRoot, uncertain("0", "1")), Err(Error::DivisionByZero));
+        assert_eq!(apply(Function::Factorial, uncertain("5", "1")), Err(Error::UnexpectedUncertainty));
+    }
+}
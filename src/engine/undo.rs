@@ -0,0 +1,145 @@
+//! This module contains the [`UndoStack`] used to step back and forward through the states of the [`Calculator`].
+//!
+//! [`Calculator`]: super::Calculator
+
+use std::collections::VecDeque;
+
+/// A bounded stack of snapshots for undo and redo. When the stack is full, the oldest snapshot is dropped.
+#[derive(Clone, Debug)]
+pub struct UndoStack<T> {
+    undo:  VecDeque<T>,
+    redo:  Vec<T>,
+    limit: usize,
+}
+
+impl<T: Clone + PartialEq> UndoStack<T> {
+    /// Creates a new empty [`UndoStack`].
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The largest number of snapshots that can be undone.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Records a state transition. Transitions that did not change the state are ignored. Recording a transition
+    /// discards the snapshots that could be redone.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The state before the transition.
+    /// * `after` - The state after the transition.
+    pub fn record(&mut self, before: T, after: &T) {
+        if before == *after || self.limit == 0 {
+            return;
+        }
+        if self.undo.len() == self.limit {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(before);
+        self.redo.clear();
+    }
+
+    /// Steps back to the previous state.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The current state, which can be restored with [`UndoStack::redo`].
+    ///
+    /// # Returns
+    ///
+    /// The previous state, or `None` if there is nothing to undo.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// Steps forward to the state that was last undone.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The current state, which can be restored with [`UndoStack::undo`].
+    ///
+    /// # Returns
+    ///
+    /// The next state, or `None` if there is nothing to redo.
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+        Some(next)
+    }
+
+    /// Returns `true` if there is a state to step back to.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns `true` if there is a state to step forward to.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+impl<T: Clone + PartialEq> Default for UndoStack<T> {
+    /// The default implementation of the [`UndoStack`]. It creates an empty stack that keeps 100 snapshots.
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_and_redo() {
+        let mut stack = UndoStack::new(10);
+        assert!(!stack.can_undo());
+        stack.record(1, &2);
+        stack.record(2, &3);
+        assert_eq!(stack.undo(3), Some(2));
+        assert_eq!(stack.undo(2), Some(1));
+        assert_eq!(stack.undo(1), None);
+        assert_eq!(stack.redo(1), Some(2));
+        assert_eq!(stack.redo(2), Some(3));
+        assert_eq!(stack.redo(3), None);
+    }
+
+    #[test]
+    fn recording_clears_redo() {
+        let mut stack = UndoStack::new(10);
+        stack.record(1, &2);
+        assert_eq!(stack.undo(2), Some(1));
+        assert!(stack.can_redo());
+        stack.record(1, &5);
+        assert!(!stack.can_redo());
+        assert_eq!(stack.undo(5), Some(1));
+    }
+
+    #[test]
+    fn unchanged_states_are_ignored() {
+        let mut stack = UndoStack::new(10);
+        stack.record(1, &1);
+        assert!(!stack.can_undo());
+    }
+
+    #[test]
+    fn limit() {
+        let mut stack = UndoStack::new(2);
+        for state in 0..5 {
+            stack.record(state, &(state + 1));
+        }
+        assert_eq!(stack.undo(5), Some(4));
+        assert_eq!(stack.undo(4), Some(3));
+        assert_eq!(stack.undo(3), None);
+
+        let mut stack = UndoStack::new(0);
+        stack.record(1, &2);
+        assert!(!stack.can_undo());
+    }
+}
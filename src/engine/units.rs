@@ -0,0 +1,734 @@
+//! This module contains the units of measurement shared by typed expressions (e.g. `3 ft + 20 cm to m`) and the
+//! conversion page: the [`UNITS`] table, products of units ([`Units`]) and numbers with units ([`Quantity`]).
+//!
+//! Quantities keep the units they were written in. A sum or difference is expressed in the units of its first term,
+//! and adding quantities of different dimensions (e.g. `kg + m`) is an error. A product or quotient combines the units
+//! of its operands and cancels them where possible: `60 mph * 2.5 h` is `150 mi` and `1 km / 1 m` is `1000`.
+//! Conversions between temperatures (e.g. `20 degC to degF`) take the zero of the scales into account; in any other
+//! calculation, temperatures are treated as differences.
+
+use std::fmt;
+use std::str::FromStr;
+
+use num_rational::BigRational;
+use num_traits::ToPrimitive;
+
+use super::{AngleUnit, Error, Function, Number, Operator};
+
+/// The physical dimension of a unit: the exponents of the SI base quantities length, mass, time, electric current,
+/// temperature, amount of substance and luminous intensity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Dimension([i32; 7]);
+
+impl Dimension {
+    /// The dimension of plain numbers.
+    pub const NONE: Dimension = Dimension([0; 7]);
+    const LENGTH: Dimension = Dimension::base(0);
+    const MASS: Dimension = Dimension::base(1);
+    const TIME: Dimension = Dimension::base(2);
+    const CURRENT: Dimension = Dimension::base(3);
+    const TEMPERATURE: Dimension = Dimension::base(4);
+    const AMOUNT: Dimension = Dimension::base(5);
+    const LUMINOSITY: Dimension = Dimension::base(6);
+    const AREA: Dimension = Dimension::LENGTH.pow(2);
+    const VOLUME: Dimension = Dimension::LENGTH.pow(3);
+    const FREQUENCY: Dimension = Dimension::TIME.pow(-1);
+    const SPEED: Dimension = Dimension::LENGTH.mul(Dimension::FREQUENCY);
+    const FORCE: Dimension = Dimension::MASS.mul(Dimension::SPEED).mul(Dimension::FREQUENCY);
+    const ENERGY: Dimension = Dimension::FORCE.mul(Dimension::LENGTH);
+    const POWER: Dimension = Dimension::ENERGY.mul(Dimension::FREQUENCY);
+    const PRESSURE: Dimension = Dimension::FORCE.mul(Dimension::AREA.pow(-1));
+    const CHARGE: Dimension = Dimension::CURRENT.mul(Dimension::TIME);
+    const VOLTAGE: Dimension = Dimension::POWER.mul(Dimension::CURRENT.pow(-1));
+    const RESISTANCE: Dimension = Dimension::VOLTAGE.mul(Dimension::CURRENT.pow(-1));
+    const CAPACITANCE: Dimension = Dimension::CHARGE.mul(Dimension::VOLTAGE.pow(-1));
+
+    /// Returns the dimension of the base quantity with the given index.
+    const fn base(index: usize) -> Dimension {
+        let mut exponents = [0; 7];
+        exponents[index] = 1;
+        Dimension(exponents)
+    }
+
+    /// Returns the dimension of the product of quantities of both dimensions.
+    const fn mul(self, other: Dimension) -> Dimension {
+        let mut exponents = self.0;
+        let mut i = 0;
+        while i < exponents.len() {
+            exponents[i] += other.0[i];
+            i += 1;
+        }
+        Dimension(exponents)
+    }
+
+    /// Returns the dimension of a quantity of this dimension raised to the given power.
+    const fn pow(self, exponent: i32) -> Dimension {
+        let mut exponents = self.0;
+        let mut i = 0;
+        while i < exponents.len() {
+            exponents[i] *= exponent;
+            i += 1;
+        }
+        Dimension(exponents)
+    }
+}
+
+/// A kind of quantity offered by the conversion page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    Length,
+    Area,
+    Volume,
+    Mass,
+    Time,
+    Speed,
+    Temperature,
+    Energy,
+    Power,
+    Force,
+    Pressure,
+    Frequency,
+}
+
+impl Category {
+    /// All categories, in the order they are offered.
+    pub const ALL: [Category; 12] = [
+        Category::Length,
+        Category::Area,
+        Category::Volume,
+        Category::Mass,
+        Category::Time,
+        Category::Speed,
+        Category::Temperature,
+        Category::Energy,
+        Category::Power,
+        Category::Force,
+        Category::Pressure,
+        Category::Frequency,
+    ];
+
+    /// Returns the name of the category as shown to the user.
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Length => "Length",
+            Category::Area => "Area",
+            Category::Volume => "Volume",
+            Category::Mass => "Mass",
+            Category::Time => "Time",
+            Category::Speed => "Speed",
+            Category::Temperature => "Temperature",
+            Category::Energy => "Energy",
+            Category::Power => "Power",
+            Category::Force => "Force",
+            Category::Pressure => "Pressure",
+            Category::Frequency => "Frequency",
+        }
+    }
+
+    /// Returns the units of the category, in the order of the [`UNITS`] table.
+    pub fn units(self) -> impl Iterator<Item = &'static Unit> {
+        UNITS
+            .iter()
+            .filter(move |unit| unit.category == Some(self))
+    }
+}
+
+/// A unit of measurement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unit {
+    /// The names of the unit in expressions. The first one is its symbol.
+    pub names:       &'static [&'static str],
+    /// The name of the unit as shown to the user (e.g. `Foot`).
+    pub description: &'static str,
+    /// The category of the unit on the conversion page, or `None` if it is only available in expressions.
+    pub category:    Option<Category>,
+    /// The dimension of the unit.
+    pub dimension:   Dimension,
+    /// The value of the unit in coherent SI units, as an exact decimal or fraction (e.g. `0.3048` for the foot).
+    factor:          &'static str,
+    /// The value in this unit of the zero of the SI scale, for units with a different zero (e.g. `273.15` for the
+    /// degree Celsius).
+    offset:          &'static str,
+    /// The same unit as a product of other units (e.g. `mi/h` for `mph`), used to cancel units in products, or an
+    /// empty string.
+    expansion:       &'static str,
+}
+
+impl Unit {
+    /// Creates a unit with the zero of the SI scale and no expansion.
+    const fn new(
+        names: &'static [&'static str],
+        description: &'static str,
+        category: Option<Category>,
+        dimension: Dimension,
+        factor: &'static str,
+    ) -> Unit {
+        Unit {
+            names,
+            description,
+            category,
+            dimension,
+            factor,
+            offset: "0",
+            expansion: "",
+        }
+    }
+
+    /// Returns the unit with the given zero offset.
+    const fn offset(self, offset: &'static str) -> Unit {
+        Unit { offset, ..self }
+    }
+
+    /// Returns the unit with the given expansion into other units.
+    const fn per(self, expansion: &'static str) -> Unit {
+        Unit { expansion, ..self }
+    }
+
+    /// Returns the symbol of the unit (e.g. `ft`).
+    pub fn symbol(&self) -> &'static str {
+        self.names[0]
+    }
+
+    /// Returns the value of the unit in coherent SI units.
+    fn factor(&self) -> Number {
+        self.factor
+            .parse()
+            .expect("The factor of a unit needs to be a valid number")
+    }
+
+    /// Returns the value in this unit of the zero of the SI scale.
+    fn zero(&self) -> Number {
+        self.offset
+            .parse()
+            .expect("The offset of a unit needs to be a valid number")
+    }
+}
+
+/// All units, by category.
+pub const UNITS: [Unit; 85] = [
+    // Length
+    Unit::new(&["m", "metre", "meter"], "Metre", Some(Category::Length), Dimension::LENGTH, "1"),
+    Unit::new(&["km"], "Kilometre", Some(Category::Length), Dimension::LENGTH, "1000"),
+    Unit::new(&["cm"], "Centimetre", Some(Category::Length), Dimension::LENGTH, "0.01"),
+    Unit::new(&["mm"], "Millimetre", Some(Category::Length), Dimension::LENGTH, "0.001"),
+    Unit::new(&["µm", "um"], "Micrometre", Some(Category::Length), Dimension::LENGTH, "1e-6"),
+    Unit::new(&["nm"], "Nanometre", Some(Category::Length), Dimension::LENGTH, "1e-9"),
+    Unit::new(&["in", "inch"], "Inch", Some(Category::Length), Dimension::LENGTH, "0.0254"),
+    Unit::new(&["ft", "foot", "feet"], "Foot", Some(Category::Length), Dimension::LENGTH, "0.3048"),
+    Unit::new(&["yd", "yard"], "Yard", Some(Category::Length), Dimension::LENGTH, "0.9144"),
+    Unit::new(&["mi", "mile"], "Mile", Some(Category::Length), Dimension::LENGTH, "1609.344"),
+    Unit::new(&["nmi"], "Nautical mile", Some(Category::Length), Dimension::LENGTH, "1852"),
+    Unit::new(&["au"], "Astronomical unit", Some(Category::Length), Dimension::LENGTH, "149597870700"),
+    Unit::new(&["ly"], "Light-year", Some(Category::Length), Dimension::LENGTH, "9460730472580800"),
+    // Area
+    Unit::new(&["m²", "m2"], "Square metre", Some(Category::Area), Dimension::AREA, "1"),
+    Unit::new(&["km²", "km2"], "Square kilometre", Some(Category::Area), Dimension::AREA, "1000000"),
+    Unit::new(&["cm²", "cm2"], "Square centimetre", Some(Category::Area), Dimension::AREA, "0.0001"),
+    Unit::new(&["in²", "in2"], "Square inch", Some(Category::Area), Dimension::AREA, "0.00064516"),
+    Unit::new(&["ft²", "ft2"], "Square foot", Some(Category::Area), Dimension::AREA, "0.09290304"),
+    Unit::new(&["mi²", "mi2"], "Square mile", Some(Category::Area), Dimension::AREA, "2589988.110336"),
+    Unit::new(&["ha", "hectare"], "Hectare", Some(Category::Area), Dimension::AREA, "10000"),
+    Unit::new(&["acre"], "Acre", Some(Category::Area), Dimension::AREA, "4046.8564224"),
+    // Volume
+    Unit::new(&["m³", "m3"], "Cubic metre", Some(Category::Volume), Dimension::VOLUME, "1"),
+    Unit::new(&["cm³", "cm3", "cc"], "Cubic centimetre", Some(Category::Volume), Dimension::VOLUME, "1e-6"),
+    Unit::new(&["ft³", "ft3"], "Cubic foot", Some(Category::Volume), Dimension::VOLUME, "0.028316846592"),
+    Unit::new(&["L", "l", "litre", "liter"], "Litre", Some(Category::Volume), Dimension::VOLUME, "0.001"),
+    Unit::new(&["mL", "ml"], "Millilitre", Some(Category::Volume), Dimension::VOLUME, "1e-6"),
+    Unit::new(&["gal", "gallon"], "US gallon", Some(Category::Volume), Dimension::VOLUME, "0.003785411784"),
+    Unit::new(&["qt", "quart"], "US quart", Some(Category::Volume), Dimension::VOLUME, "0.000946352946"),
+    Unit::new(&["pt", "pint"], "US pint", Some(Category::Volume), Dimension::VOLUME, "0.000473176473"),
+    Unit::new(&["cup"], "US cup", Some(Category::Volume), Dimension::VOLUME, "0.0002365882365"),
+    Unit::new(&["floz"], "US fluid ounce", Some(Category::Volume), Dimension::VOLUME, "0.0000295735295625"),
+    Unit::new(&["tbsp"], "Tablespoon", Some(Category::Volume), Dimension::VOLUME, "0.00001478676478125"),
+    Unit::new(&["tsp"], "Teaspoon", Some(Category::Volume), Dimension::VOLUME, "0.00000492892159375"),
+    // Mass
+    Unit::new(&["kg"], "Kilogram", Some(Category::Mass), Dimension::MASS, "1"),
+    Unit::new(&["g", "gram"], "Gram", Some(Category::Mass), Dimension::MASS, "0.001"),
+    Unit::new(&["mg"], "Milligram", Some(Category::Mass), Dimension::MASS, "1e-6"),
+    Unit::new(&["t", "tonne"], "Tonne", Some(Category::Mass), Dimension::MASS, "1000"),
+    Unit::new(&["lb", "pound"], "Pound", Some(Category::Mass), Dimension::MASS, "0.45359237"),
+    Unit::new(&["oz", "ounce"], "Ounce", Some(Category::Mass), Dimension::MASS, "0.028349523125"),
+    Unit::new(&["st", "stone"], "Stone", Some(Category::Mass), Dimension::MASS, "6.35029318"),
+    // Time
+    Unit::new(&["s", "sec"], "Second", Some(Category::Time), Dimension::TIME, "1"),
+    Unit::new(&["ms"], "Millisecond", Some(Category::Time), Dimension::TIME, "0.001"),
+    Unit::new(&["min"], "Minute", Some(Category::Time), Dimension::TIME, "60"),
+    Unit::new(&["h", "hr", "hour"], "Hour", Some(Category::Time), Dimension::TIME, "3600"),
+    Unit::new(&["d", "day"], "Day", Some(Category::Time), Dimension::TIME, "86400"),
+    Unit::new(&["wk", "week"], "Week", Some(Category::Time), Dimension::TIME, "604800"),
+    Unit::new(&["yr", "year"], "Julian year", Some(Category::Time), Dimension::TIME, "31557600"),
+    // Speed
+    Unit::new(&["mph"], "Mile per hour", Some(Category::Speed), Dimension::SPEED, "0.44704").per("mi/h"),
+    Unit::new(&["kph", "kmh"], "Kilometre per hour", Some(Category::Speed), Dimension::SPEED, "5/18").per("km/h"),
+    Unit::new(&["kn", "knot"], "Knot", Some(Category::Speed), Dimension::SPEED, "463/900").per("nmi/h"),
+    Unit::new(&["mps"], "Metre per second", Some(Category::Speed), Dimension::SPEED, "1").per("m/s"),
+    // Temperature
+    Unit::new(&["K", "kelvin"], "Kelvin", Some(Category::Temperature), Dimension::TEMPERATURE, "1"),
+    Unit::new(&["degC", "°C", "celsius"], "Degree Celsius", Some(Category::Temperature), Dimension::TEMPERATURE, "1")
+        .offset("273.15"),
+    Unit::new(
+        &["degF", "°F", "fahrenheit"],
+        "Degree Fahrenheit",
+        Some(Category::Temperature),
+        Dimension::TEMPERATURE,
+        "5/9",
+    )
+    .offset("459.67"),
+    // Energy
+    Unit::new(&["J", "joule"], "Joule", Some(Category::Energy), Dimension::ENERGY, "1"),
+    Unit::new(&["kJ"], "Kilojoule", Some(Category::Energy), Dimension::ENERGY, "1000"),
+    Unit::new(&["cal"], "Calorie", Some(Category::Energy), Dimension::ENERGY, "4.184"),
+    Unit::new(&["kcal"], "Kilocalorie", Some(Category::Energy), Dimension::ENERGY, "4184"),
+    Unit::new(&["Wh"], "Watt-hour", Some(Category::Energy), Dimension::ENERGY, "3600").per("W*h"),
+    Unit::new(&["kWh"], "Kilowatt-hour", Some(Category::Energy), Dimension::ENERGY, "3600000").per("kW*h"),
+    Unit::new(&["eV"], "Electronvolt", Some(Category::Energy), Dimension::ENERGY, "1.602176634e-19"),
+    Unit::new(&["BTU"], "British thermal unit", Some(Category::Energy), Dimension::ENERGY, "1055.05585262"),
+    // Power
+    Unit::new(&["W", "watt"], "Watt", Some(Category::Power), Dimension::POWER, "1").per("J/s"),
+    Unit::new(&["kW"], "Kilowatt", Some(Category::Power), Dimension::POWER, "1000").per("kJ/s"),
+    Unit::new(&["hp"], "Horsepower", Some(Category::Power), Dimension::POWER, "745.69987158227022"),
+    // Force
+    Unit::new(&["N", "newton"], "Newton", Some(Category::Force), Dimension::FORCE, "1"),
+    Unit::new(&["kN"], "Kilonewton", Some(Category::Force), Dimension::FORCE, "1000"),
+    Unit::new(&["lbf"], "Pound-force", Some(Category::Force), Dimension::FORCE, "4.4482216152605"),
+    // Pressure
+    Unit::new(&["Pa", "pascal"], "Pascal", Some(Category::Pressure), Dimension::PRESSURE, "1"),
+    Unit::new(&["kPa"], "Kilopascal", Some(Category::Pressure), Dimension::PRESSURE, "1000"),
+    Unit::new(&["bar"], "Bar", Some(Category::Pressure), Dimension::PRESSURE, "100000"),
+    Unit::new(&["atm"], "Standard atmosphere", Some(Category::Pressure), Dimension::PRESSURE, "101325"),
+    Unit::new(&["psi"], "Pound per square inch", Some(Category::Pressure), Dimension::PRESSURE, "6894.757293168361"),
+    Unit::new(&["mmHg"], "Millimetre of mercury", Some(Category::Pressure), Dimension::PRESSURE, "133.322387415"),
+    // Frequency
+    Unit::new(&["Hz", "hertz"], "Hertz", Some(Category::Frequency), Dimension::FREQUENCY, "1"),
+    Unit::new(&["kHz"], "Kilohertz", Some(Category::Frequency), Dimension::FREQUENCY, "1000"),
+    Unit::new(&["MHz"], "Megahertz", Some(Category::Frequency), Dimension::FREQUENCY, "1e6"),
+    // Only available in expressions
+    Unit::new(&["A", "ampere"], "Ampere", None, Dimension::CURRENT, "1"),
+    Unit::new(&["C", "coulomb"], "Coulomb", None, Dimension::CHARGE, "1"),
+    Unit::new(&["V", "volt"], "Volt", None, Dimension::VOLTAGE, "1"),
+    Unit::new(&["Ω", "ohm"], "Ohm", None, Dimension::RESISTANCE, "1"),
+    Unit::new(&["F", "farad"], "Farad", None, Dimension::CAPACITANCE, "1"),
+    Unit::new(&["mol"], "Mole", None, Dimension::AMOUNT, "1"),
+    Unit::new(&["cd", "candela"], "Candela", None, Dimension::LUMINOSITY, "1"),
+    Unit::new(&[CLOCK], "Hours, minutes and seconds", None, Dimension::TIME, "1"),
+];
+
+/// The name of the unit of durations written `h:mm:ss`. Its values are in seconds.
+const CLOCK: &str = "hms";
+
+/// Returns the unit with the given name in expressions, or `None` if there is no such unit.
+///
+/// # Arguments
+///
+/// * `name` - The name of the unit (e.g. `ft` or `foot`).
+pub fn find(name: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|unit| unit.names.contains(&name))
+}
+
+/// A product of powers of units (e.g. `m/s^2`). The empty product is the unit of plain numbers.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Units(Vec<(&'static Unit, i32)>);
+
+impl Units {
+    /// Returns the given unit on its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `unit` - The unit.
+    pub fn unit(unit: &'static Unit) -> Units {
+        Units(vec![(unit, 1)])
+    }
+
+    /// Returns the unit of durations and times of day written `h:mm:ss`. Values in this unit are in seconds.
+    pub fn clock() -> Units {
+        Units::unit(find(CLOCK).expect("The unit of durations needs to exist"))
+    }
+
+    /// Returns `true` if these are the units of durations written `h:mm:ss`.
+    pub fn is_clock(&self) -> bool {
+        matches!(self.0.as_slice(), [(unit, 1)] if unit.names == [CLOCK])
+    }
+
+    /// Returns `true` if these are the units of plain numbers.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the dimension of the units.
+    pub fn dimension(&self) -> Dimension {
+        self.0
+            .iter()
+            .fold(Dimension::NONE, |dimension, (unit, exponent)| {
+                dimension.mul(unit.dimension.pow(*exponent))
+            })
+    }
+
+    /// Returns the units raised to an integer power.
+    ///
+    /// # Arguments
+    ///
+    /// * `exponent` - The power to raise the units to.
+    pub fn pow(&self, exponent: i32) -> Units {
+        Units::merged(
+            self.0
+                .iter()
+                .map(|(unit, power)| (*unit, power * exponent))
+                .collect(),
+        )
+    }
+
+    /// Returns the product of the units as written, only combining the powers of the same unit (e.g. `km/h` stays
+    /// `km/h` and `m·m` becomes `m^2`).
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The units to multiply with.
+    pub fn mul(&self, other: &Units) -> Units {
+        Units::merged(self.0.iter().chain(&other.0).copied().collect())
+    }
+
+    /// Returns the factor converting a value in these units to the given units.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The units to convert to.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::IncompatibleUnits`] if the units have different dimensions.
+    pub fn conversion_factor(&self, to: &Units) -> Result<Number, Error> {
+        if self.dimension() != to.dimension() {
+            return Err(Error::IncompatibleUnits(self.describe(), to.describe()));
+        }
+        self.factor()?.div(&to.factor()?)
+    }
+
+    /// Returns the units as written in error messages: `1` for plain numbers.
+    fn describe(&self) -> String {
+        if self.is_empty() {
+            "1".to_string()
+        }
+        else {
+            self.to_string()
+        }
+    }
+
+    /// Returns the value of the units in coherent SI units.
+    fn factor(&self) -> Result<Number, Error> {
+        self.0
+            .iter()
+            .try_fold(Number::from_integer(1), |factor, (unit, exponent)| {
+                factor.mul(&unit.factor().pow(&Number::from_integer(i64::from(*exponent)))?)
+            })
+    }
+
+    /// Combines the powers of the same unit and drops the units raised to the power zero.
+    fn merged(parts: Vec<(&'static Unit, i32)>) -> Units {
+        let mut merged: Vec<(&'static Unit, i32)> = Vec::new();
+        for (unit, exponent) in parts {
+            match merged.iter_mut().find(|(merged_unit, _)| *merged_unit == unit) {
+                Some((_, merged_exponent)) => *merged_exponent += exponent,
+                None => merged.push((unit, exponent)),
+            }
+        }
+        merged.retain(|(_, exponent)| *exponent != 0);
+        Units(merged)
+    }
+
+    /// Simplifies the product of units of a calculation. Units are replaced by their expansion when that cancels units
+    /// (e.g. `mph·h` becomes `mi`), and units of the same dimension are converted to the first of them (e.g. `km/m`
+    /// becomes a plain number).
+    ///
+    /// # Returns
+    ///
+    /// The simplified units and the factor the value needs to be multiplied with.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Overflow`] if the factor is too large.
+    fn simplified(&self) -> Result<(Units, Number), Error> {
+        let mut units = self.clone();
+        let mut i = 0;
+        while i < units.0.len() {
+            let (unit, exponent) = units.0[i];
+            if !unit.expansion.is_empty() {
+                let expansion = unit
+                    .expansion
+                    .parse::<Units>()
+                    .expect("The expansion of a unit needs to be valid units");
+                let mut parts = units.0.clone();
+                parts.remove(i);
+                let expanded = Units(parts).mul(&expansion.pow(exponent));
+                if expanded.0.len() < units.0.len() {
+                    units = expanded;
+                    i = 0;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+
+        let mut factor = Number::from_integer(1);
+        while let Some((first, second)) = (0..units.0.len()).find_map(|first| {
+            (first + 1..units.0.len())
+                .find(|&second| units.0[first].0.dimension == units.0[second].0.dimension)
+                .map(|second| (first, second))
+        }) {
+            let (unit, exponent) = units.0.remove(second);
+            let target = units.0[first].0;
+            factor = factor.mul(
+                &unit
+                    .factor()
+                    .div(&target.factor())?
+                    .pow(&Number::from_integer(i64::from(exponent)))?,
+            )?;
+            units = Units(units.0).mul(&Units(vec![(target, exponent)]));
+        }
+        Ok((units, factor))
+    }
+}
+
+impl fmt::Display for Units {
+    /// Formats the units as they can be typed again (e.g. `m/s^2` or `J/(mol·K)`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let power = |unit: &Unit, exponent: i32| {
+            if exponent == 1 {
+                unit.symbol().to_string()
+            }
+            else {
+                format!("{}^{}", unit.symbol(), exponent)
+            }
+        };
+        let numerator = self
+            .0
+            .iter()
+            .filter(|(_, exponent)| *exponent > 0)
+            .map(|(unit, exponent)| power(unit, *exponent))
+            .collect::<Vec<String>>();
+        let denominator = self
+            .0
+            .iter()
+            .filter(|(_, exponent)| *exponent < 0)
+            .map(|(unit, exponent)| power(unit, -exponent))
+            .collect::<Vec<String>>();
+        if numerator.is_empty() {
+            let parts = self
+                .0
+                .iter()
+                .map(|(unit, exponent)| power(unit, *exponent))
+                .collect::<Vec<String>>();
+            write!(f, "{}", parts.join("·"))
+        }
+        else if denominator.is_empty() {
+            write!(f, "{}", numerator.join("·"))
+        }
+        else if denominator.len() == 1 {
+            write!(f, "{}/{}", numerator.join("·"), denominator[0])
+        }
+        else {
+            write!(f, "{}/({})", numerator.join("·"), denominator.join("·"))
+        }
+    }
+}
+
+impl FromStr for Units {
+    type Err = Error;
+
+    /// Parses units as written in expressions (e.g. `km/h` or `m^3/(kg·s^2)`). An empty string is the unit of plain
+    /// numbers.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(Units::default());
+        }
+        super::expression::parse_units(s)
+    }
+}
+
+/// A number with units (e.g. `3 ft`). Plain numbers have empty units.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quantity {
+    /// The value, in the units of the quantity.
+    pub value: Number,
+    /// The units of the quantity.
+    pub units: Units,
+}
+
+impl Quantity {
+    /// Creates a new quantity.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value, in the given units.
+    /// * `units` - The units of the quantity.
+    pub fn new(value: Number, units: Units) -> Self {
+        Self { value, units }
+    }
+
+    /// Returns the value of a plain number.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedUnits`] if the quantity has units.
+    pub fn into_number(self) -> Result<Number, Error> {
+        if self.units.is_empty() {
+            Ok(self.value)
+        }
+        else {
+            Err(Error::UnexpectedUnits(self.units.to_string()))
+        }
+    }
+
+    /// Returns the negated quantity.
+    pub fn neg(&self) -> Quantity {
+        Quantity::new(self.value.neg(), self.units.clone())
+    }
+
+    /// Applies a binary operator to two quantities. Sums and differences are expressed in the units of this
+    /// quantity; products and quotients are simplified.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator` - The operator to apply.
+    /// * `other` - The right-hand side operand.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::IncompatibleUnits`] if a sum or difference has terms of different dimensions.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn apply(&self, operator: Operator, other: &Quantity) -> Result<Quantity, Error> {
+        match operator {
+            Operator::Add | Operator::Subtract => {
+                if self.units.dimension() != other.units.dimension() {
+                    return Err(Error::IncompatibleUnits(self.units.describe(), other.units.describe()));
+                }
+                let other = other
+                    .value
+                    .mul(&other.units.conversion_factor(&self.units)?)?;
+                Ok(Quantity::new(operator.apply(&self.value, &other)?, self.units.clone()))
+            }
+            Operator::Multiply | Operator::Divide => {
+                let units = match operator {
+                    Operator::Multiply => self.units.mul(&other.units),
+                    _ => self.units.mul(&other.units.pow(-1)),
+                };
+                let (units, factor) = units.simplified()?;
+                let value = operator.apply(&self.value, &other.value)?.mul(&factor)?;
+                Ok(Quantity::new(value, units))
+            }
+        }
+    }
+
+    /// Raises the quantity to a power. The exponent needs to be a plain number and, for a quantity with units, an
+    /// exact number that leaves integer powers of the units (e.g. `(4 m^2)^0.5` is `2 m`).
+    ///
+    /// # Arguments
+    ///
+    /// * `exponent` - The power to raise the quantity to.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedUnits`] if the exponent has units or the powers of the units would not be integers.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn pow(&self, exponent: &Quantity) -> Result<Quantity, Error> {
+        let exponent = exponent.clone().into_number()?;
+        let units = self.units_pow(&exponent)?;
+        Ok(Quantity::new(self.value.pow(&exponent)?, units))
+    }
+
+    /// Applies a function of the keypad. The square, the square root and the reciprocal keep the units; the other
+    /// functions take plain numbers.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function to apply.
+    /// * `angle_unit` - The unit of the angles taken and returned by the trigonometric functions.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnexpectedUnits`] if the function does not take quantities with units, or the square root of the
+    ///   units is not a product of integer powers.
+    /// * The error of the function.
+    pub fn apply_function(&self, function: Function, angle_unit: AngleUnit) -> Result<Quantity, Error> {
+        let exponent = match function {
+            Function::Square => "2",
+            Function::SquareRoot => "1/2",
+            Function::Reciprocal => "-1",
+            _ => return Ok(Quantity::from(function.apply(&self.clone().into_number()?, angle_unit)?)),
+        };
+        let units = self.units_pow(&exponent.parse()?)?;
+        Ok(Quantity::new(function.apply(&self.value, angle_unit)?, units))
+    }
+
+    /// Converts the quantity to other units of the same dimension. A temperature in a single unit is converted
+    /// between the scales (e.g. `20 degC` is `68 degF`).
+    ///
+    /// # Arguments
+    ///
+    /// * `units` - The units to convert to.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::IncompatibleUnits`] if the units have a different dimension.
+    /// * The error of the underlying [`Number`] operation.
+    pub fn convert(&self, units: &Units) -> Result<Quantity, Error> {
+        let factor = self.units.conversion_factor(units)?;
+        let value = match (self.units.0.as_slice(), units.0.as_slice()) {
+            ([(from, 1)], [(to, 1)]) => {
+                self.value
+                    .add(&from.zero())?
+                    .mul(&factor)?
+                    .sub(&to.zero())?
+            }
+            _ => self.value.mul(&factor)?,
+        };
+        Ok(Quantity::new(value, units.clone()))
+    }
+
+    /// Returns the units of the quantity raised to a power.
+    fn units_pow(&self, exponent: &Number) -> Result<Units, Error> {
+        if self.units.is_empty() {
+            return Ok(Units::default());
+        }
+        let unexpected = || Error::UnexpectedUnits(self.units.to_string());
+        let exponent = exponent
+            .is_exact()
+            .then(|| exponent.to_rational())
+            .flatten()
+            .ok_or_else(unexpected)?;
+        let parts = self
+            .units
+            .0
+            .iter()
+            .map(|(unit, power)| {
+                let power = exponent.clone() * BigRational::from_integer((*power).into());
+                power
+                    .is_integer()
+                    .then(|| power.to_integer().to_i32())
+                    .flatten()
+                    .map(|power| (*unit, power))
+                    .ok_or_else(unexpected)
+            })
+            .collect::<Result<Vec<(&'static Unit, i32)>, Error>>()?;
+        Ok(Units::merged(parts))
+    }
+}
+
+impl From<Number> for Quantity {
+    /// Creates a plain number.
+    fn from(value: Number) -> Self {
+        Quantity::new(value, Units::default())
+    }
+}
+
+impl fmt::Display for Quantity {
+    /// Formats the quantity as it can be typed again (e.g. `1.1144 m`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.units.is_empty() {
+            write!(f, "{}", self.value)
+        }
+        else {
+            write!(f, "{} {}", self.value, self.units)
+        }
+    }
+}
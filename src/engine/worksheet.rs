@@ -0,0 +1,175 @@
+//! This module contains the evaluation of the worksheet page: a text of several lines, each an expression, an
+//! assignment or a function definition, evaluated from top to bottom (e.g. `rate = 0.0725` on one line and
+//! `price * (1 + rate)` on the next). Blank lines and comments, which start with `#`, have no result.
+//!
+//! Assignments and definitions only apply to the lines below them and are not stored with the variables and functions
+//! of the window. `lineN` is the result of line `N` above (e.g. `line3 * 2`), if it is a number or a quantity; lines
+//! are numbered from 1, counting blank lines and comments.
+
+use std::collections::BTreeMap;
+
+use super::{Context, Error, Quantity, Statement, UserFunction, Value};
+
+/// The prefix of the names of the results of the lines (e.g. `line3`).
+const LINE_PREFIX: &str = "line";
+
+/// The start of comment lines.
+const COMMENT: char = '#';
+
+/// The result of a line of a worksheet.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineResult {
+    /// A blank line or a comment.
+    Empty,
+    /// The value of an expression or of an assignment.
+    Value(Value),
+    /// A function definition, by its signature (e.g. `f(x, y)`).
+    Definition(String),
+    /// The error of a line that could not be parsed or evaluated.
+    Error(Error),
+}
+
+/// Evaluates the lines of a worksheet.
+///
+/// # Arguments
+///
+/// * `text` - The text of the worksheet.
+/// * `context` - The variables, functions and previous results of the window, which the lines can refer to.
+///
+/// # Returns
+///
+/// The result of each line of the text, in order.
+pub fn evaluate_worksheet(text: &str, context: &Context) -> Vec<LineResult> {
+    let mut variables = context.variables.clone();
+    let mut functions = context.functions.clone();
+    let mut results = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(COMMENT) {
+            results.push(LineResult::Empty);
+            continue;
+        }
+        let result = evaluate_line(line, context, &mut variables, &mut functions);
+        if let LineResult::Value(value) = &result {
+            if let Ok(quantity) = value.clone().into_quantity() {
+                variables.insert(format!("{}{}", LINE_PREFIX, index + 1), quantity);
+            }
+        }
+        results.push(result);
+    }
+    results
+}
+
+/// Evaluates a line of a worksheet that is not blank, storing its assignment or definition.
+///
+/// # Arguments
+///
+/// * `line` - The text of the line.
+/// * `context` - The context of the window.
+/// * `variables` - The variables of the window and those assigned above the line.
+/// * `functions` - The functions of the window and those defined above the line.
+fn evaluate_line(
+    line: &str,
+    context: &Context,
+    variables: &mut BTreeMap<String, Quantity>,
+    functions: &mut BTreeMap<String, UserFunction>,
+) -> LineResult {
+    let statement = match Statement::parse(line) {
+        Ok(statement) => statement,
+        Err(error) => return LineResult::Error(error),
+    };
+    let context = Context {
+        variables: &*variables,
+        functions: &*functions,
+        ..*context
+    };
+    match statement {
+        Statement::Expression(expression) => {
+            match expression.value(&context) {
+                Ok(value) => LineResult::Value(value),
+                Err(error) => LineResult::Error(error),
+            }
+        }
+        Statement::Assignment(name, expression) => {
+            match expression.evaluate(&context) {
+                Ok(quantity) => {
+                    variables.insert(name, quantity.clone());
+                    LineResult::Value(Value::Quantity(quantity))
+                }
+                Err(error) => LineResult::Error(error),
+            }
+        }
+        Statement::Definition(function) => {
+            if let Err(error) = context.check(&function) {
+                return LineResult::Error(error);
+            }
+            let signature = function.signature();
+            functions.insert(function.name.clone(), function);
+            LineResult::Definition(signature)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{AngleUnit, Number};
+
+    /// Evaluates a worksheet with a window variable `tax = 0.2`.
+    fn evaluate(text: &str) -> Vec<LineResult> {
+        let variables = BTreeMap::from([("tax".to_string(), Quantity::from(number("0.2")))]);
+        let functions = BTreeMap::new();
+        let context = Context {
+            variables:  &variables,
+            functions:  &functions,
+            answers:    &[],
+            angle_unit: AngleUnit::Degrees,
+            complex:    false,
+            interval:   false,
+        };
+        evaluate_worksheet(text, &context)
+    }
+
+    /// Parses an exact number.
+    fn number(text: &str) -> Number {
+        text.parse().unwrap()
+    }
+
+    /// Returns the result of a line holding a number.
+    fn value(text: &str) -> LineResult {
+        LineResult::Value(Value::Quantity(Quantity::from(number(text))))
+    }
+
+    #[test]
+    fn lines() {
+        let results = evaluate("# Prices\nrate = 0.0725\n  price = 200\n\nprice * (1 + rate)\nprice * tax\n2 +");
+        assert_eq!(results.len(), 7);
+        assert_eq!(results[0], LineResult::Empty);
+        assert_eq!(results[1], value("0.0725"));
+        assert_eq!(results[2], value("200"));
+        assert_eq!(results[3], LineResult::Empty);
+        assert_eq!(results[4], value("214.5"));
+        assert_eq!(results[5], value("40"));
+        assert!(matches!(results[6], LineResult::Error(Error::Syntax(_))));
+    }
+
+    #[test]
+    fn line_references() {
+        let results = evaluate("10\n\nline1 * 2\nline2\nline6\nline3 + line1\nline4");
+        assert_eq!(results[2], value("20"));
+        assert_eq!(results[3], LineResult::Error(Error::UnknownName("line2".to_string())));
+        assert_eq!(results[4], LineResult::Error(Error::UnknownName("line6".to_string())));
+        assert_eq!(results[5], value("30"));
+        assert_eq!(results[6], LineResult::Error(Error::UnknownName("line4".to_string())));
+    }
+
+    #[test]
+    fn assignments_and_definitions_apply_below() {
+        let results = evaluate("double(2)\ndouble(x) = x * 2\ndouble(rate)\nrate = 3\ndouble(rate)\ndouble(line5)");
+        assert_eq!(results[0], LineResult::Error(Error::UnknownName("double".to_string())));
+        assert_eq!(results[1], LineResult::Definition("double(x)".to_string()));
+        assert_eq!(results[2], LineResult::Error(Error::UnknownName("rate".to_string())));
+        assert_eq!(results[4], value("6"));
+        assert_eq!(results[5], value("12"));
+    }
+}
@@ -0,0 +1,29 @@
+use std::cell::RefCell;
+
+use glib::Properties;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::engine::UserFunction;
+
+// Object holding the state
+#[derive(Properties, Default)]
+#[properties(wrapper_type = super::FunctionObject)]
+pub struct FunctionObject {
+    #[property(get, set)]
+    name: RefCell<String>,
+    pub function: RefCell<Option<UserFunction>>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for FunctionObject {
+    type Type = super::FunctionObject;
+
+    const NAME: &'static str = "CalculatorFunctionObject";
+}
+
+// Trait shared by all GObjects
+#[glib::derived_properties]
+impl ObjectImpl for FunctionObject {}
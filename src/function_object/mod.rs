@@ -0,0 +1,33 @@
+mod imp;
+
+use glib::Object;
+use gtk::glib;
+use gtk::subclass::prelude::*;
+
+use crate::engine::UserFunction;
+
+glib::wrapper! {
+    pub struct FunctionObject(ObjectSubclass<imp::FunctionObject>);
+}
+
+impl FunctionObject {
+    pub fn new(function: UserFunction) -> Self {
+        let object: Self = Object::builder()
+            .property("name", &function.name)
+            .build();
+        object.imp().function.replace(Some(function));
+        object
+    }
+
+    pub fn function(&self) -> UserFunction {
+        self.imp()
+            .function
+            .borrow()
+            .clone()
+            .expect("Function object has no function")
+    }
+
+    pub fn set_function(&self, function: UserFunction) {
+        self.imp().function.replace(Some(function));
+    }
+}
@@ -0,0 +1,38 @@
+use std::cell::{Cell, RefCell};
+
+use glib::Properties;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::engine::{Complex, Factorization, Interval, Number, Units};
+
+// Object holding the state
+#[derive(Properties, Default)]
+#[properties(wrapper_type = super::HistoryObject)]
+pub struct HistoryObject {
+    #[property(get, set)]
+    expression: RefCell<String>,
+    #[property(get, set)]
+    pinned: Cell<bool>,
+    #[property(get, set)]
+    annotation: RefCell<String>,
+    pub result: RefCell<Option<Complex>>,
+    pub units: RefCell<Units>,
+    pub factorization: RefCell<Option<Factorization>>,
+    pub uncertainty: RefCell<Option<Number>>,
+    pub interval: RefCell<Option<Interval>>,
+    pub time: RefCell<Option<glib::DateTime>>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for HistoryObject {
+    type Type = super::HistoryObject;
+
+    const NAME: &'static str = "CalculatorHistoryObject";
+}
+
+// Trait shared by all GObjects
+#[glib::derived_properties]
+impl ObjectImpl for HistoryObject {}
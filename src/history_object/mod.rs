@@ -0,0 +1,54 @@
+mod imp;
+
+use glib::Object;
+use gtk::glib;
+use gtk::subclass::prelude::*;
+
+use crate::engine::{Calculation, Complex, Factorization, Interval, Number, Units};
+
+glib::wrapper! {
+    pub struct HistoryObject(ObjectSubclass<imp::HistoryObject>);
+}
+
+impl HistoryObject {
+    pub fn new(calculation: Calculation) -> Self {
+        let object: Self = Object::builder()
+            .property("expression", calculation.expression)
+            .build();
+        object.imp().result.replace(Some(calculation.result));
+        object.imp().units.replace(calculation.units);
+        object.imp().factorization.replace(calculation.factorization);
+        object.imp().uncertainty.replace(calculation.uncertainty);
+        object.imp().interval.replace(calculation.interval);
+        object.imp().time.replace(glib::DateTime::now_local().ok());
+        object
+    }
+
+    pub fn result(&self) -> Complex {
+        self.imp()
+            .result
+            .borrow()
+            .clone()
+            .expect("History object has no result")
+    }
+
+    pub fn units(&self) -> Units {
+        self.imp().units.borrow().clone()
+    }
+
+    pub fn factorization(&self) -> Option<Factorization> {
+        self.imp().factorization.borrow().clone()
+    }
+
+    pub fn uncertainty(&self) -> Option<Number> {
+        self.imp().uncertainty.borrow().clone()
+    }
+
+    pub fn interval(&self) -> Option<Interval> {
+        self.imp().interval.borrow().clone()
+    }
+
+    pub fn time(&self) -> Option<glib::DateTime> {
+        self.imp().time.borrow().clone()
+    }
+}
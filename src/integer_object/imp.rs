@@ -1,26 +0,0 @@
-use std::cell::Cell;
-
-use glib::Properties;
-use gtk::glib;
-use gtk::prelude::*;
-use gtk::subclass::prelude::*;
-
-// Object holding the state
-#[derive(Properties, Default)]
-#[properties(wrapper_type = super::IntegerObject)]
-pub struct IntegerObject {
-    #[property(get, set)]
-    number: Cell<i32>,
-}
-
-// The central trait for subclassing a GObject
-#[glib::object_subclass]
-impl ObjectSubclass for IntegerObject {
-    type Type = super::IntegerObject;
-
-    const NAME: &'static str = "MyGtkAppIntegerObject";
-}
-
-// Trait shared by all GObjects
-#[glib::derived_properties]
-impl ObjectImpl for IntegerObject {}
@@ -1,5 +1,8 @@
-mod integer_object;
+mod engine;
+mod function_object;
+mod history_object;
 mod utils;
+mod variable_object;
 mod widgets;
 use gdk::Display;
 use gtk::prelude::*;
@@ -12,8 +15,9 @@ fn main() -> glib::ExitCode {
 
     let app = adw::Application::builder().application_id(APP_ID).build();
 
-    app.connect_startup(|_| {
+    app.connect_startup(|app| {
         load_css();
+        setup_shortcuts(app);
     });
 
     app.connect_activate(build_ui);
@@ -32,6 +36,18 @@ fn load_css() {
     );
 }
 
+/// Sets up the keyboard shortcuts of the application's actions
+/// # Arguments
+/// * `app` - The application
+/// # Returns
+/// None
+fn setup_shortcuts(app: &adw::Application) {
+    app.set_accels_for_action("win.copy", &["<Ctrl>c"]);
+    app.set_accels_for_action("win.preferences", &["<Ctrl>comma"]);
+    app.set_accels_for_action("win.undo", &["<Ctrl>z"]);
+    app.set_accels_for_action("win.redo", &["<Ctrl><Shift>z"]);
+}
+
 /// Builds the UI
 /// # Arguments
 /// * `app` - The application
@@ -0,0 +1,575 @@
+//! This module contains the number formatter shared by the input display, the history and the clipboard.
+
+use super::NumberLocale;
+use num_bigint::BigInt;
+use num_traits::Zero;
+
+use crate::engine::{AngleUnit, Complex, Digits, Interval, Number, Uncertain};
+
+/// The number of significant digits shown in [`Notation::Normal`].
+const NORMAL_DIGITS: usize = 16;
+
+/// The SI prefixes for the exponents `-24` to `24` in steps of three.
+const SI_PREFIXES: [&str; 17] = [
+    "y", "z", "a", "f", "p", "n", "µ", "m", "", "k", "M", "G", "T", "P", "E", "Z", "Y",
+];
+
+/// The notation used to format numbers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Notation {
+    /// Positional notation with up to 16 significant digits, switching to scientific notation for very large and
+    /// very small numbers. Exact integers of up to 10000 digits are written in full (e.g. `2^100` is
+    /// `1267650600228229401496703205376`).
+    #[default]
+    Normal,
+    /// Positional notation with a fixed number of decimal places (e.g. `1234.50`).
+    Fixed,
+    /// Scientific notation with a fixed number of decimal places in the mantissa (e.g. `1.234e+12`).
+    Scientific,
+    /// Scientific notation with an exponent that is a multiple of three (e.g. `12.34e+3` or `12.34 k`).
+    Engineering,
+    /// A fixed number of significant figures (e.g. `1230` for three significant figures).
+    Significant,
+    /// A fraction or mixed number (e.g. `7/16` or `2 3/8`). Numbers that cannot be written with a bounded denominator
+    /// are rounded to the nearest fraction and marked with `≈`.
+    Fraction,
+    /// An angle in degrees, minutes and seconds (e.g. `12°30'15"`), with a fixed number of decimal places in the
+    /// seconds and trailing zeros removed.
+    Dms,
+}
+
+impl Notation {
+    /// All notations, in the order they are listed in the preferences.
+    pub const ALL: [Notation; 7] = [
+        Notation::Normal,
+        Notation::Fixed,
+        Notation::Scientific,
+        Notation::Engineering,
+        Notation::Significant,
+        Notation::Fraction,
+        Notation::Dms,
+    ];
+
+    /// Returns the name of the notation as stored in the settings file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Notation::Normal => "normal",
+            Notation::Fixed => "fixed",
+            Notation::Scientific => "scientific",
+            Notation::Engineering => "engineering",
+            Notation::Significant => "significant",
+            Notation::Fraction => "fraction",
+            Notation::Dms => "dms",
+        }
+    }
+
+    /// Returns the notation with the given name, or `None` if there is no such notation.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the notation as stored in the settings file.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Notation::ALL.into_iter().find(|notation| notation.name() == name)
+    }
+}
+
+/// The settings used to format numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// The notation of the number.
+    pub notation:        Notation,
+    /// The number of decimal places, or the number of significant figures for [`Notation::Significant`].
+    pub precision:       usize,
+    /// Whether [`Notation::Engineering`] uses SI prefixes instead of exponents.
+    pub si_prefixes:     bool,
+    /// Whether the integer part is grouped with thousands separators.
+    pub grouping:        bool,
+    /// The largest denominator of [`Notation::Fraction`].
+    pub max_denominator: u64,
+    /// The conventions used to write the number.
+    pub locale:          NumberLocale,
+    /// Whether complex numbers are written in polar form (e.g. `5∠53.13°`) rather than as `a + bi`.
+    pub polar:           bool,
+}
+
+impl Default for NumberFormat {
+    /// The default implementation of the [`NumberFormat`]. It formats numbers in [`Notation::Normal`] with thousands
+    /// separators, following the conventions of the system locale.
+    fn default() -> Self {
+        Self {
+            notation:        Notation::Normal,
+            precision:       6,
+            si_prefixes:     false,
+            grouping:        true,
+            max_denominator: 64,
+            locale:          NumberLocale::system(),
+            polar:           false,
+        }
+    }
+}
+
+/// Formats a number with the given format.
+///
+/// # Arguments
+///
+/// * `number` - The number to format.
+/// * `format` - The format to use.
+///
+/// # Returns
+///
+/// The formatted number.
+pub fn format_number(number: &Number, format: &NumberFormat) -> String {
+    let text = match format.notation {
+        Notation::Normal => format_normal(number),
+        Notation::Fixed => format_fixed(number, format.precision),
+        Notation::Scientific => format_scientific(number, format.precision),
+        Notation::Engineering => format_engineering(number, format.precision, format.si_prefixes),
+        Notation::Significant => format_significant(number, format.precision.max(1)),
+        Notation::Fraction => format_fraction(number, format.max_denominator),
+        Notation::Dms => format_dms(number, format.precision),
+    }
+    .unwrap_or_else(|| "NaN".to_string());
+    format.locale.localize(&text, format.grouping)
+}
+
+/// Formats a complex number with the given format, as `a + bi` (e.g. `3 − 4i`) or in polar form (e.g. `5∠53.13°`).
+/// The argument of the polar form is in degrees whatever the angle unit. Real numbers are formatted like with
+/// [`format_number`].
+///
+/// # Arguments
+///
+/// * `complex` - The number to format.
+/// * `format` - The format to use for the parts of the number.
+///
+/// # Returns
+///
+/// The formatted number.
+pub fn format_complex(complex: &Complex, format: &NumberFormat) -> String {
+    if complex.is_real() {
+        return format_number(&complex.re, format);
+    }
+    if format.polar {
+        if let (Ok(modulus), Ok(argument)) = (complex.abs(), complex.arg(AngleUnit::Degrees)) {
+            let degree_sign = if format.notation == Notation::Dms { "" } else { "°" };
+            return format!(
+                "{}∠{}{}",
+                format_number(&modulus, format),
+                format_number(&argument, format),
+                degree_sign
+            );
+        }
+    }
+    let magnitude = if complex.im.is_negative() { complex.im.neg() } else { complex.im.clone() };
+    let imaginary = if magnitude == Number::from_integer(1) {
+        "i".to_string()
+    }
+    else {
+        format!("{}i", format_number(&magnitude, format))
+    };
+    match (complex.re.is_zero(), complex.im.is_negative()) {
+        (true, false) => imaginary,
+        (true, true) => format!("−{}", imaginary),
+        (false, false) => format!("{} + {}", format_number(&complex.re, format), imaginary),
+        (false, true) => format!("{} − {}", format_number(&complex.re, format), imaginary),
+    }
+}
+
+/// Formats a number with an uncertainty (e.g. `12.30 ± 0.20`). The uncertainty is rounded to two significant digits if
+/// its first digit is 1 or 2, and to one otherwise, and the value is rounded to the same decimal place. Numbers too
+/// large or too small for positional notation share their exponent (e.g. `(6.022141 ± 0.000012)e+23`). The notation
+/// of the format is not used, since the uncertainty sets the digits shown.
+///
+/// # Arguments
+///
+/// * `uncertain` - The number to format.
+/// * `format` - The format whose grouping and locale are used.
+///
+/// # Returns
+///
+/// The formatted number.
+pub fn format_uncertain(uncertain: &Uncertain, format: &NumberFormat) -> String {
+    if uncertain.uncertainty.is_zero() {
+        return format!("{} ± 0", with_minus_sign(format_number(&uncertain.value, format)));
+    }
+    let parts = uncertainty_digits(&uncertain.uncertainty).and_then(|last| {
+        let exponent = uncertain
+            .value
+            .to_significant_digits(1)?
+            .exponent
+            .max(last);
+        let shift = if exponent >= NORMAL_DIGITS as i64 || exponent < -5 { exponent } else { 0 };
+        let value = round_to_place(&uncertain.value.shifted(-shift), last - shift)?;
+        let uncertainty = round_to_place(&uncertain.uncertainty.shifted(-shift), last - shift)?;
+        Some((value, uncertainty, shift))
+    });
+    let Some((value, uncertainty, shift)) = parts
+    else {
+        return "NaN".to_string();
+    };
+    let value = with_minus_sign(format.locale.localize(&value, format.grouping));
+    let uncertainty = format.locale.localize(&uncertainty, format.grouping);
+    if shift == 0 {
+        format!("{} ± {}", value, uncertainty)
+    }
+    else {
+        format!("({} ± {})e{}{}", value, uncertainty, if shift < 0 { '-' } else { '+' }, shift.abs())
+    }
+}
+
+/// Formats an interval as its bounds in square brackets (e.g. `[1.2, 1.5]`), each formatted like with
+/// [`format_number`]. The bounds are separated by a semicolon in locales with a decimal comma (e.g. `[1,2; 1,5]`).
+///
+/// # Arguments
+///
+/// * `interval` - The interval to format.
+/// * `format` - The format to use for the bounds.
+///
+/// # Returns
+///
+/// The formatted interval.
+pub fn format_interval(interval: &Interval, format: &NumberFormat) -> String {
+    let separator = if format.locale.decimal_separator == ',' { ';' } else { ',' };
+    format!(
+        "[{}{} {}]",
+        format_number(&interval.lower, format),
+        separator,
+        format_number(&interval.upper, format)
+    )
+}
+
+/// Formats a number in scientific notation with at most `places` decimal places in the mantissa, removing trailing
+/// zeros. Used when a number does not fit the input display in its configured format.
+///
+/// # Arguments
+///
+/// * `number` - The number to format.
+/// * `places` - The maximum number of decimal places in the mantissa.
+///
+/// # Returns
+///
+/// The formatted number.
+pub fn format_compact(number: &Number, places: usize) -> String {
+    match number.to_significant_digits(places + 1) {
+        Some(mut digits) => {
+            digits.trim();
+            digits.to_scientific()
+        }
+        None => "NaN".to_string(),
+    }
+}
+
+/// Formats a number of seconds as a duration or a time of day (e.g. `4:05:30` or `−0:30:00`), with at most `places`
+/// decimal places in the seconds and trailing zeros removed. Durations are not wrapped at 24 hours.
+///
+/// # Arguments
+///
+/// * `number` - The number of seconds.
+/// * `format` - The format whose precision and locale are used.
+///
+/// # Returns
+///
+/// The formatted duration.
+pub fn format_clock(number: &Number, format: &NumberFormat) -> String {
+    let text = sexagesimal(number, format.precision)
+        .map(|(negative, hours, minutes, seconds, fraction)| {
+            let mut result = format!("{}{}:{:02}:{:02}", if negative { "−" } else { "" }, hours, minutes, seconds);
+            if !fraction.is_empty() {
+                result.push('.');
+                result.push_str(&fraction);
+            }
+            result
+        })
+        .unwrap_or_else(|| "NaN".to_string());
+    format.locale.localize(&text, false)
+}
+
+/// Returns the decimal exponent of the last digit shown of an uncertainty: the second significant digit if the
+/// rounded uncertainty starts with 1 or 2, and the first otherwise (e.g. `-2` for `0.0296`, rounded to `0.03`, and
+/// `-3` for `0.0096`, rounded to `0.010`).
+fn uncertainty_digits(uncertainty: &Number) -> Option<i64> {
+    let two = uncertainty.to_significant_digits(2)?;
+    if two.digits[0] <= 2 {
+        return Some(two.exponent - 1);
+    }
+    let one = uncertainty.to_significant_digits(1)?;
+    Some(if one.digits[0] == 1 { one.exponent - 1 } else { one.exponent })
+}
+
+/// Writes the hyphen of a negative number as a minus sign (e.g. `−12.30` for `-12.30`).
+fn with_minus_sign(text: String) -> String {
+    match text.strip_prefix('-') {
+        Some(magnitude) => format!("−{}", magnitude),
+        None => text,
+    }
+}
+
+/// Formats a number in positional notation, rounded half away from zero to a multiple of `10^exponent` (e.g.
+/// `123500` for `123456` rounded to the exponent `2`, or `12.30` for `12.3` rounded to the exponent `-2`).
+fn round_to_place(number: &Number, exponent: i64) -> Option<String> {
+    if exponent < 0 {
+        return format_fixed(number, exponent.unsigned_abs() as usize);
+    }
+    let (negative, integer, _) = number.shifted(-exponent).to_fixed_digits(0)?;
+    let zeros = if integer == "0" { String::new() } else { "0".repeat(exponent as usize) };
+    Some(format!("{}{}{}", if negative { "-" } else { "" }, integer, zeros))
+}
+
+/// Formats a number in [`Notation::Normal`]. Exact integers of up to 10000 digits are written in full.
+fn format_normal(number: &Number) -> Option<String> {
+    if let Some(digits) = number.integer_digits() {
+        return Some(digits);
+    }
+    let mut digits = number.to_significant_digits(NORMAL_DIGITS)?;
+    digits.trim();
+    Some(positional_or_scientific(&digits))
+}
+
+/// Formats a number in [`Notation::Fixed`] with `places` decimal places.
+fn format_fixed(number: &Number, places: usize) -> Option<String> {
+    let (negative, integer, fraction) = number.to_fixed_digits(places)?;
+    let mut result = String::new();
+    if negative {
+        result.push('-');
+    }
+    result.push_str(&integer);
+    if !fraction.is_empty() {
+        result.push('.');
+        result.push_str(&fraction);
+    }
+    Some(result)
+}
+
+/// Formats a number in [`Notation::Scientific`] with `places` decimal places in the mantissa.
+fn format_scientific(number: &Number, places: usize) -> Option<String> {
+    let mut digits = number.to_significant_digits(places + 1)?;
+    digits.pad(places + 1);
+    Some(digits.to_scientific())
+}
+
+/// Formats a number in [`Notation::Engineering`] with `places` decimal places in the mantissa.
+fn format_engineering(number: &Number, places: usize, si_prefixes: bool) -> Option<String> {
+    let digits = number.to_significant_digits(places + 1)?;
+    let mut exponent = digits.exponent.div_euclid(3) * 3;
+    let (negative, mut integer, mut fraction) = number.shifted(-exponent).to_fixed_digits(places)?;
+    if integer.len() > 3 {
+        exponent += 3;
+        (_, integer, fraction) = number.shifted(-exponent).to_fixed_digits(places)?;
+    }
+
+    let mut result = String::new();
+    if negative {
+        result.push('-');
+    }
+    result.push_str(&integer);
+    if !fraction.is_empty() {
+        result.push('.');
+        result.push_str(&fraction);
+    }
+    let prefix = usize::try_from(exponent / 3 + 8)
+        .ok()
+        .and_then(|index| SI_PREFIXES.get(index));
+    match prefix {
+        Some(prefix) if si_prefixes => {
+            if !prefix.is_empty() {
+                result.push(' ');
+                result.push_str(prefix);
+            }
+        }
+        _ => result.push_str(&format!("e{}{}", if exponent < 0 { '-' } else { '+' }, exponent.abs())),
+    }
+    Some(result)
+}
+
+/// Formats a number in [`Notation::Significant`] with `figures` significant figures.
+fn format_significant(number: &Number, figures: usize) -> Option<String> {
+    let mut digits = number.to_significant_digits(figures)?;
+    digits.pad(figures);
+    Some(positional_or_scientific(&digits))
+}
+
+/// Formats a number in [`Notation::Fraction`] with a denominator of at most `max_denominator`.
+fn format_fraction(number: &Number, max_denominator: u64) -> Option<String> {
+    let fraction = number.to_fraction(max_denominator)?;
+    let mut result = String::new();
+    if !fraction.exact {
+        result.push_str("≈ ");
+    }
+    if fraction.negative {
+        result.push('-');
+    }
+    if fraction.numerator.is_zero() {
+        result.push_str(&fraction.whole.to_string());
+    }
+    else {
+        if !fraction.whole.is_zero() {
+            result.push_str(&format!("{} ", fraction.whole));
+        }
+        result.push_str(&format!("{}/{}", fraction.numerator, fraction.denominator));
+    }
+    Some(result)
+}
+
+/// Formats a number of degrees in [`Notation::Dms`] with at most `places` decimal places in the seconds.
+fn format_dms(number: &Number, places: usize) -> Option<String> {
+    let (negative, degrees, minutes, seconds, fraction) =
+        sexagesimal(&number.mul(&Number::from_integer(3600)).ok()?, places)?;
+
+    let mut result = String::new();
+    if negative {
+        result.push('-');
+    }
+    result.push_str(&format!("{}°{}'{}", degrees, minutes, seconds));
+    if !fraction.is_empty() {
+        result.push('.');
+        result.push_str(&fraction);
+    }
+    result.push('"');
+    Some(result)
+}
+
+/// Splits a number of seconds rounded to `places` decimal places into units of 3600 and 60 seconds.
+///
+/// # Returns
+///
+/// A tuple of whether the rounded number is negative, its whole units of 3600 and 60 seconds, its whole seconds and
+/// its fractional digits without trailing zeros, or `None` if the number is not finite.
+fn sexagesimal(seconds: &Number, places: usize) -> Option<(bool, BigInt, BigInt, BigInt, String)> {
+    let (negative, seconds, fraction) = seconds.to_fixed_digits(places)?;
+    let seconds = seconds.parse::<BigInt>().ok()?;
+    let sixty = BigInt::from(60);
+    let (minutes, seconds) = (&seconds / &sixty, &seconds % &sixty);
+    let (hours, minutes) = (&minutes / &sixty, &minutes % &sixty);
+    Some((negative, hours, minutes, seconds, fraction.trim_end_matches('0').to_string()))
+}
+
+/// Formats digits in positional notation, or in scientific notation for very large and very small numbers.
+fn positional_or_scientific(digits: &Digits) -> String {
+    if digits.exponent >= NORMAL_DIGITS as i64 || digits.exponent < -5 {
+        digits.to_scientific()
+    }
+    else {
+        digits.to_positional()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses an exact number.
+    fn number(text: &str) -> Number {
+        text.parse().unwrap()
+    }
+
+    /// Returns a format with the given notation, in the English locale.
+    fn format(notation: Notation) -> NumberFormat {
+        NumberFormat {
+            notation,
+            locale: NumberLocale::ENGLISH,
+            ..NumberFormat::default()
+        }
+    }
+
+    /// Formats a number and reads it back in the locale of the format.
+    fn round_trip(value: &Number, format: &NumberFormat) -> Number {
+        let text = format_number(value, format);
+        let parsed = format
+            .locale
+            .parse(&text)
+            .unwrap_or_else(|| panic!("{} is not a number", text));
+        number(&parsed)
+    }
+
+    #[test]
+    fn fractions() {
+        let format = format(Notation::Fraction);
+        assert_eq!(format_number(&number("2.375"), &format), "2 3/8");
+        assert_eq!(format_number(&number("-0.375"), &format), "-3/8");
+        assert_eq!(format_number(&number("12345.5"), &format), "12,345 1/2");
+        assert_eq!(format_number(&number("7"), &format), "7");
+        assert_eq!(format_number(&number("0.3"), &format), "3/10");
+        assert_eq!(format_number(&number("0.01"), &format), "≈ 1/64");
+        assert_eq!(format_number(&Number::Real(0.1), &format), "≈ 3/32");
+    }
+
+    #[test]
+    fn fraction_round_trips() {
+        let mut format = format(Notation::Fraction);
+        for locale in [NumberLocale::ENGLISH, NumberLocale::EUROPEAN, NumberLocale::FRENCH, NumberLocale::INDIAN] {
+            format.locale = locale;
+            for value in ["2.375", "-2.375", "1234567/64", "-5", "1/3"] {
+                assert_eq!(round_trip(&number(value), &format), number(value));
+            }
+        }
+    }
+
+    #[test]
+    fn dms() {
+        let format = NumberFormat {
+            precision: 2,
+            ..format(Notation::Dms)
+        };
+        assert_eq!(format_number(&number("12.5"), &format), "12°30'0\"");
+        assert_eq!(format_number(&number("-12.504166"), &format), "-12°30'15\"");
+        assert_eq!(format_number(&number("359.99999"), &format), "359°59'59.96\"");
+        assert_eq!(format_number(&number("0.0000001"), &format), "0°0'0\"");
+    }
+
+    #[test]
+    fn dms_round_trips() {
+        let mut format = NumberFormat {
+            precision: 4,
+            ..format(Notation::Dms)
+        };
+        for locale in [NumberLocale::ENGLISH, NumberLocale::EUROPEAN, NumberLocale::SWISS] {
+            format.locale = locale;
+            for value in ["12.5", "-12.504166", "0.25", "1234.0001", "90"] {
+                let value = number(value);
+                let error = round_trip(&value, &format).sub(&value).unwrap().to_f64().abs();
+                assert!(error <= 0.00005 / 3600.0, "{} does not round trip", value);
+            }
+        }
+        assert_eq!(number("12°30'15\""), number("3001/240"));
+        assert_eq!(NumberLocale::EUROPEAN.parse("12° 30′ 7,5″"), Some("12°30'7.5\"".to_string()));
+    }
+
+    #[test]
+    fn clock() {
+        let format = format(Notation::Normal);
+        assert_eq!(format_clock(&number("14730"), &format), "4:05:30");
+        assert_eq!(format_clock(&number("-1800"), &format), "−0:30:00");
+        assert_eq!(format_clock(&number("90000.25"), &format), "25:00:00.25");
+        let european = NumberFormat {
+            locale: NumberLocale::EUROPEAN,
+            ..format
+        };
+        assert_eq!(format_clock(&number("1.5"), &european), "0:00:01,5");
+    }
+
+    #[test]
+    fn exact_integers_in_full() {
+        let power = number("2").pow(&number("100")).unwrap();
+        let format = NumberFormat {
+            grouping: false,
+            ..format(Notation::Normal)
+        };
+        assert_eq!(format_number(&power, &format), "1267650600228229401496703205376");
+        assert_eq!(format_number(&power.neg(), &format), "-1267650600228229401496703205376");
+    }
+
+    #[test]
+    fn uncertain() {
+        let uncertain = |value: &str, uncertainty: &str| {
+            let uncertain = Uncertain::new(number(value), number(uncertainty)).unwrap();
+            format_uncertain(&uncertain, &format(Notation::Normal))
+        };
+        assert_eq!(uncertain("12.3", "0.2"), "12.30 ± 0.20");
+        assert_eq!(uncertain("-12.3", "0.2"), "−12.30 ± 0.20");
+        assert_eq!(uncertain("1.23456", "0.0296"), "1.23 ± 0.03");
+        assert_eq!(uncertain("1.23456", "0.0096"), "1.235 ± 0.010");
+        assert_eq!(uncertain("1234.5", "15"), "1,235 ± 15");
+        assert_eq!(uncertain("-5", "0"), "−5 ± 0");
+        assert_eq!(uncertain("602214076000000000000000", "1200000000000000000"), "(6.022141 ± 0.000012)e+23");
+        assert_eq!(uncertain("-0.000001234", "0.000000012"), "(−1.234 ± 0.012)e-6");
+    }
+}
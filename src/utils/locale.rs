@@ -0,0 +1,447 @@
+//! This module contains the locale conventions used to write and read numbers: the decimal separator, the group
+//! separator and the size of the digit groups.
+
+#[cfg(unix)]
+use std::ffi::CStr;
+use std::sync::OnceLock;
+
+use crate::engine::Number;
+
+/// The way the digits of the integer part of a number are grouped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grouping {
+    /// Groups of three digits (e.g. `1,234,567`).
+    Thousands,
+    /// A group of three digits followed by groups of two digits, as used for lakh and crore (e.g. `12,34,567`).
+    Indian,
+}
+
+/// The conventions used to write numbers in a locale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberLocale {
+    /// The character separating the integer part from the fractional part.
+    pub decimal_separator: char,
+    /// The character separating the digit groups of the integer part.
+    pub group_separator:   char,
+    /// The size of the digit groups of the integer part.
+    pub grouping:          Grouping,
+}
+
+impl NumberLocale {
+    /// The conventions of English and most other locales (e.g. `1,234,567.89`).
+    pub const ENGLISH: NumberLocale = NumberLocale {
+        decimal_separator: '.',
+        group_separator:   ',',
+        grouping:          Grouping::Thousands,
+    };
+    /// The conventions of German and many other European locales (e.g. `1.234.567,89`).
+    pub const EUROPEAN: NumberLocale = NumberLocale {
+        decimal_separator: ',',
+        group_separator:   '.',
+        grouping:          Grouping::Thousands,
+    };
+    /// The conventions of French and many Nordic and Slavic locales (e.g. `1 234 567,89`). The group separator is a
+    /// no-break space, so numbers are never wrapped.
+    pub const FRENCH: NumberLocale = NumberLocale {
+        decimal_separator: ',',
+        group_separator:   '\u{a0}',
+        grouping:          Grouping::Thousands,
+    };
+    /// The conventions of Swiss locales (e.g. `1'234'567.89`).
+    pub const SWISS: NumberLocale = NumberLocale {
+        decimal_separator: '.',
+        group_separator:   '\'',
+        grouping:          Grouping::Thousands,
+    };
+    /// The conventions of Indian locales, grouping by lakh and crore (e.g. `12,34,567.89`).
+    pub const INDIAN: NumberLocale = NumberLocale {
+        decimal_separator: '.',
+        group_separator:   ',',
+        grouping:          Grouping::Indian,
+    };
+
+    /// Returns the conventions of the system locale, read from the `LC_NUMERIC` category of the C library. Where the
+    /// C library gives no group separator (e.g. in the `C` locale, or if the locale is not installed) and on other
+    /// platforms, they are looked up by the name of the locale, taken from the `LC_ALL`, `LC_NUMERIC` and `LANG`
+    /// environment variables, in that order. The locale is read on the first call, at startup, and kept for the rest
+    /// of the session.
+    pub fn system() -> Self {
+        static SYSTEM: OnceLock<NumberLocale> = OnceLock::new();
+        *SYSTEM.get_or_init(|| {
+            Self::from_numeric_category().unwrap_or_else(|| {
+                let name = ["LC_ALL", "LC_NUMERIC", "LANG"]
+                    .iter()
+                    .find_map(|variable| std::env::var(variable).ok().filter(|value| !value.is_empty()))
+                    .unwrap_or_default();
+                Self::from_locale_name(&name)
+            })
+        })
+    }
+
+    /// Returns the conventions of the `LC_NUMERIC` category of the C library, set from the environment. Spaces used as
+    /// group separators (e.g. the narrow no-break space of French) are written as no-break spaces, and grouping by
+    /// three then two digits is read as [`Grouping::Indian`].
+    ///
+    /// # Returns
+    ///
+    /// The conventions, or `None` if the locale of the environment is not installed or has no group separator.
+    #[cfg(unix)]
+    fn from_numeric_category() -> Option<Self> {
+        // SAFETY: `setlocale` and `localeconv` are called once, from the main thread at startup like the `setlocale`
+        // call of GTK, and the strings of `localeconv` are copied before any other call may change them.
+        let (decimal_point, thousands_sep, grouping) = unsafe {
+            if libc::setlocale(libc::LC_NUMERIC, c"".as_ptr()).is_null() {
+                return None;
+            }
+            let conventions = libc::localeconv();
+            if conventions.is_null() {
+                return None;
+            }
+            let conventions = &*conventions;
+            (
+                CStr::from_ptr(conventions.decimal_point).to_string_lossy().into_owned(),
+                CStr::from_ptr(conventions.thousands_sep).to_string_lossy().into_owned(),
+                CStr::from_ptr(conventions.grouping).to_bytes().to_vec(),
+            )
+        };
+        let decimal_separator = decimal_point.chars().next()?;
+        let group_separator = match thousands_sep.chars().next()? {
+            c if c.is_whitespace() => '\u{a0}',
+            c => c,
+        };
+        if group_separator == decimal_separator {
+            return None;
+        }
+        let grouping = match grouping.as_slice() {
+            [3, 2, ..] => Grouping::Indian,
+            _ => Grouping::Thousands,
+        };
+        Some(Self {
+            decimal_separator,
+            group_separator,
+            grouping,
+        })
+    }
+
+    /// Returns `None`, as the C library locale is only read on Unix platforms.
+    #[cfg(not(unix))]
+    fn from_numeric_category() -> Option<Self> {
+        None
+    }
+
+    /// Returns the conventions of the locale with the given POSIX name (e.g. `de_CH.UTF-8`), from its territory and
+    /// its language. It is the fallback of [`NumberLocale::system`] when the C library gives no conventions. Unknown
+    /// locales use the [`NumberLocale::ENGLISH`] conventions.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the locale.
+    pub fn from_locale_name(name: &str) -> Self {
+        let name = name.split(['.', '@']).next().unwrap_or_default();
+        let (language, territory) = name.split_once('_').unwrap_or((name, ""));
+        match (language, territory) {
+            (_, "IN") => Self::INDIAN,
+            (_, "CH" | "LI") => Self::SWISS,
+            // Spanish of North and Central America writes a decimal point.
+            ("es", "MX" | "US" | "PR" | "DO" | "GT" | "HN" | "NI" | "PA" | "SV" | "CU") => Self::ENGLISH,
+            (
+                "de" | "es" | "it" | "nl" | "pt" | "da" | "el" | "id" | "tr" | "ro" | "hr" | "sl" | "sr" | "is" | "ca"
+                | "gl" | "eu" | "vi" | "mk" | "bs" | "az",
+                _,
+            ) => Self::EUROPEAN,
+            (
+                "fr" | "ru" | "uk" | "be" | "pl" | "cs" | "sk" | "hu" | "bg" | "sv" | "fi" | "nb" | "nn" | "no" | "et"
+                | "lv" | "lt" | "kk" | "ka" | "hy",
+                _,
+            ) => Self::FRENCH,
+            _ => Self::ENGLISH,
+        }
+    }
+
+    /// Writes a plain number in this locale, leaving any exponent, SI prefix or approximation mark as is. Each part of
+    /// a fraction or mixed number is written separately (e.g. `1234 3/8`).
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The number with `.` as decimal point and without separators (e.g. `-1234.5` or `1.5e+3`).
+    /// * `grouping` - Whether integer parts are grouped with the group separator.
+    ///
+    /// # Returns
+    ///
+    /// The number written in this locale (e.g. `-1.234,5` for [`NumberLocale::EUROPEAN`]).
+    pub fn localize(&self, number: &str, grouping: bool) -> String {
+        let (mantissa, exponent) = number.split_at(number.find('e').unwrap_or(number.len()));
+        let mut result = String::new();
+        let mut rest = mantissa;
+        while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+            result.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .map_or(rest.len(), |end| start + end);
+            let (integer, fraction) = match rest[start..end].split_once('.') {
+                Some((integer, fraction)) => (integer, Some(fraction)),
+                None => (&rest[start..end], None),
+            };
+            if grouping {
+                result.push_str(&self.group(integer));
+            }
+            else {
+                result.push_str(integer);
+            }
+            if let Some(fraction) = fraction {
+                result.push(self.decimal_separator);
+                result.push_str(fraction);
+            }
+            rest = &rest[end..];
+        }
+        result.push_str(rest);
+        result.push_str(exponent);
+        result
+    }
+
+    /// Writes the numbers of an expression (e.g. `12.5 + 3 =`) with the decimal separator of this locale. The numbers
+    /// are not grouped.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - The expression with `.` as decimal point.
+    pub fn localize_expression(&self, expression: &str) -> String {
+        let chars = expression.chars().collect::<Vec<char>>();
+        chars
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let between_digits = i > 0
+                    && chars[i - 1].is_ascii_digit()
+                    && chars.get(i + 1).is_some_and(|next| next.is_ascii_digit());
+                if c == '.' && between_digits {
+                    self.decimal_separator
+                }
+                else {
+                    c
+                }
+            })
+            .collect()
+    }
+
+    /// Reads a number written in this locale (e.g. pasted from the clipboard). Group separators and whitespace are
+    /// ignored. Fractions and mixed numbers (e.g. `2 3/8`) and angles in degrees, minutes and seconds (e.g. `12°30'15"`)
+    /// are read as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to parse.
+    ///
+    /// # Returns
+    ///
+    /// The number with `.` as decimal point and without separators, or `None` if the text is not a number.
+    pub fn parse(&self, text: &str) -> Option<String> {
+        if let Some((whole, denominator)) = text.trim().rsplit_once('/') {
+            return self.parse_fraction(whole, denominator);
+        }
+        if text.contains('°') {
+            return self.parse_dms(text);
+        }
+        let number = text
+            .chars()
+            .filter(|&c| c != self.group_separator && !c.is_whitespace())
+            .map(|c| if c == self.decimal_separator { '.' } else { c })
+            .collect::<String>();
+        let digits = number.strip_prefix('-').unwrap_or(&number);
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        if int_part.is_empty()
+            || !int_part.chars().all(|c| c.is_ascii_digit())
+            || !frac_part.chars().all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        Some(number)
+    }
+
+    /// Reads a fraction or a mixed number, split at its fraction bar. The denominator must not be zero.
+    fn parse_fraction(&self, whole: &str, denominator: &str) -> Option<String> {
+        let (sign, whole) = match whole.trim().strip_prefix('-') {
+            Some(whole) => ("-", whole.trim()),
+            None => ("", whole.trim()),
+        };
+        let (whole, numerator) = match whole.rsplit_once(char::is_whitespace) {
+            Some((whole, numerator)) => (Some(whole), numerator),
+            None => (None, whole),
+        };
+        let integer = |part: &str| {
+            let part = part
+                .chars()
+                .filter(|&c| c != self.group_separator && !c.is_whitespace())
+                .collect::<String>();
+            (!part.is_empty() && part.chars().all(|c| c.is_ascii_digit())).then_some(part)
+        };
+        let numerator = integer(numerator)?;
+        let denominator = integer(denominator)?;
+        if denominator.chars().all(|c| c == '0') {
+            return None;
+        }
+        match whole {
+            Some(whole) => Some(format!("{}{} {}/{}", sign, integer(whole)?, numerator, denominator)),
+            None => Some(format!("{}{}/{}", sign, numerator, denominator)),
+        }
+    }
+
+    /// Reads an angle in degrees, minutes and seconds. Primes are read as minute and second signs. The degrees may be
+    /// grouped, but not the minutes and seconds, since the Swiss group separator is also the minute sign.
+    fn parse_dms(&self, text: &str) -> Option<String> {
+        let (degrees, rest) = text.split_once('°')?;
+        let degrees = degrees.chars().filter(|&c| c != self.group_separator);
+        let number = degrees
+            .chain(std::iter::once('°'))
+            .chain(rest.chars())
+            .filter(|c| !c.is_whitespace())
+            .map(|c| {
+                match c {
+                    '′' => '\'',
+                    '″' => '"',
+                    c if c == self.decimal_separator => '.',
+                    c => c,
+                }
+            })
+            .collect::<String>();
+        number.parse::<Number>().is_ok().then_some(number)
+    }
+
+    /// Groups the digits of an integer with the group separator.
+    fn group(&self, integer: &str) -> String {
+        let mut result = Vec::new();
+        let mut group_size = 3;
+        let mut count = 0;
+        for c in integer.chars().rev() {
+            if count == group_size {
+                result.push(self.group_separator);
+                count = 0;
+                if self.grouping == Grouping::Indian {
+                    group_size = 2;
+                }
+            }
+            result.push(c);
+            count += 1;
+        }
+        result.iter().rev().collect()
+    }
+}
+
+impl Default for NumberLocale {
+    /// The default implementation of the [`NumberLocale`]. It uses the conventions of the system locale.
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+/// The locale chosen in the preferences to write numbers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LocaleSetting {
+    /// The conventions of the system locale.
+    #[default]
+    System,
+    /// [`NumberLocale::ENGLISH`]
+    English,
+    /// [`NumberLocale::EUROPEAN`]
+    European,
+    /// [`NumberLocale::FRENCH`]
+    French,
+    /// [`NumberLocale::SWISS`]
+    Swiss,
+    /// [`NumberLocale::INDIAN`]
+    Indian,
+}
+
+impl LocaleSetting {
+    /// All locale settings, in the order they are listed in the preferences.
+    pub const ALL: [LocaleSetting; 6] = [
+        LocaleSetting::System,
+        LocaleSetting::English,
+        LocaleSetting::European,
+        LocaleSetting::French,
+        LocaleSetting::Swiss,
+        LocaleSetting::Indian,
+    ];
+
+    /// Returns the name of the locale setting as stored in the settings file.
+    pub fn name(self) -> &'static str {
+        match self {
+            LocaleSetting::System => "system",
+            LocaleSetting::English => "english",
+            LocaleSetting::European => "european",
+            LocaleSetting::French => "french",
+            LocaleSetting::Swiss => "swiss",
+            LocaleSetting::Indian => "indian",
+        }
+    }
+
+    /// Returns the locale setting with the given name, or `None` if there is no such setting.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the locale setting as stored in the settings file.
+    pub fn from_name(name: &str) -> Option<Self> {
+        LocaleSetting::ALL.into_iter().find(|setting| setting.name() == name)
+    }
+
+    /// Returns the conventions used to write numbers with this setting.
+    pub fn locale(self) -> NumberLocale {
+        match self {
+            LocaleSetting::System => NumberLocale::system(),
+            LocaleSetting::English => NumberLocale::ENGLISH,
+            LocaleSetting::European => NumberLocale::EUROPEAN,
+            LocaleSetting::French => NumberLocale::FRENCH,
+            LocaleSetting::Swiss => NumberLocale::SWISS,
+            LocaleSetting::Indian => NumberLocale::INDIAN,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grouping() {
+        assert_eq!(NumberLocale::ENGLISH.localize("-1234567.89", true), "-1,234,567.89");
+        assert_eq!(NumberLocale::EUROPEAN.localize("1234567.89", true), "1.234.567,89");
+        assert_eq!(NumberLocale::FRENCH.localize("1234567.89", true), "1\u{a0}234\u{a0}567,89");
+        assert_eq!(NumberLocale::SWISS.localize("1234567.89", true), "1'234'567.89");
+        assert_eq!(NumberLocale::INDIAN.localize("1234567.89", true), "12,34,567.89");
+        assert_eq!(NumberLocale::INDIAN.localize("123456789012", true), "1,23,45,67,89,012");
+        assert_eq!(NumberLocale::INDIAN.localize("999", true), "999");
+    }
+
+    #[test]
+    fn localize_without_grouping() {
+        assert_eq!(NumberLocale::EUROPEAN.localize("1234567.89", false), "1234567,89");
+        assert_eq!(NumberLocale::EUROPEAN.localize("1.5e+30", true), "1,5e+30");
+        assert_eq!(NumberLocale::SWISS.localize("12345 3/8", true), "12'345 3/8");
+        assert_eq!(NumberLocale::EUROPEAN.localize_expression("12.5 + 3 ="), "12,5 + 3 =");
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(NumberLocale::ENGLISH.parse("-1,234,567.89"), Some("-1234567.89".to_string()));
+        assert_eq!(NumberLocale::EUROPEAN.parse("1.234.567,89"), Some("1234567.89".to_string()));
+        assert_eq!(NumberLocale::FRENCH.parse("1 234 567,89"), Some("1234567.89".to_string()));
+        assert_eq!(NumberLocale::SWISS.parse("1'234'567.89"), Some("1234567.89".to_string()));
+        assert_eq!(NumberLocale::INDIAN.parse("12,34,567.89"), Some("1234567.89".to_string()));
+        assert_eq!(NumberLocale::EUROPEAN.parse("1,2,3"), None);
+        assert_eq!(NumberLocale::ENGLISH.parse("12abc"), None);
+        assert_eq!(NumberLocale::ENGLISH.parse(""), None);
+    }
+
+    #[test]
+    fn locale_names() {
+        assert_eq!(NumberLocale::from_locale_name("de_CH.UTF-8"), NumberLocale::SWISS);
+        assert_eq!(NumberLocale::from_locale_name("hi_IN"), NumberLocale::INDIAN);
+        assert_eq!(NumberLocale::from_locale_name("de_DE.UTF-8"), NumberLocale::EUROPEAN);
+        assert_eq!(NumberLocale::from_locale_name("fr_FR@euro"), NumberLocale::FRENCH);
+        assert_eq!(NumberLocale::from_locale_name("es_MX"), NumberLocale::ENGLISH);
+        assert_eq!(NumberLocale::from_locale_name("C"), NumberLocale::ENGLISH);
+        for setting in LocaleSetting::ALL {
+            assert_eq!(LocaleSetting::from_name(setting.name()), Some(setting));
+        }
+    }
+}
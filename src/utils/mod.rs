@@ -0,0 +1,155 @@
+//! This module contains utility functions used throughout the application.
+
+use std::path::PathBuf;
+
+use gtk::glib;
+
+use crate::APP_ID;
+
+mod format;
+pub use self::format::{
+    format_clock, format_compact, format_complex, format_interval, format_number, format_uncertain, Notation,
+    NumberFormat,
+};
+
+mod locale;
+pub use self::locale::{LocaleSetting, NumberLocale};
+
+/// Creates the directory to store the settings file and returns the path to the settings file.
+/// If the directory already exists, it will just return the path to the settings file.
+/// If the directory does not exist, it will create the directory and then return the path to the settings file.
+/// 
+/// # Returns
+/// 
+/// The path to the settings file.
+pub fn settings_path() -> PathBuf {
+    config_path("settings.toml")
+}
+
+/// Creates the directory to store the settings file and returns the path to the file storing the variables, next to
+/// the settings file.
+///
+/// # Returns
+///
+/// The path to the variables file.
+pub fn variables_path() -> PathBuf {
+    config_path("variables.toml")
+}
+
+/// Creates the directory to store the settings file and returns the path to the file storing the user-defined
+/// functions, next to the settings file.
+///
+/// # Returns
+///
+/// The path to the functions file.
+pub fn functions_path() -> PathBuf {
+    config_path("functions.toml")
+}
+
+/// Creates the configuration directory of the application if it does not exist yet, and returns the path to the
+/// given file in it.
+///
+/// # Arguments
+///
+/// * `file_name` - The name of the file.
+///
+/// # Panics
+///
+/// * Panics if the directory cannot be created.
+fn config_path(file_name: &str) -> PathBuf {
+    let mut path = glib::user_config_dir();
+    path.push(APP_ID);
+    std::fs::create_dir_all(&path).expect("Failed to create settings directory");
+    path.push(file_name);
+    path
+}
+
+/// Displays a number in scientific notation, rounding the mantissa to the given number of fractional digits.
+/// Trailing zeros in the mantissa are removed.
+///
+/// # Arguments
+///
+/// * `number` - The number to display, without thousands separators (e.g. `-1234.5`).
+/// * `precision` - The maximum number of digits after the decimal point of the mantissa.
+///
+/// # Returns
+///
+/// The number formatted in scientific notation (e.g. `-1.2345e+3`).
+pub fn display_scientific(number: &str, precision: usize) -> String {
+    let (is_negative, number) = match number.strip_prefix('-') {
+        Some(number) => (true, number),
+        None => (false, number),
+    };
+    let (int_part, frac_part) = number.split_once('.').unwrap_or((number, ""));
+    let digits = format!("{}{}", int_part, frac_part);
+    let Some(first) = digits.find(|c: char| c != '0')
+    else {
+        return "0".to_string();
+    };
+    let mut exponent = int_part.len() as i64 - 1 - first as i64;
+    let significant = &digits.as_bytes()[first..];
+
+    let mut mantissa = significant
+        .iter()
+        .take(precision + 1)
+        .map(|b| b - b'0')
+        .collect::<Vec<u8>>();
+    if significant.get(precision + 1).is_some_and(|&b| b >= b'5') {
+        let mut i = mantissa.len();
+        loop {
+            if i == 0 {
+                mantissa.insert(0, 1);
+                mantissa.pop();
+                exponent += 1;
+                break;
+            }
+            i -= 1;
+            if mantissa[i] == 9 {
+                mantissa[i] = 0;
+            }
+            else {
+                mantissa[i] += 1;
+                break;
+            }
+        }
+    }
+
+    let mut result = String::new();
+    if is_negative {
+        result.push('-');
+    }
+    result.push(char::from(b'0' + mantissa[0]));
+    let fraction = mantissa[1..]
+        .iter()
+        .map(|d| char::from(b'0' + d))
+        .collect::<String>();
+    let fraction = fraction.trim_end_matches('0');
+    if !fraction.is_empty() {
+        result.push('.');
+        result.push_str(fraction);
+    }
+    result.push_str(&format!("e{}{}", if exponent < 0 { '-' } else { '+' }, exponent.abs()));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scientific_display() {
+        assert_eq!(display_scientific("1234.5", 10), "1.2345e+3");
+        assert_eq!(display_scientific("-1234.5", 2), "-1.23e+3");
+        assert_eq!(display_scientific("0.000123", 5), "1.23e-4");
+        assert_eq!(display_scientific("100000000000000000000", 6), "1e+20");
+        assert_eq!(display_scientific("0", 6), "0");
+    }
+
+    #[test]
+    fn scientific_display_rounding() {
+        assert_eq!(display_scientific("1234.5", 3), "1.235e+3");
+        assert_eq!(display_scientific("99999.5", 3), "1e+5");
+        assert_eq!(display_scientific("-0.0099999", 2), "-1e-2");
+        assert_eq!(display_scientific("123456789", 0), "1e+8");
+    }
+}
@@ -0,0 +1,29 @@
+use std::cell::RefCell;
+
+use glib::Properties;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::engine::Quantity;
+
+// Object holding the state
+#[derive(Properties, Default)]
+#[properties(wrapper_type = super::VariableObject)]
+pub struct VariableObject {
+    #[property(get, set)]
+    name: RefCell<String>,
+    pub value: RefCell<Option<Quantity>>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for VariableObject {
+    type Type = super::VariableObject;
+
+    const NAME: &'static str = "CalculatorVariableObject";
+}
+
+// Trait shared by all GObjects
+#[glib::derived_properties]
+impl ObjectImpl for VariableObject {}
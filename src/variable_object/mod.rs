@@ -0,0 +1,31 @@
+mod imp;
+
+use glib::Object;
+use gtk::glib;
+use gtk::subclass::prelude::*;
+
+use crate::engine::Quantity;
+
+glib::wrapper! {
+    pub struct VariableObject(ObjectSubclass<imp::VariableObject>);
+}
+
+impl VariableObject {
+    pub fn new(name: &str, value: Quantity) -> Self {
+        let object: Self = Object::builder().property("name", name).build();
+        object.imp().value.replace(Some(value));
+        object
+    }
+
+    pub fn value(&self) -> Quantity {
+        self.imp()
+            .value
+            .borrow()
+            .clone()
+            .expect("Variable object has no value")
+    }
+
+    pub fn set_value(&self, value: Quantity) {
+        self.imp().value.replace(Some(value));
+    }
+}
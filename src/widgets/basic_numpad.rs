@@ -10,7 +10,7 @@ mod imp {
 
     use adw::subclass::prelude::*;
     use glib::subclass::InitializingObject;
-    use gtk::{glib, Button, CompositeTemplate, Grid};
+    use gtk::{glib, Button, CompositeTemplate, Grid, ToggleButton};
 
     /// The `BasicNumpad` widget. It provides a basic numpad for the calculator, allowing the user to input numbers and
     /// perform basic operations. It is a subclass of [`gtk::Grid`] allowing for easy layout of the buttons.
@@ -18,53 +18,69 @@ mod imp {
     #[template(resource = "/com/nc/calculator/basic_numpad.ui")]
     pub struct BasicNumpad {
         #[template_child]
-        pub button_percent:     TemplateChild<Button>,
+        pub button_exponent:        TemplateChild<ToggleButton>,
         #[template_child]
-        pub button_clear_entry: TemplateChild<Button>,
+        pub button_fraction_view:   TemplateChild<ToggleButton>,
         #[template_child]
-        pub button_clear:       TemplateChild<Button>,
+        pub button_fraction:        TemplateChild<Button>,
         #[template_child]
-        pub button_backspace:   TemplateChild<Button>,
+        pub button_memory_clear:    TemplateChild<Button>,
         #[template_child]
-        pub button_one_over:    TemplateChild<Button>,
+        pub button_memory_recall:   TemplateChild<Button>,
         #[template_child]
-        pub button_square:      TemplateChild<Button>,
+        pub button_memory_add:      TemplateChild<Button>,
         #[template_child]
-        pub button_square_root: TemplateChild<Button>,
+        pub button_memory_subtract: TemplateChild<Button>,
         #[template_child]
-        pub button_divide:      TemplateChild<Button>,
+        pub button_memory_store:    TemplateChild<Button>,
         #[template_child]
-        pub button_seven:       TemplateChild<Button>,
+        pub button_percent:         TemplateChild<Button>,
         #[template_child]
-        pub button_eight:       TemplateChild<Button>,
+        pub button_clear_entry:     TemplateChild<Button>,
         #[template_child]
-        pub button_nine:        TemplateChild<Button>,
+        pub button_clear:           TemplateChild<Button>,
         #[template_child]
-        pub button_multiply:    TemplateChild<Button>,
+        pub button_backspace:       TemplateChild<Button>,
         #[template_child]
-        pub button_four:        TemplateChild<Button>,
+        pub button_one_over:        TemplateChild<Button>,
         #[template_child]
-        pub button_five:        TemplateChild<Button>,
+        pub button_square:          TemplateChild<Button>,
         #[template_child]
-        pub button_six:         TemplateChild<Button>,
+        pub button_square_root:     TemplateChild<Button>,
         #[template_child]
-        pub button_subtract:    TemplateChild<Button>,
+        pub button_divide:          TemplateChild<Button>,
         #[template_child]
-        pub button_one:         TemplateChild<Button>,
+        pub button_seven:           TemplateChild<Button>,
         #[template_child]
-        pub button_two:         TemplateChild<Button>,
+        pub button_eight:           TemplateChild<Button>,
         #[template_child]
-        pub button_three:       TemplateChild<Button>,
+        pub button_nine:            TemplateChild<Button>,
         #[template_child]
-        pub button_add:         TemplateChild<Button>,
+        pub button_multiply:        TemplateChild<Button>,
         #[template_child]
-        pub button_plus_minus:  TemplateChild<Button>,
+        pub button_four:            TemplateChild<Button>,
         #[template_child]
-        pub button_zero:        TemplateChild<Button>,
+        pub button_five:            TemplateChild<Button>,
         #[template_child]
-        pub button_decimal:     TemplateChild<Button>,
+        pub button_six:             TemplateChild<Button>,
         #[template_child]
-        pub button_equals:      TemplateChild<Button>,
+        pub button_subtract:        TemplateChild<Button>,
+        #[template_child]
+        pub button_one:             TemplateChild<Button>,
+        #[template_child]
+        pub button_two:             TemplateChild<Button>,
+        #[template_child]
+        pub button_three:           TemplateChild<Button>,
+        #[template_child]
+        pub button_add:             TemplateChild<Button>,
+        #[template_child]
+        pub button_plus_minus:      TemplateChild<Button>,
+        #[template_child]
+        pub button_zero:            TemplateChild<Button>,
+        #[template_child]
+        pub button_decimal:         TemplateChild<Button>,
+        #[template_child]
+        pub button_equals:          TemplateChild<Button>,
     }
 
     #[glib::object_subclass]
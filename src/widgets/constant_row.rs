@@ -0,0 +1,110 @@
+//! This module contains the implementation of the [`ConstantRow`] widget. It displays a single constant of the
+//! constants library: its symbol, its description, its value with its uncertainty and unit, and the name to use in
+//! expressions. It is a subclass of [`gtk::Box`].
+
+use glib::Object;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::engine::Constant;
+
+mod imp {
+    //! Private implementation details of the [`ConstantRow`] widget.
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, CompositeTemplate, Label};
+
+    /// The `ConstantRow` widget. It displays a single constant of the constants library: its symbol, its description,
+    /// its value with its uncertainty and unit, and the name to use in expressions. It is a subclass of [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/constant_row.ui")]
+    pub struct ConstantRow {
+        #[template_child]
+        pub symbol_label:      TemplateChild<Label>,
+        #[template_child]
+        pub description_label: TemplateChild<Label>,
+        #[template_child]
+        pub value_label:       TemplateChild<Label>,
+        #[template_child]
+        pub name_label:        TemplateChild<Label>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ConstantRow {
+        type ParentType = gtk::Box;
+        type Type = super::ConstantRow;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "ConstantRow";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ConstantRow {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+        }
+    }
+
+    impl WidgetImpl for ConstantRow {}
+
+    impl BoxImpl for ConstantRow {}
+}
+
+glib::wrapper! {
+    /// A [`ConstantRow`] widget. It displays a single constant of the constants library: its symbol, its
+    /// description, its value with its uncertainty and unit, and the name to use in expressions. It is a subclass of
+    /// [`gtk::Box`].
+    pub struct ConstantRow(ObjectSubclass<imp::ConstantRow>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl ConstantRow {
+    /// Creates a new [`ConstantRow`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Shows the given constant in the row. Exact values are marked as such; measured values are followed by their
+    /// standard uncertainty.
+    ///
+    /// # Arguments
+    ///
+    /// * `constant` - The constant to show.
+    pub fn bind(&self, constant: &Constant) {
+        let mut value = constant.value.to_string();
+        if let Some(uncertainty) = constant.uncertainty {
+            value = format!("{value} ± {uncertainty}");
+        }
+        if !constant.unit.is_empty() {
+            value = format!("{value} {}", constant.units());
+        }
+        if constant.exact {
+            value = format!("{value} (exact)");
+        }
+        self.imp().symbol_label.set_label(constant.symbol);
+        self.imp()
+            .description_label
+            .set_label(constant.description);
+        self.imp().value_label.set_label(&value);
+        self.imp().name_label.set_label(constant.name());
+        self.set_tooltip_text(Some(&format!("Insert ‘{}’ into the expression", constant.name())));
+    }
+}
+
+impl Default for ConstantRow {
+    /// The default implementation of the [`ConstantRow`] widget. It creates a new [`ConstantRow`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,202 @@
+//! This module contains the implementation of the [`ConversionPage`] widget. It converts a value between the units of
+//! a category (e.g. from feet to metres). The units are the ones of typed expressions, so both always agree. It is a
+//! subclass of [`gtk::Box`].
+
+use glib::{clone, Object};
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::engine::{Category, Number, Quantity, Unit, Units};
+use crate::utils::{format_number, NumberFormat};
+
+mod imp {
+    //! Private implementation details of the [`ConversionPage`] widget.
+
+    use std::cell::Cell;
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Button, CompositeTemplate, DropDown, Entry, Label};
+
+    use crate::utils::NumberFormat;
+
+    /// The `ConversionPage` widget. It converts a value between the units of a category (e.g. from feet to metres).
+    /// It is a subclass of [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/conversion_page.ui")]
+    pub struct ConversionPage {
+        #[template_child]
+        pub category_dropdown: TemplateChild<DropDown>,
+        #[template_child]
+        pub value_entry:       TemplateChild<Entry>,
+        #[template_child]
+        pub from_dropdown:     TemplateChild<DropDown>,
+        #[template_child]
+        pub result_label:      TemplateChild<Label>,
+        #[template_child]
+        pub to_dropdown:       TemplateChild<DropDown>,
+        #[template_child]
+        pub swap_button:       TemplateChild<Button>,
+        pub number_format:     Cell<NumberFormat>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ConversionPage {
+        type ParentType = gtk::Box;
+        type Type = super::ConversionPage;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "ConversionPage";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ConversionPage {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for ConversionPage {}
+
+    impl BoxImpl for ConversionPage {}
+}
+
+glib::wrapper! {
+    /// A [`ConversionPage`] widget. It converts a value between the units of a category (e.g. from feet to metres).
+    /// It is a subclass of [`gtk::Box`].
+    pub struct ConversionPage(ObjectSubclass<imp::ConversionPage>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl ConversionPage {
+    /// Creates a new [`ConversionPage`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Sets the format of the value and of the result, and converts the value again.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_format` - The format of the numbers.
+    pub fn set_number_format(&self, number_format: NumberFormat) {
+        self.imp().number_format.set(number_format);
+        self.convert();
+    }
+
+    /// Fills the category list and connects the callbacks of the child widgets.
+    fn setup(&self) {
+        let imp = self.imp();
+        let categories = Category::ALL
+            .iter()
+            .map(|category| category.name())
+            .collect::<Vec<&str>>();
+        imp.category_dropdown
+            .set_model(Some(&gtk::StringList::new(&categories)));
+        imp.category_dropdown
+            .connect_selected_notify(clone!(@weak self as page => move |_| {
+                page.update_units();
+            }));
+        imp.from_dropdown
+            .connect_selected_notify(clone!(@weak self as page => move |_| {
+                page.convert();
+            }));
+        imp.to_dropdown
+            .connect_selected_notify(clone!(@weak self as page => move |_| {
+                page.convert();
+            }));
+        imp.value_entry
+            .connect_changed(clone!(@weak self as page => move |_| {
+                page.convert();
+            }));
+        imp.swap_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                let imp = page.imp();
+                let from = imp.from_dropdown.selected();
+                imp.from_dropdown.set_selected(imp.to_dropdown.selected());
+                imp.to_dropdown.set_selected(from);
+            }));
+        self.update_units();
+    }
+
+    /// Returns the selected category.
+    fn category(&self) -> Category {
+        usize::try_from(self.imp().category_dropdown.selected())
+            .ok()
+            .and_then(|index| Category::ALL.get(index).copied())
+            .unwrap_or(Category::Length)
+    }
+
+    /// Returns the unit of the selected category at the position selected in the given dropdown.
+    fn unit(&self, dropdown: &gtk::DropDown) -> Option<&'static Unit> {
+        let index = usize::try_from(dropdown.selected()).ok()?;
+        self.category().units().nth(index)
+    }
+
+    /// Fills the unit lists with the units of the selected category, converting from the first unit to the second.
+    fn update_units(&self) {
+        let imp = self.imp();
+        let units = self
+            .category()
+            .units()
+            .map(|unit| format!("{} ({})", unit.description, unit.symbol()))
+            .collect::<Vec<String>>();
+        let units = units.iter().map(String::as_str).collect::<Vec<&str>>();
+        imp.from_dropdown
+            .set_model(Some(&gtk::StringList::new(&units)));
+        imp.to_dropdown
+            .set_model(Some(&gtk::StringList::new(&units)));
+        imp.from_dropdown.set_selected(0);
+        imp.to_dropdown.set_selected(1);
+        self.convert();
+    }
+
+    /// Converts the value to the selected unit and shows the result, or the error of the conversion.
+    fn convert(&self) {
+        let imp = self.imp();
+        let format = imp.number_format.get();
+        imp.result_label.remove_css_class("error");
+        let (Some(from), Some(to)) = (self.unit(&imp.from_dropdown), self.unit(&imp.to_dropdown))
+        else {
+            imp.result_label.set_label("");
+            return;
+        };
+        let Some(value) = format
+            .locale
+            .parse(&imp.value_entry.text())
+            .and_then(|value| value.parse::<Number>().ok())
+        else {
+            imp.result_label.set_label("");
+            return;
+        };
+        match Quantity::new(value, Units::unit(from)).convert(&Units::unit(to)) {
+            Ok(result) => {
+                imp.result_label
+                    .set_label(&format!("{} {}", format_number(&result.value, &format), to.symbol()));
+            }
+            Err(error) => {
+                imp.result_label.add_css_class("error");
+                imp.result_label.set_label(&error.to_string());
+            }
+        }
+    }
+}
+
+impl Default for ConversionPage {
+    /// The default implementation of the [`ConversionPage`] widget. It creates a new [`ConversionPage`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
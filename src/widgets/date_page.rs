@@ -0,0 +1,382 @@
+//! This module contains the implementation of the [`DatePage`] widget. It computes the difference between two dates in
+//! years, months, weeks, days and business days, and adds or subtracts a period from a date. Dates may be followed by
+//! a time of day and a time zone identifier (e.g. `2024-03-10 09:30 Europe/Paris`); time zones are looked up in the
+//! time zone database of the system, and dates without a time zone are in the local time zone. It is a subclass of
+//! [`gtk::Box`].
+
+use glib::{clone, Object};
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::engine::{Date, Error, Number, Period};
+use crate::utils::{format_clock, NumberFormat};
+
+mod imp {
+    //! Private implementation details of the [`DatePage`] widget.
+
+    use std::cell::Cell;
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Calendar, CheckButton, CompositeTemplate, DropDown, Entry, Label, Popover, SpinButton};
+
+    use crate::utils::NumberFormat;
+
+    /// The `DatePage` widget. It computes the difference between two dates and adds or subtracts a period from a
+    /// date. It is a subclass of [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/date_page.ui")]
+    pub struct DatePage {
+        #[template_child]
+        pub from_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub from_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub from_calendar: TemplateChild<Calendar>,
+        #[template_child]
+        pub to_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub to_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub to_calendar: TemplateChild<Calendar>,
+        #[template_child]
+        pub difference_label: TemplateChild<Label>,
+        #[template_child]
+        pub start_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub start_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub start_calendar: TemplateChild<Calendar>,
+        #[template_child]
+        pub operation_dropdown: TemplateChild<DropDown>,
+        #[template_child]
+        pub years_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub months_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub weeks_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub days_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub business_check: TemplateChild<CheckButton>,
+        #[template_child]
+        pub sum_label: TemplateChild<Label>,
+        pub number_format: Cell<NumberFormat>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for DatePage {
+        type ParentType = gtk::Box;
+        type Type = super::DatePage;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "DatePage";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for DatePage {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for DatePage {}
+
+    impl BoxImpl for DatePage {}
+}
+
+glib::wrapper! {
+    /// A [`DatePage`] widget. It computes the difference between two dates and adds or subtracts a period from a
+    /// date. It is a subclass of [`gtk::Box`].
+    pub struct DatePage(ObjectSubclass<imp::DatePage>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+/// A date typed in an entry, with an optional time of day and time zone.
+struct Moment {
+    /// The calendar date.
+    date:     Date,
+    /// The date and time in its time zone. The time is midnight if only a date was typed.
+    time:     glib::DateTime,
+    /// Whether a time of day or a time zone was typed.
+    has_time: bool,
+}
+
+impl Moment {
+    /// Parses a date followed by an optional time of day and an optional time zone identifier (e.g. `2024-03-10`,
+    /// `2024-03-10 09:30` or `2024-03-10 09:30:15 America/New_York`). Time zones are looked up in the time zone
+    /// database of the system; without one, the local time zone is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to parse.
+    ///
+    /// # Returns
+    ///
+    /// The moment, or `None` if the text is not a valid date, time or time zone.
+    fn parse(text: &str) -> Option<Self> {
+        let mut words = text.split_whitespace();
+        let date = words.next()?.parse::<Date>().ok()?;
+        let (mut hour, mut minute, mut second) = (0, 0, 0.0);
+        let mut zone = None;
+        for word in words {
+            if zone.is_none() && word.starts_with(|c: char| c.is_ascii_digit()) {
+                let mut parts = word.split(':');
+                hour = parts.next()?.parse::<i32>().ok()?;
+                minute = parts.next()?.parse::<i32>().ok()?;
+                second = parts.next().map_or(Some(0.0), |second| second.parse::<f64>().ok())?;
+                if parts.next().is_some() {
+                    return None;
+                }
+            }
+            else if zone.is_none() {
+                zone = Some(time_zone(word)?);
+            }
+            else {
+                return None;
+            }
+        }
+        let has_time = text.split_whitespace().count() > 1;
+        let time = glib::DateTime::new(
+            &zone.unwrap_or_else(glib::TimeZone::local),
+            date.year(),
+            date.month() as i32,
+            date.day() as i32,
+            hour,
+            minute,
+            second,
+        )
+        .ok()?;
+        Some(Self { date, time, has_time })
+    }
+}
+
+impl DatePage {
+    /// Creates a new [`DatePage`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Sets the format of the elapsed times, and computes the results again.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_format` - The format of the numbers.
+    pub fn set_number_format(&self, number_format: NumberFormat) {
+        self.imp().number_format.set(number_format);
+        self.update_difference();
+        self.update_sum();
+    }
+
+    /// Fills in today's date and connects the callbacks of the child widgets.
+    fn setup(&self) {
+        let imp = self.imp();
+        let today = glib::DateTime::now_local()
+            .ok()
+            .and_then(|now| Date::new(now.year(), now.month() as u32, now.day_of_month() as u32))
+            .map(|today| today.to_string())
+            .unwrap_or_default();
+        for (entry, popover, calendar) in [
+            (imp.from_entry.get(), imp.from_popover.get(), imp.from_calendar.get()),
+            (imp.to_entry.get(), imp.to_popover.get(), imp.to_calendar.get()),
+            (imp.start_entry.get(), imp.start_popover.get(), imp.start_calendar.get()),
+        ] {
+            entry.set_text(&today);
+            popover.connect_show(clone!(@weak entry, @weak calendar => move |_| {
+                if let Some(moment) = Moment::parse(&entry.text()) {
+                    calendar.select_day(&moment.time);
+                }
+            }));
+            calendar.connect_day_selected(clone!(@weak entry => move |calendar| {
+                let Some(date) = Date::new(calendar.year(), calendar.month() as u32 + 1, calendar.day() as u32)
+                else {
+                    return;
+                };
+                let text = entry.text();
+                let (current, rest) = text.trim_start().split_once(' ').unwrap_or((text.as_str(), ""));
+                if current != date.to_string() {
+                    let text = format!("{} {}", date, rest);
+                    entry.set_text(text.trim_end());
+                }
+            }));
+        }
+
+        imp.from_entry
+            .connect_changed(clone!(@weak self as page => move |_| {
+                page.update_difference();
+            }));
+        imp.to_entry
+            .connect_changed(clone!(@weak self as page => move |_| {
+                page.update_difference();
+            }));
+        imp.start_entry
+            .connect_changed(clone!(@weak self as page => move |_| {
+                page.update_sum();
+            }));
+        imp.operation_dropdown
+            .connect_selected_notify(clone!(@weak self as page => move |_| {
+                page.update_sum();
+            }));
+        for spin in [&imp.years_spin, &imp.months_spin, &imp.weeks_spin, &imp.days_spin] {
+            spin.connect_value_changed(clone!(@weak self as page => move |_| {
+                page.update_sum();
+            }));
+        }
+        imp.business_check
+            .connect_toggled(clone!(@weak self as page => move |_| {
+                page.update_sum();
+            }));
+        self.update_difference();
+        self.update_sum();
+    }
+
+    /// Shows the difference between the two dates: in years, months and days, in days, in weeks and days and in
+    /// business days. If a time of day or a time zone was typed, the exact elapsed time is also shown.
+    fn update_difference(&self) {
+        let imp = self.imp();
+        let label = &imp.difference_label;
+        let (Some(from), Some(to)) = (Moment::parse(&imp.from_entry.text()), Moment::parse(&imp.to_entry.text()))
+        else {
+            label.add_css_class("error");
+            label.set_label("Invalid date");
+            return;
+        };
+        label.remove_css_class("error");
+
+        let days = from.date.days_until(to.date);
+        let sign = if days < 0 { "-" } else { "" };
+        let mut lines = vec![
+            format_period(&from.date.period_until(to.date)),
+            format!("{}{}", sign, count(days.abs(), "day", "days")),
+            format!(
+                "{}{}, {}",
+                sign,
+                count(days.abs() / 7, "week", "weeks"),
+                count(days.abs() % 7, "day", "days")
+            ),
+            format!(
+                "{}{}",
+                sign,
+                count(from.date.business_days_until(to.date).abs(), "business day", "business days")
+            ),
+        ];
+        if from.has_time || to.has_time {
+            let elapsed = Number::from_integer(to.time.difference(&from.time).as_microseconds())
+                .div(&Number::from_integer(1_000_000))
+                .map(|seconds| format_clock(&seconds, &imp.number_format.get()))
+                .unwrap_or_default();
+            lines.push(format!("Elapsed time: {}", elapsed));
+        }
+        label.set_label(&lines.join("\n"));
+    }
+
+    /// Shows the date after adding or subtracting the period to the start date, with its day of the week.
+    fn update_sum(&self) {
+        let imp = self.imp();
+        let label = &imp.sum_label;
+        match self.sum() {
+            Ok((start, date)) => {
+                label.remove_css_class("error");
+                let time = glib::DateTime::new(
+                    &start.time.timezone(),
+                    date.year(),
+                    date.month() as i32,
+                    date.day() as i32,
+                    start.time.hour(),
+                    start.time.minute(),
+                    start.time.seconds(),
+                );
+                let pattern = if start.has_time { "%A, %Y-%m-%d %H:%M:%S %Z" } else { "%A, %Y-%m-%d" };
+                let text = time
+                    .and_then(|time| time.format(pattern))
+                    .map(String::from)
+                    .unwrap_or_else(|_| date.to_string());
+                label.set_label(&text);
+            }
+            Err(error) => {
+                label.add_css_class("error");
+                label.set_label(&error);
+            }
+        }
+    }
+
+    /// Adds or subtracts the period to the start date. Years and months are added first, then weeks and days.
+    ///
+    /// # Returns
+    ///
+    /// The start date and the resulting date, or the error message to show.
+    fn sum(&self) -> Result<(Moment, Date), String> {
+        let imp = self.imp();
+        let start = Moment::parse(&imp.start_entry.text()).ok_or_else(|| "Invalid date".to_string())?;
+        let sign = if imp.operation_dropdown.selected() == 1 { -1 } else { 1 };
+        let value = |spin: &gtk::SpinButton| sign * i64::from(spin.value_as_int());
+        let date = start
+            .date
+            .add_months(value(&imp.years_spin) * 12 + value(&imp.months_spin))
+            .and_then(|date| date.add_days(value(&imp.weeks_spin) * 7))
+            .and_then(|date| {
+                if imp.business_check.is_active() {
+                    date.add_business_days(value(&imp.days_spin))
+                }
+                else {
+                    date.add_days(value(&imp.days_spin))
+                }
+            })
+            .map_err(|error| {
+                match error {
+                    Error::Overflow => "The date is outside of the years 1 to 9999".to_string(),
+                    error => error.to_string(),
+                }
+            })?;
+        Ok((start, date))
+    }
+}
+
+impl Default for DatePage {
+    /// The default implementation of the [`DatePage`] widget. It creates a new [`DatePage`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the time zone with the given identifier (e.g. `Europe/Paris`, `UTC` or `+02:00`) from the time zone
+/// database of the system, or `None` if there is no such time zone.
+fn time_zone(identifier: &str) -> Option<glib::TimeZone> {
+    // Unknown identifiers give UTC.
+    #[allow(deprecated)]
+    let zone = glib::TimeZone::new(Some(identifier));
+    (zone.identifier() != "UTC" || identifier == "UTC").then_some(zone)
+}
+
+/// Formats a period in years, months and days (e.g. `1 year, 2 months, 3 days`), leaving out the years and months if
+/// they are zero.
+fn format_period(period: &Period) -> String {
+    let mut parts = Vec::new();
+    if period.years != 0 {
+        parts.push(count(i64::from(period.years), "year", "years"));
+    }
+    if period.months != 0 {
+        parts.push(count(i64::from(period.months), "month", "months"));
+    }
+    if period.days != 0 || parts.is_empty() {
+        parts.push(count(period.days, "day", "days"));
+    }
+    format!("{}{}", if period.negative { "-" } else { "" }, parts.join(", "))
+}
+
+/// Formats a count with the singular or plural of its noun (e.g. `1 day` or `3 days`).
+fn count(n: i64, singular: &str, plural: &str) -> String {
+    format!("{} {}", n, if n == 1 { singular } else { plural })
+}
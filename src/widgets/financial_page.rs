@@ -0,0 +1,375 @@
+//! This module contains the implementation of the [`FinancialPage`] widget. It solves time value of money problems
+//! (e.g. the monthly payment of a loan) for any of the number of periods, the interest rate, the present value, the
+//! payment and the future value, and shows the amortization schedule, which can be exported as a CSV file. It is a
+//! subclass of [`gtk::Box`].
+
+use glib::{clone, Object};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::engine::{AmortizationRow, Number, Tvm, TvmValue};
+use crate::utils::{format_number, Notation, NumberFormat};
+
+/// The number of decimal places of amounts of money.
+const CENTS: usize = 2;
+
+/// The width of the columns of amounts of money in the amortization schedule, in characters.
+const COLUMN_WIDTH: usize = 15;
+
+mod imp {
+    //! Private implementation details of the [`FinancialPage`] widget.
+
+    use std::cell::Cell;
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Button, CheckButton, CompositeTemplate, Entry, Label, SpinButton};
+
+    use crate::utils::NumberFormat;
+
+    /// The `FinancialPage` widget. It solves time value of money problems and shows their amortization schedule.
+    /// It is a subclass of [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/financial_page.ui")]
+    pub struct FinancialPage {
+        #[template_child]
+        pub periods_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub periods_button: TemplateChild<Button>,
+        #[template_child]
+        pub rate_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub rate_button: TemplateChild<Button>,
+        #[template_child]
+        pub present_value_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub present_value_button: TemplateChild<Button>,
+        #[template_child]
+        pub payment_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub payment_button: TemplateChild<Button>,
+        #[template_child]
+        pub future_value_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub future_value_button: TemplateChild<Button>,
+        #[template_child]
+        pub periods_per_year_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub due_check: TemplateChild<CheckButton>,
+        #[template_child]
+        pub status_label: TemplateChild<Label>,
+        #[template_child]
+        pub export_button: TemplateChild<Button>,
+        #[template_child]
+        pub summary_label: TemplateChild<Label>,
+        #[template_child]
+        pub schedule_label: TemplateChild<Label>,
+        pub number_format: Cell<NumberFormat>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FinancialPage {
+        type ParentType = gtk::Box;
+        type Type = super::FinancialPage;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "FinancialPage";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for FinancialPage {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for FinancialPage {}
+
+    impl BoxImpl for FinancialPage {}
+}
+
+glib::wrapper! {
+    /// A [`FinancialPage`] widget. It solves time value of money problems and shows their amortization schedule.
+    /// It is a subclass of [`gtk::Box`].
+    pub struct FinancialPage(ObjectSubclass<imp::FinancialPage>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl FinancialPage {
+    /// Creates a new [`FinancialPage`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Sets the format of the numbers of the amortization schedule, and shows it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_format` - The format of the numbers.
+    pub fn set_number_format(&self, number_format: NumberFormat) {
+        self.imp().number_format.set(number_format);
+        self.update_schedule();
+    }
+
+    /// Connects the callbacks of the child widgets.
+    fn setup(&self) {
+        let imp = self.imp();
+        for (value, button) in [
+            (TvmValue::Periods, &imp.periods_button),
+            (TvmValue::Rate, &imp.rate_button),
+            (TvmValue::PresentValue, &imp.present_value_button),
+            (TvmValue::Payment, &imp.payment_button),
+            (TvmValue::FutureValue, &imp.future_value_button),
+        ] {
+            button.connect_clicked(clone!(@weak self as page => move |_| {
+                page.compute(value);
+            }));
+        }
+        for entry in [
+            &imp.periods_entry,
+            &imp.rate_entry,
+            &imp.present_value_entry,
+            &imp.payment_entry,
+            &imp.future_value_entry,
+        ] {
+            entry.connect_changed(clone!(@weak self as page => move |_| {
+                page.update_schedule();
+            }));
+        }
+        imp.periods_per_year_spin
+            .connect_value_changed(clone!(@weak self as page => move |_| {
+                page.update_schedule();
+            }));
+        imp.due_check
+            .connect_toggled(clone!(@weak self as page => move |_| {
+                page.update_schedule();
+            }));
+        imp.export_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.export_schedule();
+            }));
+        self.update_schedule();
+    }
+
+    /// Returns the entry of one of the values of the problem.
+    fn entry(&self, value: TvmValue) -> &gtk::Entry {
+        let imp = self.imp();
+        match value {
+            TvmValue::Periods => &imp.periods_entry,
+            TvmValue::Rate => &imp.rate_entry,
+            TvmValue::PresentValue => &imp.present_value_entry,
+            TvmValue::Payment => &imp.payment_entry,
+            TvmValue::FutureValue => &imp.future_value_entry,
+        }
+    }
+
+    /// Returns the value typed in the entry of one of the values of the problem. An empty entry is zero.
+    ///
+    /// # Returns
+    ///
+    /// The value, or `None` if the entry is not a number.
+    fn value(&self, value: TvmValue) -> Option<Number> {
+        let text = self.entry(value).text();
+        if text.trim().is_empty() {
+            return Some(Number::zero());
+        }
+        self.imp()
+            .number_format
+            .get()
+            .locale
+            .parse(&text)
+            .and_then(|text| text.parse::<Number>().ok())
+    }
+
+    /// Returns the problem typed in the page.
+    ///
+    /// # Returns
+    ///
+    /// The problem, or `None` if one of the values is not a number.
+    fn tvm(&self) -> Option<Tvm> {
+        let imp = self.imp();
+        Some(Tvm {
+            periods:          self.value(TvmValue::Periods)?,
+            rate:             self.value(TvmValue::Rate)?,
+            present_value:    self.value(TvmValue::PresentValue)?,
+            payment:          self.value(TvmValue::Payment)?,
+            future_value:     self.value(TvmValue::FutureValue)?,
+            periods_per_year: u32::try_from(imp.periods_per_year_spin.value_as_int()).unwrap_or(1),
+            due:              imp.due_check.is_active(),
+        })
+    }
+
+    /// Solves the problem for one of its values from the others and shows the result in its entry. Amounts of money
+    /// are rounded to cents with banker's rounding.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to solve for.
+    fn compute(&self, value: TvmValue) {
+        let imp = self.imp();
+        let Some(tvm) = self.tvm()
+        else {
+            self.show_status("Invalid number");
+            return;
+        };
+        let result = tvm.solve(value).and_then(|result| {
+            match value {
+                TvmValue::Periods | TvmValue::Rate => Ok(result),
+                _ => result.round_half_even(CENTS),
+            }
+        });
+        match result {
+            Ok(result) => {
+                let format = NumberFormat {
+                    notation: match value {
+                        TvmValue::Periods | TvmValue::Rate => Notation::Normal,
+                        _ => Notation::Fixed,
+                    },
+                    precision: CENTS,
+                    grouping: false,
+                    ..imp.number_format.get()
+                };
+                self.show_status("");
+                self.entry(value)
+                    .set_text(&format_number(&result, &format));
+            }
+            Err(error) => self.show_status(&format!("No solution: {}", error)),
+        }
+    }
+
+    /// Shows a message below the values, or hides it if the message is empty.
+    fn show_status(&self, message: &str) {
+        let status_label = &self.imp().status_label;
+        status_label.set_label(message);
+        status_label.set_visible(!message.is_empty());
+    }
+
+    /// Returns the amortization schedule of the problem typed in the page, or `None` if it has none (e.g. the number
+    /// of periods is not an integer).
+    fn schedule(&self) -> Option<Vec<AmortizationRow>> {
+        self.tvm()?.amortization().ok()
+    }
+
+    /// Shows the amortization schedule of the problem typed in the page and its totals.
+    fn update_schedule(&self) {
+        let imp = self.imp();
+        let Some(rows) = self.schedule()
+        else {
+            imp.summary_label
+                .set_label("The schedule needs a whole number of periods N");
+            imp.schedule_label.set_label("");
+            imp.export_button.set_sensitive(false);
+            return;
+        };
+        let format = NumberFormat {
+            notation: Notation::Fixed,
+            precision: CENTS,
+            ..imp.number_format.get()
+        };
+        let amount = |number: &Number| format!("{:>width$}", format_number(number, &format), width = COLUMN_WIDTH);
+        let mut lines = vec![format!(
+            "{:>6}{:>width$}{:>width$}{:>width$}{:>width$}",
+            "Period",
+            "Payment",
+            "Interest",
+            "Principal",
+            "Balance",
+            width = COLUMN_WIDTH
+        )];
+        lines.extend(rows.iter().map(|row| {
+            format!(
+                "{:>6}{}{}{}{}",
+                row.period,
+                amount(&row.payment),
+                amount(&row.interest),
+                amount(&row.principal),
+                amount(&row.balance)
+            )
+        }));
+        let total = |field: fn(&AmortizationRow) -> &Number| {
+            rows.iter()
+                .try_fold(Number::zero(), |total, row| total.add(field(row)))
+                .map(|total| format_number(&total, &format))
+                .unwrap_or_default()
+        };
+        imp.summary_label.set_label(&format!(
+            "Total payments: {} · Total interest: {}",
+            total(|row| &row.payment),
+            total(|row| &row.interest)
+        ));
+        imp.schedule_label.set_label(&lines.join("\n"));
+        imp.export_button.set_sensitive(true);
+    }
+
+    /// Asks for a file and saves the amortization schedule in it as comma-separated values, with a header row and
+    /// amounts of money written with a `.` decimal point and without thousands separators.
+    fn export_schedule(&self) {
+        let Some(rows) = self.schedule()
+        else {
+            return;
+        };
+        let mut csv = String::from("period,payment,interest,principal,balance\n");
+        for row in &rows {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                row.period,
+                csv_amount(&row.payment),
+                csv_amount(&row.interest),
+                csv_amount(&row.principal),
+                csv_amount(&row.balance)
+            ));
+        }
+        let dialog = gtk::FileDialog::builder()
+            .title("Export Amortization Schedule")
+            .initial_name("amortization.csv")
+            .modal(true)
+            .build();
+        let window = self.root().and_downcast::<gtk::Window>();
+        dialog.save(
+            window.as_ref(),
+            gio::Cancellable::NONE,
+            clone!(@weak self as page => move |result| {
+                let Ok(file) = result
+                else {
+                    return;
+                };
+                if let Err(error) = file.replace_contents(
+                    csv.as_bytes(),
+                    None,
+                    false,
+                    gio::FileCreateFlags::REPLACE_DESTINATION,
+                    gio::Cancellable::NONE,
+                ) {
+                    page.show_status(&format!("Could not save the schedule: {}", error));
+                }
+            }),
+        );
+    }
+}
+
+impl Default for FinancialPage {
+    /// The default implementation of the [`FinancialPage`] widget. It creates a new [`FinancialPage`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Formats an amount of money for a CSV file, with two decimal places and a `.` decimal point (e.g. `-1199.10`).
+fn csv_amount(number: &Number) -> String {
+    match number.to_fixed_digits(CENTS) {
+        Some((negative, whole, fraction)) => format!("{}{}.{}", if negative { "-" } else { "" }, whole, fraction),
+        None => String::new(),
+    }
+}
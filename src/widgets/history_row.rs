@@ -0,0 +1,169 @@
+//! This module contains the implementation of the [`HistoryRow`] widget. It displays a single calculation of the
+//! history: the evaluated expression and its result with its units, a note that can be edited, and a button pinning
+//! it so that it is never removed from the history. It is a subclass of [`gtk::Box`].
+
+use glib::Object;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::engine::Uncertain;
+use crate::history_object::HistoryObject;
+use crate::utils::{format_clock, format_complex, format_interval, format_uncertain, NumberFormat};
+
+mod imp {
+    //! Private implementation details of the [`HistoryRow`] widget.
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, CompositeTemplate, Entry, Label, ToggleButton};
+
+    /// The `HistoryRow` widget. It displays a single calculation of the history: the evaluated expression and its
+    /// result. It is a subclass of [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/history_row.ui")]
+    pub struct HistoryRow {
+        #[template_child]
+        pub pin_button:       TemplateChild<ToggleButton>,
+        #[template_child]
+        pub expression_label: TemplateChild<Label>,
+        #[template_child]
+        pub result_label:     TemplateChild<Label>,
+        #[template_child]
+        pub annotation_entry: TemplateChild<Entry>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HistoryRow {
+        type ParentType = gtk::Box;
+        type Type = super::HistoryRow;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "HistoryRow";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HistoryRow {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+        }
+    }
+
+    impl WidgetImpl for HistoryRow {}
+
+    impl BoxImpl for HistoryRow {}
+}
+
+glib::wrapper! {
+    /// A [`HistoryRow`] widget. It displays a single calculation of the history: the evaluated expression and its result. It is a subclass of [`gtk::Box`].
+    pub struct HistoryRow(ObjectSubclass<imp::HistoryRow>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl HistoryRow {
+    /// Creates a new [`HistoryRow`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Shows the given calculation in the row, and binds the pin button to whether it is pinned and the note entry to
+    /// its annotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `history_object` - The calculation to show.
+    /// * `format` - The format of the result. Its locale is also used for the numbers of the expression.
+    pub fn bind(&self, history_object: &HistoryObject, format: &NumberFormat) {
+        self.imp()
+            .expression_label
+            .set_label(&format.locale.localize_expression(&history_object.expression()));
+        self.imp()
+            .result_label
+            .set_label(&Self::result_text(history_object, format));
+        history_object
+            .bind_property("pinned", &*self.imp().pin_button, "active")
+            .bidirectional()
+            .sync_create()
+            .build();
+        history_object
+            .bind_property("annotation", &*self.imp().annotation_entry, "text")
+            .bidirectional()
+            .sync_create()
+            .build();
+    }
+
+    /// Returns the result of a calculation as the row shows it, with its units.
+    ///
+    /// # Arguments
+    ///
+    /// * `history_object` - The calculation.
+    /// * `format` - The format of the result.
+    pub fn result_text(history_object: &HistoryObject, format: &NumberFormat) -> String {
+        let units = history_object.units();
+        let result = history_object.result();
+        let mut result = if let Some(factorization) = history_object.factorization() {
+            factorization.to_string()
+        }
+        else if let Some(uncertainty) = history_object.uncertainty() {
+            format_uncertain(
+                &Uncertain {
+                    value: result.re,
+                    uncertainty,
+                },
+                format,
+            )
+        }
+        else if let Some(interval) = history_object.interval() {
+            format_interval(&interval, format)
+        }
+        else if units.is_clock() {
+            format_clock(&result.re, format)
+        }
+        else {
+            format_complex(&result, format)
+        };
+        if !units.is_empty() && !units.is_clock() {
+            result = format!("{} {}", result, units);
+        }
+        result
+    }
+
+    /// Returns the header of the calculations of a day: `Today`, `Yesterday` or the date (e.g. `Monday 19 October
+    /// 2026`).
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The local time of a calculation of the day.
+    pub fn day_label(time: &glib::DateTime) -> String {
+        let day = (time.year(), time.day_of_year());
+        let today = glib::DateTime::now_local().ok();
+        let yesterday = today.as_ref().and_then(|today| today.add_days(-1).ok());
+        if today.is_some_and(|today| (today.year(), today.day_of_year()) == day) {
+            "Today".to_string()
+        }
+        else if yesterday.is_some_and(|yesterday| (yesterday.year(), yesterday.day_of_year()) == day) {
+            "Yesterday".to_string()
+        }
+        else {
+            time.format("%A %-d %B %Y")
+                .map(|label| label.to_string())
+                .unwrap_or_default()
+        }
+    }
+}
+
+impl Default for HistoryRow {
+    /// The default implementation of the [`HistoryRow`] widget. It creates a new [`HistoryRow`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,462 @@
+//! This module contains the implementation of the [`MatrixPage`] widget. It edits two matrices, A and B, in grids of
+//! entries and shows the results of the matrix operations on them: the determinant, the inverse, the transpose, the
+//! rank and the eigenvalues of A, the sum, the difference and the product of A and B, and the solution of the linear
+//! system A X = B. It also shows the matrices resulting from typed expressions. It is a subclass of [`gtk::Box`].
+
+use glib::{clone, Object};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{glib, Entry, Grid, SpinButton};
+
+use crate::engine::{Error, Matrix, Number, Root, Statement};
+use crate::utils::{format_number, NumberFormat};
+use crate::widgets::Window;
+
+/// The width of the entries of the grids, in characters.
+const ENTRY_WIDTH: i32 = 6;
+
+mod imp {
+    //! Private implementation details of the [`MatrixPage`] widget.
+
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Button, CompositeTemplate, Grid, Label, SpinButton};
+
+    use crate::utils::NumberFormat;
+
+    /// The `MatrixPage` widget. It edits two matrices and shows the results of the matrix operations on them. It is a
+    /// subclass of [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/matrix_page.ui")]
+    pub struct MatrixPage {
+        #[template_child]
+        pub a_rows_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub a_columns_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub a_grid: TemplateChild<Grid>,
+        #[template_child]
+        pub b_rows_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub b_columns_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub b_grid: TemplateChild<Grid>,
+        #[template_child]
+        pub determinant_button: TemplateChild<Button>,
+        #[template_child]
+        pub inverse_button: TemplateChild<Button>,
+        #[template_child]
+        pub transpose_button: TemplateChild<Button>,
+        #[template_child]
+        pub rank_button: TemplateChild<Button>,
+        #[template_child]
+        pub eigenvalues_button: TemplateChild<Button>,
+        #[template_child]
+        pub add_button: TemplateChild<Button>,
+        #[template_child]
+        pub subtract_button: TemplateChild<Button>,
+        #[template_child]
+        pub multiply_button: TemplateChild<Button>,
+        #[template_child]
+        pub solve_button: TemplateChild<Button>,
+        #[template_child]
+        pub status_label: TemplateChild<Label>,
+        #[template_child]
+        pub expression_label: TemplateChild<Label>,
+        #[template_child]
+        pub use_button: TemplateChild<Button>,
+        #[template_child]
+        pub result_label: TemplateChild<Label>,
+        pub result: RefCell<Option<super::MatrixResult>>,
+        pub number_format: Cell<NumberFormat>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for MatrixPage {
+        type ParentType = gtk::Box;
+        type Type = super::MatrixPage;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "MatrixPage";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for MatrixPage {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for MatrixPage {}
+
+    impl BoxImpl for MatrixPage {}
+}
+
+glib::wrapper! {
+    /// A [`MatrixPage`] widget. It edits two matrices and shows the results of the matrix operations on them. It is a
+    /// subclass of [`gtk::Box`].
+    pub struct MatrixPage(ObjectSubclass<imp::MatrixPage>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+/// The result shown by the page.
+pub enum MatrixResult {
+    /// A number (e.g. a determinant).
+    Number(Number),
+    /// A matrix (e.g. an inverse).
+    Matrix(Matrix),
+    /// The eigenvalues of a matrix, real and complex.
+    Eigenvalues(Vec<Root>),
+}
+
+/// An operation of the buttons of the page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operation {
+    Determinant,
+    Inverse,
+    Transpose,
+    Rank,
+    Eigenvalues,
+    Add,
+    Subtract,
+    Multiply,
+    Solve,
+}
+
+impl Operation {
+    /// Returns the operation as shown above its result.
+    fn label(self) -> &'static str {
+        match self {
+            Operation::Determinant => "det A",
+            Operation::Inverse => "A⁻¹",
+            Operation::Transpose => "Aᵀ",
+            Operation::Rank => "rank A",
+            Operation::Eigenvalues => "Eigenvalues of A",
+            Operation::Add => "A + B",
+            Operation::Subtract => "A − B",
+            Operation::Multiply => "A × B",
+            Operation::Solve => "X such that A X = B",
+        }
+    }
+
+    /// Returns `true` if the operation takes B as well as A.
+    fn uses_b(self) -> bool {
+        matches!(
+            self,
+            Operation::Add | Operation::Subtract | Operation::Multiply | Operation::Solve
+        )
+    }
+
+    /// Applies the operation to A and B.
+    fn apply(self, a: &Matrix, b: &Matrix) -> Result<MatrixResult, Error> {
+        let result = match self {
+            Operation::Determinant => MatrixResult::Number(a.determinant()?),
+            Operation::Inverse => MatrixResult::Matrix(a.inverse()?),
+            Operation::Transpose => MatrixResult::Matrix(a.transpose()),
+            Operation::Rank => MatrixResult::Number(Number::from_integer(a.rank()? as i64)),
+            Operation::Eigenvalues => MatrixResult::Eigenvalues(a.eigenvalues()?),
+            Operation::Add => MatrixResult::Matrix(a.add(b)?),
+            Operation::Subtract => MatrixResult::Matrix(a.sub(b)?),
+            Operation::Multiply => MatrixResult::Matrix(a.mul(b)?),
+            Operation::Solve => MatrixResult::Matrix(a.solve(b)?),
+        };
+        Ok(result)
+    }
+}
+
+impl MatrixPage {
+    /// Creates a new [`MatrixPage`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Sets the format of the result, and shows it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_format` - The format of the numbers.
+    pub fn set_number_format(&self, number_format: NumberFormat) {
+        self.imp().number_format.set(number_format);
+        self.update_result();
+    }
+
+    /// Shows a matrix computed elsewhere, such as the value of a typed expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - The expression the matrix is the value of, shown above it.
+    /// * `matrix` - The matrix to show.
+    pub fn show_result(&self, expression: &str, matrix: &Matrix) {
+        self.show_status("");
+        self.imp().expression_label.set_label(expression);
+        self.imp()
+            .result
+            .replace(Some(MatrixResult::Matrix(matrix.clone())));
+        self.update_result();
+    }
+
+    /// Connects the callbacks of the child widgets and creates the entries of both matrices.
+    fn setup(&self) {
+        let imp = self.imp();
+        for (_, rows_spin, columns_spin, grid) in self.editors() {
+            for spin in [rows_spin, columns_spin] {
+                spin.connect_value_changed(
+                    clone!(@weak self as page, @weak rows_spin, @weak columns_spin, @weak grid => move |_| {
+                        page.resize_grid(&grid, &rows_spin, &columns_spin);
+                    }),
+                );
+            }
+            self.resize_grid(grid, rows_spin, columns_spin);
+        }
+        for (button, operation) in [
+            (&imp.determinant_button, Operation::Determinant),
+            (&imp.inverse_button, Operation::Inverse),
+            (&imp.transpose_button, Operation::Transpose),
+            (&imp.rank_button, Operation::Rank),
+            (&imp.eigenvalues_button, Operation::Eigenvalues),
+            (&imp.add_button, Operation::Add),
+            (&imp.subtract_button, Operation::Subtract),
+            (&imp.multiply_button, Operation::Multiply),
+            (&imp.solve_button, Operation::Solve),
+        ] {
+            button.connect_clicked(clone!(@weak self as page => move |_| {
+                page.compute(operation);
+            }));
+        }
+        imp.use_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.use_as_a();
+            }));
+    }
+
+    /// Returns the name, the spin buttons of the number of rows and columns, and the grid of entries of A and B.
+    fn editors(&self) -> [(&'static str, &SpinButton, &SpinButton, &Grid); 2] {
+        let imp = self.imp();
+        [
+            ("A", &imp.a_rows_spin, &imp.a_columns_spin, &imp.a_grid),
+            ("B", &imp.b_rows_spin, &imp.b_columns_spin, &imp.b_grid),
+        ]
+    }
+
+    /// Adds or removes entries so that a grid has the number of rows and columns of its spin buttons. The entries
+    /// kept keep their text.
+    fn resize_grid(&self, grid: &Grid, rows_spin: &SpinButton, columns_spin: &SpinButton) {
+        let (rows, columns) = (rows_spin.value_as_int(), columns_spin.value_as_int());
+        let mut child = grid.first_child();
+        while let Some(widget) = child {
+            child = widget.next_sibling();
+            let (column, row, _, _) = grid.query_child(&widget);
+            if row >= rows || column >= columns {
+                grid.remove(&widget);
+            }
+        }
+        for row in 0..rows {
+            for column in 0..columns {
+                if grid.child_at(column, row).is_none() {
+                    let entry = Entry::builder()
+                        .width_chars(ENTRY_WIDTH)
+                        .xalign(1.0)
+                        .placeholder_text("0")
+                        .build();
+                    grid.attach(&entry, column, row, 1, 1);
+                }
+            }
+        }
+    }
+
+    /// Evaluates the entries of a matrix with the context of typed expressions in the window. Empty entries are zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The window the page is in.
+    /// * `index` - The index of the matrix: `0` for A and `1` for B.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Syntax`] if an entry is not a valid expression, with its position in the message.
+    /// * The error of the evaluation of an entry, with its position in the message.
+    fn read_matrix(&self, window: &Window, index: usize) -> Result<Matrix, Error> {
+        let (name, rows_spin, columns_spin, grid) = self.editors()[index];
+        let (rows, columns) = (rows_spin.value_as_int(), columns_spin.value_as_int());
+        window.with_context(|context| {
+            (0..rows)
+                .map(|row| {
+                    (0..columns)
+                        .map(|column| {
+                            let text = grid
+                                .child_at(column, row)
+                                .and_downcast::<Entry>()
+                                .map(|entry| entry.text().to_string())
+                                .unwrap_or_default();
+                            if text.trim().is_empty() {
+                                return Ok(Number::zero());
+                            }
+                            let number = match Statement::parse(&text) {
+                                Ok(Statement::Expression(expression)) => {
+                                    expression.evaluate(context).and_then(|value| value.into_number())
+                                }
+                                Ok(_) => Err(Error::Syntax(format!("Expected a number, not ‘{}’", text))),
+                                Err(error) => Err(error),
+                            };
+                            number.map_err(|error| {
+                                Error::Syntax(format!("{}, row {}, column {}: {}", name, row + 1, column + 1, error))
+                            })
+                        })
+                        .collect::<Result<Vec<Number>, Error>>()
+                })
+                .collect::<Result<Vec<Vec<Number>>, Error>>()
+                .and_then(Matrix::new)
+        })
+    }
+
+    /// Applies an operation to the matrices of the page and shows its result.
+    fn compute(&self, operation: Operation) {
+        let imp = self.imp();
+        let Some(window) = self.root().and_downcast::<Window>()
+        else {
+            return;
+        };
+        let result = self.read_matrix(&window, 0).and_then(|a| {
+            let b = if operation.uses_b() {
+                self.read_matrix(&window, 1)?
+            }
+            else {
+                a.clone()
+            };
+            operation.apply(&a, &b)
+        });
+        match result {
+            Ok(result) => {
+                self.show_status("");
+                imp.expression_label.set_label(operation.label());
+                imp.result.replace(Some(result));
+            }
+            Err(error) => {
+                self.show_status(&error.to_string());
+                imp.expression_label.set_label("");
+                imp.result.replace(None);
+            }
+        }
+        self.update_result();
+    }
+
+    /// Copies the resulting matrix into the entries of A.
+    fn use_as_a(&self) {
+        let imp = self.imp();
+        let result = imp.result.borrow();
+        let Some(MatrixResult::Matrix(matrix)) = result.as_ref()
+        else {
+            return;
+        };
+        imp.a_rows_spin.set_value(matrix.rows() as f64);
+        imp.a_columns_spin.set_value(matrix.columns() as f64);
+        for row in 0..matrix.rows() {
+            for column in 0..matrix.columns() {
+                if let Some(entry) = imp
+                    .a_grid
+                    .child_at(column as i32, row as i32)
+                    .and_downcast::<Entry>()
+                {
+                    entry.set_text(&matrix.get(row, column).to_string());
+                }
+            }
+        }
+    }
+
+    /// Shows the last result, with the columns of matrices aligned.
+    fn update_result(&self) {
+        let imp = self.imp();
+        let result = imp.result.borrow();
+        imp.use_button
+            .set_sensitive(matches!(result.as_ref(), Some(MatrixResult::Matrix(_))));
+        let text = match result.as_ref() {
+            None => String::new(),
+            Some(MatrixResult::Number(number)) => self.format(number),
+            Some(MatrixResult::Matrix(matrix)) => {
+                let cells = (0..matrix.rows())
+                    .map(|row| {
+                        (0..matrix.columns())
+                            .map(|column| self.format(matrix.get(row, column)))
+                            .collect::<Vec<String>>()
+                    })
+                    .collect::<Vec<Vec<String>>>();
+                let widths = (0..matrix.columns())
+                    .map(|column| {
+                        cells
+                            .iter()
+                            .map(|row| row[column].chars().count())
+                            .max()
+                            .unwrap_or_default()
+                    })
+                    .collect::<Vec<usize>>();
+                cells
+                    .iter()
+                    .map(|row| {
+                        let entries = row
+                            .iter()
+                            .zip(&widths)
+                            .map(|(cell, width)| format!("{:>width$}", cell, width = width))
+                            .collect::<Vec<String>>();
+                        format!("[ {} ]", entries.join("  "))
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            }
+            Some(MatrixResult::Eigenvalues(roots)) => {
+                roots
+                    .iter()
+                    .map(|root| self.format_root(root))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            }
+        };
+        imp.result_label.set_label(&text);
+    }
+
+    /// Formats a number with the number format of the page.
+    fn format(&self, number: &Number) -> String {
+        format_number(number, &self.imp().number_format.get())
+    }
+
+    /// Formats an eigenvalue, as `a + bi` or `a − bi` if it is complex.
+    fn format_root(&self, root: &Root) -> String {
+        let format = |value: f64| {
+            match Number::from_real(value) {
+                Ok(number) => self.format(&number),
+                Err(_) => value.to_string(),
+            }
+        };
+        if root.is_real() {
+            return format(root.re);
+        }
+        let sign = if root.im < 0.0 { '−' } else { '+' };
+        format!("{} {} {}i", format(root.re), sign, format(root.im.abs()))
+    }
+
+    /// Shows a message above the result, or hides it if the message is empty.
+    fn show_status(&self, message: &str) {
+        let status_label = &self.imp().status_label;
+        status_label.set_label(message);
+        status_label.set_visible(!message.is_empty());
+    }
+}
+
+impl Default for MatrixPage {
+    /// The default implementation of the [`MatrixPage`] widget. It creates a new [`MatrixPage`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
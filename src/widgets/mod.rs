@@ -1,10 +1,49 @@
 //! This module contains all the widgets that are used in the application.
 
-mod skeleton;
-pub use self::skeleton::Skeleton;
-
 mod basic_numpad;
 pub use self::basic_numpad::BasicNumpad;
 
+mod scientific_numpad;
+pub use self::scientific_numpad::ScientificNumpad;
+
+mod history_row;
+pub use self::history_row::HistoryRow;
+
+mod variable_row;
+pub use self::variable_row::VariableRow;
+
+mod constant_row;
+pub use self::constant_row::ConstantRow;
+
+mod conversion_page;
+pub use self::conversion_page::ConversionPage;
+
+mod date_page;
+pub use self::date_page::DatePage;
+
+mod financial_page;
+pub use self::financial_page::FinancialPage;
+
+mod solver_page;
+pub use self::solver_page::SolverPage;
+
+mod statistics_page;
+pub use self::statistics_page::StatisticsPage;
+
+mod plot_page;
+pub use self::plot_page::PlotPage;
+
+mod matrix_page;
+pub use self::matrix_page::MatrixPage;
+
+mod worksheet_page;
+pub use self::worksheet_page::WorksheetPage;
+
+mod tape_page;
+pub use self::tape_page::TapePage;
+
+mod preferences;
+pub use self::preferences::Preferences;
+
 mod window;
-pub use self::window::Window;
\ No newline at end of file
+pub use self::window::Window;
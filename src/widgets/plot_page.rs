@@ -0,0 +1,777 @@
+//! This module contains the implementation of the [`PlotPage`] widget. It draws the curves of functions of `x` in
+//! different colours, can be panned by dragging and zoomed by scrolling, traces the curves under the pointer, finds
+//! their roots, extrema and intersections in the visible range, and exports the plot as a PNG or SVG image. It is a
+//! subclass of [`gtk::Box`].
+
+use glib::{clone, Object};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, gio, glib};
+
+use crate::engine::{extrema, intersections, roots, Context, Curve, Feature, FeatureKind, Number, Viewport};
+use crate::utils::{format_number, NumberFormat};
+use crate::widgets::Window;
+
+/// The colours of the curves, as red, green and blue components from 0 to 1. Curves after the last colour start over.
+const COLORS: [(f64, f64, f64); 6] = [
+    (0.21, 0.52, 0.89),
+    (0.88, 0.11, 0.14),
+    (0.18, 0.76, 0.49),
+    (1.00, 0.47, 0.00),
+    (0.57, 0.25, 0.67),
+    (0.60, 0.42, 0.27),
+];
+
+/// The approximate distance between grid lines, in pixels.
+const GRID_SPACING: f64 = 80.0;
+
+/// The factor the viewport is scaled by for each step of zooming out.
+const ZOOM_STEP: f64 = 1.25;
+
+/// The size of exported images when the plot has not been shown yet, in pixels.
+const EXPORT_SIZE: (i32, i32) = (800, 600);
+
+mod imp {
+    //! Private implementation details of the [`PlotPage`] widget.
+
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Box, Button, CompositeTemplate, DrawingArea, Entry, Label, ToggleButton};
+
+    use crate::engine::{Curve, Feature, Viewport};
+    use crate::utils::NumberFormat;
+
+    /// The `PlotPage` widget. It draws the curves of functions of `x`. It is a subclass of [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/plot_page.ui")]
+    pub struct PlotPage {
+        #[template_child]
+        pub add_button: TemplateChild<Button>,
+        #[template_child]
+        pub functions_box: TemplateChild<Box>,
+        #[template_child]
+        pub plot_area: TemplateChild<DrawingArea>,
+        #[template_child]
+        pub trace_label: TemplateChild<Label>,
+        #[template_child]
+        pub zoom_in_button: TemplateChild<Button>,
+        #[template_child]
+        pub zoom_out_button: TemplateChild<Button>,
+        #[template_child]
+        pub reset_button: TemplateChild<Button>,
+        #[template_child]
+        pub roots_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub extrema_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub intersections_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub export_png_button: TemplateChild<Button>,
+        #[template_child]
+        pub export_svg_button: TemplateChild<Button>,
+        #[template_child]
+        pub status_label: TemplateChild<Label>,
+        #[template_child]
+        pub features_label: TemplateChild<Label>,
+        pub entries: RefCell<Vec<Entry>>,
+        pub curves: RefCell<Vec<Option<Curve>>>,
+        pub features: RefCell<Vec<(usize, Feature)>>,
+        pub features_outdated: Cell<bool>,
+        pub viewport: Cell<Viewport>,
+        pub drag_start: Cell<Option<Viewport>>,
+        pub pointer: Cell<Option<(f64, f64)>>,
+        pub number_format: Cell<NumberFormat>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PlotPage {
+        type ParentType = gtk::Box;
+        type Type = super::PlotPage;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "PlotPage";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for PlotPage {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for PlotPage {}
+
+    impl BoxImpl for PlotPage {}
+}
+
+glib::wrapper! {
+    /// A [`PlotPage`] widget. It draws the curves of functions of `x`. It is a subclass of [`gtk::Box`].
+    pub struct PlotPage(ObjectSubclass<imp::PlotPage>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl PlotPage {
+    /// Creates a new [`PlotPage`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Sets the format of the numbers of the axes, the trace and the features, and shows them again.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_format` - The format of the numbers.
+    pub fn set_number_format(&self, number_format: NumberFormat) {
+        self.imp().number_format.set(number_format);
+        self.refresh();
+    }
+
+    /// Returns the visible rectangle of the plane.
+    pub fn viewport(&self) -> Viewport {
+        self.imp().viewport.get()
+    }
+
+    /// Shows a rectangle of the plane. An invalid rectangle (e.g. an empty one) shows the standard range instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `viewport` - The rectangle to show.
+    pub fn set_viewport(&self, viewport: Viewport) {
+        let viewport = if viewport.is_valid() { viewport } else { Viewport::default() };
+        self.imp().viewport.set(viewport);
+        self.refresh();
+    }
+
+    /// Returns the functions typed in the page, including empty ones.
+    pub fn functions(&self) -> Vec<String> {
+        self.imp()
+            .entries
+            .borrow()
+            .iter()
+            .map(|entry| entry.text().to_string())
+            .collect()
+    }
+
+    /// Replaces the functions of the page. There is always at least one function, empty if none is given.
+    ///
+    /// # Arguments
+    ///
+    /// * `functions` - The functions, as typed.
+    pub fn set_functions(&self, functions: &[String]) {
+        let imp = self.imp();
+        for entry in imp.entries.take() {
+            if let Some(row) = entry.parent() {
+                imp.functions_box.remove(&row);
+            }
+        }
+        imp.curves.borrow_mut().clear();
+        if functions.is_empty() {
+            self.add_function("");
+        }
+        for function in functions {
+            self.add_function(function);
+        }
+    }
+
+    /// Redraws the curves after the viewport, the curves or the context they are evaluated in (e.g. a variable) has
+    /// changed. Their features are found again the next time the plot is drawn, so that nothing is evaluated while
+    /// the page is hidden.
+    pub fn refresh(&self) {
+        self.imp().features_outdated.set(true);
+        self.update_trace();
+        self.imp().plot_area.queue_draw();
+    }
+
+    /// Connects the callbacks of the child widgets and of the plot.
+    fn setup(&self) {
+        let imp = self.imp();
+        imp.viewport.set(Viewport::default());
+        let plot_area = imp.plot_area.get();
+        plot_area.set_draw_func(clone!(@weak self as page => move |area, cr, width, height| {
+            if page.imp().features_outdated.replace(false) {
+                page.update_features();
+            }
+            // Errors of cairo leave the drawing incomplete, and there is nothing else to do about them.
+            let _ = page.draw(cr, f64::from(width), f64::from(height), area.color(), None);
+        }));
+
+        let drag = gtk::GestureDrag::new();
+        drag.connect_drag_begin(clone!(@weak self as page => move |_, _, _| {
+            page.imp().drag_start.set(Some(page.viewport()));
+        }));
+        drag.connect_drag_update(clone!(@weak self as page, @weak plot_area => move |_, dx, dy| {
+            let Some(start) = page.imp().drag_start.get()
+            else {
+                return;
+            };
+            let (width, height) = (f64::from(plot_area.width()), f64::from(plot_area.height()));
+            if width > 0.0 && height > 0.0 {
+                let dx = -dx / width * (start.x_max - start.x_min);
+                let dy = dy / height * (start.y_max - start.y_min);
+                page.imp().viewport.set(start.pan(dx, dy));
+                plot_area.queue_draw();
+            }
+        }));
+        drag.connect_drag_end(clone!(@weak self as page => move |_, _, _| {
+            page.imp().drag_start.set(None);
+            page.refresh();
+        }));
+        plot_area.add_controller(drag);
+
+        let scroll = gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+        scroll.connect_scroll(clone!(@weak self as page => @default-return glib::Propagation::Proceed, move |_, _, dy| {
+            let (x, y) = page.pointer_position().unwrap_or_else(|| page.center());
+            page.set_viewport(page.viewport().zoom(ZOOM_STEP.powf(dy), x, y));
+            glib::Propagation::Stop
+        }));
+        plot_area.add_controller(scroll);
+
+        let motion = gtk::EventControllerMotion::new();
+        motion.connect_motion(clone!(@weak self as page => move |_, x, y| {
+            page.imp().pointer.set(Some((x, y)));
+            page.update_trace();
+            page.imp().plot_area.queue_draw();
+        }));
+        motion.connect_leave(clone!(@weak self as page => move |_| {
+            page.imp().pointer.set(None);
+            page.update_trace();
+            page.imp().plot_area.queue_draw();
+        }));
+        plot_area.add_controller(motion);
+
+        imp.add_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.add_function("");
+            }));
+        imp.zoom_in_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                let (x, y) = page.center();
+                page.set_viewport(page.viewport().zoom(1.0 / ZOOM_STEP, x, y));
+            }));
+        imp.zoom_out_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                let (x, y) = page.center();
+                page.set_viewport(page.viewport().zoom(ZOOM_STEP, x, y));
+            }));
+        imp.reset_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.set_viewport(Viewport::default());
+            }));
+        for toggle in [&imp.roots_toggle, &imp.extrema_toggle, &imp.intersections_toggle] {
+            toggle.connect_toggled(clone!(@weak self as page => move |_| {
+                page.refresh();
+            }));
+        }
+        imp.export_png_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.export(false);
+            }));
+        imp.export_svg_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.export(true);
+            }));
+        self.add_function("");
+    }
+
+    /// Adds a function at the end of the list, with an entry to type it and a button removing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The function, as typed.
+    fn add_function(&self, text: &str) {
+        let imp = self.imp();
+        let index = imp.entries.borrow().len();
+        let (red, green, blue) = COLORS[index % COLORS.len()];
+        let row = gtk::Box::builder().spacing(6).build();
+        let swatch = gtk::Label::builder()
+            .use_markup(true)
+            .label(format!(
+                "<span foreground=\"#{:02x}{:02x}{:02x}\">●</span> f{}",
+                (red * 255.0) as u8,
+                (green * 255.0) as u8,
+                (blue * 255.0) as u8,
+                index + 1
+            ))
+            .build();
+        let entry = gtk::Entry::builder()
+            .text(text)
+            .hexpand(true)
+            .placeholder_text("e.g. x^2 - 2 or sin(x)")
+            .build();
+        let remove_button = gtk::Button::builder()
+            .icon_name("list-remove-symbolic")
+            .tooltip_text("Remove")
+            .valign(gtk::Align::Center)
+            .css_classes(["flat"])
+            .build();
+        entry.connect_changed(clone!(@weak self as page => move |entry| {
+            page.update_curve(entry);
+        }));
+        remove_button.connect_clicked(clone!(@weak self as page, @weak entry => move |_| {
+            page.remove_function(&entry);
+        }));
+        row.append(&swatch);
+        row.append(&entry);
+        row.append(&remove_button);
+        imp.functions_box.append(&row);
+        imp.entries.borrow_mut().push(entry.clone());
+        imp.curves.borrow_mut().push(None);
+        self.update_curve(&entry);
+    }
+
+    /// Removes a function. The colours and names of the functions after it are renumbered.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The entry of the function.
+    fn remove_function(&self, entry: &gtk::Entry) {
+        let mut functions = self.functions();
+        if let Some(index) = self
+            .imp()
+            .entries
+            .borrow()
+            .iter()
+            .position(|other| other == entry)
+        {
+            functions.remove(index);
+        }
+        self.set_functions(&functions);
+    }
+
+    /// Parses the function typed in an entry again, marking the entry if it is invalid, and redraws the plot.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The entry of the function.
+    fn update_curve(&self, entry: &gtk::Entry) {
+        let imp = self.imp();
+        let Some(index) = imp.entries.borrow().iter().position(|other| other == entry)
+        else {
+            return;
+        };
+        let text = entry.text();
+        let curve = if text.trim().is_empty() {
+            Ok(None)
+        }
+        else {
+            Curve::parse(&text).map(Some)
+        };
+        match curve {
+            Ok(curve) => {
+                entry.remove_css_class("error");
+                entry.set_tooltip_text(None);
+                imp.curves.borrow_mut()[index] = curve;
+            }
+            Err(error) => {
+                entry.add_css_class("error");
+                entry.set_tooltip_text(Some(&error.to_string()));
+                imp.curves.borrow_mut()[index] = None;
+            }
+        }
+        self.refresh();
+    }
+
+    /// Runs a function with the context the curves are evaluated in: the one of typed expressions in the window.
+    ///
+    /// # Returns
+    ///
+    /// The return value of the function, or `None` if the page is not in a window.
+    fn with_context<T>(&self, f: impl FnOnce(&Context) -> T) -> Option<T> {
+        let window = self.root().and_downcast::<Window>()?;
+        Some(window.with_context(f))
+    }
+
+    /// Returns the center of the viewport.
+    fn center(&self) -> (f64, f64) {
+        let viewport = self.viewport();
+        ((viewport.x_min + viewport.x_max) / 2.0, (viewport.y_min + viewport.y_max) / 2.0)
+    }
+
+    /// Returns the point of the plane under the pointer, or `None` if the pointer is not over the plot.
+    fn pointer_position(&self) -> Option<(f64, f64)> {
+        let imp = self.imp();
+        let (x, y) = imp.pointer.get()?;
+        let (width, height) = (f64::from(imp.plot_area.width()), f64::from(imp.plot_area.height()));
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        let viewport = self.viewport();
+        Some((
+            viewport.x_min + x / width * (viewport.x_max - viewport.x_min),
+            viewport.y_max - y / height * (viewport.y_max - viewport.y_min),
+        ))
+    }
+
+    /// Formats a coordinate with the number format of the page.
+    fn format(&self, value: f64) -> String {
+        match Number::from_real(value) {
+            Ok(number) => format_number(&number, &self.imp().number_format.get()),
+            Err(_) => value.to_string(),
+        }
+    }
+
+    /// Finds the features selected with the toggle buttons in the visible range, and lists them below the plot.
+    fn update_features(&self) {
+        let imp = self.imp();
+        let curves = imp.curves.borrow().clone();
+        let viewport = self.viewport();
+        let (from, to) = (viewport.x_min, viewport.x_max);
+        let (find_roots, find_extrema, find_intersections) = (
+            imp.roots_toggle.is_active(),
+            imp.extrema_toggle.is_active(),
+            imp.intersections_toggle.is_active(),
+        );
+        let mut lines = Vec::new();
+        let features = self
+            .with_context(|context| {
+                let mut features = Vec::new();
+                for (index, curve) in curves.iter().enumerate() {
+                    let Some(curve) = curve
+                    else {
+                        continue;
+                    };
+                    let f = |x: f64| curve.evaluate(context, x);
+                    let name = format!("f{}", index + 1);
+                    if find_roots {
+                        for x in roots(f, from, to) {
+                            lines.push(format!("Root of {}: x = {}", name, self.format(x)));
+                            features.push((index, Feature { kind: FeatureKind::Root, x, y: 0.0 }));
+                        }
+                    }
+                    if find_extrema {
+                        for feature in extrema(f, from, to) {
+                            lines.push(format!(
+                                "{} of {}: x = {}, y = {}",
+                                feature.kind.name(),
+                                name,
+                                self.format(feature.x),
+                                self.format(feature.y)
+                            ));
+                            features.push((index, feature));
+                        }
+                    }
+                    if find_intersections {
+                        for (other_index, other) in curves.iter().enumerate().skip(index + 1) {
+                            let Some(other) = other
+                            else {
+                                continue;
+                            };
+                            for feature in intersections(f, |x| other.evaluate(context, x), from, to) {
+                                lines.push(format!(
+                                    "Intersection of {} and f{}: x = {}, y = {}",
+                                    name,
+                                    other_index + 1,
+                                    self.format(feature.x),
+                                    self.format(feature.y)
+                                ));
+                                features.push((index, feature));
+                            }
+                        }
+                    }
+                }
+                features
+            })
+            .unwrap_or_default();
+        imp.features.replace(features);
+        imp.features_label.set_label(&lines.join("\n"));
+    }
+
+    /// Shows the values of the curves at the `x` under the pointer below the plot.
+    fn update_trace(&self) {
+        let imp = self.imp();
+        let Some((x, _)) = self.pointer_position()
+        else {
+            imp.trace_label.set_label("");
+            return;
+        };
+        let curves = imp.curves.borrow().clone();
+        let values = self
+            .with_context(|context| {
+                curves
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, curve)| {
+                        let curve = curve.as_ref()?;
+                        let value = curve
+                            .evaluate(context, x)
+                            .map_or_else(|| "undefined".to_string(), |y| self.format(y));
+                        Some(format!("f{} = {}", index + 1, value))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let mut trace = vec![format!("x = {}", self.format(x))];
+        trace.extend(values);
+        imp.trace_label.set_label(&trace.join("   "));
+    }
+
+    /// Draws the plot: the grid, the axes with their labels, the curves, the features found and the trace of the
+    /// pointer.
+    ///
+    /// # Arguments
+    ///
+    /// * `cr` - The cairo context to draw with.
+    /// * `width` - The width of the plot, in pixels.
+    /// * `height` - The height of the plot, in pixels.
+    /// * `foreground` - The colour of the axes and of the labels.
+    /// * `background` - The colour to fill the plot with first, or `None` to keep the background of the widget.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the underlying cairo operation.
+    fn draw(
+        &self,
+        cr: &cairo::Context,
+        width: f64,
+        height: f64,
+        foreground: gdk::RGBA,
+        background: Option<gdk::RGBA>,
+    ) -> Result<(), cairo::Error> {
+        let imp = self.imp();
+        let viewport = self.viewport();
+        if width <= 0.0 || height <= 0.0 || !viewport.is_valid() {
+            return Ok(());
+        }
+        let to_x = |x: f64| (x - viewport.x_min) / (viewport.x_max - viewport.x_min) * width;
+        let to_y = |y: f64| height - (y - viewport.y_min) / (viewport.y_max - viewport.y_min) * height;
+        let set_color = |alpha: f64| {
+            cr.set_source_rgba(
+                f64::from(foreground.red()),
+                f64::from(foreground.green()),
+                f64::from(foreground.blue()),
+                f64::from(foreground.alpha()) * alpha,
+            );
+        };
+        if let Some(background) = background {
+            cr.set_source_rgba(
+                f64::from(background.red()),
+                f64::from(background.green()),
+                f64::from(background.blue()),
+                f64::from(background.alpha()),
+            );
+            cr.paint()?;
+        }
+
+        // The grid, and the axes at zero, or at the edge of the plot when zero is not visible.
+        let x_ticks = Viewport::ticks(viewport.x_min, viewport.x_max, (width / GRID_SPACING).ceil() as usize);
+        let y_ticks = Viewport::ticks(viewport.y_min, viewport.y_max, (height / GRID_SPACING).ceil() as usize);
+        cr.set_line_width(1.0);
+        set_color(0.12);
+        for x in &x_ticks {
+            cr.move_to(to_x(*x).round() + 0.5, 0.0);
+            cr.line_to(to_x(*x).round() + 0.5, height);
+        }
+        for y in &y_ticks {
+            cr.move_to(0.0, to_y(*y).round() + 0.5);
+            cr.line_to(width, to_y(*y).round() + 0.5);
+        }
+        cr.stroke()?;
+        let axis_x = to_x(0.0).clamp(0.0, width - 1.0).round() + 0.5;
+        let axis_y = to_y(0.0).clamp(0.0, height - 1.0).round() + 0.5;
+        set_color(0.6);
+        cr.move_to(axis_x, 0.0);
+        cr.line_to(axis_x, height);
+        cr.move_to(0.0, axis_y);
+        cr.line_to(width, axis_y);
+        cr.stroke()?;
+        cr.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Normal);
+        cr.set_font_size(11.0);
+        set_color(0.8);
+        for x in x_ticks.iter().filter(|x| **x != 0.0) {
+            let label = self.format(*x);
+            let extents = cr.text_extents(&label)?;
+            let below = axis_y + 4.0 + extents.height();
+            let top = if below > height { axis_y - 4.0 } else { below };
+            cr.move_to(to_x(*x) - extents.width() / 2.0, top);
+            cr.show_text(&label)?;
+        }
+        for y in y_ticks.iter().filter(|y| **y != 0.0) {
+            let label = self.format(*y);
+            let extents = cr.text_extents(&label)?;
+            let right = axis_x + 4.0 + extents.width();
+            let left = if right > width { axis_x - 4.0 - extents.width() } else { axis_x + 4.0 };
+            cr.move_to(left, to_y(*y) + extents.height() / 2.0);
+            cr.show_text(&label)?;
+        }
+
+        // The curves, sampled at every pixel and broken where they are undefined or jump across the plot (e.g. at
+        // the poles of `tan(x)`).
+        let curves = imp.curves.borrow().clone();
+        let pointer = self.pointer_position();
+        let features = imp.features.borrow().clone();
+        self.with_context(|context| -> Result<(), cairo::Error> {
+            cr.set_line_width(2.0);
+            cr.set_line_join(cairo::LineJoin::Round);
+            for (index, curve) in curves.iter().enumerate() {
+                let Some(curve) = curve
+                else {
+                    continue;
+                };
+                let (red, green, blue) = COLORS[index % COLORS.len()];
+                cr.set_source_rgb(red, green, blue);
+                let mut previous: Option<f64> = None;
+                for column in 0..=(width.ceil() as i64) {
+                    let px = column as f64;
+                    let x = viewport.x_min + px / width * (viewport.x_max - viewport.x_min);
+                    let py = curve.evaluate(context, x).map(to_y);
+                    match (previous, py) {
+                        (Some(previous), Some(py)) if (py - previous).abs() < 2.0 * height => cr.line_to(px, py),
+                        (_, Some(py)) => cr.move_to(px, py.clamp(-height, 2.0 * height)),
+                        _ => {}
+                    }
+                    previous = py;
+                }
+                cr.stroke()?;
+                if let Some((x, _)) = pointer {
+                    if let Some(y) = curve.evaluate(context, x) {
+                        cr.arc(to_x(x), to_y(y), 4.0, 0.0, std::f64::consts::TAU);
+                        cr.fill()?;
+                    }
+                }
+            }
+            Ok(())
+        })
+        .transpose()?;
+
+        // The features found, circled in the colour of their curve.
+        cr.set_line_width(2.0);
+        for (index, feature) in &features {
+            let (red, green, blue) = COLORS[index % COLORS.len()];
+            cr.set_source_rgb(red, green, blue);
+            cr.arc(to_x(feature.x), to_y(feature.y), 5.0, 0.0, std::f64::consts::TAU);
+            cr.stroke()?;
+        }
+
+        // The trace line under the pointer.
+        if let Some((x, _)) = pointer {
+            set_color(0.4);
+            cr.set_line_width(1.0);
+            cr.move_to(to_x(x).round() + 0.5, 0.0);
+            cr.line_to(to_x(x).round() + 0.5, height);
+            cr.stroke()?;
+        }
+        Ok(())
+    }
+
+    /// Asks for a file and saves the plot in it, at the size it is shown, with black axes on a white background.
+    ///
+    /// # Arguments
+    ///
+    /// * `svg` - Whether to save the plot as an SVG image rather than a PNG image.
+    fn export(&self, svg: bool) {
+        let dialog = gtk::FileDialog::builder()
+            .title(if svg { "Export Plot as SVG" } else { "Export Plot as PNG" })
+            .initial_name(if svg { "plot.svg" } else { "plot.png" })
+            .modal(true)
+            .build();
+        let window = self.root().and_downcast::<gtk::Window>();
+        dialog.save(
+            window.as_ref(),
+            gio::Cancellable::NONE,
+            clone!(@weak self as page => move |result| {
+                let Ok(file) = result
+                else {
+                    return;
+                };
+                let result = page.render(svg).and_then(|bytes| {
+                    file.replace_contents(
+                        &bytes,
+                        None,
+                        false,
+                        gio::FileCreateFlags::REPLACE_DESTINATION,
+                        gio::Cancellable::NONE,
+                    )
+                    .map(|_| ())
+                    .map_err(|error| error.to_string())
+                });
+                match result {
+                    Ok(()) => page.show_status(""),
+                    Err(error) => page.show_status(&format!("Could not export the plot: {}", error)),
+                }
+            }),
+        );
+    }
+
+    /// Renders the plot as an image file, without the trace of the pointer.
+    ///
+    /// # Arguments
+    ///
+    /// * `svg` - Whether to render an SVG image rather than a PNG image.
+    ///
+    /// # Returns
+    ///
+    /// The content of the image file, or a description of the error.
+    fn render(&self, svg: bool) -> Result<Vec<u8>, String> {
+        let imp = self.imp();
+        let (width, height) = match (imp.plot_area.width(), imp.plot_area.height()) {
+            (width, height) if width > 0 && height > 0 => (width, height),
+            _ => EXPORT_SIZE,
+        };
+        if imp.features_outdated.replace(false) {
+            self.update_features();
+        }
+        let pointer = imp.pointer.take();
+        let foreground = gdk::RGBA::BLACK;
+        let background = Some(gdk::RGBA::WHITE);
+        let result = if svg {
+            cairo::SvgSurface::for_stream(f64::from(width), f64::from(height), Vec::<u8>::new())
+                .map_err(|error| error.to_string())
+                .and_then(|surface| {
+                    let cr = cairo::Context::new(&surface).map_err(|error| error.to_string())?;
+                    self.draw(&cr, f64::from(width), f64::from(height), foreground, background)
+                        .map_err(|error| error.to_string())?;
+                    drop(cr);
+                    surface
+                        .finish_output_stream()
+                        .map_err(|error| error.to_string())?
+                        .downcast::<Vec<u8>>()
+                        .map(|bytes| *bytes)
+                        .map_err(|_| "Invalid SVG stream".to_string())
+                })
+        }
+        else {
+            cairo::ImageSurface::create(cairo::Format::ARgb32, width, height)
+                .map_err(|error| error.to_string())
+                .and_then(|surface| {
+                    let cr = cairo::Context::new(&surface).map_err(|error| error.to_string())?;
+                    self.draw(&cr, f64::from(width), f64::from(height), foreground, background)
+                        .map_err(|error| error.to_string())?;
+                    drop(cr);
+                    let mut bytes = Vec::new();
+                    surface
+                        .write_to_png(&mut bytes)
+                        .map_err(|error| error.to_string())?;
+                    Ok(bytes)
+                })
+        };
+        imp.pointer.set(pointer);
+        result
+    }
+
+    /// Shows a message below the plot, or hides it if the message is empty.
+    fn show_status(&self, message: &str) {
+        let status_label = &self.imp().status_label;
+        status_label.set_label(message);
+        status_label.set_visible(!message.is_empty());
+    }
+}
+
+impl Default for PlotPage {
+    /// The default implementation of the [`PlotPage`] widget. It creates a new [`PlotPage`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
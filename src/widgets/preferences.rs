@@ -0,0 +1,82 @@
+//! This module contains the implementation of the [`Preferences`] dialog. It lets the user change the settings of the
+//! calculator. It is a subclass of [`adw::PreferencesDialog`].
+
+use glib::Object;
+use gtk::glib;
+
+mod imp {
+    //! Private implementation details of the [`Preferences`] dialog.
+
+    use adw::subclass::prelude::*;
+    use adw::{ComboRow, SpinRow, SwitchRow};
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, CompositeTemplate};
+
+    /// The `Preferences` dialog. It lets the user change the settings of the calculator. It is a subclass of
+    /// [`adw::PreferencesDialog`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/preferences.ui")]
+    pub struct Preferences {
+        #[template_child]
+        pub notation_row:        TemplateChild<ComboRow>,
+        #[template_child]
+        pub precision_row:       TemplateChild<SpinRow>,
+        #[template_child]
+        pub si_prefixes_row:     TemplateChild<SwitchRow>,
+        #[template_child]
+        pub max_denominator_row: TemplateChild<SpinRow>,
+        #[template_child]
+        pub locale_row:          TemplateChild<ComboRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Preferences {
+        type ParentType = adw::PreferencesDialog;
+        type Type = super::Preferences;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "Preferences";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for Preferences {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+        }
+    }
+
+    impl WidgetImpl for Preferences {}
+
+    impl AdwDialogImpl for Preferences {}
+
+    impl PreferencesDialogImpl for Preferences {}
+}
+
+glib::wrapper! {
+    /// A [`Preferences`] dialog. It lets the user change the settings of the calculator. It is a subclass of [`adw::PreferencesDialog`].
+    pub struct Preferences(ObjectSubclass<imp::Preferences>)
+        @extends adw::PreferencesDialog, adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl Preferences {
+    /// Creates a new [`Preferences`] dialog.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+}
+
+impl Default for Preferences {
+    /// The default implementation of the [`Preferences`] dialog. It creates a new [`Preferences`] dialog.
+    fn default() -> Self {
+        Self::new()
+    }
+}
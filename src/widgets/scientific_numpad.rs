@@ -0,0 +1,124 @@
+//! This module contains the implementation of the [`ScientificNumpad`] widget. It provides the scientific functions of
+//! the calculator: the angle unit selector, the trigonometric functions, the entry of angles in degrees, minutes and
+//! seconds, the toggles of the complex mode and of the polar form, the number theory functions, and a custom key
+//! applying a user-defined function. It is shown next to the [`BasicNumpad`](super::BasicNumpad) in scientific mode.
+
+use glib::Object;
+use gtk::glib;
+
+mod imp {
+    //! Private implementation details of the [`ScientificNumpad`] widget.
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Button, CompositeTemplate, Grid, MenuButton, ToggleButton};
+
+    /// The `ScientificNumpad` widget. It provides the scientific functions of the calculator. It is a subclass of
+    /// [`gtk::Grid`] allowing for easy layout of the buttons.
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/scientific_numpad.ui")]
+    pub struct ScientificNumpad {
+        #[template_child]
+        pub button_degrees:       TemplateChild<ToggleButton>,
+        #[template_child]
+        pub button_radians:       TemplateChild<ToggleButton>,
+        #[template_child]
+        pub button_gradians:      TemplateChild<ToggleButton>,
+        #[template_child]
+        pub button_convert_angle: TemplateChild<Button>,
+        #[template_child]
+        pub button_sin:           TemplateChild<Button>,
+        #[template_child]
+        pub button_cos:           TemplateChild<Button>,
+        #[template_child]
+        pub button_tan:           TemplateChild<Button>,
+        #[template_child]
+        pub button_dms:           TemplateChild<Button>,
+        #[template_child]
+        pub button_asin:          TemplateChild<Button>,
+        #[template_child]
+        pub button_acos:          TemplateChild<Button>,
+        #[template_child]
+        pub button_atan:          TemplateChild<Button>,
+        #[template_child]
+        pub button_dms_view:      TemplateChild<ToggleButton>,
+        #[template_child]
+        pub button_complex_mode:  TemplateChild<ToggleButton>,
+        #[template_child]
+        pub button_polar_view:    TemplateChild<ToggleButton>,
+        #[template_child]
+        pub button_interval_mode: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub button_factorial:     TemplateChild<Button>,
+        #[template_child]
+        pub button_combinations:  TemplateChild<Button>,
+        #[template_child]
+        pub button_permutations:  TemplateChild<Button>,
+        #[template_child]
+        pub button_gcd:           TemplateChild<Button>,
+        #[template_child]
+        pub button_lcm:           TemplateChild<Button>,
+        #[template_child]
+        pub button_mod_pow:       TemplateChild<Button>,
+        #[template_child]
+        pub button_mod_inverse:   TemplateChild<Button>,
+        #[template_child]
+        pub button_totient:       TemplateChild<Button>,
+        #[template_child]
+        pub button_factor:        TemplateChild<Button>,
+        #[template_child]
+        pub button_is_prime:      TemplateChild<Button>,
+        #[template_child]
+        pub button_custom:        TemplateChild<Button>,
+        #[template_child]
+        pub custom_key_menu:      TemplateChild<MenuButton>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ScientificNumpad {
+        type ParentType = Grid;
+        type Type = super::ScientificNumpad;
+
+        const NAME: &'static str = "ScientificNumpad";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ScientificNumpad {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+        }
+    }
+
+    impl WidgetImpl for ScientificNumpad {}
+
+    impl GridImpl for ScientificNumpad {}
+}
+
+glib::wrapper! {
+    /// A [`ScientificNumpad`] widget. It provides the scientific functions of the calculator. It is a subclass of [`gtk::Grid`] allowing for easy layout of the buttons.
+    pub struct ScientificNumpad(ObjectSubclass<imp::ScientificNumpad>)
+        @extends gtk::Grid, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable,gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl ScientificNumpad {
+    /// Creates a new [`ScientificNumpad`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+}
+
+impl Default for ScientificNumpad {
+    /// The default implementation of the [`ScientificNumpad`] widget. It creates a new [`ScientificNumpad`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,247 @@
+//! This module contains the implementation of the [`SolverPage`] widget. It solves an equation in one variable and
+//! lists all the roots found: the real and complex roots of polynomials, and the real roots of other equations within
+//! a bracket. It is a subclass of [`gtk::Box`].
+
+use glib::{clone, Object};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::glib;
+
+use crate::engine::{Equation, Error, Number, Root, Statement, DEFAULT_BRACKET};
+use crate::utils::{format_number, NumberFormat};
+use crate::widgets::Window;
+
+mod imp {
+    //! Private implementation details of the [`SolverPage`] widget.
+
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Button, CompositeTemplate, Entry, Label};
+
+    use crate::engine::Solution;
+    use crate::utils::NumberFormat;
+
+    /// The `SolverPage` widget. It solves an equation in one variable and lists its roots. It is a subclass of
+    /// [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/solver_page.ui")]
+    pub struct SolverPage {
+        #[template_child]
+        pub equation_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub variable_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub from_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub to_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub solve_button: TemplateChild<Button>,
+        #[template_child]
+        pub status_label: TemplateChild<Label>,
+        #[template_child]
+        pub method_label: TemplateChild<Label>,
+        #[template_child]
+        pub roots_label: TemplateChild<Label>,
+        pub solution: RefCell<Option<Solution>>,
+        pub variable: RefCell<String>,
+        pub bracket: Cell<Option<(f64, f64)>>,
+        pub number_format: Cell<NumberFormat>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SolverPage {
+        type ParentType = gtk::Box;
+        type Type = super::SolverPage;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "SolverPage";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for SolverPage {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for SolverPage {}
+
+    impl BoxImpl for SolverPage {}
+}
+
+glib::wrapper! {
+    /// A [`SolverPage`] widget. It solves an equation in one variable and lists its roots. It is a subclass of
+    /// [`gtk::Box`].
+    pub struct SolverPage(ObjectSubclass<imp::SolverPage>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl SolverPage {
+    /// Creates a new [`SolverPage`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Sets the format of the roots, and shows them again.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_format` - The format of the numbers.
+    pub fn set_number_format(&self, number_format: NumberFormat) {
+        self.imp().number_format.set(number_format);
+        self.update_roots();
+    }
+
+    /// Connects the callbacks of the child widgets.
+    fn setup(&self) {
+        let imp = self.imp();
+        imp.solve_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.solve();
+            }));
+        for entry in [&imp.equation_entry, &imp.variable_entry, &imp.from_entry, &imp.to_entry] {
+            entry.connect_activate(clone!(@weak self as page => move |_| {
+                page.solve();
+            }));
+        }
+    }
+
+    /// Solves the equation typed in the page with the context of typed expressions in the window, and lists its
+    /// roots.
+    fn solve(&self) {
+        let imp = self.imp();
+        let Some(window) = self.root().and_downcast::<Window>()
+        else {
+            return;
+        };
+        let variable = imp.variable_entry.text().trim().to_string();
+        let equation = imp.equation_entry.text();
+        let result = window.with_context(|context| -> Result<_, Error> {
+            let bound = |entry: &gtk::Entry| -> Result<Option<f64>, Error> {
+                let text = entry.text();
+                if text.trim().is_empty() {
+                    return Ok(None);
+                }
+                match Statement::parse(&text)? {
+                    Statement::Expression(expression) => {
+                        Ok(Some(expression.evaluate(context)?.into_number()?.to_f64()))
+                    }
+                    _ => Err(Error::Syntax(format!("Expected a number, not ‘{}’", text))),
+                }
+            };
+            let (from, to) = (bound(&imp.from_entry)?, bound(&imp.to_entry)?);
+            let bracket = (from.is_some() || to.is_some())
+                .then(|| (from.unwrap_or(DEFAULT_BRACKET.0), to.unwrap_or(DEFAULT_BRACKET.1)));
+            let solution = Equation::parse(&equation, &variable)?.solve(context, bracket)?;
+            Ok((bracket, solution))
+        });
+        match result {
+            Ok((bracket, solution)) => {
+                self.show_status("");
+                imp.solution.replace(Some(solution));
+                imp.variable.replace(variable);
+                imp.bracket.set(bracket);
+            }
+            Err(error) => {
+                self.show_status(&error.to_string());
+                imp.solution.replace(None);
+            }
+        }
+        self.update_roots();
+    }
+
+    /// Lists the roots of the last equation solved, with the method used to find them. Repeated roots are listed
+    /// once, with their multiplicity.
+    fn update_roots(&self) {
+        let imp = self.imp();
+        let solution = imp.solution.borrow();
+        let Some(solution) = solution.as_ref()
+        else {
+            imp.method_label.set_label("");
+            imp.roots_label.set_label("");
+            return;
+        };
+        let bracket = imp.bracket.get();
+        let (from, to) = bracket.unwrap_or(DEFAULT_BRACKET);
+        let method = match (solution.degree, bracket) {
+            (Some(degree), None) => format!("Polynomial of degree {}: all roots, real and complex", degree),
+            (Some(degree), Some(_)) => {
+                format!(
+                    "Polynomial of degree {}: real roots between {} and {}",
+                    degree,
+                    self.format(from),
+                    self.format(to)
+                )
+            }
+            (None, _) => {
+                format!(
+                    "Not a polynomial: real roots between {} and {}, found numerically",
+                    self.format(from),
+                    self.format(to)
+                )
+            }
+        };
+        imp.method_label.set_label(&method);
+        let mut lines = Vec::new();
+        let mut roots = solution.roots.iter().peekable();
+        while let Some(root) = roots.next() {
+            let mut multiplicity = 1;
+            while roots.next_if_eq(&root).is_some() {
+                multiplicity += 1;
+            }
+            let mut line = format!("{} = {}", imp.variable.borrow(), self.format_root(root));
+            if multiplicity > 1 {
+                line.push_str(&format!("   (multiplicity {})", multiplicity));
+            }
+            lines.push(line);
+        }
+        if lines.is_empty() {
+            lines.push("No roots".to_string());
+        }
+        imp.roots_label.set_label(&lines.join("\n"));
+    }
+
+    /// Formats a number with the number format of the page.
+    fn format(&self, value: f64) -> String {
+        match Number::from_real(value) {
+            Ok(number) => format_number(&number, &self.imp().number_format.get()),
+            Err(_) => value.to_string(),
+        }
+    }
+
+    /// Formats a root, as `a + bi` or `a − bi` if it is complex.
+    fn format_root(&self, root: &Root) -> String {
+        if root.is_real() {
+            return self.format(root.re);
+        }
+        let sign = if root.im < 0.0 { '−' } else { '+' };
+        format!("{} {} {}i", self.format(root.re), sign, self.format(root.im.abs()))
+    }
+
+    /// Shows a message below the equation, or hides it if the message is empty.
+    fn show_status(&self, message: &str) {
+        let status_label = &self.imp().status_label;
+        status_label.set_label(message);
+        status_label.set_visible(!message.is_empty());
+    }
+}
+
+impl Default for SolverPage {
+    /// The default implementation of the [`SolverPage`] widget. It creates a new [`SolverPage`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
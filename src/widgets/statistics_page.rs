@@ -0,0 +1,503 @@
+//! This module contains the implementation of the [`StatisticsPage`] widget. It lists data typed, pasted or loaded
+//! from a CSV file (single values, weighted values or paired `x, y` values) and shows their descriptive statistics and,
+//! for paired values, their linear regression. The data can be saved as a CSV file. It is a subclass of [`gtk::Box`].
+
+use glib::{clone, Object};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::engine::{DataKind, DataSet, Number, Summary};
+use crate::utils::{format_number, NumberFormat};
+
+/// The width of the names of the statistics, in characters.
+const NAME_WIDTH: usize = 22;
+
+/// The width of the columns of values of the statistics, in characters.
+const COLUMN_WIDTH: usize = 18;
+
+/// The names of the statistics of a list of values, in the order of [`summary_cells`].
+const STATISTICS: [&str; 13] = [
+    "Count n",
+    "Sum",
+    "Mean",
+    "Median",
+    "Mode",
+    "Sample variance s²",
+    "Sample std. dev. s",
+    "Population variance σ²",
+    "Population std. dev. σ",
+    "Minimum",
+    "First quartile Q1",
+    "Third quartile Q3",
+    "Maximum",
+];
+
+/// The text shown for statistics that are not defined (e.g. the sample variance of a single value).
+const UNDEFINED: &str = "—";
+
+mod imp {
+    //! Private implementation details of the [`StatisticsPage`] widget.
+
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Button, CompositeTemplate, DropDown, Entry, Label, ListBox};
+
+    use crate::engine::DataPoint;
+    use crate::utils::NumberFormat;
+
+    /// The `StatisticsPage` widget. It lists data and shows its descriptive statistics. It is a subclass of
+    /// [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/statistics_page.ui")]
+    pub struct StatisticsPage {
+        #[template_child]
+        pub paste_button: TemplateChild<Button>,
+        #[template_child]
+        pub open_button: TemplateChild<Button>,
+        #[template_child]
+        pub save_button: TemplateChild<Button>,
+        #[template_child]
+        pub clear_button: TemplateChild<Button>,
+        #[template_child]
+        pub kind_dropdown: TemplateChild<DropDown>,
+        #[template_child]
+        pub point_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub add_button: TemplateChild<Button>,
+        #[template_child]
+        pub status_label: TemplateChild<Label>,
+        #[template_child]
+        pub points_list: TemplateChild<ListBox>,
+        #[template_child]
+        pub results_label: TemplateChild<Label>,
+        pub points: RefCell<Vec<DataPoint>>,
+        pub number_format: Cell<NumberFormat>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for StatisticsPage {
+        type ParentType = gtk::Box;
+        type Type = super::StatisticsPage;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "StatisticsPage";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for StatisticsPage {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for StatisticsPage {}
+
+    impl BoxImpl for StatisticsPage {}
+}
+
+glib::wrapper! {
+    /// A [`StatisticsPage`] widget. It lists data and shows its descriptive statistics. It is a subclass of
+    /// [`gtk::Box`].
+    pub struct StatisticsPage(ObjectSubclass<imp::StatisticsPage>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl StatisticsPage {
+    /// Creates a new [`StatisticsPage`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Sets the format of the numbers of the data and of the statistics, and shows them again.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_format` - The format of the numbers.
+    pub fn set_number_format(&self, number_format: NumberFormat) {
+        self.imp().number_format.set(number_format);
+        self.update();
+    }
+
+    /// Connects the callbacks of the child widgets.
+    fn setup(&self) {
+        let imp = self.imp();
+        imp.point_entry
+            .connect_activate(clone!(@weak self as page => move |_| {
+                page.add_typed();
+            }));
+        imp.add_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.add_typed();
+            }));
+        imp.kind_dropdown
+            .connect_selected_notify(clone!(@weak self as page => move |_| {
+                page.update();
+            }));
+        imp.paste_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.paste();
+            }));
+        imp.open_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.open();
+            }));
+        imp.save_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.save();
+            }));
+        imp.clear_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.imp().points.borrow_mut().clear();
+                page.show_status("");
+                page.update();
+            }));
+        self.update();
+    }
+
+    /// Returns how the data is read, as selected in the drop-down.
+    fn kind(&self) -> DataKind {
+        match self.imp().kind_dropdown.selected() {
+            1 => DataKind::Weighted,
+            2 => DataKind::Paired,
+            _ => DataKind::Values,
+        }
+    }
+
+    /// Selects how the data is read in the drop-down.
+    fn set_kind(&self, kind: DataKind) {
+        let index = match kind {
+            DataKind::Values => 0,
+            DataKind::Weighted => 1,
+            DataKind::Paired => 2,
+        };
+        self.imp().kind_dropdown.set_selected(index);
+    }
+
+    /// Returns the data of the page.
+    fn data_set(&self) -> DataSet {
+        DataSet {
+            kind:   self.kind(),
+            points: self.imp().points.borrow().clone(),
+        }
+    }
+
+    /// Adds the data typed in the entry, and clears the entry if it is valid.
+    fn add_typed(&self) {
+        let entry = &self.imp().point_entry;
+        if self.add(&entry.text()) {
+            entry.set_text("");
+        }
+    }
+
+    /// Adds data points read from text (e.g. pasted or typed) after the existing ones, or shows why they are invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The data points, one per line.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the data points were added.
+    fn add(&self, text: &str) -> bool {
+        match DataSet::parse(text, self.kind()) {
+            Ok(data_set) => {
+                self.imp().points.borrow_mut().extend(data_set.points);
+                self.show_status("");
+                self.update();
+                true
+            }
+            Err(error) => {
+                self.show_status(&error.to_string());
+                false
+            }
+        }
+    }
+
+    /// Removes a data point.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the data point in the list.
+    fn remove(&self, index: usize) {
+        let mut points = self.imp().points.borrow_mut();
+        if index < points.len() {
+            points.remove(index);
+        }
+        drop(points);
+        self.update();
+    }
+
+    /// Adds the data points of the text in the clipboard.
+    fn paste(&self) {
+        self.clipboard().read_text_async(
+            gio::Cancellable::NONE,
+            clone!(@weak self as page => move |result| {
+                if let Ok(Some(text)) = result {
+                    page.add(&text);
+                }
+            }),
+        );
+    }
+
+    /// Asks for a CSV file and replaces the data by its content. The way the data is read is taken from the header of
+    /// the file if it has one.
+    fn open(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Open Data")
+            .modal(true)
+            .build();
+        let window = self.root().and_downcast::<gtk::Window>();
+        dialog.open(
+            window.as_ref(),
+            gio::Cancellable::NONE,
+            clone!(@weak self as page => move |result| {
+                let Ok(file) = result
+                else {
+                    return;
+                };
+                let text = match file.load_contents(gio::Cancellable::NONE) {
+                    Ok((contents, _)) => String::from_utf8_lossy(&contents).into_owned(),
+                    Err(error) => {
+                        page.show_status(&format!("Could not open the file: {}", error));
+                        return;
+                    }
+                };
+                if let Some(kind) = text.lines().next().and_then(DataKind::from_header) {
+                    page.set_kind(kind);
+                }
+                match DataSet::parse(&text, page.kind()) {
+                    Ok(data_set) => {
+                        page.imp().points.replace(data_set.points);
+                        page.show_status("");
+                        page.update();
+                    }
+                    Err(error) => page.show_status(&error.to_string()),
+                }
+            }),
+        );
+    }
+
+    /// Asks for a file and saves the data in it as comma-separated values, with a header line and numbers written
+    /// with a `.` decimal point.
+    fn save(&self) {
+        let csv = self.data_set().to_csv();
+        let dialog = gtk::FileDialog::builder()
+            .title("Save Data")
+            .initial_name("data.csv")
+            .modal(true)
+            .build();
+        let window = self.root().and_downcast::<gtk::Window>();
+        dialog.save(
+            window.as_ref(),
+            gio::Cancellable::NONE,
+            clone!(@weak self as page => move |result| {
+                let Ok(file) = result
+                else {
+                    return;
+                };
+                if let Err(error) = file.replace_contents(
+                    csv.as_bytes(),
+                    None,
+                    false,
+                    gio::FileCreateFlags::REPLACE_DESTINATION,
+                    gio::Cancellable::NONE,
+                ) {
+                    page.show_status(&format!("Could not save the data: {}", error));
+                }
+            }),
+        );
+    }
+
+    /// Shows a message below the entry, or hides it if the message is empty.
+    fn show_status(&self, message: &str) {
+        let status_label = &self.imp().status_label;
+        status_label.set_label(message);
+        status_label.set_visible(!message.is_empty());
+    }
+
+    /// Shows the data points in the list and their statistics.
+    fn update(&self) {
+        let imp = self.imp();
+        let data_set = self.data_set();
+        let format = imp.number_format.get();
+        imp.points_list.remove_all();
+        for (index, point) in data_set.points.iter().enumerate() {
+            let text = match data_set.kind {
+                DataKind::Values => format_number(&point.x, &format),
+                DataKind::Weighted => {
+                    format!(
+                        "{}   × {}",
+                        format_number(&point.x, &format),
+                        format_number(&point.y, &format)
+                    )
+                }
+                DataKind::Paired => {
+                    format!(
+                        "{}   ; {}",
+                        format_number(&point.x, &format),
+                        format_number(&point.y, &format)
+                    )
+                }
+            };
+            imp.points_list.append(&self.point_row(index, &text));
+        }
+        imp.save_button
+            .set_sensitive(!data_set.points.is_empty());
+        imp.clear_button
+            .set_sensitive(!data_set.points.is_empty());
+        imp.results_label
+            .set_label(&self.results(&data_set));
+    }
+
+    /// Creates the row of a data point in the list, with a button removing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the data point.
+    /// * `text` - The data point as shown in the list.
+    fn point_row(&self, index: usize, text: &str) -> gtk::Box {
+        let row = gtk::Box::builder()
+            .spacing(6)
+            .margin_start(12)
+            .margin_end(6)
+            .build();
+        let label = gtk::Label::builder()
+            .label(text)
+            .xalign(0.0)
+            .hexpand(true)
+            .selectable(true)
+            .build();
+        let remove_button = gtk::Button::builder()
+            .icon_name("list-remove-symbolic")
+            .tooltip_text("Remove")
+            .valign(gtk::Align::Center)
+            .css_classes(["flat"])
+            .build();
+        remove_button.connect_clicked(clone!(@weak self as page => move |_| {
+            page.remove(index);
+        }));
+        row.append(&label);
+        row.append(&remove_button);
+        row
+    }
+
+    /// Returns the statistics of data as a table, with a column for `x` and a column for `y` for paired values and
+    /// the regression line below them.
+    fn results(&self, data_set: &DataSet) -> String {
+        let format = self.imp().number_format.get();
+        let number = |number: &Number| format_number(number, &format);
+        let optional = |value: &Option<Number>| value.as_ref().map_or_else(|| UNDEFINED.to_string(), number);
+        let Ok(x) = data_set.summary()
+        else {
+            return if data_set.points.is_empty() {
+                String::new()
+            }
+            else {
+                "The weights need to be whole numbers of zero or more, and one of them more than zero".to_string()
+            };
+        };
+        let y = data_set.y_summary().ok();
+        let mut lines = Vec::new();
+        if y.is_some() {
+            lines.push(format!(
+                "{:name$}{:>width$}{:>width$}",
+                "",
+                "x",
+                "y",
+                name = NAME_WIDTH,
+                width = COLUMN_WIDTH
+            ));
+        }
+        let x_cells = summary_cells(&x, &format);
+        let y_cells = y.map(|y| summary_cells(&y, &format));
+        for (index, name) in STATISTICS.iter().enumerate() {
+            let mut line = format!("{:name$}{:>width$}", name, x_cells[index], name = NAME_WIDTH, width = COLUMN_WIDTH);
+            if let Some(y_cells) = &y_cells {
+                line.push_str(&format!("{:>width$}", y_cells[index], width = COLUMN_WIDTH));
+            }
+            lines.push(line);
+        }
+        if data_set.kind == DataKind::Paired {
+            lines.push(String::new());
+            match data_set.regression() {
+                Ok(regression) => {
+                    lines.push(format!(
+                        "{:name$}y = {} · x + {}",
+                        "Linear regression",
+                        number(&regression.slope),
+                        number(&regression.intercept),
+                        name = NAME_WIDTH
+                    ));
+                    lines.push(format!(
+                        "{:name$}{}",
+                        "Correlation r",
+                        optional(&regression.correlation),
+                        name = NAME_WIDTH
+                    ));
+                    lines.push(format!(
+                        "{:name$}{}",
+                        "Determination r²",
+                        optional(&regression.r_squared),
+                        name = NAME_WIDTH
+                    ));
+                }
+                Err(_) => lines.push("The regression needs two different x values".to_string()),
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl Default for StatisticsPage {
+    /// The default implementation of the [`StatisticsPage`] widget. It creates a new [`StatisticsPage`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Formats the statistics of a list of values, in the order of [`STATISTICS`]. Several modes are separated by
+/// semicolons, since commas may be decimal separators.
+fn summary_cells(summary: &Summary, format: &NumberFormat) -> Vec<String> {
+    let number = |number: &Number| format_number(number, format);
+    let optional = |value: Option<&Number>| value.map_or_else(|| UNDEFINED.to_string(), number);
+    let modes = if summary.modes.is_empty() {
+        UNDEFINED.to_string()
+    }
+    else {
+        summary
+            .modes
+            .iter()
+            .map(number)
+            .collect::<Vec<_>>()
+            .join("; ")
+    };
+    vec![
+        number(&summary.count),
+        number(&summary.sum),
+        number(&summary.mean),
+        number(&summary.median),
+        modes,
+        optional(summary.sample_variance.as_ref()),
+        optional(summary.sample_deviation.as_ref()),
+        number(&summary.population_variance),
+        number(&summary.population_deviation),
+        number(&summary.minimum),
+        optional(summary.quartiles.as_ref().map(|(first, _)| first)),
+        optional(summary.quartiles.as_ref().map(|(_, third)| third)),
+        number(&summary.maximum),
+    ]
+}
@@ -0,0 +1,404 @@
+//! This module contains the implementation of the [`TapePage`] widget. It shows the calculation tape of the keypad and
+//! of typed expressions, like the paper tape of an adding machine: every operand with the operator applied to it and
+//! the running subtotal, and the total of every completed calculation. Each line can be annotated. The tape can be
+//! printed, or saved as a PDF file from the print dialog, and saved as a plain text file. It is a subclass of
+//! [`gtk::Box`].
+
+use glib::{clone, Object};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::engine::{Calculation, Event, TapeLine, Uncertain};
+use crate::utils::{format_clock, format_complex, format_interval, format_uncertain, NumberFormat};
+
+/// The width of the columns of subtotals on the page, in characters.
+const SUBTOTAL_WIDTH: i32 = 16;
+
+/// The size of the font of the printed tape, in points.
+const PRINT_FONT_SIZE: f64 = 10.0;
+
+/// The height of the lines of the printed tape, in points.
+const PRINT_LINE_HEIGHT: f64 = 14.0;
+
+mod imp {
+    //! Private implementation details of the [`TapePage`] widget.
+
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Button, CompositeTemplate, Label, ListBox};
+
+    use crate::engine::Tape;
+    use crate::utils::NumberFormat;
+
+    /// The `TapePage` widget. It shows the calculation tape and its annotations. It is a subclass of [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/tape_page.ui")]
+    pub struct TapePage {
+        #[template_child]
+        pub print_button: TemplateChild<Button>,
+        #[template_child]
+        pub save_button: TemplateChild<Button>,
+        #[template_child]
+        pub clear_button: TemplateChild<Button>,
+        #[template_child]
+        pub status_label: TemplateChild<Label>,
+        #[template_child]
+        pub lines_list: TemplateChild<ListBox>,
+        pub tape: RefCell<Tape>,
+        pub number_format: Cell<NumberFormat>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TapePage {
+        type ParentType = gtk::Box;
+        type Type = super::TapePage;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "TapePage";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for TapePage {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for TapePage {}
+
+    impl BoxImpl for TapePage {}
+}
+
+glib::wrapper! {
+    /// A [`TapePage`] widget. It shows the calculation tape and its annotations. It is a subclass of [`gtk::Box`].
+    pub struct TapePage(ObjectSubclass<imp::TapePage>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl TapePage {
+    /// Creates a new [`TapePage`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Sets the format of the numbers of the tape, and shows it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_format` - The format of the numbers.
+    pub fn set_number_format(&self, number_format: NumberFormat) {
+        self.imp().number_format.set(number_format);
+        self.update();
+    }
+
+    /// Adds the events of the calculator to the end of the tape.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The events taken from the calculator, oldest first.
+    pub fn record(&self, events: Vec<Event>) {
+        if events.is_empty() {
+            return;
+        }
+        let imp = self.imp();
+        let start = imp.tape.borrow().lines().len();
+        imp.tape.borrow_mut().record(events);
+        // Only the new lines are added, so that the note being typed in an older line keeps the focus.
+        let lines = imp.tape.borrow().lines()[start..].to_vec();
+        for (index, line) in lines.iter().enumerate() {
+            imp.lines_list.append(&self.line_row(start + index, line));
+        }
+        self.update_buttons();
+    }
+
+    /// Connects the callbacks of the child widgets.
+    fn setup(&self) {
+        let imp = self.imp();
+        imp.print_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.print();
+            }));
+        imp.save_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.save();
+            }));
+        imp.clear_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.imp().tape.borrow_mut().clear();
+                page.show_status("");
+                page.update();
+            }));
+        self.update_buttons();
+    }
+
+    /// Shows all the lines of the tape again.
+    fn update(&self) {
+        let imp = self.imp();
+        imp.lines_list.remove_all();
+        let lines = imp.tape.borrow().lines().to_vec();
+        for (index, line) in lines.iter().enumerate() {
+            imp.lines_list.append(&self.line_row(index, line));
+        }
+        self.update_buttons();
+    }
+
+    /// Enables the buttons exporting and clearing the tape if it has lines.
+    fn update_buttons(&self) {
+        let imp = self.imp();
+        let empty = imp.tape.borrow().is_empty();
+        for button in [&imp.print_button, &imp.save_button, &imp.clear_button] {
+            button.set_sensitive(!empty);
+        }
+    }
+
+    /// Creates the row of a line of the tape, with an entry for its note.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the line in the tape.
+    /// * `line` - The line to show.
+    fn line_row(&self, index: usize, line: &TapeLine) -> gtk::Box {
+        let (operator, amount, subtotal) = self.cells(line);
+        let row = gtk::Box::builder()
+            .spacing(12)
+            .margin_start(12)
+            .margin_end(6)
+            .margin_top(3)
+            .margin_bottom(3)
+            .build();
+        let operator_label = gtk::Label::builder()
+            .label(operator)
+            .width_chars(1)
+            .css_classes(["monospace"])
+            .build();
+        let amount_label = gtk::Label::builder()
+            .label(amount)
+            .xalign(1.0)
+            .hexpand(true)
+            .selectable(true)
+            .ellipsize(gtk::pango::EllipsizeMode::Start)
+            .css_classes(["monospace"])
+            .build();
+        match line.event {
+            Event::Total(_) => amount_label.add_css_class("heading"),
+            Event::Failed(_) => amount_label.add_css_class("error"),
+            Event::Operand { .. } | Event::Cleared => {}
+        }
+        let subtotal_label = gtk::Label::builder()
+            .label(subtotal)
+            .xalign(1.0)
+            .width_chars(SUBTOTAL_WIDTH)
+            .css_classes(["monospace", "dim-label"])
+            .build();
+        let annotation_entry = gtk::Entry::builder()
+            .text(line.annotation.as_str())
+            .placeholder_text("Note")
+            .width_chars(12)
+            .valign(gtk::Align::Center)
+            .build();
+        annotation_entry.connect_changed(clone!(@weak self as page => move |entry| {
+            page.imp().tape.borrow_mut().annotate(index, &entry.text());
+        }));
+        row.append(&operator_label);
+        row.append(&amount_label);
+        row.append(&subtotal_label);
+        row.append(&annotation_entry);
+        row
+    }
+
+    /// Returns the operator, the amount and the subtotal shown by a line of the tape. Totals are marked with `=`,
+    /// cleared calculations with `C` and failed ones with `E`.
+    fn cells(&self, line: &TapeLine) -> (String, String, String) {
+        let format = self.imp().number_format.get();
+        match &line.event {
+            Event::Operand {
+                operator,
+                operand,
+                subtotal,
+            } => {
+                (
+                    operator.map_or("", |operator| operator.symbol()).to_string(),
+                    format.locale.localize_expression(operand),
+                    format_complex(subtotal, &format),
+                )
+            }
+            Event::Total(calculation) => ("=".to_string(), format_calculation(calculation, &format), String::new()),
+            Event::Cleared => ("C".to_string(), String::new(), String::new()),
+            Event::Failed(error) => ("E".to_string(), error.to_string(), String::new()),
+        }
+    }
+
+    /// Returns the lines of the tape as text, with the amounts and the subtotals right-aligned in columns and the
+    /// notes after them.
+    fn text_lines(&self) -> Vec<String> {
+        let lines = self.imp().tape.borrow().lines().to_vec();
+        let cells = lines
+            .iter()
+            .map(|line| self.cells(line))
+            .collect::<Vec<(String, String, String)>>();
+        let amount_width = cells
+            .iter()
+            .map(|(_, amount, _)| amount.chars().count())
+            .max()
+            .unwrap_or_default();
+        let subtotal_width = cells
+            .iter()
+            .map(|(_, _, subtotal)| subtotal.chars().count())
+            .max()
+            .unwrap_or_default();
+        cells
+            .iter()
+            .zip(&lines)
+            .map(|((operator, amount, subtotal), line)| {
+                format!(
+                    "{:1} {:>amount_width$}  {:>subtotal_width$}  {}",
+                    operator,
+                    amount,
+                    subtotal,
+                    line.annotation,
+                    amount_width = amount_width,
+                    subtotal_width = subtotal_width
+                )
+                .trim_end()
+                .to_string()
+            })
+            .collect()
+    }
+
+    /// Opens the print dialog to print the tape, which can also save it as a PDF file.
+    fn print(&self) {
+        let lines = self.text_lines();
+        let line_count = lines.len();
+        let operation = gtk::PrintOperation::builder()
+            .job_name("Calculation Tape")
+            .unit(gtk::Unit::Points)
+            .build();
+        operation.connect_begin_print(move |operation, context| {
+            let lines_per_page = lines_per_page(context.height());
+            operation.set_n_pages(line_count.div_ceil(lines_per_page).max(1) as i32);
+        });
+        operation.connect_draw_page(move |_, context, page| {
+            let lines_per_page = lines_per_page(context.height());
+            let cr = context.cairo_context();
+            cr.set_source_rgb(0.0, 0.0, 0.0);
+            cr.select_font_face("Monospace", cairo::FontSlant::Normal, cairo::FontWeight::Normal);
+            cr.set_font_size(PRINT_FONT_SIZE);
+            let page_lines = lines
+                .iter()
+                .skip(page as usize * lines_per_page)
+                .take(lines_per_page);
+            for (index, line) in page_lines.enumerate() {
+                cr.move_to(0.0, (index + 1) as f64 * PRINT_LINE_HEIGHT);
+                // Errors of cairo leave the page incomplete, and there is nothing else to do about them.
+                let _ = cr.show_text(line);
+            }
+        });
+        let window = self.root().and_downcast::<gtk::Window>();
+        if let Err(error) = operation.run(gtk::PrintOperationAction::PrintDialog, window.as_ref()) {
+            self.show_status(&format!("Could not print the tape: {}", error));
+        }
+    }
+
+    /// Asks for a file and saves the tape in it as plain text.
+    fn save(&self) {
+        let mut text = self.text_lines().join("\n");
+        text.push('\n');
+        let dialog = gtk::FileDialog::builder()
+            .title("Save Tape")
+            .initial_name("tape.txt")
+            .modal(true)
+            .build();
+        let window = self.root().and_downcast::<gtk::Window>();
+        dialog.save(
+            window.as_ref(),
+            gio::Cancellable::NONE,
+            clone!(@weak self as page => move |result| {
+                let Ok(file) = result
+                else {
+                    return;
+                };
+                if let Err(error) = file.replace_contents(
+                    text.as_bytes(),
+                    None,
+                    false,
+                    gio::FileCreateFlags::REPLACE_DESTINATION,
+                    gio::Cancellable::NONE,
+                ) {
+                    page.show_status(&format!("Could not save the tape: {}", error));
+                }
+            }),
+        );
+    }
+
+    /// Shows a message above the tape, or hides it if the message is empty.
+    fn show_status(&self, message: &str) {
+        let status_label = &self.imp().status_label;
+        status_label.set_label(message);
+        status_label.set_visible(!message.is_empty());
+    }
+}
+
+impl Default for TapePage {
+    /// The default implementation of the [`TapePage`] widget. It creates a new [`TapePage`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the number of lines of the printed tape that fit on a page.
+///
+/// # Arguments
+///
+/// * `height` - The height of the printable area of the page, in points.
+fn lines_per_page(height: f64) -> usize {
+    ((height / PRINT_LINE_HEIGHT).floor() as usize).max(1)
+}
+
+/// Formats the result of a calculation as the history shows it, with its units.
+///
+/// # Arguments
+///
+/// * `calculation` - The completed calculation.
+/// * `format` - The format of the numbers.
+fn format_calculation(calculation: &Calculation, format: &NumberFormat) -> String {
+    let units = &calculation.units;
+    let mut result = if let Some(factorization) = &calculation.factorization {
+        factorization.to_string()
+    }
+    else if let Some(uncertainty) = &calculation.uncertainty {
+        format_uncertain(
+            &Uncertain {
+                value:       calculation.result.re.clone(),
+                uncertainty: uncertainty.clone(),
+            },
+            format,
+        )
+    }
+    else if let Some(interval) = &calculation.interval {
+        format_interval(interval, format)
+    }
+    else if units.is_clock() {
+        format_clock(&calculation.result.re, format)
+    }
+    else {
+        format_complex(&calculation.result, format)
+    };
+    if !units.is_empty() && !units.is_clock() {
+        result = format!("{} {}", result, units);
+    }
+    result
+}
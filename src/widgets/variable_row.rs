@@ -0,0 +1,112 @@
+//! This module contains the implementation of the [`VariableRow`] widget. It displays a single variable or
+//! user-defined function: its name, an entry to edit its value or body and a button to delete it. It is a subclass of
+//! [`gtk::Box`].
+
+use glib::Object;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::function_object::FunctionObject;
+use crate::variable_object::VariableObject;
+
+mod imp {
+    //! Private implementation details of the [`VariableRow`] widget.
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Button, CompositeTemplate, Entry, Label};
+
+    /// The `VariableRow` widget. It displays a single variable or user-defined function: its name, an entry to edit
+    /// its value or body and a button to delete it. It is a subclass of [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/variable_row.ui")]
+    pub struct VariableRow {
+        #[template_child]
+        pub name_label:    TemplateChild<Label>,
+        #[template_child]
+        pub value_entry:   TemplateChild<Entry>,
+        #[template_child]
+        pub delete_button: TemplateChild<Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for VariableRow {
+        type ParentType = gtk::Box;
+        type Type = super::VariableRow;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "VariableRow";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for VariableRow {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+        }
+    }
+
+    impl WidgetImpl for VariableRow {}
+
+    impl BoxImpl for VariableRow {}
+}
+
+glib::wrapper! {
+    /// A [`VariableRow`] widget. It displays a single variable or user-defined function: its name, an entry to edit
+    /// its value or body and a button to delete it. It is a subclass of [`gtk::Box`].
+    pub struct VariableRow(ObjectSubclass<imp::VariableRow>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl VariableRow {
+    /// Creates a new [`VariableRow`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Shows the given variable in the row. The value is written with `.` as decimal point and without separators, as
+    /// read by the expression parser, so it can be edited in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable_object` - The variable to show.
+    pub fn bind(&self, variable_object: &VariableObject) {
+        self.imp().name_label.set_label(&variable_object.name());
+        self.imp()
+            .value_entry
+            .set_text(&variable_object.value().to_string());
+    }
+
+    /// Shows the given user-defined function in the row: its signature (e.g. `f(x, y)`) and its body as typed.
+    ///
+    /// # Arguments
+    ///
+    /// * `function_object` - The function to show.
+    pub fn bind_function(&self, function_object: &FunctionObject) {
+        let function = function_object.function();
+        self.imp().name_label.set_label(&function.signature());
+        self.imp().value_entry.set_text(&function.source);
+        self.imp()
+            .value_entry
+            .set_tooltip_text(Some("Press Enter to redefine the function"));
+        self.imp()
+            .delete_button
+            .set_tooltip_text(Some("Delete Function"));
+    }
+}
+
+impl Default for VariableRow {
+    /// The default implementation of the [`VariableRow`] widget. It creates a new [`VariableRow`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,20 +1,56 @@
 //! This module contains the implementation of the [`Window`] object. The [`Window`] object is a subclass of
 //! [`adw::ApplicationWindow`] and is the main window of the application.
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Read;
 
+use adw::prelude::*;
 use adw::subclass::prelude::*;
 use gdk::Key;
 use gio::ActionEntry;
 use glib::{clone, Object};
 use gtk::glib::object::Cast;
-use gtk::prelude::*;
-use gtk::{gdk, gio, glib, EventControllerKey, NoSelection};
-use toml_edit::DocumentMut;
+use gtk::{gdk, gio, glib, pango, EventControllerKey, NoSelection};
+use toml_edit::{table, value, DocumentMut, InlineTable, Value};
 
-use crate::integer_object::IntegerObject;
-use crate::utils::{display_thousands_separator, settings_path};
-use crate::widgets::Skeleton;
+use crate::engine::{
+    AngleUnit, Calculation, Calculator, Context, Display, Error, Function, Matrix, Number, Operator, Quantity,
+    Statement, Uncertain, UserFunction, Units, Viewport, CONSTANTS,
+};
+use crate::function_object::FunctionObject;
+use crate::history_object::HistoryObject;
+use crate::utils::{
+    display_scientific, format_clock, format_compact, format_complex, format_interval, format_number, format_uncertain,
+    functions_path, settings_path, variables_path, LocaleSetting, Notation, NumberFormat,
+};
+use crate::variable_object::VariableObject;
+use crate::widgets::{ConstantRow, HistoryRow, Preferences, VariableRow};
+
+/// The font size of the input display in pixels. Matches the `.input-display` font size in `style.css`.
+const DISPLAY_MAX_FONT_SIZE: f64 = 64.0;
+
+/// The smallest font size in pixels the input display shrinks to before falling back to scientific notation.
+const DISPLAY_MIN_FONT_SIZE: f64 = 24.0;
+
+/// The largest number of mantissa digits shown when the input display falls back to scientific notation.
+const DISPLAY_MAX_PRECISION: usize = 15;
+
+/// The largest number of calculations kept in the history. The oldest calculations that are not pinned are removed
+/// beyond it.
+const HISTORY_LIMIT: u32 = 1000;
+
+/// The stateful actions that show results in another notation while they are active, with the notation they select.
+/// At most one of them is active at a time.
+const RESULT_NOTATION_ACTIONS: [(&str, Notation); 3] = [
+    ("exponent-notation", Notation::Scientific),
+    ("fraction-view", Notation::Fraction),
+    ("dms-view", Notation::Dms),
+];
+
+/// The keypad modes selected by the `keypad-mode` action, as stored in the settings file. The scientific mode shows
+/// the scientific keypad next to the basic one; the financial and solver modes show the financial page and the
+/// equation solver instead of the basic keypad.
+const KEYPAD_MODES: [&str; 4] = ["basic", "scientific", "financial", "solver"];
 
 mod imp {
     //! The private implementation details of the [`Window`] object.
@@ -27,11 +63,18 @@ mod imp {
     use glib::subclass::InitializingObject;
     use glib::SignalHandlerId;
     use gtk::prelude::*;
-    use gtk::{gio, glib, Box, Button, CompositeTemplate, Expander, ListBox, Notebook, Text};
-    use toml_edit::{table, value, DocumentMut};
+    use gtk::{
+        gio, glib, Box, Button, CompositeTemplate, Entry, Expander, Label, ListBox, MenuButton, Notebook, SearchEntry,
+        Stack, Text,
+    };
+    use toml_edit::{table, value, Array, DocumentMut};
 
-    use crate::utils::settings_path;
-    use crate::widgets::{BasicNumpad, Skeleton};
+    use crate::engine::{Calculator, UndoStack};
+    use crate::utils::{settings_path, LocaleSetting, Notation, NumberFormat};
+    use crate::widgets::{
+        BasicNumpad, ConversionPage, DatePage, FinancialPage, MatrixPage, PlotPage, ScientificNumpad, SolverPage,
+        StatisticsPage, TapePage, WorksheetPage,
+    };
 
     /// The `Window` widget. It is the main window of the application.
     /// 
@@ -40,12 +83,50 @@ mod imp {
     /// The `Window` implements the following actions:
     /// * `num-insert` - Inserts a number into the display.
     /// * `op-insert` - Inserts an operator into the display.
+    /// * `exponent-notation` - Toggles scientific notation for results.
+    /// * `fraction-view` - Toggles between fractions and decimals for results.
+    /// * `dms-view` - Toggles degrees, minutes and seconds for results.
+    /// * `angle-unit` - Sets the angle unit of the trigonometric functions.
+    /// * `complex-mode` - Toggles complex results of functions outside of their real domain.
+    /// * `polar-view` - Toggles the polar form of complex results.
+    /// * `interval-mode` - Toggles intervals in typed expressions (e.g. `[1.2, 1.5]`).
+    /// * `keypad-mode` - Switches between the basic and scientific keypads and the financial and solver pages.
+    /// * `custom-key` - Chooses the user-defined function of the custom key.
+    /// * `custom-function` - Applies the user-defined function of the custom key.
+    /// * `undo` - Steps back to the previous state of the calculator.
+    /// * `redo` - Steps forward to the state of the calculator that was last undone.
+    /// * `copy` - Copies the displayed value to the clipboard.
+    /// * `preferences` - Shows the preferences dialog.
     #[derive(CompositeTemplate, Default)]
     #[template(resource = "/com/nc/calculator/window.ui")]
     pub struct Window {
         #[template_child]
         pub mem_hist_list:                TemplateChild<ListBox>,
         #[template_child]
+        pub history_search:               TemplateChild<SearchEntry>,
+        #[template_child]
+        pub variables_list:               TemplateChild<ListBox>,
+        #[template_child]
+        pub functions_list:               TemplateChild<ListBox>,
+        #[template_child]
+        pub constants_list:               TemplateChild<ListBox>,
+        #[template_child]
+        pub constants_search:             TemplateChild<SearchEntry>,
+        #[template_child]
+        pub conversion_window:            TemplateChild<ConversionPage>,
+        #[template_child]
+        pub date_page:                    TemplateChild<DatePage>,
+        #[template_child]
+        pub statistics_page:              TemplateChild<StatisticsPage>,
+        #[template_child]
+        pub plot_page:                    TemplateChild<PlotPage>,
+        #[template_child]
+        pub matrix_page:                  TemplateChild<MatrixPage>,
+        #[template_child]
+        pub worksheet_page:               TemplateChild<WorksheetPage>,
+        #[template_child]
+        pub tape_page:                    TemplateChild<TapePage>,
+        #[template_child]
         pub tabs:                         TemplateChild<Notebook>,
         #[template_child]
         pub expander_keypad:              TemplateChild<Expander>,
@@ -58,12 +139,47 @@ mod imp {
         #[template_child]
         pub keypad_lock:                  TemplateChild<Button>,
         #[template_child]
+        pub angle_label:                  TemplateChild<Label>,
+        #[template_child]
+        pub complex_label:                TemplateChild<Label>,
+        #[template_child]
+        pub interval_label:               TemplateChild<Label>,
+        #[template_child]
+        pub memory_label:                 TemplateChild<Label>,
+        #[template_child]
+        pub units_label:                  TemplateChild<Label>,
+        #[template_child]
+        pub expression_label:             TemplateChild<Label>,
+        #[template_child]
+        pub full_result_button:           TemplateChild<MenuButton>,
+        #[template_child]
+        pub full_result_label:            TemplateChild<Label>,
+        #[template_child]
         pub input_display:                TemplateChild<Text>,
         #[template_child]
+        pub expression_entry:             TemplateChild<Entry>,
+        #[template_child]
         pub basic_numpad:                 TemplateChild<BasicNumpad>,
+        #[template_child]
+        pub scientific_numpad:            TemplateChild<ScientificNumpad>,
+        #[template_child]
+        pub keypad_stack:                 TemplateChild<Stack>,
+        #[template_child]
+        pub financial_page:               TemplateChild<FinancialPage>,
+        #[template_child]
+        pub solver_page:                  TemplateChild<SolverPage>,
         pub input_display_changed_signal: RefCell<Option<SignalHandlerId>>,
+        pub input_display_edited:         Cell<bool>,
+        pub calculator:                   RefCell<Calculator>,
+        pub undo_stack:                   RefCell<UndoStack<Calculator>>,
+        pub number_format:                Cell<NumberFormat>,
+        pub locale_setting:               Cell<LocaleSetting>,
+        pub result_notation:              Cell<Option<Notation>>,
         pub persistent_keypad:            Cell<bool>,
         pub history:                      RefCell<Option<gio::ListStore>>,
+        pub variables:                    RefCell<Option<gio::ListStore>>,
+        pub functions:                    RefCell<Option<gio::ListStore>>,
+        pub custom_key:                   RefCell<String>,
     }
 
     #[glib::object_subclass]
@@ -75,8 +191,17 @@ mod imp {
         const NAME: &'static str = "MainWindow";
 
         fn class_init(klass: &mut Self::Class) {
-            Skeleton::ensure_type();
+            ConversionPage::ensure_type();
+            DatePage::ensure_type();
+            StatisticsPage::ensure_type();
+            PlotPage::ensure_type();
+            MatrixPage::ensure_type();
+            WorksheetPage::ensure_type();
+            TapePage::ensure_type();
+            FinancialPage::ensure_type();
+            SolverPage::ensure_type();
             BasicNumpad::ensure_type();
+            ScientificNumpad::ensure_type();
 
             klass.bind_template();
             klass.bind_template_callbacks();
@@ -120,8 +245,9 @@ mod imp {
         /// Callback for the `on_expander_history_expanded` signal.
         /// 
         /// If the history expander is expanded, it will show the tabs and hide the keypad buttons if persistent keypad
-        /// is `false`. If the history expander is collapsed and the convert expander is not expanded, it will
-        /// hide the tabs. If the history expander is expanded and the convert expander was expanded, it will
+        /// is `false`. The history page is selected unless another page than the conversion page is already selected
+        /// (e.g. the variables page). If the history expander is collapsed and the convert expander is not expanded,
+        /// it will hide the tabs. If the history expander is expanded and the convert expander was expanded, it will
         /// collapse the convert expander.
         /// 
         /// # Arguments
@@ -130,7 +256,9 @@ mod imp {
         #[template_callback]
         fn on_expander_history_expanded(&self, _p: glib::ParamSpec) {
             if self.expander_history.is_expanded() {
-                self.tabs.set_current_page(Some(0));
+                if self.tabs.current_page() == self.tabs.page_num(&*self.conversion_window) {
+                    self.tabs.set_current_page(Some(0));
+                }
                 self.show_tabs(true);
                 if !self.persistent_keypad.get() && self.expander_keypad.is_expanded() {
                     self.expander_keypad.set_expanded(false);
@@ -156,7 +284,8 @@ mod imp {
         #[template_callback]
         fn on_expander_convert_expanded(&self, _p: glib::ParamSpec) {
             if self.expander_convert.is_expanded() {
-                self.tabs.set_current_page(Some(1));
+                self.tabs
+                    .set_current_page(self.tabs.page_num(&*self.conversion_window));
                 self.show_tabs(true);
                 if !self.persistent_keypad.get() && self.expander_keypad.is_expanded() {
                     self.expander_keypad.set_expanded(false);
@@ -310,7 +439,9 @@ mod imp {
             obj.setup_actions();
             obj.setup_event_controllers();
             obj.setup_history();
-            obj.create_rows();
+            obj.setup_variables();
+            obj.setup_functions();
+            obj.setup_constants();
         }
     }
 
@@ -327,19 +458,48 @@ mod imp {
             settings_table["history_expanded"] = value(self.expander_history.is_expanded());
             settings_table["convert_expanded"] = value(self.expander_convert.is_expanded());
 
+            // Number format settings
+            let number_format = self.number_format.get();
+            settings_table["notation"] = value(number_format.notation.name());
+            settings_table["precision"] =
+                value(i64::try_from(number_format.precision).expect("Cannot convert precision to i64"));
+            settings_table["si_prefixes"] = value(number_format.si_prefixes);
+            settings_table["max_denominator"] = value(
+                i64::try_from(number_format.max_denominator).expect("Cannot convert max_denominator to i64"),
+            );
+            settings_table["locale"] = value(self.locale_setting.get().name());
+            settings_table["polar"] = value(number_format.polar);
+            settings_table["angle_unit"] = value(self.calculator.borrow().angle_unit().name());
+            settings_table["complex_mode"] = value(self.calculator.borrow().complex_mode());
+            settings_table["interval_mode"] = value(self.calculator.borrow().interval_mode());
+            settings_table["custom_key"] = value(self.custom_key.borrow().as_str());
+            settings_table["keypad_mode"] = value(self.obj().keypad_mode());
+
             // Window Settings
             let mut window_settings = table();
             let (w, _h) = self.obj().default_size();
             window_settings["width"] = value(i64::try_from(w).expect("Cannot convert width to i64"));
             window_settings["is_maximized"] = value(self.obj().is_maximized());
 
+            // Plot settings
+            let mut plot_settings = table();
+            let viewport = self.plot_page.viewport();
+            plot_settings["x_min"] = value(viewport.x_min);
+            plot_settings["x_max"] = value(viewport.x_max);
+            plot_settings["y_min"] = value(viewport.y_min);
+            plot_settings["y_max"] = value(viewport.y_max);
+            plot_settings["functions"] = value(self.plot_page.functions().iter().collect::<Array>());
+
             let mut doc = DocumentMut::new();
             doc.insert("settings", settings_table);
             doc.insert("window", window_settings);
+            doc.insert("plot", plot_settings);
 
             let mut file = File::create(settings_path()).expect("Failed to create settings file");
-            file.write(doc.to_string().as_bytes())
+            file.write_all(doc.to_string().as_bytes())
                 .expect("Failed to write settings file");
+            self.obj().save_variables();
+            self.obj().save_functions();
 
             // Pass close request on to the parent
             self.parent_close_request()
@@ -361,6 +521,20 @@ glib::wrapper! {
     /// The [`Window`] implements the following actions:
     /// * `num-insert` - Inserts a number into the display.
     /// * `op-insert` - Inserts an operator into the display.
+    /// * `exponent-notation` - Toggles scientific notation for results.
+    /// * `fraction-view` - Toggles between fractions and decimals for results.
+    /// * `dms-view` - Toggles degrees, minutes and seconds for results.
+    /// * `angle-unit` - Sets the angle unit of the trigonometric functions.
+    /// * `complex-mode` - Toggles complex results of functions outside of their real domain.
+    /// * `polar-view` - Toggles the polar form of complex results.
+    /// * `interval-mode` - Toggles intervals in typed expressions (e.g. `[1.2, 1.5]`).
+    /// * `keypad-mode` - Switches between the basic and scientific keypads and the financial and solver pages.
+    /// * `custom-key` - Chooses the user-defined function of the custom key.
+    /// * `custom-function` - Applies the user-defined function of the custom key.
+    /// * `undo` - Steps back to the previous state of the calculator.
+    /// * `redo` - Steps forward to the state of the calculator that was last undone.
+    /// * `copy` - Copies the displayed value to the clipboard.
+    /// * `preferences` - Shows the preferences dialog.
     pub struct Window(ObjectSubclass<imp::Window>)
         @extends adw::ApplicationWindow, gtk::ApplicationWindow, gtk::Window, gtk::Widget,
         @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
@@ -421,6 +595,60 @@ impl Window {
                 .expect("Failed to get convert_expanded value")
                 .as_bool()
                 .expect("Failed to get convert_expanded as bool");
+            let locale_setting = settings
+                .get("locale")
+                .and_then(|locale| locale.as_str())
+                .and_then(LocaleSetting::from_name)
+                .unwrap_or_default();
+            let angle_unit = settings
+                .get("angle_unit")
+                .and_then(|angle_unit| angle_unit.as_str())
+                .and_then(AngleUnit::from_name)
+                .unwrap_or_default();
+            let complex_mode = settings
+                .get("complex_mode")
+                .and_then(|complex_mode| complex_mode.as_bool())
+                .unwrap_or_default();
+            let interval_mode = settings
+                .get("interval_mode")
+                .and_then(|interval_mode| interval_mode.as_bool())
+                .unwrap_or_default();
+            let custom_key = settings
+                .get("custom_key")
+                .and_then(|custom_key| custom_key.as_str())
+                .unwrap_or_default();
+            let keypad_mode = settings
+                .get("keypad_mode")
+                .and_then(|keypad_mode| keypad_mode.as_str())
+                .unwrap_or_default();
+            let default_format = NumberFormat::default();
+            let number_format = NumberFormat {
+                notation: settings
+                    .get("notation")
+                    .and_then(|notation| notation.as_str())
+                    .and_then(Notation::from_name)
+                    .unwrap_or(default_format.notation),
+                precision: settings
+                    .get("precision")
+                    .and_then(|precision| precision.as_integer())
+                    .and_then(|precision| usize::try_from(precision).ok())
+                    .unwrap_or(default_format.precision),
+                si_prefixes: settings
+                    .get("si_prefixes")
+                    .and_then(|si_prefixes| si_prefixes.as_bool())
+                    .unwrap_or(default_format.si_prefixes),
+                max_denominator: settings
+                    .get("max_denominator")
+                    .and_then(|max_denominator| max_denominator.as_integer())
+                    .and_then(|max_denominator| u64::try_from(max_denominator).ok())
+                    .unwrap_or(default_format.max_denominator),
+                locale: locale_setting.locale(),
+                polar: settings
+                    .get("polar")
+                    .and_then(|polar| polar.as_bool())
+                    .unwrap_or(default_format.polar),
+                ..default_format
+            };
 
             // Get window settings
             let window_settings = doc.get("window").expect("Failed to get window table");
@@ -440,7 +668,44 @@ impl Window {
                 .as_bool()
                 .expect("Failed to get maximized as bool");
 
+            // Get plot settings, which are missing from settings files of older versions
+            if let Some(plot_settings) = doc.get("plot") {
+                let bound = |name: &str, default: f64| {
+                    plot_settings
+                        .get(name)
+                        .and_then(|bound| bound.as_float())
+                        .unwrap_or(default)
+                };
+                let default_viewport = Viewport::default();
+                imp.plot_page.set_viewport(Viewport {
+                    x_min: bound("x_min", default_viewport.x_min),
+                    x_max: bound("x_max", default_viewport.x_max),
+                    y_min: bound("y_min", default_viewport.y_min),
+                    y_max: bound("y_max", default_viewport.y_max),
+                });
+                let functions = plot_settings
+                    .get("functions")
+                    .and_then(|functions| functions.as_array())
+                    .map(|functions| {
+                        functions
+                            .iter()
+                            .filter_map(|function| function.as_str())
+                            .map(str::to_string)
+                            .collect::<Vec<String>>()
+                    })
+                    .unwrap_or_default();
+                imp.plot_page.set_functions(&functions);
+            }
+
             // Set settings
+            imp.number_format.set(number_format);
+            self.set_pages_number_format(number_format);
+            imp.locale_setting.set(locale_setting);
+            imp.calculator.borrow_mut().set_angle_unit(angle_unit);
+            imp.calculator.borrow_mut().set_complex_mode(complex_mode);
+            imp.calculator.borrow_mut().set_interval_mode(interval_mode);
+            self.set_keypad_mode(keypad_mode);
+            imp.custom_key.replace(custom_key.to_string());
             imp.persistent_keypad.set(persistent_keypad);
             imp.keypad_lock.set_icon_name(
                 if persistent_keypad {
@@ -505,6 +770,7 @@ impl Window {
             // Set default window settings
             self.set_default_size(675, 76);
         }
+        self.update_decimal_button();
     }
 
     /// The [`gio::ListStore`] representing the calculation history.
@@ -524,163 +790,1400 @@ impl Window {
             .expect("Could not get current mem_hist")
     }
 
-    /// Sets up the history list on first creation.
+    /// Sets up the history list on first creation. The list shows the calculations matching the search entry, under
+    /// the headers of their days.
     fn setup_history(&self) {
-        let model = gio::ListStore::new::<IntegerObject>();
-        self.imp().history.replace(Some(model));
+        let imp = self.imp();
+        let model = gio::ListStore::new::<HistoryObject>();
+        imp.history.replace(Some(model));
 
-        let selection_model = NoSelection::new(Some(self.history()));
-        self.imp().mem_hist_list.bind_model(
+        // The lines of the worksheet may use the previous results
+        self.history()
+            .connect_items_changed(clone!(@weak self as window => move |_, _, _, _| {
+                window.imp().worksheet_page.refresh();
+            }));
+        let filter = gtk::CustomFilter::new(clone!(@weak self as window => @default-return true, move |object| {
+            object
+                .downcast_ref::<HistoryObject>()
+                .is_none_or(|history_object| window.history_matches(history_object))
+        }));
+        let filter_model = gtk::FilterListModel::new(Some(self.history()), Some(filter.clone()));
+        imp.history_search
+            .connect_search_changed(move |_| filter.changed(gtk::FilterChange::Different));
+        imp.mem_hist_list
+            .set_header_func(clone!(@weak filter_model => move |row, before| {
+                let day = |row: &gtk::ListBoxRow| {
+                    filter_model
+                        .item(row.index() as u32)
+                        .and_downcast::<HistoryObject>()
+                        .and_then(|history_object| history_object.time())
+                };
+                let time = day(row);
+                let same_day = match (&time, before.and_then(day)) {
+                    (Some(time), Some(before)) => {
+                        (time.year(), time.day_of_year()) == (before.year(), before.day_of_year())
+                    }
+                    _ => false,
+                };
+                let header = time.filter(|_| !same_day).map(|time| {
+                    gtk::Label::builder()
+                        .label(HistoryRow::day_label(&time))
+                        .xalign(0.0)
+                        .margin_top(6)
+                        .margin_start(12)
+                        .css_classes(["heading"])
+                        .build()
+                });
+                row.set_header(header.as_ref());
+            }));
+        let placeholder = gtk::Label::builder()
+            .label("No calculations match the search.")
+            .wrap(true)
+            .margin_top(12)
+            .margin_bottom(12)
+            .css_classes(["dim-label"])
+            .build();
+        imp.mem_hist_list.set_placeholder(Some(&placeholder));
+        let selection_model = NoSelection::new(Some(filter_model));
+        imp.mem_hist_list.bind_model(
             Some(&selection_model),
-            clone!(@weak self as window => @default-panic, move |_|{
-                let row = window.create_integer_row();
+            clone!(@weak self as window => @default-panic, move |obj|{
+                let history_object = obj
+                    .downcast_ref::<HistoryObject>()
+                    .expect("The object needs to be of type `HistoryObject`.");
+                let row = window.create_history_row(history_object);
                 row.upcast()
             }),
         );
     }
 
+    /// Returns `true` if the expression, the result or the annotation of a calculation contains the text of the history
+    /// search entry, ignoring case. Every calculation matches an empty search.
+    ///
+    /// # Arguments
+    ///
+    /// * `history_object` - The calculation to match.
+    fn history_matches(&self, history_object: &HistoryObject) -> bool {
+        let query = self.imp().history_search.text().trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        let format = self.display_format();
+        let expression = history_object.expression();
+        [
+            expression.clone(),
+            format.locale.localize_expression(&expression),
+            HistoryRow::result_text(history_object, &format),
+            history_object.annotation(),
+        ]
+        .iter()
+        .any(|text| text.to_lowercase().contains(&query))
+    }
+
+    /// Adds a calculation to the top of the history, and removes the oldest calculations that are not pinned beyond
+    /// [`HISTORY_LIMIT`].
+    ///
+    /// # Arguments
+    ///
+    /// * `calculation` - The completed calculation.
+    fn add_to_history(&self, calculation: Calculation) {
+        let history = self.history();
+        history.insert(0, &HistoryObject::new(calculation));
+        let mut position = history.n_items();
+        while history.n_items() > HISTORY_LIMIT && position > 0 {
+            position -= 1;
+            let pinned = history
+                .item(position)
+                .and_downcast::<HistoryObject>()
+                .is_some_and(|history_object| history_object.pinned());
+            if !pinned {
+                history.remove(position);
+            }
+        }
+    }
+
     /// Creates a new row widget for the history list.
     ///
+    /// # Arguments
+    ///
+    /// * `history_object` - The calculation shown by the row.
+    ///
+    /// # Returns
+    ///
+    /// The new row widget.
+    fn create_history_row(&self, history_object: &HistoryObject) -> HistoryRow {
+        let row = HistoryRow::new();
+        row.bind(history_object, &self.display_format());
+        row
+    }
+
+    /// The [`gio::ListStore`] holding the variables, sorted by name.
+    ///
+    /// # Returns
+    ///
+    /// The variables list store.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if the variables list store has not been set up.
+    fn variables(&self) -> gio::ListStore {
+        self.imp()
+            .variables
+            .borrow()
+            .clone()
+            .expect("Could not get variables")
+    }
+
+    /// Sets up the variables list on first creation and loads the variables saved by the last session.
+    fn setup_variables(&self) {
+        let model = gio::ListStore::new::<VariableObject>();
+        self.imp().variables.replace(Some(model));
+
+        let selection_model = NoSelection::new(Some(self.variables()));
+        self.imp().variables_list.bind_model(
+            Some(&selection_model),
+            clone!(@weak self as window => @default-panic, move |obj|{
+                let variable_object = obj
+                    .downcast_ref::<VariableObject>()
+                    .expect("The object needs to be of type `VariableObject`.");
+                let row = window.create_variable_row(variable_object);
+                row.upcast()
+            }),
+        );
+        let placeholder = gtk::Label::builder()
+            .label("No variables yet. Assign one by typing an expression like rate = 0.0725.")
+            .wrap(true)
+            .margin_top(12)
+            .margin_bottom(12)
+            .css_classes(["dim-label"])
+            .build();
+        self.imp().variables_list.set_placeholder(Some(&placeholder));
+
+        // The curves of the plot page and the lines of the worksheet may use the variables
+        self.variables()
+            .connect_items_changed(clone!(@weak self as window => move |_, _, _, _| {
+                window.imp().plot_page.refresh();
+                window.imp().worksheet_page.refresh();
+            }));
+        self.load_variables();
+    }
+
+    /// Creates a new row widget for the variables list. Activating the value entry of the row assigns the value of
+    /// the typed expression to the variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable_object` - The variable shown by the row.
+    ///
     /// # Returns
     ///
     /// The new row widget.
-    fn create_integer_row(&self) -> Skeleton {
-        let row = Skeleton::new();
+    fn create_variable_row(&self, variable_object: &VariableObject) -> VariableRow {
+        let row = VariableRow::new();
+        row.bind(variable_object);
+        row.imp().value_entry.connect_activate(
+            clone!(@weak self as window, @weak variable_object => move |entry| {
+                window.edit_variable(&variable_object, entry);
+            }),
+        );
+        row.imp().delete_button.connect_clicked(
+            clone!(@weak self as window, @weak variable_object => move |_| {
+                window.delete_variable(&variable_object.name());
+            }),
+        );
         row
     }
 
-    /// Inserts the given text into the input display.
+    /// Sets up the constants list: one row per constant of the library, filtered by the search entry. Activating a
+    /// row inserts the name of the constant into the expression entry.
+    fn setup_constants(&self) {
+        let imp = self.imp();
+        for constant in &CONSTANTS {
+            let row = ConstantRow::new();
+            row.bind(constant);
+            imp.constants_list.append(&row);
+        }
+        imp.constants_list
+            .set_filter_func(clone!(@weak self as window => @default-return true, move |row| {
+                let query = window.imp().constants_search.text();
+                usize::try_from(row.index())
+                    .ok()
+                    .and_then(|index| CONSTANTS.get(index))
+                    .is_none_or(|constant| constant.matches(&query))
+            }));
+        imp.constants_list
+            .connect_row_activated(clone!(@weak self as window => move |_list, row| {
+                if let Some(constant) = usize::try_from(row.index()).ok().and_then(|index| CONSTANTS.get(index)) {
+                    window.insert_expression_text(constant.name());
+                }
+            }));
+        imp.constants_search
+            .connect_search_changed(clone!(@weak self as window => move |_| {
+                window.imp().constants_list.invalidate_filter();
+            }));
+        let placeholder = gtk::Label::builder()
+            .label("No constants match the search.")
+            .wrap(true)
+            .margin_top(12)
+            .margin_bottom(12)
+            .css_classes(["dim-label"])
+            .build();
+        imp.constants_list.set_placeholder(Some(&placeholder));
+    }
+
+    /// Inserts text at the cursor of the expression entry and focuses the entry, so the expression can be completed.
     ///
     /// # Arguments
     ///
-    /// * `text` - The text to be inserted.
+    /// * `text` - The text to insert (e.g. the name of a constant).
+    fn insert_expression_text(&self, text: &str) {
+        let entry = &self.imp().expression_entry;
+        let mut position = entry.position();
+        entry.grab_focus_without_selecting();
+        entry.insert_text(text, &mut position);
+        entry.set_position(position);
+    }
+
+    /// Loads the variables from the variables file. Exact values are stored as strings (e.g. `"29/400"`) and
+    /// approximate values as floats. Values with units are stored as inline tables with the value and the units (e.g.
+    /// `{ value = "1.1144", units = "m" }`). Entries that cannot be read are skipped.
+    fn load_variables(&self) {
+        let Ok(contents) = std::fs::read_to_string(variables_path())
+        else {
+            return;
+        };
+        let Ok(doc) = contents.parse::<DocumentMut>()
+        else {
+            return;
+        };
+        let Some(variables) = doc.get("variables").and_then(|variables| variables.as_table())
+        else {
+            return;
+        };
+        for (name, item) in variables.iter() {
+            let (number, units) = match item.as_inline_table() {
+                Some(quantity) => (quantity.get("value"), quantity.get("units").and_then(|units| units.as_str())),
+                None => (item.as_value(), Some("")),
+            };
+            let value = match number.map(|number| (number.as_str(), number.as_float())) {
+                Some((Some(exact), _)) => exact.parse::<Number>().ok(),
+                Some((_, Some(approximate))) => Number::from_real(approximate).ok(),
+                _ => None,
+            };
+            let units = units.and_then(|units| units.parse::<Units>().ok());
+            if let (Some(value), Some(units)) = (value, units) {
+                self.set_variable(name, Quantity::new(value, units));
+            }
+        }
+    }
+
+    /// Saves the variables to the variables file.
     ///
     /// # Panics
     ///
-    /// * Panics if the input display changed signal cannot be retrieved.
-    fn insert_display_text(&self, text: &str) {
-        if self.imp().input_display.text().as_str() == "0" {
-            self.imp().input_display.block_signal(
-                &self
-                    .imp()
-                    .input_display_changed_signal
-                    .borrow()
-                    .as_ref()
-                    .expect("Could not get input_display_changed_signal"),
-            );
-            self.imp().input_display.set_text("");
-            self.imp().input_display.unblock_signal(
-                &self
-                    .imp()
-                    .input_display_changed_signal
-                    .borrow()
-                    .as_ref()
-                    .expect("Could not get input_display_changed_signal"),
-            );
+    /// * Panics if the variables file cannot be written.
+    fn save_variables(&self) {
+        let mut variables = table();
+        let store = self.variables();
+        for position in 0..store.n_items() {
+            let Some(variable) = store.item(position).and_downcast::<VariableObject>()
+            else {
+                continue;
+            };
+            let quantity = variable.value();
+            let number = match quantity.value {
+                Number::Rational(exact) => Value::from(exact.to_string()),
+                Number::Real(approximate) => Value::from(approximate),
+            };
+            variables[variable.name().as_str()] = if quantity.units.is_empty() {
+                value(number)
+            }
+            else {
+                let mut inline = InlineTable::new();
+                inline.insert("value", number);
+                inline.insert("units", Value::from(quantity.units.to_string()));
+                value(inline)
+            };
         }
-        let mut pos = -1;
-        self.imp().input_display.insert_text(text, &mut pos);
+        let mut doc = DocumentMut::new();
+        doc.insert("variables", variables);
+        std::fs::write(variables_path(), doc.to_string()).expect("Failed to write variables file");
     }
 
-    /// Sets the text of the input display.
+    /// Assigns a value to a variable, creating the variable if it does not exist yet.
     ///
     /// # Arguments
     ///
-    /// * `text` - The text to set the input display to.
-    fn set_display_text(&self, text: &str) {
-        self.imp().input_display.block_signal(
-            &self
-                .imp()
-                .input_display_changed_signal
-                .borrow()
-                .as_ref()
-                .expect("Could not get input_display_changed_signal"),
+    /// * `name` - The name of the variable.
+    /// * `value` - The new value of the variable.
+    fn set_variable(&self, name: &str, value: Quantity) {
+        let store = self.variables();
+        match position_by_name(&store, name) {
+            Ok(position) => {
+                if let Some(variable) = store.item(position).and_downcast::<VariableObject>() {
+                    variable.set_value(value);
+                    store.items_changed(position, 1, 1);
+                }
+            }
+            Err(position) => store.insert(position, &VariableObject::new(name, value)),
+        }
+    }
+
+    /// Deletes a variable. Does nothing if there is no variable with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the variable.
+    fn delete_variable(&self, name: &str) {
+        let store = self.variables();
+        if let Ok(position) = position_by_name(&store, name) {
+            store.remove(position);
+        }
+    }
+
+    /// Assigns the value of the expression typed in the value entry of a variable row to the variable. If the
+    /// expression cannot be evaluated, the entry is marked as erroneous and its tooltip shows the error until a valid
+    /// expression is typed.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable_object` - The edited variable.
+    /// * `entry` - The value entry of the row.
+    fn edit_variable(&self, variable_object: &VariableObject, entry: &gtk::Entry) {
+        entry.remove_css_class("error");
+        entry.set_tooltip_text(None);
+        let text = entry.text();
+        if text == variable_object.value().to_string() {
+            return;
+        }
+        let result = Statement::parse(&text).and_then(|statement| {
+            match statement {
+                Statement::Expression(expression) => self.with_context(|context| expression.evaluate(context)),
+                _ => Err(Error::Syntax(format!("Type only the new value of ‘{}’", variable_object.name()))),
+            }
+        });
+        match result {
+            Ok(value) => self.set_variable(&variable_object.name(), value),
+            Err(error) => {
+                entry.add_css_class("error");
+                entry.set_tooltip_text(Some(&error.to_string()));
+            }
+        }
+    }
+
+    /// The [`gio::ListStore`] holding the user-defined functions, sorted by name.
+    ///
+    /// # Returns
+    ///
+    /// The functions list store.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if the functions list store has not been set up.
+    fn functions(&self) -> gio::ListStore {
+        self.imp()
+            .functions
+            .borrow()
+            .clone()
+            .expect("Could not get functions")
+    }
+
+    /// Sets up the functions list on first creation and loads the functions saved by the last session.
+    fn setup_functions(&self) {
+        let model = gio::ListStore::new::<FunctionObject>();
+        self.imp().functions.replace(Some(model));
+
+        let selection_model = NoSelection::new(Some(self.functions()));
+        self.imp().functions_list.bind_model(
+            Some(&selection_model),
+            clone!(@weak self as window => @default-panic, move |obj|{
+                let function_object = obj
+                    .downcast_ref::<FunctionObject>()
+                    .expect("The object needs to be of type `FunctionObject`.");
+                let row = window.create_function_row(function_object);
+                row.upcast()
+            }),
         );
-        self.imp().input_display.set_text(text);
-        self.imp().input_display.unblock_signal(
-            &self
-                .imp()
-                .input_display_changed_signal
-                .borrow()
-                .as_ref()
-                .expect("Could not get input_display_changed_signal"),
+        let placeholder = gtk::Label::builder()
+            .label("No functions yet. Define one by typing an expression like f(x, y) = sqrt(x^2 + y^2).")
+            .wrap(true)
+            .margin_top(12)
+            .margin_bottom(12)
+            .css_classes(["dim-label"])
+            .build();
+        self.imp().functions_list.set_placeholder(Some(&placeholder));
+
+        // The curves of the plot page and the lines of the worksheet may use the functions
+        self.functions()
+            .connect_items_changed(clone!(@weak self as window => move |_, _, _, _| {
+                window.imp().plot_page.refresh();
+                window.imp().worksheet_page.refresh();
+            }));
+        self.load_functions();
+        self.update_custom_key();
+    }
+
+    /// Creates a new row widget for the functions list. Activating the entry of the row redefines the function with
+    /// the typed body.
+    ///
+    /// # Arguments
+    ///
+    /// * `function_object` - The function shown by the row.
+    ///
+    /// # Returns
+    ///
+    /// The new row widget.
+    fn create_function_row(&self, function_object: &FunctionObject) -> VariableRow {
+        let row = VariableRow::new();
+        row.bind_function(function_object);
+        row.imp().value_entry.connect_activate(
+            clone!(@weak self as window, @weak function_object => move |entry| {
+                window.edit_function(&function_object, entry);
+            }),
         );
+        row.imp().delete_button.connect_clicked(
+            clone!(@weak self as window, @weak function_object => move |_| {
+                window.delete_function(&function_object.name());
+            }),
+        );
+        row
     }
 
-    /// Sets up the event controllers for the [`Window`].
-    /// The event controllers are used to handle key presses and releases.
-    /// The key presses and releases are used to interact with the calculator.
-    fn setup_event_controllers(&self) {
-        let controller = EventControllerKey::builder()
-            .name("keypad-controller")
-            .propagation_phase(gtk::PropagationPhase::Target)
-            .propagation_limit(gtk::PropagationLimit::SameNative)
-            .build();
-        controller.connect_key_pressed(
-            clone!(@weak self as window => @default-return glib::Propagation::Stop, move |_controller, key, _keyval, _state| {
-                match key {
-                    Key::BackSpace => {
-                        window
-                            .imp()
-                            .basic_numpad
-                            .imp()
-                            .button_backspace
-                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
-                        if window.imp().input_display.text().as_str() != "0" {
-                            window
-                                .imp()
-                                .input_display
-                                .delete_text((window.imp().input_display.text().len() - 1) as i32, -1);
-                        }
-                    }
-                    Key::_0 | Key::KP_0 => {
-                        window
-                            .imp()
-                            .basic_numpad
-                            .imp()
-                            .button_zero
-                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
-                        window.insert_display_text("0");
-                    }
-                    Key::_1 | Key::KP_1 => {
-                        window
-                            .imp()
-                            .basic_numpad
-                            .imp()
-                            .button_one
-                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
-                        window.insert_display_text("1");
-                    }
-                    Key::_2 | Key::KP_2 => {
-                        window
-                            .imp()
-                            .basic_numpad
-                            .imp()
-                            .button_two
-                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
-                        window.insert_display_text("2");
-                    }
-                    Key::_3 | Key::KP_3 => {
-                        window
-                            .imp()
-                            .basic_numpad
-                            .imp()
-                            .button_three
-                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
-                        window.insert_display_text("3");
-                    }
-                    Key::_4 | Key::KP_4 => {
-                        window
-                            .imp()
-                            .basic_numpad
-                            .imp()
-                            .button_four
-                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
-                        window.insert_display_text("4");
-                    }
-                    Key::_5 | Key::KP_5 => {
-                        window
-                            .imp()
+    /// Loads the user-defined functions from the functions file, where each function is stored as its definition
+    /// (e.g. `f = "f(x, y) = sqrt(x^2 + y^2)"`). Definitions that cannot be parsed, or that do not pass the checks of
+    /// a typed definition once all functions are loaded, are skipped.
+    fn load_functions(&self) {
+        let Ok(contents) = std::fs::read_to_string(functions_path())
+        else {
+            return;
+        };
+        let Ok(doc) = contents.parse::<DocumentMut>()
+        else {
+            return;
+        };
+        let Some(functions) = doc.get("functions").and_then(|functions| functions.as_table())
+        else {
+            return;
+        };
+        for (_, item) in functions.iter() {
+            if let Some(Ok(Statement::Definition(function))) = item.as_str().map(Statement::parse) {
+                self.set_function(function);
+            }
+        }
+
+        // Check the functions only once all are loaded, as they may call each other. Removing a function may break
+        // the functions that call it, so check again until all remaining functions pass.
+        loop {
+            let invalid = self.with_context(|context| {
+                context
+                    .functions
+                    .values()
+                    .filter(|function| context.check(function).is_err())
+                    .map(|function| function.name.clone())
+                    .collect::<Vec<String>>()
+            });
+            if invalid.is_empty() {
+                break;
+            }
+            for name in invalid {
+                self.delete_function(&name);
+            }
+        }
+    }
+
+    /// Saves the user-defined functions to the functions file.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if the functions file cannot be written.
+    fn save_functions(&self) {
+        let mut functions = table();
+        let store = self.functions();
+        for position in 0..store.n_items() {
+            let Some(function) = store.item(position).and_downcast::<FunctionObject>()
+            else {
+                continue;
+            };
+            functions[function.name().as_str()] = value(function.function().definition());
+        }
+        let mut doc = DocumentMut::new();
+        doc.insert("functions", functions);
+        std::fs::write(functions_path(), doc.to_string()).expect("Failed to write functions file");
+    }
+
+    /// Stores a user-defined function, replacing the function with the same name if there is one. The first function
+    /// defined is put on the custom key.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function to store.
+    fn set_function(&self, function: UserFunction) {
+        let store = self.functions();
+        match position_by_name(&store, &function.name) {
+            Ok(position) => {
+                if let Some(function_object) = store.item(position).and_downcast::<FunctionObject>() {
+                    function_object.set_function(function);
+                    store.items_changed(position, 1, 1);
+                }
+            }
+            Err(position) => {
+                if self.imp().custom_key.borrow().is_empty() {
+                    self.imp().custom_key.replace(function.name.clone());
+                }
+                store.insert(position, &FunctionObject::new(function));
+            }
+        }
+        self.update_custom_key();
+    }
+
+    /// Deletes a user-defined function. Does nothing if there is no function with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the function.
+    fn delete_function(&self, name: &str) {
+        let store = self.functions();
+        if let Ok(position) = position_by_name(&store, name) {
+            store.remove(position);
+        }
+        self.update_custom_key();
+    }
+
+    /// Redefines a user-defined function with the body typed in the entry of a function row. If the definition is
+    /// invalid, the entry is marked as erroneous and its tooltip shows the error until a valid body is typed.
+    ///
+    /// # Arguments
+    ///
+    /// * `function_object` - The edited function.
+    /// * `entry` - The entry of the row.
+    fn edit_function(&self, function_object: &FunctionObject, entry: &gtk::Entry) {
+        entry.remove_css_class("error");
+        entry.set_tooltip_text(None);
+        let definition = format!("{} = {}", function_object.function().signature(), entry.text());
+        let result = Statement::parse(&definition).and_then(|statement| {
+            match statement {
+                Statement::Definition(function) => {
+                    self.with_context(|context| context.check(&function))?;
+                    Ok(function)
+                }
+                _ => Err(Error::Syntax("Invalid function body".to_string())),
+            }
+        });
+        match result {
+            Ok(function) => self.set_function(function),
+            Err(error) => {
+                entry.add_css_class("error");
+                entry.set_tooltip_text(Some(&error.to_string()));
+            }
+        }
+    }
+
+    /// Updates the custom key of the scientific keypad: its label, the menu to choose its function and the state of
+    /// the `custom-key` and `custom-function` actions. If the function of the key no longer exists, the key is
+    /// cleared.
+    fn update_custom_key(&self) {
+        let imp = self.imp();
+        let store = self.functions();
+        let menu = gio::Menu::new();
+        let mut custom_function = None;
+        for position in 0..store.n_items() {
+            let Some(function_object) = store.item(position).and_downcast::<FunctionObject>()
+            else {
+                continue;
+            };
+            let function = function_object.function();
+            let item = gio::MenuItem::new(Some(&function.signature()), None);
+            item.set_action_and_target_value(Some("win.custom-key"), Some(&function.name.to_variant()));
+            menu.append_item(&item);
+            if function.name == *imp.custom_key.borrow() {
+                custom_function = Some(function);
+            }
+        }
+        if custom_function.is_none() {
+            imp.custom_key.replace(String::new());
+        }
+
+        let scientific_numpad = imp.scientific_numpad.imp();
+        scientific_numpad.custom_key_menu.set_menu_model(Some(&menu));
+        scientific_numpad
+            .custom_key_menu
+            .set_sensitive(store.n_items() > 0);
+        match &custom_function {
+            Some(function) => {
+                scientific_numpad.button_custom.set_label(&function.signature());
+                scientific_numpad
+                    .button_custom
+                    .set_tooltip_text(Some(&function.definition()));
+            }
+            None => {
+                scientific_numpad.button_custom.set_label("f(x)");
+                scientific_numpad
+                    .button_custom
+                    .set_tooltip_text(Some("Define a function, e.g. f(x) = x^2 + 1, to use it on this key"));
+            }
+        }
+        if let Some(action) = self
+            .lookup_action("custom-function")
+            .and_downcast::<gio::SimpleAction>()
+        {
+            action.set_enabled(custom_function.is_some());
+        }
+        if self.lookup_action("custom-key").is_some() {
+            self.set_action_state("custom-key", &imp.custom_key.borrow().to_variant());
+        }
+    }
+
+    /// Applies the function of the custom key. A function of one argument is applied to the displayed value. For
+    /// other functions, a call with the displayed value as first argument is started in the expression entry, so the
+    /// other arguments can be typed.
+    fn apply_custom_function(&self) {
+        let imp = self.imp();
+        let name = imp.custom_key.borrow().clone();
+        let store = self.functions();
+        let Some(function) = position_by_name(&store, &name)
+            .ok()
+            .and_then(|position| store.item(position).and_downcast::<FunctionObject>())
+            .map(|function_object| function_object.function())
+        else {
+            return;
+        };
+        if function.parameters.len() == 1 {
+            let value = imp.calculator.borrow().value();
+            let result = self
+                .with_context(|context| context.call_function(&name, &[Quantity::from(value)]))
+                .and_then(Quantity::into_number);
+            self.update_calculator(|calculator| calculator.apply_named(&name, |_| result));
+            return;
+        }
+        self.start_call(&name, function.parameters.len());
+    }
+
+    /// Starts a call of a function in the expression entry, with the displayed value as first argument (e.g.
+    /// `gcd(12, `), so the other arguments can be typed.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the function.
+    /// * `arity` - The number of arguments the function takes. A function without arguments is called with none.
+    fn start_call(&self, name: &str, arity: usize) {
+        let imp = self.imp();
+        let call = if arity == 0 {
+            format!("{}()", name)
+        }
+        else {
+            format!("{}({}, ", name, imp.calculator.borrow().value())
+        };
+        imp.expression_entry.set_text(&call);
+        imp.expression_entry.grab_focus();
+        imp.expression_entry.set_position(-1);
+    }
+
+    /// Runs a function with the context typed expressions are evaluated in: the variables, the user-defined
+    /// functions, the results of the history, and the angle unit and the complex mode of the calculator.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function to run with the context.
+    ///
+    /// # Returns
+    ///
+    /// The return value of the function.
+    pub fn with_context<T>(&self, f: impl FnOnce(&Context) -> T) -> T {
+        let store = self.variables();
+        let variables = (0..store.n_items())
+            .filter_map(|position| store.item(position).and_downcast::<VariableObject>())
+            .map(|variable| (variable.name(), variable.value()))
+            .collect::<BTreeMap<String, Quantity>>();
+        let store = self.functions();
+        let functions = (0..store.n_items())
+            .filter_map(|position| store.item(position).and_downcast::<FunctionObject>())
+            .map(|function_object| (function_object.name(), function_object.function()))
+            .collect::<BTreeMap<String, UserFunction>>();
+        let history = self.history();
+        let answers = (0..history.n_items())
+            .filter_map(|position| history.item(position).and_downcast::<HistoryObject>())
+            .map(|history_object| {
+                let result = history_object.result();
+                if let Some(uncertainty) = history_object.uncertainty() {
+                    crate::engine::Value::Uncertain(Uncertain {
+                        value: result.re,
+                        uncertainty,
+                    })
+                }
+                else if let Some(interval) = history_object.interval() {
+                    crate::engine::Value::Interval(interval)
+                }
+                else if result.is_real() {
+                    crate::engine::Value::Quantity(Quantity::new(result.re, history_object.units()))
+                }
+                else {
+                    crate::engine::Value::Complex(result)
+                }
+            })
+            .collect::<Vec<crate::engine::Value>>();
+        let context = Context {
+            variables:  &variables,
+            functions:  &functions,
+            answers:    &answers,
+            angle_unit: self.imp().calculator.borrow().angle_unit(),
+            complex:    self.imp().calculator.borrow().complex_mode(),
+            interval:   self.imp().calculator.borrow().interval_mode(),
+        };
+        f(&context)
+    }
+
+    /// Evaluates the expression typed in the expression entry and shows its result, clearing the entry if the
+    /// expression was evaluated or stored.
+    fn evaluate_expression(&self) {
+        let entry = &self.imp().expression_entry;
+        let text = entry.text().trim().to_string();
+        if !text.is_empty() && self.evaluate_statement(&text) {
+            entry.set_text("");
+        }
+    }
+
+    /// Evaluates the expression typed in the input display. A plain number, or the unedited value shown, completes
+    /// the pending calculation as equals on the keypad; any other text is evaluated as a typed expression and replaced
+    /// by its result.
+    fn evaluate_display(&self) {
+        let imp = self.imp();
+        let text = imp.input_display.text().trim().to_string();
+        let locale = imp.number_format.get().locale;
+        if !imp.input_display_edited.replace(false) {
+            self.evaluate();
+        }
+        else if let Some(number) = locale.parse(&text) {
+            self.update_calculator(|calculator| calculator.set_entry(&number));
+            self.evaluate();
+        }
+        else if !text.is_empty() {
+            self.evaluate_statement(&text);
+        }
+        self.update_display();
+    }
+
+    /// Enters the number in the input display on the keypad, or shows the keypad value again if the text is not a
+    /// number (e.g. an expression left without pressing Enter).
+    fn sync_display_entry(&self) {
+        let locale = self.imp().number_format.get().locale;
+        match locale.parse(self.imp().input_display.text().as_str()) {
+            Some(number) => self.update_calculator(|calculator| calculator.set_entry(&number)),
+            None => self.update_display(),
+        }
+    }
+
+    /// Evaluates a typed statement and shows its result. An assignment (e.g. `rate = 0.0725`) also stores the value
+    /// in the variable, and a valid function definition (e.g. `f(x) = x^2 + 1`) is stored without being evaluated.
+    /// Successful evaluations are added to the top of the history, except matrices, which are shown in the matrix
+    /// page.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The statement typed.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the statement was evaluated or stored, `false` if it failed.
+    fn evaluate_statement(&self, text: &str) -> bool {
+        let statement = Statement::parse(text);
+        let result = match &statement {
+            Ok(Statement::Definition(function)) => {
+                match self.with_context(|context| context.check(function)) {
+                    Ok(()) => {
+                        self.set_function(function.clone());
+                        return true;
+                    }
+                    Err(error) => Err(error),
+                }
+            }
+            Ok(Statement::Expression(expression)) => {
+                match self.with_context(|context| expression.value(context)) {
+                    Ok(crate::engine::Value::Matrix(matrix)) => {
+                        self.show_matrix(text, &matrix);
+                        return true;
+                    }
+                    result => result,
+                }
+            }
+            Ok(Statement::Assignment(_, expression)) => {
+                self.with_context(|context| expression.evaluate(context))
+                    .map(crate::engine::Value::Quantity)
+            }
+            Err(error) => Err(error.clone()),
+        };
+        let expression = match &statement {
+            Ok(Statement::Assignment(name, _)) => {
+                if let Ok(crate::engine::Value::Quantity(value)) = &result {
+                    self.set_variable(name, value.clone());
+                }
+                text.to_string()
+            }
+            Ok(Statement::Definition(_)) => text.to_string(),
+            _ => format!("{} =", text.trim_end_matches('=').trim_end()),
+        };
+
+        let mut calculation = None;
+        self.update_calculator(|calculator| calculation = calculator.set_result(expression, result));
+        match calculation {
+            Some(calculation) => {
+                self.add_to_history(calculation);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Shows a matrix resulting from a typed expression in the matrix page, and selects that page.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - The expression typed.
+    /// * `matrix` - The value of the expression.
+    fn show_matrix(&self, expression: &str, matrix: &Matrix) {
+        let imp = self.imp();
+        imp.matrix_page.show_result(expression, matrix);
+        imp.expander_history.set_expanded(true);
+        let page = (0..imp.tabs.n_pages()).find(|index| {
+            imp.tabs
+                .nth_page(Some(*index))
+                .is_some_and(|page| imp.matrix_page.is_ancestor(&page))
+        });
+        imp.tabs.set_current_page(page);
+    }
+
+    /// Applies the given update to the calculator, adds its events to the tape, records the state transition for undo
+    /// and updates the input display.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The update to apply to the calculator.
+    fn update_calculator(&self, update: impl FnOnce(&mut Calculator)) {
+        let imp = self.imp();
+        let before = imp.calculator.borrow().clone();
+        update(&mut imp.calculator.borrow_mut());
+        let events = imp.calculator.borrow_mut().take_events();
+        imp.tape_page.record(events);
+        imp.undo_stack
+            .borrow_mut()
+            .record(before, &imp.calculator.borrow());
+        self.update_undo_actions();
+        self.update_display();
+    }
+
+    /// Applies the given change of mode (angle unit, complex mode or interval mode) to the calculator and updates the
+    /// input display. Mode changes are settings rather than steps of a calculation, so they are not recorded for
+    /// undo.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The change of mode to apply to the calculator.
+    fn update_calculator_mode(&self, update: impl FnOnce(&mut Calculator)) {
+        update(&mut self.imp().calculator.borrow_mut());
+        self.update_display();
+    }
+
+    /// Steps back to the previous state of the calculator, or forward to the state that was last undone. The current
+    /// modes are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `redo` - Whether to step forward instead of back.
+    fn undo(&self, redo: bool) {
+        let imp = self.imp();
+        let current = imp.calculator.borrow().clone();
+        let (angle_unit, complex_mode, interval_mode) =
+            (current.angle_unit(), current.complex_mode(), current.interval_mode());
+        let mut undo_stack = imp.undo_stack.borrow_mut();
+        let state = if redo {
+            undo_stack.redo(current)
+        }
+        else {
+            undo_stack.undo(current)
+        };
+        drop(undo_stack);
+        if let Some(mut state) = state {
+            state.set_angle_unit(angle_unit);
+            state.set_complex_mode(complex_mode);
+            state.set_interval_mode(interval_mode);
+            imp.calculator.replace(state);
+        }
+        self.update_undo_actions();
+        self.update_display();
+    }
+
+    /// Enables the `undo` and `redo` actions if there is a state to step back or forward to.
+    fn update_undo_actions(&self) {
+        let undo_stack = self.imp().undo_stack.borrow();
+        for (name, enabled) in [("undo", undo_stack.can_undo()), ("redo", undo_stack.can_redo())] {
+            if let Some(action) = self.lookup_action(name).and_downcast::<gio::SimpleAction>() {
+                action.set_enabled(enabled);
+            }
+        }
+    }
+
+    /// Inserts the given digits and decimal points into the number being entered.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to be inserted.
+    fn insert_display_text(&self, text: &str) {
+        self.update_calculator(|calculator| {
+            for c in text.chars() {
+                if c == '.' {
+                    calculator.input_decimal();
+                }
+                else {
+                    calculator.input_digit(c);
+                }
+            }
+        });
+    }
+
+    /// Evaluates the pending calculation and adds it to the top of the history.
+    fn evaluate(&self) {
+        let mut calculation = None;
+        self.update_calculator(|calculator| calculation = calculator.equals());
+        if let Some(calculation) = calculation {
+            self.add_to_history(calculation);
+        }
+    }
+
+    /// Factors the displayed value into primes and adds the factorization to the top of the history.
+    fn factor(&self) {
+        let mut calculation = None;
+        self.update_calculator(|calculator| calculation = calculator.factor());
+        if let Some(calculation) = calculation {
+            self.add_to_history(calculation);
+        }
+    }
+
+    /// The format used to display results: the configured number format, or the notation selected by the active
+    /// `F-E`, `F↔D` or `▸DMS` toggle.
+    fn display_format(&self) -> NumberFormat {
+        let number_format = self.imp().number_format.get();
+        NumberFormat {
+            notation: self
+                .imp()
+                .result_notation
+                .get()
+                .unwrap_or(number_format.notation),
+            ..number_format
+        }
+    }
+
+    /// Toggles showing results in the given notation, turning off the other toggles of
+    /// [`RESULT_NOTATION_ACTIONS`].
+    ///
+    /// # Arguments
+    ///
+    /// * `notation` - The notation of the toggle.
+    fn toggle_result_notation(&self, notation: Notation) {
+        let result_notation = if self.imp().result_notation.get() == Some(notation) {
+            None
+        }
+        else {
+            Some(notation)
+        };
+        self.imp().result_notation.set(result_notation);
+        for (name, notation) in RESULT_NOTATION_ACTIONS {
+            self.set_action_state(name, &(result_notation == Some(notation)).to_variant());
+        }
+        self.refresh_number_format();
+    }
+
+    /// Returns the selected keypad mode, one of [`KEYPAD_MODES`].
+    fn keypad_mode(&self) -> &'static str {
+        let imp = self.imp();
+        let page = imp.keypad_stack.visible_child_name();
+        if page.as_deref() == Some("financial") {
+            "financial"
+        }
+        else if page.as_deref() == Some("solver") {
+            "solver"
+        }
+        else if imp.scientific_numpad.is_visible() {
+            "scientific"
+        }
+        else {
+            "basic"
+        }
+    }
+
+    /// Shows the keypad of a mode and updates the state of the `keypad-mode` action.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - One of [`KEYPAD_MODES`]. Other names select the basic keypad.
+    fn set_keypad_mode(&self, mode: &str) {
+        let imp = self.imp();
+        let mode = KEYPAD_MODES
+            .into_iter()
+            .find(|keypad_mode| *keypad_mode == mode)
+            .unwrap_or("basic");
+        imp.scientific_numpad.set_visible(mode == "scientific");
+        imp.keypad_stack
+            .set_visible_child_name(if matches!(mode, "financial" | "solver") { mode } else { "basic" });
+        if self.lookup_action("keypad-mode").is_some() {
+            self.set_action_state("keypad-mode", &mode.to_variant());
+        }
+    }
+
+    /// Sets the state of a stateful action of the [`Window`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the action.
+    /// * `state` - The new state of the action.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if the action does not exist.
+    fn set_action_state(&self, name: &str, state: &glib::Variant) {
+        self.lookup_action(name)
+            .and_downcast::<gio::SimpleAction>()
+            .expect("The action needs to be of type `gio::SimpleAction`.")
+            .set_state(state);
+    }
+
+    /// Redraws the input display, the history and the pages showing numbers after the number format has changed.
+    fn refresh_number_format(&self) {
+        self.update_display();
+        self.set_pages_number_format(self.imp().number_format.get());
+        let history = self.history();
+        let n_items = history.n_items();
+        history.items_changed(0, n_items, n_items);
+    }
+
+    /// Sets the number format of all the pages showing numbers: the conversion, date, statistics, plot, matrix,
+    /// worksheet, tape, financial and solver pages.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_format` - The format of the numbers.
+    fn set_pages_number_format(&self, number_format: NumberFormat) {
+        let imp = self.imp();
+        imp.conversion_window.set_number_format(number_format);
+        imp.date_page.set_number_format(number_format);
+        imp.statistics_page.set_number_format(number_format);
+        imp.plot_page.set_number_format(number_format);
+        imp.matrix_page.set_number_format(number_format);
+        imp.worksheet_page.set_number_format(number_format);
+        imp.tape_page.set_number_format(number_format);
+        imp.financial_page.set_number_format(number_format);
+        imp.solver_page.set_number_format(number_format);
+    }
+
+    /// Updates the input display with the state of the calculator. The font of the input display is scaled down to
+    /// fit the available width, down to [`DISPLAY_MIN_FONT_SIZE`]. If the value does not fit at the minimum font size,
+    /// it is displayed in scientific notation with as many mantissa digits as fit, and the full value is shown in the
+    /// popover of the full result button (e.g. all the digits of `50!`).
+    fn update_display(&self) {
+        let imp = self.imp();
+        let input_display = &imp.input_display;
+        let format = self.display_format();
+        let calculator = imp.calculator.borrow();
+        imp.expression_label
+            .set_label(&format.locale.localize_expression(calculator.expression()));
+        imp.angle_label
+            .set_label(calculator.angle_unit().abbreviation());
+        imp.complex_label
+            .set_visible(calculator.complex_mode());
+        imp.interval_label
+            .set_visible(calculator.interval_mode());
+        imp.memory_label
+            .set_visible(calculator.memory().is_some());
+        imp.units_label
+            .set_label(&calculator.units().to_string());
+        imp.units_label
+            .set_visible(!calculator.units().is_empty() && !calculator.units().is_clock());
+        if self.lookup_action("angle-unit").is_some() {
+            self.set_action_state("angle-unit", &calculator.angle_unit().name().to_variant());
+        }
+        if self.lookup_action("complex-mode").is_some() {
+            self.set_action_state("complex-mode", &calculator.complex_mode().to_variant());
+        }
+        if self.lookup_action("interval-mode").is_some() {
+            self.set_action_state("interval-mode", &calculator.interval_mode().to_variant());
+        }
+
+        let available = f64::from(input_display.width());
+        let fit = |text: &str| {
+            let width = input_display.create_pango_layout(Some(text)).pixel_size().0.max(1);
+            (DISPLAY_MAX_FONT_SIZE * available / f64::from(width)).min(DISPLAY_MAX_FONT_SIZE)
+        };
+        let scientific = |precision: usize| {
+            match calculator.display() {
+                Display::Entry(entry) if entry.contains(['/', '°']) => {
+                    Some(format_compact(&calculator.value(), precision))
+                }
+                Display::Entry(entry) => Some(display_scientific(entry, precision)),
+                Display::Result(number) => Some(format_compact(number, precision)),
+                Display::Complex(_)
+                | Display::Factorization(_)
+                | Display::Uncertain(_)
+                | Display::Interval(_)
+                | Display::Error(_) => None,
+            }
+            .map(|text| format.locale.localize(&text, false))
+        };
+
+        let mut text = match calculator.display() {
+            Display::Entry(entry) => format.locale.localize(entry, true),
+            Display::Result(number) if calculator.units().is_clock() => format_clock(number, &format),
+            Display::Result(number) => format_number(number, &format),
+            Display::Complex(complex) => format_complex(complex, &format),
+            Display::Factorization(factorization) => factorization.to_string(),
+            Display::Uncertain(uncertain) => format_uncertain(uncertain, &format),
+            Display::Interval(interval) => format_interval(interval, &format),
+            Display::Error(error) => error.to_string(),
+        };
+        let full_text = text.clone();
+        let mut font_size = DISPLAY_MAX_FONT_SIZE;
+        let mut abbreviated = false;
+        if available > 0.0 {
+            font_size = fit(&text);
+            if font_size < DISPLAY_MIN_FONT_SIZE {
+                for precision in (0..=DISPLAY_MAX_PRECISION).rev() {
+                    let Some(candidate) = scientific(precision)
+                    else {
+                        break;
+                    };
+                    text = candidate;
+                    font_size = fit(&text);
+                    if font_size >= DISPLAY_MIN_FONT_SIZE {
+                        break;
+                    }
+                }
+            }
+            abbreviated = text != full_text || font_size < DISPLAY_MIN_FONT_SIZE;
+            font_size = font_size.max(DISPLAY_MIN_FONT_SIZE);
+        }
+        drop(calculator);
+        imp.full_result_label.set_label(&full_text);
+        imp.full_result_button.set_visible(abbreviated);
+
+        let attributes = pango::AttrList::new();
+        attributes.insert(pango::AttrSize::new_size_absolute(
+            (font_size * f64::from(pango::SCALE)) as i32,
+        ));
+        input_display.set_attributes(Some(&attributes));
+        self.set_display_text(&text);
+    }
+
+    /// Schedules an update of the input display. The update runs once the window has been laid out, so the font size
+    /// is computed from the new width of the input display.
+    fn queue_display_update(&self) {
+        glib::idle_add_local_once(clone!(@weak self as window => move || {
+            window.update_display();
+        }));
+    }
+
+    /// Shows the [`Preferences`] dialog. Changes made in the dialog are applied immediately.
+    fn show_preferences(&self) {
+        let preferences = Preferences::new();
+        let number_format = self.imp().number_format.get();
+        let notation = Notation::ALL
+            .iter()
+            .position(|notation| *notation == number_format.notation)
+            .unwrap_or_default();
+        preferences
+            .imp()
+            .notation_row
+            .set_selected(u32::try_from(notation).expect("Cannot convert notation to u32"));
+        preferences
+            .imp()
+            .precision_row
+            .set_value(number_format.precision as f64);
+        preferences
+            .imp()
+            .si_prefixes_row
+            .set_active(number_format.si_prefixes);
+        preferences
+            .imp()
+            .max_denominator_row
+            .set_value(number_format.max_denominator as f64);
+        let locale_setting = LocaleSetting::ALL
+            .iter()
+            .position(|setting| *setting == self.imp().locale_setting.get())
+            .unwrap_or_default();
+        preferences
+            .imp()
+            .locale_row
+            .set_selected(u32::try_from(locale_setting).expect("Cannot convert locale setting to u32"));
+
+        preferences
+            .imp()
+            .notation_row
+            .connect_selected_notify(clone!(@weak self as window => move |row| {
+                let notation = Notation::ALL
+                    .get(row.selected() as usize)
+                    .copied()
+                    .unwrap_or_default();
+                window.update_number_format(|number_format| number_format.notation = notation);
+            }));
+        preferences
+            .imp()
+            .precision_row
+            .connect_value_notify(clone!(@weak self as window => move |row| {
+                let precision = row.value() as usize;
+                window.update_number_format(|number_format| number_format.precision = precision);
+            }));
+        preferences
+            .imp()
+            .si_prefixes_row
+            .connect_active_notify(clone!(@weak self as window => move |row| {
+                let si_prefixes = row.is_active();
+                window.update_number_format(|number_format| number_format.si_prefixes = si_prefixes);
+            }));
+        preferences
+            .imp()
+            .max_denominator_row
+            .connect_value_notify(clone!(@weak self as window => move |row| {
+                let max_denominator = row.value() as u64;
+                window.update_number_format(|number_format| number_format.max_denominator = max_denominator);
+            }));
+        preferences
+            .imp()
+            .locale_row
+            .connect_selected_notify(clone!(@weak self as window => move |row| {
+                let locale_setting = LocaleSetting::ALL
+                    .get(row.selected() as usize)
+                    .copied()
+                    .unwrap_or_default();
+                window.imp().locale_setting.set(locale_setting);
+                window.update_number_format(|number_format| number_format.locale = locale_setting.locale());
+                window.update_decimal_button();
+            }));
+        preferences.present(self);
+    }
+
+    /// Applies the given update to the number format and redraws the input display and the history with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The update to apply to the number format.
+    fn update_number_format(&self, update: impl FnOnce(&mut NumberFormat)) {
+        let mut number_format = self.imp().number_format.get();
+        update(&mut number_format);
+        self.imp().number_format.set(number_format);
+        self.refresh_number_format();
+    }
+
+    /// Shows the decimal separator of the current locale on the decimal button.
+    fn update_decimal_button(&self) {
+        let decimal_separator = self.imp().number_format.get().locale.decimal_separator;
+        self.imp()
+            .basic_numpad
+            .imp()
+            .button_decimal
+            .set_label(&decimal_separator.to_string());
+    }
+
+    /// Whether the given key enters the decimal separator: the key of the decimal separator of the current locale,
+    /// or the decimal key of the keypad.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The pressed or released key.
+    fn is_decimal_key(&self, key: Key) -> bool {
+        matches!(key, Key::KP_Decimal | Key::KP_Separator)
+            || key.to_unicode() == Some(self.imp().number_format.get().locale.decimal_separator)
+    }
+
+    /// Sets the text of the input display.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to set the input display to.
+    fn set_display_text(&self, text: &str) {
+        self.imp().input_display.block_signal(
+            &self
+                .imp()
+                .input_display_changed_signal
+                .borrow()
+                .as_ref()
+                .expect("Could not get input_display_changed_signal"),
+        );
+        self.imp().input_display.set_text(text);
+        self.imp().input_display.unblock_signal(
+            &self
+                .imp()
+                .input_display_changed_signal
+                .borrow()
+                .as_ref()
+                .expect("Could not get input_display_changed_signal"),
+        );
+    }
+
+    /// Sets up the event controllers for the [`Window`].
+    /// The event controllers are used to handle key presses and releases.
+    /// The key presses and releases are used to interact with the calculator.
+    fn setup_event_controllers(&self) {
+        let controller = EventControllerKey::builder()
+            .name("keypad-controller")
+            .propagation_phase(gtk::PropagationPhase::Target)
+            .propagation_limit(gtk::PropagationLimit::SameNative)
+            .build();
+        controller.connect_key_pressed(
+            clone!(@weak self as window => @default-return glib::Propagation::Stop, move |_controller, key, _keyval, _state| {
+                match key {
+                    Key::BackSpace => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_backspace
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.update_calculator(Calculator::backspace);
+                    }
+                    Key::_0 | Key::KP_0 => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_zero
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.insert_display_text("0");
+                    }
+                    Key::_1 | Key::KP_1 => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_one
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.insert_display_text("1");
+                    }
+                    Key::_2 | Key::KP_2 => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_two
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.insert_display_text("2");
+                    }
+                    Key::_3 | Key::KP_3 => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_three
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.insert_display_text("3");
+                    }
+                    Key::_4 | Key::KP_4 => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_four
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.insert_display_text("4");
+                    }
+                    Key::_5 | Key::KP_5 => {
+                        window
+                            .imp()
                             .basic_numpad
                             .imp()
                             .button_five
@@ -730,10 +2233,9 @@ impl Window {
                             .imp()
                             .button_clear_entry
                             .set_state_flags(gtk::StateFlags::ACTIVE, false);
-                        window.set_display_text("0");
-                        window.imp().input_display.set_max_length(21);
+                        window.update_calculator(Calculator::clear_entry);
                     }
-                    Key::period | Key::KP_Decimal => {
+                    key if window.is_decimal_key(key) => {
                         window
                             .imp()
                             .basic_numpad
@@ -749,28 +2251,70 @@ impl Window {
                             .imp()
                             .button_plus_minus
                             .set_state_flags(gtk::StateFlags::ACTIVE, false);
-                        let text = window.imp().input_display.text();
-                        if text != "0" {
-                            if text.starts_with('-') {
-                                window.imp().input_display.delete_text(0, 1);
-                                window.imp().input_display.set_max_length(21);
-                            }
-                            else {
-                                let mut pos = 0;
-                                window.imp().input_display.set_max_length(22);
-                                window.imp().input_display.insert_text("-", &mut pos);
-                            }
-                        }
+                        window.update_calculator(Calculator::negate);
                     }
                     Key::Escape => {
-                        // TODO: Clear calculation buffer
                         window
                             .imp()
                             .basic_numpad
                             .imp()
                             .button_clear
                             .set_state_flags(gtk::StateFlags::ACTIVE, false);
-                        window.set_display_text("0");
+                        window.update_calculator(Calculator::clear);
+                    }
+                    Key::plus | Key::KP_Add => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_add
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.update_calculator(|calculator| calculator.apply_operator(Operator::Add));
+                    }
+                    Key::minus | Key::KP_Subtract => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_subtract
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.update_calculator(|calculator| calculator.apply_operator(Operator::Subtract));
+                    }
+                    Key::asterisk | Key::KP_Multiply => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_multiply
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.update_calculator(|calculator| calculator.apply_operator(Operator::Multiply));
+                    }
+                    Key::slash | Key::KP_Divide => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_divide
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.update_calculator(|calculator| calculator.apply_operator(Operator::Divide));
+                    }
+                    Key::percent => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_percent
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.update_calculator(Calculator::percent);
+                    }
+                    Key::Return | Key::KP_Enter | Key::equal => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_equals
+                            .set_state_flags(gtk::StateFlags::ACTIVE, false);
+                        window.evaluate();
                     }
                     _ => {}
                 }
@@ -876,7 +2420,7 @@ impl Window {
                             .button_clear_entry
                             .unset_state_flags(gtk::StateFlags::ACTIVE);
                     }
-                    Key::period | Key::KP_Decimal => {
+                    key if window.is_decimal_key(key) => {
                         window
                             .imp()
                             .basic_numpad
@@ -900,6 +2444,54 @@ impl Window {
                             .button_clear
                             .unset_state_flags(gtk::StateFlags::ACTIVE);
                     }
+                    Key::plus | Key::KP_Add => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_add
+                            .unset_state_flags(gtk::StateFlags::ACTIVE);
+                    }
+                    Key::minus | Key::KP_Subtract => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_subtract
+                            .unset_state_flags(gtk::StateFlags::ACTIVE);
+                    }
+                    Key::asterisk | Key::KP_Multiply => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_multiply
+                            .unset_state_flags(gtk::StateFlags::ACTIVE);
+                    }
+                    Key::slash | Key::KP_Divide => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_divide
+                            .unset_state_flags(gtk::StateFlags::ACTIVE);
+                    }
+                    Key::percent => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_percent
+                            .unset_state_flags(gtk::StateFlags::ACTIVE);
+                    }
+                    Key::Return | Key::KP_Enter | Key::equal => {
+                        window
+                            .imp()
+                            .basic_numpad
+                            .imp()
+                            .button_equals
+                            .unset_state_flags(gtk::StateFlags::ACTIVE);
+                    }
                     _ => {
                         println!("Key released: {:?}, Key Name: {:?}", key, key.name());
                     }
@@ -927,30 +2519,61 @@ impl Window {
             .input_display_changed_signal
             .replace(Some(self.imp().input_display.connect_changed(
                 clone!(@weak self as window => move |disp| {
-                    let binding = disp.text().replace(",", "");
-                    let text = binding.trim();
-                    if text.is_empty() {
-                        disp.set_max_length(21);
-                        window.set_display_text("0");
+                    // While the input display has the focus, its text is an expression being typed.
+                    if disp.has_focus() {
+                        window.imp().input_display_edited.set(true);
                     }
                     else {
-                        let text = display_thousands_separator(text);
-                        if text.starts_with("-") {
-                            disp.set_max_length(22);
-                        }
-                        else {
-                            disp.set_max_length(21);
-                        }
-                        window.set_display_text(text.as_str());
+                        window.sync_display_entry();
                     }
                 }),
             )));
-    }
+        let focus_controller = gtk::EventControllerFocus::new();
+        focus_controller.connect_leave(clone!(@weak self as window => move |_| {
+            if window.imp().input_display_edited.replace(false) {
+                window.sync_display_entry();
+            }
+        }));
+        self.imp()
+            .input_display
+            .add_controller(focus_controller);
+        self.imp()
+            .input_display
+            .connect_activate(clone!(@weak self as window => move |_| {
+                window.evaluate_display();
+            }));
 
-    /// Creates the rows for the history list.
-    fn create_rows(&self) {
-        let vector: Vec<IntegerObject> = (0..=10).map(IntegerObject::new).collect();
-        self.history().extend_from_slice(&vector);
+        self.imp()
+            .expression_entry
+            .connect_activate(clone!(@weak self as window => move |_| {
+                window.evaluate_expression();
+            }));
+        self.imp()
+            .tabs
+            .connect_switch_page(clone!(@weak self as window => move |_tabs, page, _page_num| {
+                let imp = window.imp();
+                if page == imp.conversion_window.upcast_ref::<gtk::Widget>() {
+                    imp.expander_convert.set_expanded(true);
+                }
+                else if imp.expander_convert.is_expanded() {
+                    imp.expander_history.set_expanded(true);
+                }
+            }));
+
+        self.connect_default_width_notify(clone!(@weak self as window => move |_| {
+            window.queue_display_update();
+        }));
+        self.connect_maximized_notify(clone!(@weak self as window => move |_| {
+            window.queue_display_update();
+        }));
+        self.connect_fullscreened_notify(clone!(@weak self as window => move |_| {
+            window.queue_display_update();
+        }));
+        self.imp()
+            .input_display
+            .connect_map(clone!(@weak self as window => move |_| {
+                window.queue_display_update();
+            }));
     }
 
     /// Sets up the actions for the [`Window`].
@@ -962,6 +2585,12 @@ impl Window {
     /// * Panics if the action `num-insert` parameter type is not `i32`.
     /// * Panics if the action `op-insert` parameter cannot be retrieved.
     /// * Panics if the action `op-insert` parameter type is not `String`.
+    /// * Panics if the action `angle-unit` parameter cannot be retrieved.
+    /// * Panics if the action `angle-unit` parameter type is not `String`.
+    /// * Panics if the action `keypad-mode` parameter cannot be retrieved.
+    /// * Panics if the action `keypad-mode` parameter type is not `String`.
+    /// * Panics if the action `custom-key` parameter cannot be retrieved.
+    /// * Panics if the action `custom-key` parameter type is not `String`.
     fn setup_actions(&self) {
         let action_num_insert = ActionEntry::builder("num-insert")
             .parameter_type(Some(&i32::static_variant_type()))
@@ -983,46 +2612,257 @@ impl Window {
                     .expect("The variant needs to be of type `String`.");
                 match parameter.as_str() {
                     "backspace" => {
-                        if window.imp().input_display.text().as_str() != "0" {
-                            window
-                                .imp()
-                                .input_display
-                                .delete_text((window.imp().input_display.text().len() - 1) as i32, -1);
-                        }
+                        window.update_calculator(Calculator::backspace);
                     }
                     "decimal" => {
                         window.insert_display_text(".");
                     }
-                    "plus_minus" => {
-                        let text = window.imp().input_display.text();
-                        if text != "0" {
-                            if text.starts_with('-') {
-                                window.imp().input_display.delete_text(0, 1);
-                                window.imp().input_display.set_max_length(21);
-                            }
-                            else {
-                                let mut pos = 0;
-                                window.imp().input_display.set_max_length(22);
-                                window.imp().input_display.insert_text("-", &mut pos);
-                            }
-                        }
+                    "fraction" => {
+                        window.update_calculator(Calculator::input_fraction);
+                    }
+                    "dms" => {
+                        window.update_calculator(Calculator::input_dms);
+                    }
+                    "convert-angle" => {
+                        window.update_calculator(Calculator::convert_angle);
+                    }
+                    "memory-clear" => {
+                        window.update_calculator(Calculator::memory_clear);
+                    }
+                    "memory-recall" => {
+                        window.update_calculator(Calculator::memory_recall);
+                    }
+                    "memory-add" => {
+                        window.update_calculator(Calculator::memory_add);
+                    }
+                    "memory-subtract" => {
+                        window.update_calculator(Calculator::memory_subtract);
+                    }
+                    "memory-store" => {
+                        window.update_calculator(Calculator::memory_store);
+                    }
+                    "plus-minus" => {
+                        window.update_calculator(Calculator::negate);
+                    }
+                    "percent" => {
+                        window.update_calculator(Calculator::percent);
                     }
                     "clear-entry" => {
-                        window.set_display_text("0");
-                        window.imp().input_display.set_max_length(21);
+                        window.update_calculator(Calculator::clear_entry);
                     }
                     "clear" => {
-                        // TODO: Clear calculation buffer
-                        window.set_display_text("0");
+                        window.update_calculator(Calculator::clear);
                     }
-                    _ => {
-                        println!("Op insert: {}", parameter);
+                    "equals" => {
+                        window.evaluate();
+                    }
+                    "factor" => {
+                        window.factor();
+                    }
+                    name => {
+                        if let Some(operator) = Operator::from_name(name) {
+                            window.update_calculator(|calculator| calculator.apply_operator(operator));
+                        }
+                        else if let Some(function) = Function::from_name(name) {
+                            window.update_calculator(|calculator| calculator.apply_function(function));
+                        }
+                        else if let Some(arity) = window
+                            .with_context(|context| context.arity(name))
+                            .ok()
+                            .filter(|arity| *arity > 1)
+                        {
+                            window.start_call(name, arity);
+                        }
+                        else {
+                            println!("Op insert: {}", parameter);
+                        }
                     }
                 }
             })
             .build();
+        let result_notation_actions = RESULT_NOTATION_ACTIONS.map(|(name, notation)| {
+            ActionEntry::builder(name)
+                .state(false.to_variant())
+                .activate(move |window: &Self, _action, _parameter| {
+                    window.toggle_result_notation(notation);
+                })
+                .build()
+        });
+        let action_angle_unit = ActionEntry::builder("angle-unit")
+            .parameter_type(Some(&String::static_variant_type()))
+            .state(self.imp().calculator.borrow().angle_unit().name().to_variant())
+            .activate(move |window: &Self, _action, parameter| {
+                let parameter = parameter
+                    .expect("Could not get parameter.")
+                    .get::<String>()
+                    .expect("The variant needs to be of type `String`.");
+                if let Some(angle_unit) = AngleUnit::from_name(&parameter) {
+                    window.update_calculator_mode(|calculator| calculator.set_angle_unit(angle_unit));
+                    window.imp().plot_page.refresh();
+                    window.imp().worksheet_page.refresh();
+                }
+            })
+            .build();
+        let action_complex_mode = ActionEntry::builder("complex-mode")
+            .state(self.imp().calculator.borrow().complex_mode().to_variant())
+            .activate(move |window: &Self, _action, _parameter| {
+                window.update_calculator_mode(|calculator| calculator.set_complex_mode(!calculator.complex_mode()));
+                window.imp().worksheet_page.refresh();
+            })
+            .build();
+        let action_interval_mode = ActionEntry::builder("interval-mode")
+            .state(self.imp().calculator.borrow().interval_mode().to_variant())
+            .activate(move |window: &Self, _action, _parameter| {
+                window.update_calculator_mode(|calculator| calculator.set_interval_mode(!calculator.interval_mode()));
+                window.imp().worksheet_page.refresh();
+            })
+            .build();
+        let action_polar_view = ActionEntry::builder("polar-view")
+            .state(self.imp().number_format.get().polar.to_variant())
+            .activate(move |window: &Self, action, _parameter| {
+                window.update_number_format(|number_format| number_format.polar = !number_format.polar);
+                action.set_state(&window.imp().number_format.get().polar.to_variant());
+            })
+            .build();
+        let action_keypad_mode = ActionEntry::builder("keypad-mode")
+            .parameter_type(Some(&String::static_variant_type()))
+            .state(self.keypad_mode().to_variant())
+            .activate(move |window: &Self, _action, parameter| {
+                let parameter = parameter
+                    .expect("Could not get parameter.")
+                    .get::<String>()
+                    .expect("The variant needs to be of type `String`.");
+                window.set_keypad_mode(&parameter);
+                window.queue_display_update();
+            })
+            .build();
+        let action_custom_key = ActionEntry::builder("custom-key")
+            .parameter_type(Some(&String::static_variant_type()))
+            .state(self.imp().custom_key.borrow().to_variant())
+            .activate(move |window: &Self, _action, parameter| {
+                let parameter = parameter
+                    .expect("Could not get parameter.")
+                    .get::<String>()
+                    .expect("The variant needs to be of type `String`.");
+                window.imp().custom_key.replace(parameter);
+                window.update_custom_key();
+            })
+            .build();
+        let action_custom_function = ActionEntry::builder("custom-function")
+            .activate(move |window: &Self, _action, _parameter| {
+                window.apply_custom_function();
+            })
+            .build();
+        let action_copy = ActionEntry::builder("copy")
+            .activate(move |window: &Self, _action, _parameter| {
+                let text = match window.imp().calculator.borrow().display() {
+                    Display::Entry(entry) => window.imp().number_format.get().locale.localize(entry, false),
+                    Display::Result(number) => {
+                        format_number(
+                            number,
+                            &NumberFormat {
+                                grouping: false,
+                                ..window.display_format()
+                            },
+                        )
+                    }
+                    Display::Complex(complex) => {
+                        format_complex(
+                            complex,
+                            &NumberFormat {
+                                grouping: false,
+                                ..window.display_format()
+                            },
+                        )
+                    }
+                    Display::Factorization(factorization) => factorization.to_string(),
+                    Display::Uncertain(uncertain) => {
+                        format_uncertain(
+                            uncertain,
+                            &NumberFormat {
+                                grouping: false,
+                                ..window.display_format()
+                            },
+                        )
+                    }
+                    Display::Interval(interval) => {
+                        format_interval(
+                            interval,
+                            &NumberFormat {
+                                grouping: false,
+                                ..window.display_format()
+                            },
+                        )
+                    }
+                    Display::Error(_) => return,
+                };
+                window.clipboard().set_text(&text);
+            })
+            .build();
+        let action_undo = ActionEntry::builder("undo")
+            .activate(move |window: &Self, _action, _parameter| {
+                window.undo(false);
+            })
+            .build();
+        let action_redo = ActionEntry::builder("redo")
+            .activate(move |window: &Self, _action, _parameter| {
+                window.undo(true);
+            })
+            .build();
+        let action_preferences = ActionEntry::builder("preferences")
+            .activate(move |window: &Self, _action, _parameter| {
+                window.show_preferences();
+            })
+            .build();
 
         self.add_action_entries([action_num_insert]);
         self.add_action_entries([action_op_insert]);
+        self.add_action_entries(result_notation_actions);
+        self.add_action_entries([action_angle_unit, action_keypad_mode, action_copy, action_preferences]);
+        self.add_action_entries([action_complex_mode, action_polar_view, action_interval_mode]);
+        self.add_action_entries([action_undo, action_redo]);
+        self.add_action_entries([action_custom_key, action_custom_function]);
+        self.update_undo_actions();
+    }
+}
+
+/// Returns the position of the object with the given `name` property in a list store sorted by name, or the position
+/// it would be inserted at to keep the list sorted.
+///
+/// # Arguments
+///
+/// * `store` - The list store, holding objects with a `name` property.
+/// * `name` - The name to look for.
+fn position_by_name(store: &gio::ListStore, name: &str) -> Result<u32, u32> {
+    for position in 0..store.n_items() {
+        let Some(object) = store.item(position)
+        else {
+            continue;
+        };
+        match object.property::<String>("name").as_str().cmp(name) {
+            std::cmp::Ordering::Less => {}
+            std::cmp::Ordering::Equal => return Ok(position),
+            std::cmp::Ordering::Greater => return Err(position),
+        }
+    }
+    Err(store.n_items())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Constructs the window the way the application does when it is activated, so a setup step that runs before
+    /// the state it needs exists fails here instead of on launch. Skipped where there is no display to open.
+    #[test]
+    fn window_can_be_constructed() {
+        if adw::init().is_err() {
+            return;
+        }
+        gio::resources_register_include!("resource.gresource").expect("Failed to include our compiled resources");
+        let app = adw::Application::builder().build();
+        let window = Window::new(&app);
+        assert!(window.imp().functions.borrow().is_some());
+        assert!(window.lookup_action("custom-key").is_some());
     }
 }
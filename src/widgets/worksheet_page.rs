@@ -0,0 +1,261 @@
+//! This module contains the implementation of the [`WorksheetPage`] widget. It edits a text of several lines, each an
+//! expression, an assignment or a function definition, and shows the result of each line to its right as the text is
+//! typed. The text can be opened from and saved as a plain text file. It is a subclass of [`gtk::Box`].
+
+use glib::{clone, Object};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::engine::{evaluate_worksheet, LineResult, Matrix, Quantity, Value};
+use crate::utils::{format_clock, format_complex, format_interval, format_number, format_uncertain, NumberFormat};
+use crate::widgets::Window;
+
+mod imp {
+    //! Private implementation details of the [`WorksheetPage`] widget.
+
+    use std::cell::Cell;
+
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{glib, Button, CompositeTemplate, Label, TextView};
+
+    use crate::utils::NumberFormat;
+
+    /// The `WorksheetPage` widget. It edits lines of expressions and shows the result of each line. It is a subclass
+    /// of [`gtk::Box`].
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/com/nc/calculator/worksheet_page.ui")]
+    pub struct WorksheetPage {
+        #[template_child]
+        pub open_button: TemplateChild<Button>,
+        #[template_child]
+        pub save_button: TemplateChild<Button>,
+        #[template_child]
+        pub clear_button: TemplateChild<Button>,
+        #[template_child]
+        pub status_label: TemplateChild<Label>,
+        #[template_child]
+        pub text_view: TemplateChild<TextView>,
+        #[template_child]
+        pub results_view: TemplateChild<TextView>,
+        pub number_format: Cell<NumberFormat>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for WorksheetPage {
+        type ParentType = gtk::Box;
+        type Type = super::WorksheetPage;
+
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "WorksheetPage";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for WorksheetPage {
+        fn constructed(&self) {
+            // Call "constructed" on parent
+            self.parent_constructed();
+
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for WorksheetPage {}
+
+    impl BoxImpl for WorksheetPage {}
+}
+
+glib::wrapper! {
+    /// A [`WorksheetPage`] widget. It edits lines of expressions and shows the result of each line. It is a subclass
+    /// of [`gtk::Box`].
+    pub struct WorksheetPage(ObjectSubclass<imp::WorksheetPage>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl WorksheetPage {
+    /// Creates a new [`WorksheetPage`] widget.
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Sets the format of the results, and shows them again.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_format` - The format of the numbers.
+    pub fn set_number_format(&self, number_format: NumberFormat) {
+        self.imp().number_format.set(number_format);
+        self.refresh();
+    }
+
+    /// Evaluates the lines again and shows their results, after the variables, the functions, the history or the
+    /// angle unit of the window changed.
+    pub fn refresh(&self) {
+        let imp = self.imp();
+        let Some(window) = self.root().and_downcast::<Window>()
+        else {
+            return;
+        };
+        let buffer = imp.text_view.buffer();
+        let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        let format = imp.number_format.get();
+        let results = window.with_context(|context| evaluate_worksheet(&text, context));
+        let lines = results
+            .iter()
+            .map(|result| {
+                match result {
+                    LineResult::Empty => String::new(),
+                    LineResult::Value(value) => format_value(value, &format),
+                    LineResult::Definition(signature) => signature.clone(),
+                    LineResult::Error(error) => error.to_string(),
+                }
+            })
+            .collect::<Vec<String>>();
+        imp.results_view.buffer().set_text(&lines.join("\n"));
+    }
+
+    /// Connects the callbacks of the child widgets.
+    fn setup(&self) {
+        let imp = self.imp();
+        imp.text_view
+            .buffer()
+            .connect_changed(clone!(@weak self as page => move |_| {
+                page.refresh();
+            }));
+        imp.open_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.open();
+            }));
+        imp.save_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.save();
+            }));
+        imp.clear_button
+            .connect_clicked(clone!(@weak self as page => move |_| {
+                page.imp().text_view.buffer().set_text("");
+                page.show_status("");
+            }));
+    }
+
+    /// Asks for a plain text file and replaces the text of the worksheet with its contents.
+    fn open(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Open Worksheet")
+            .modal(true)
+            .build();
+        let window = self.root().and_downcast::<gtk::Window>();
+        dialog.open(
+            window.as_ref(),
+            gio::Cancellable::NONE,
+            clone!(@weak self as page => move |result| {
+                let Ok(file) = result
+                else {
+                    return;
+                };
+                match file.load_contents(gio::Cancellable::NONE) {
+                    Ok((contents, _)) => {
+                        page.imp().text_view.buffer().set_text(&String::from_utf8_lossy(&contents));
+                        page.show_status("");
+                    }
+                    Err(error) => page.show_status(&format!("Could not open the file: {}", error)),
+                }
+            }),
+        );
+    }
+
+    /// Asks for a file and saves the text of the worksheet in it, without the results.
+    fn save(&self) {
+        let buffer = self.imp().text_view.buffer();
+        let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        let dialog = gtk::FileDialog::builder()
+            .title("Save Worksheet")
+            .initial_name("worksheet.txt")
+            .modal(true)
+            .build();
+        let window = self.root().and_downcast::<gtk::Window>();
+        dialog.save(
+            window.as_ref(),
+            gio::Cancellable::NONE,
+            clone!(@weak self as page => move |result| {
+                let Ok(file) = result
+                else {
+                    return;
+                };
+                if let Err(error) = file.replace_contents(
+                    text.as_bytes(),
+                    None,
+                    false,
+                    gio::FileCreateFlags::REPLACE_DESTINATION,
+                    gio::Cancellable::NONE,
+                ) {
+                    page.show_status(&format!("Could not save the worksheet: {}", error));
+                }
+            }),
+        );
+    }
+
+    /// Shows a message above the worksheet, or hides it if the message is empty.
+    fn show_status(&self, message: &str) {
+        let status_label = &self.imp().status_label;
+        status_label.set_label(message);
+        status_label.set_visible(!message.is_empty());
+    }
+}
+
+impl Default for WorksheetPage {
+    /// The default implementation of the [`WorksheetPage`] widget. It creates a new [`WorksheetPage`] widget.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Formats the value of a line on a single line, as the history shows results.
+///
+/// # Arguments
+///
+/// * `value` - The value of the line.
+/// * `format` - The format of the numbers.
+fn format_value(value: &Value, format: &NumberFormat) -> String {
+    match value {
+        Value::Quantity(Quantity { value, units }) => {
+            if units.is_clock() {
+                format_clock(value, format)
+            }
+            else if units.is_empty() {
+                format_number(value, format)
+            }
+            else {
+                format!("{} {}", format_number(value, format), units)
+            }
+        }
+        Value::Complex(complex) => format_complex(complex, format),
+        Value::Matrix(matrix) => format_matrix(matrix, format),
+        Value::Factorization(factorization) => factorization.to_string(),
+        Value::Uncertain(uncertain) => format_uncertain(uncertain, format),
+        Value::Interval(interval) => format_interval(interval, format),
+    }
+}
+
+/// Formats a matrix as it is typed (e.g. `[[1, 2], [3, 4]]`), with the numbers in the given format. The entries are
+/// separated by semicolons in locales with a decimal comma, as the bounds of intervals.
+fn format_matrix(matrix: &Matrix, format: &NumberFormat) -> String {
+    let separator = if format.locale.decimal_separator == ',' { "; " } else { ", " };
+    let rows = (0..matrix.rows())
+        .map(|row| {
+            let entries = (0..matrix.columns())
+                .map(|column| format_number(matrix.get(row, column), format))
+                .collect::<Vec<String>>();
+            format!("[{}]", entries.join(separator))
+        })
+        .collect::<Vec<String>>();
+    format!("[{}]", rows.join(separator))
+}